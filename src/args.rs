@@ -0,0 +1,134 @@
+use std::path::PathBuf;
+
+/// Command line options of bountui.
+#[derive(Debug, Default, PartialEq)]
+pub struct Args {
+    /// Write logs to this file instead of the rotated files in the default log directory.
+    pub log_file: Option<PathBuf>,
+    /// Log specification in `RUST_LOG` style, e.g. `debug` or `info,bountui=trace`.
+    pub log_level: Option<String>,
+    /// Seconds between two reloads of the sessions pages, overrides the config file.
+    pub refresh_interval: Option<u64>,
+    /// Print the version of bountui and exit.
+    pub version: bool,
+    /// Print diagnostic information for bug reports and exit.
+    pub doctor: bool,
+    /// Record the boundary commands run, the last one is shown with `:last-command`.
+    pub debug: bool,
+    /// Auth method to authenticate with, overrides the `BOUNTUI_AUTH_METHOD_ID` env var and the
+    /// config file.
+    pub auth_method_id: Option<String>,
+    /// Login name to authenticate with, overrides the `BOUNTUI_LOGIN_NAME` env var and the config
+    /// file.
+    pub login_name: Option<String>,
+    /// Unix socket other processes list connections and connect or disconnect through.
+    pub control_socket: Option<PathBuf>,
+}
+
+impl Args {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Args, String> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("Missing value for '{name}'"))
+            };
+            match name.as_str() {
+                "--version" => parsed.version = true,
+                "--doctor" => parsed.doctor = true,
+                "--debug" => parsed.debug = true,
+                "--log-file" => parsed.log_file = Some(PathBuf::from(value()?)),
+                "--log-level" => parsed.log_level = Some(value()?),
+                "--auth-method-id" => parsed.auth_method_id = Some(value()?),
+                "--login-name" => parsed.login_name = Some(value()?),
+                "--control-socket" => parsed.control_socket = Some(PathBuf::from(value()?)),
+                "--refresh-interval" => {
+                    let value = value()?;
+                    let seconds = value
+                        .parse()
+                        .ok()
+                        .filter(|s| *s > 0)
+                        .ok_or_else(|| format!("Invalid refresh interval '{value}'"))?;
+                    parsed.refresh_interval = Some(seconds);
+                }
+                _ => return Err(format!("Unknown argument '{name}'")),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn parse_without_arguments() {
+        assert_eq!(parse(&[]), Ok(Args::default()));
+    }
+
+    #[test]
+    fn parse_log_file_and_level() {
+        let args = parse(&["--log-file", "/tmp/bountui.log", "--log-level=debug"]).unwrap();
+        assert_eq!(args.log_file, Some(PathBuf::from("/tmp/bountui.log")));
+        assert_eq!(args.log_level, Some("debug".to_string()));
+    }
+
+    #[test]
+    fn parse_version_and_doctor_flags() {
+        let args = parse(&["--version", "--doctor"]).unwrap();
+        assert!(args.version);
+        assert!(args.doctor);
+    }
+
+    #[test]
+    fn parse_refresh_interval() {
+        assert_eq!(
+            parse(&["--refresh-interval", "30"]).unwrap().refresh_interval,
+            Some(30)
+        );
+        assert!(parse(&["--refresh-interval=0"]).is_err());
+        assert!(parse(&["--refresh-interval=soon"]).is_err());
+    }
+
+    #[test]
+    fn parse_debug_flag() {
+        assert!(parse(&["--debug"]).unwrap().debug);
+        assert!(!parse(&[]).unwrap().debug);
+    }
+
+    #[test]
+    fn parse_auth_method_and_login_name() {
+        let args = parse(&["--auth-method-id", "ampw_1234567890", "--login-name=jane"]).unwrap();
+        assert_eq!(args.auth_method_id, Some("ampw_1234567890".to_string()));
+        assert_eq!(args.login_name, Some("jane".to_string()));
+    }
+
+    #[test]
+    fn parse_control_socket() {
+        let args = parse(&["--control-socket", "/tmp/bountui.sock"]).unwrap();
+        assert_eq!(args.control_socket, Some(PathBuf::from("/tmp/bountui.sock")));
+        assert!(parse(&["--control-socket"]).is_err());
+    }
+
+    #[test]
+    fn parse_missing_value() {
+        assert!(parse(&["--log-file"]).is_err());
+    }
+
+    #[test]
+    fn parse_unknown_argument() {
+        assert!(parse(&["--verbose"]).is_err());
+    }
+}
@@ -6,7 +6,9 @@ use tokio::process::Command;
 
 pub trait Child {
     type Stdout: io::AsyncRead;
+    type Stderr: io::AsyncRead;
     fn stdout(&mut self) -> Option<Self::Stdout>;
+    fn stderr(&mut self) -> Option<Self::Stderr>;
     fn wait(&mut self) -> impl Future<Output = io::Result<ExitStatus>> + Send;
     fn kill(&mut self) -> impl Future<Output = io::Result<()>> + Send;
 }
@@ -30,11 +32,16 @@ where
 
 impl Child for tokio::process::Child {
     type Stdout = tokio::process::ChildStdout;
+    type Stderr = tokio::process::ChildStderr;
 
     fn stdout(&mut self) -> Option<Self::Stdout> {
         self.stdout.take()
     }
 
+    fn stderr(&mut self) -> Option<Self::Stderr> {
+        self.stderr.take()
+    }
+
     fn wait(&mut self) -> impl Future<Output = io::Result<ExitStatus>> {
         self.wait()
     }
@@ -87,6 +94,7 @@ pub mod mock {
     pub struct MockChild {
         status: Option<std::io::Result<ExitStatus>>,
         stdout: Option<tokio_test::io::Mock>,
+        stderr: Option<tokio_test::io::Mock>,
     }
 
     impl MockChild {
@@ -94,19 +102,30 @@ pub mod mock {
             Self {
                 status: Some(status.map(|code| ExitStatus::from_raw(code))),
                 stdout,
+                stderr: None,
             }
         }
+
+        pub fn with_stderr(mut self, stderr: tokio_test::io::Mock) -> Self {
+            self.stderr = Some(stderr);
+            self
+        }
     }
 
     impl Child for MockChild
     where
     {
         type Stdout = tokio_test::io::Mock;
+        type Stderr = tokio_test::io::Mock;
 
         fn stdout(&mut self) -> Option<Self::Stdout> {
             self.stdout.take()
         }
 
+        fn stderr(&mut self) -> Option<Self::Stderr> {
+            self.stderr.take()
+        }
+
         async fn wait(&mut self) -> std::io::Result<ExitStatus> {
             self.status.take().expect("wait called more than once")
         }
@@ -119,20 +138,43 @@ pub mod mock {
 
     pub struct MockCommandRunner {
         commands: Mutex<VecDeque<MockChild>>,
+        /// Program and arguments of every command run so far.
+        pub executed: Mutex<Vec<Vec<String>>>,
+        /// The environment variables set for every command run so far.
+        pub envs: Mutex<Vec<Vec<(String, String)>>>,
     }
 
     impl MockCommandRunner {
         pub fn new(commds: VecDeque<MockChild>) -> Self {
             Self {
-                commands: Mutex::new(commds)
+                commands: Mutex::new(commds),
+                executed: Mutex::new(Vec::new()),
+                envs: Mutex::new(Vec::new()),
             }
         }
+
+        fn record(&self, command: &Command) {
+            let command = command.as_std();
+            let args = std::iter::once(command.get_program())
+                .chain(command.get_args())
+                .map(|arg| arg.to_string_lossy().to_string())
+                .collect();
+            self.executed.lock().expect("Failed to lock executed mutex").push(args);
+            let envs = command
+                .get_envs()
+                .filter_map(|(key, value)| {
+                    Some((key.to_string_lossy().to_string(), value?.to_string_lossy().to_string()))
+                })
+                .collect();
+            self.envs.lock().expect("Failed to lock envs mutex").push(envs);
+        }
     }
 
     impl CommandRunner for MockCommandRunner {
         type Child = MockChild;
 
-        async fn output(&self, _command: &mut Command) -> std::io::Result<Output> {
+        async fn output(&self, command: &mut Command) -> std::io::Result<Output> {
+            self.record(command);
             let mut child = self.commands.lock().expect("Failed to lock commands mutex").remove(0).expect("command not found");
             let stdout = match child.stdout() {
                 Some(mut s) => {
@@ -142,16 +184,21 @@ pub mod mock {
                 },
                 None => Vec::new(),
             };
+            let mut stderr = Vec::new();
+            if let Some(mut s) = child.stderr() {
+                s.read_to_end(&mut stderr).await?;
+            }
             let status = child.wait().await?;
 
             Ok(Output {
                 status,
                 stdout,
-                stderr: Vec::new(),
+                stderr,
             })
         }
 
-        fn spawn(&self, _command: &mut Command) -> std::io::Result<Self::Child> {
+        fn spawn(&self, command: &mut Command) -> std::io::Result<Self::Child> {
+            self.record(command);
             Ok(self.commands.lock().expect("Failed to lock commands mutex").remove(0).expect("command not found"))
         }
     }
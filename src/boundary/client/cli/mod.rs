@@ -3,23 +3,29 @@ mod command_runner;
 use crate::boundary::client::cli::command_runner::Child;
 use crate::boundary::client::cli::command_runner::{CommandRunner, DefaultCommandRunner};
 use crate::boundary::client::response::{
-    AuthenticateResponse, ErrorResponse, ItemResponse, ListResponse,
+    AuthenticateResponse, ErrorResponse, HostSet, ItemResponse, ListResponse, TargetHostSources,
+    VersionResponse,
 };
-use crate::boundary::client::BoundaryConnectionHandle;
-use crate::boundary::models::{ConnectResponse, Target};
+use crate::boundary::client::{BoundaryConnectionHandle, SCOPE_LISTING_CONCURRENCY};
+use crate::boundary::models::{ConnectResponse, Host, Target, UserSessions};
 use crate::boundary::Error::CliError;
 use crate::boundary::{ApiClient, Error, Scope, Session};
+use futures::StreamExt;
 use log::debug;
 use semver::Version;
-use serde::de::IgnoredAny;
+use serde::de::{DeserializeOwned, IgnoredAny};
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::net::TcpListener;
+use std::path::PathBuf;
 use std::process::{Output, Stdio};
-use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
 use tokio::sync::OnceCell;
 
 const CONNECT_TIMEOUT_MS: i32 = 5000;
+/// Number of characters of an unparsable response included in the error.
+const RESPONSE_SNIPPET_LEN: usize = 300;
 
 /// Parse the Boundary CLI version from the `boundary version` command output.
 /// Extracts the version string from "Version Number: X.Y.Z" format.
@@ -33,11 +39,27 @@ fn parse_boundary_version(output: &str) -> Result<Version, String> {
     Err("Version Number line not found in output".to_string())
 }
 
+// The password collected by bountui is passed to boundary in this env var, so it is neither
+// visible in the process list nor recorded with the command line
+const PASSWORD_ENV_VAR: &str = "BOUNTUI_BOUNDARY_PASSWORD";
+const PASSWORD_ENV_REF: &str = "env://BOUNTUI_BOUNDARY_PASSWORD";
+// The auth token is passed to every command in this env var instead of setting it for bountui
+const TOKEN_ENV_VAR: &str = "BOUNDARY_TOKEN";
+
 #[derive(Clone)]
 pub struct CliClient<R> {
     bin_path: String,
     command_runner: R,
     cached_version: Arc<OnceCell<Result<Version, String>>>,
+    // The last command run, only recorded if enabled with `with_command_recording`
+    last_command: Option<Arc<Mutex<Option<String>>>>,
+    // Passed to `boundary authenticate` if set, otherwise the primary auth method is used
+    auth_method_id: Option<String>,
+    login_name: Option<String>,
+    // Whether `connect` binds the port first to fail early if it is in use
+    check_port: bool,
+    // Set by authenticating or `set_token`, shared by the clones of the client
+    token: Arc<Mutex<Option<String>>>,
 }
 
 impl Default for CliClient<DefaultCommandRunner> {
@@ -46,25 +68,138 @@ impl Default for CliClient<DefaultCommandRunner> {
             bin_path: "boundary".to_string(),
             command_runner: DefaultCommandRunner,
             cached_version: Arc::new(OnceCell::new()),
+            last_command: None,
+            auth_method_id: None,
+            login_name: None,
+            check_port: true,
+            token: Arc::new(Mutex::new(None)),
         }
     }
 }
 
+/// Formats the command like it would be typed into a shell, quoting arguments where needed.
+fn command_line(command: &tokio::process::Command) -> String {
+    let command = command.as_std();
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|arg| {
+            let arg = arg.to_string_lossy();
+            if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:=@,".contains(c)) {
+                arg.to_string()
+            } else {
+                format!("'{}'", arg.replace('\'', "'\\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether `boundary connect` failed because it could not listen on the port.
+fn is_bind_failure(error: &Error) -> bool {
+    let message = match error {
+        Error::ApiError(_, message) | Error::CliError(_, message) => message.to_lowercase(),
+        _ => return false,
+    };
+    message.contains("address already in use")
+        || message.contains("only one usage of each socket address")
+}
+
+/// Turns a response that does not match the expected shape into an error that shows
+/// the beginning of the raw JSON, so users can report what their Boundary version returns.
+fn unexpected_response(json: &[u8], error: serde_json::Error) -> Error {
+    let response_text = String::from_utf8_lossy(json);
+    let snippet: String = response_text.trim().chars().take(RESPONSE_SNIPPET_LEN).collect();
+    Error::UnexpectedResponse(error.to_string(), snippet)
+}
+
 impl<R> CliClient<R> {
-    fn parse_success_response<'a, T: Deserialize<'a>>(
-        &self,
-        json: &'a [u8],
-    ) -> Result<T, serde_json::Error> {
+    /// Records every command run, the last one is returned by `last_command`. The auth token is
+    /// passed in the environment and thus never recorded.
+    pub fn with_command_recording(mut self) -> Self {
+        self.last_command = Some(Arc::new(Mutex::new(None)));
+        self
+    }
+
+    /// Authenticates with the auth method with this id instead of the primary one of the global
+    /// scope, e.g. if there are several.
+    pub fn with_auth_method_id(mut self, auth_method_id: Option<String>) -> Self {
+        self.auth_method_id = auth_method_id;
+        self
+    }
+
+    /// Passes the login name to `boundary authenticate`, so only the password is asked for.
+    pub fn with_login_name(mut self, login_name: Option<String>) -> Self {
+        self.login_name = login_name;
+        self
+    }
+
+    /// Skips binding the port before `boundary connect` if disabled, for systems where that check
+    /// is wrong. A port in use is then told from the bind error of boundary.
+    pub fn with_port_check(mut self, check_port: bool) -> Self {
+        self.check_port = check_port;
+        self
+    }
+
+    fn authenticate_args(&self) -> Vec<&str> {
+        let mut args = vec!["authenticate"];
+        if let Some(auth_method_id) = &self.auth_method_id {
+            args.extend(["-auth-method-id", auth_method_id]);
+        }
+        if let Some(login_name) = &self.login_name {
+            args.extend(["-login-name", login_name]);
+        }
+        args.extend(["-format", "json"]);
+        args
+    }
+
+    fn password_authenticate_args<'a>(&'a self, login_name: &'a str) -> Vec<&'a str> {
+        let method = match &self.auth_method_id {
+            Some(id) if id.starts_with("amldap_") => "ldap",
+            _ => "password",
+        };
+        let mut args = vec!["authenticate", method];
+        if let Some(auth_method_id) = &self.auth_method_id {
+            args.extend(["-auth-method-id", auth_method_id]);
+        }
+        args.extend(["-login-name", login_name, "-password", PASSWORD_ENV_REF, "-format", "json"]);
+        args
+    }
+
+    fn record(&self, command: &tokio::process::Command) {
+        if let Some(last_command) = &self.last_command {
+            let line = command_line(command);
+            debug!("Running {}", line);
+            *last_command.lock().unwrap() = Some(line);
+        }
+    }
+
+    /// Resolves the boundary binary like the shell would, `None` if it cannot be found.
+    pub fn resolve_bin_path(&self) -> Option<PathBuf> {
+        let bin_path = PathBuf::from(&self.bin_path);
+        if bin_path.components().count() > 1 {
+            return bin_path.is_file().then_some(bin_path);
+        }
+        let file_name = if cfg!(target_os = "windows") {
+            format!("{}.exe", self.bin_path)
+        } else {
+            self.bin_path.clone()
+        };
+        std::env::split_paths(&std::env::var_os("PATH")?)
+            .map(|dir| dir.join(&file_name))
+            .find(|path| path.is_file())
+    }
+
+    fn parse_success_response<'a, T: Deserialize<'a>>(&self, json: &'a [u8]) -> Result<T, Error> {
         let response_text = String::from_utf8_lossy(json);
         debug!("Response: {}", response_text);
-        let response = serde_json::from_slice(json)?;
-        Ok(response)
+        serde_json::from_slice(json).map_err(|e| unexpected_response(json, e))
     }
 
-    fn parse_error_response(&self, json: &[u8]) -> Result<Error, serde_json::Error> {
+    fn parse_error_response(&self, json: &[u8]) -> Result<Error, Error> {
         let response_text = String::from_utf8_lossy(json);
         debug!("Response: {}", response_text);
-        let response: ErrorResponse = serde_json::from_slice(json)?;
+        let response: ErrorResponse =
+            serde_json::from_slice(json).map_err(|e| unexpected_response(json, e))?;
         Ok(Error::ApiError(
             response.status_code,
             response.api_error.message,
@@ -94,12 +229,39 @@ impl<R> CliClient<R>
 where
     R: CommandRunner + Send + Sync + 'static,
 {
+    async fn output(&self, command: &mut tokio::process::Command) -> std::io::Result<Output> {
+        self.record(command);
+        self.pass_token(command);
+        self.command_runner.output(command).await
+    }
+
+    fn spawn(&self, command: &mut tokio::process::Command) -> std::io::Result<R::Child> {
+        self.record(command);
+        self.pass_token(command);
+        self.command_runner.spawn(command)
+    }
+
+    fn pass_token(&self, command: &mut tokio::process::Command) {
+        if let Some(token) = self.token.lock().unwrap().as_ref() {
+            command.env(TOKEN_ENV_VAR, token);
+        }
+    }
+
+    fn authenticated(
+        &self,
+        result: Result<ItemResponse<AuthenticateResponse>, Error>,
+    ) -> Result<AuthenticateResponse, Error> {
+        let response = result?.item;
+        *self.token.lock().unwrap() = Some(response.attributes.token.clone());
+        Ok(response)
+    }
+
     async fn get_version(&self) -> Result<Version, Error> {
         self.cached_version
             .get_or_init(|| async {
                 let mut command = tokio::process::Command::new(&self.bin_path);
                 command.arg("version");
-                match self.command_runner.output(&mut command).await {
+                match self.output(&mut command).await {
                     Ok(output) if output.status.success() => {
                         let stdout = String::from_utf8_lossy(&output.stdout);
                         parse_boundary_version(&stdout).map_err(|e| e.to_string())
@@ -116,6 +278,38 @@ where
             .clone()
             .map_err(Error::VersionParseError)
     }
+
+    /// Runs boundary with the arguments and parses its JSON output.
+    async fn run_json<T: DeserializeOwned>(&self, args: Vec<&str>) -> Result<T, Error> {
+        let mut command = tokio::process::Command::new(&self.bin_path);
+        let configured_command = command.args(&args);
+        let output = self.output(configured_command).await?;
+        self.get_result_from_output(&output)
+    }
+
+    /// Reads why `boundary connect` exited without a response from its error output.
+    async fn connect_error(&self, child: &mut R::Child) -> Error
+    where
+        <R::Child as Child>::Stderr: Unpin,
+    {
+        let mut stderr = Vec::new();
+        if let Some(mut s) = child.stderr() {
+            if let Err(e) = s.read_to_end(&mut stderr).await {
+                debug!("Failed to read the error output of boundary connect: {}", e);
+            }
+        }
+        if stderr.iter().all(u8::is_ascii_whitespace) {
+            return CliError(None, "No response from boundary".to_string());
+        }
+        self.parse_error_response(&stderr).unwrap_or_else(|_| {
+            CliError(None, String::from_utf8_lossy(&stderr).trim().to_string())
+        })
+    }
+
+    /// Returns the version information reported by `boundary version -format json`.
+    pub async fn boundary_version(&self) -> Result<VersionResponse, Error> {
+        self.run_json(vec!["version", "-format", "json"]).await
+    }
 }
 
 impl<R> ApiClient for CliClient<R>
@@ -123,6 +317,7 @@ where
     R: CommandRunner + Send + Sync + 'static,
     R::Child: BoundaryConnectionHandle + Send + Sync + 'static,
     <<R as CommandRunner>::Child as Child>::Stdout: Unpin + Send + Sync + 'static,
+    <<R as CommandRunner>::Child as Child>::Stderr: Unpin + Send + Sync + 'static,
 {
     type ConnectionHandle = R::Child;
 
@@ -137,7 +332,7 @@ where
         }
         let mut command = tokio::process::Command::new(&self.bin_path);
         let configured_command = command.args(&args);
-        let output = self.command_runner.output(configured_command).await?;
+        let output = self.output(configured_command).await?;
         let response = self.get_result_from_output(&output);
         response.map(|r: ListResponse<Scope>| r.items.unwrap_or_default())
     }
@@ -155,7 +350,7 @@ where
         }
         let mut command = tokio::process::Command::new(&self.bin_path);
         let configured_command = command.args(&args);
-        let output = self.command_runner.output(configured_command).await?;
+        let output = self.output(configured_command).await?;
         let result = self.get_result_from_output(&output);
         result.map(|r: ListResponse<Target>| r.items.unwrap_or_default())
     }
@@ -164,53 +359,115 @@ where
         let args = vec!["sessions", "list", "-scope-id", scope, "-format", "json"];
         let mut command = tokio::process::Command::new(&self.bin_path);
         let configured_command = command.args(&args);
-        let output = self.command_runner.output(configured_command).await?;
+        let output = self.output(configured_command).await?;
         let result = self.get_result_from_output(&output);
         result.map(|r: ListResponse<Session>| r.items.unwrap_or_default())
     }
 
-    async fn get_user_sessions(&self, user_id: &str) -> Result<Vec<Session>, Error> {
+    async fn get_scope(&self, id: &str) -> Result<Scope, Error> {
+        let response: ItemResponse<Scope> = self
+            .run_json(vec!["scopes", "read", "-id", id, "-format", "json"])
+            .await?;
+        Ok(response.item)
+    }
+
+    async fn get_target(&self, id: &str) -> Result<Target, Error> {
+        let response: ItemResponse<Target> = self
+            .run_json(vec!["targets", "read", "-id", id, "-format", "json"])
+            .await?;
+        Ok(response.item)
+    }
+
+    async fn get_session(&self, id: &str) -> Result<Session, Error> {
+        let response: ItemResponse<Session> = self
+            .run_json(vec!["sessions", "read", "-id", id, "-format", "json"])
+            .await?;
+        Ok(response.item)
+    }
+
+    async fn get_user_sessions(&self, user_id: &str) -> Result<UserSessions, Error> {
         let scopes = self
             .get_scopes(None, true)
             .await?
             .into_iter()
-            .filter(|s| {
-                s.authorized_collection_actions
-                    .get("sessions")
-                    .map(|action| action.contains(&"list".to_string()))
-                    .unwrap_or(false)
-            })
+            .filter(|s| s.can_list_sessions())
             .collect::<Vec<_>>();
-        let results = futures::future::join_all(scopes.iter().map(|scope| {
-            let scope_id = &scope.id;
-            self.get_sessions(scope_id)
-        }))
-        .await;
-        let mut sessions = Vec::new();
-        for result in results {
+        let listings: Vec<_> = scopes
+            .iter()
+            .map(|scope| async move { (scope, self.get_sessions(&scope.id).await) })
+            .collect();
+        let results: Vec<(&Scope, Result<Vec<Session>, Error>)> = futures::stream::iter(listings)
+            .buffer_unordered(SCOPE_LISTING_CONCURRENCY)
+            .collect()
+            .await;
+        // A failing scope must not hide the sessions of the others
+        let mut user_sessions = UserSessions::default();
+        let mut listed_scopes = 0;
+        for (scope, result) in results {
             match result {
                 Ok(session_list) => {
-                    sessions.append(
-                        &mut session_list
-                            .into_iter()
-                            .filter(|s| s.user_id == user_id)
-                            .collect::<Vec<_>>(),
-                    );
+                    listed_scopes += 1;
+                    user_sessions
+                        .sessions
+                        .extend(session_list.into_iter().filter(|s| s.user_id == user_id));
                 }
-                Err(e) => return Err(e),
+                Err(e) => user_sessions.failed_scopes.push((scope.id.clone(), e)),
             }
         }
-        Ok(sessions)
+        if listed_scopes == 0 && !user_sessions.failed_scopes.is_empty() {
+            return Err(user_sessions.failed_scopes.remove(0).1);
+        }
+        Ok(user_sessions)
+    }
+
+    async fn get_target_hosts(&self, target_id: &str) -> Result<Vec<Host>, Error> {
+        let target: ItemResponse<TargetHostSources> = self
+            .run_json(vec!["targets", "read", "-id", target_id, "-format", "json"])
+            .await?;
+        let host_sets: Vec<ItemResponse<HostSet>> = futures::future::try_join_all(
+            target.item.host_sources.iter().map(|source| {
+                self.run_json(vec!["host-sets", "read", "-id", &source.id, "-format", "json"])
+            }),
+        )
+        .await?;
+        // Hosts are listed per catalog, a set only names the ids of its hosts
+        let mut host_ids_by_catalog: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for host_set in &host_sets {
+            host_ids_by_catalog
+                .entry(&host_set.item.host_catalog_id)
+                .or_default()
+                .extend(host_set.item.host_ids.iter().map(String::as_str));
+        }
+        let catalogs: Vec<ListResponse<Host>> = futures::future::try_join_all(
+            host_ids_by_catalog.keys().map(|catalog_id| {
+                self.run_json(vec!["hosts", "list", "-host-catalog-id", catalog_id, "-format", "json"])
+            }),
+        )
+        .await?;
+        Ok(host_ids_by_catalog
+            .values()
+            .zip(catalogs)
+            .flat_map(|(host_ids, catalog)| {
+                catalog
+                    .items
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(move |h| host_ids.contains(&h.id.as_str()))
+            })
+            .collect())
     }
 
     async fn connect(
         &self,
         target_id: &str,
+        host_id: Option<&str>,
         port: u16,
     ) -> Result<(ConnectResponse, R::Child), Error> {
         // Check if the port is available
-        TcpListener::bind(format!("127.0.0.1:{port}"))
-            .map_err(|_| Error::PortNotAvailable(port))?;
+        if self.check_port {
+            TcpListener::bind(format!("127.0.0.1:{port}"))
+                .map_err(|_| Error::PortNotAvailable(port))?;
+        }
 
         let port_str = port.to_string();
         let mut args = vec![
@@ -222,6 +479,10 @@ where
             "-format",
             "json",
         ];
+        if let Some(host_id) = host_id {
+            args.push("-host-id");
+            args.push(host_id);
+        }
 
         let version = self.get_version().await?;
         if version >= Version::new(0, 21, 0) {
@@ -234,7 +495,7 @@ where
             .args(&args)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
-        let mut child = self.command_runner.spawn(configured_command)?;
+        let mut child = self.spawn(configured_command)?;
 
         let stdout = child
             .stdout()
@@ -249,11 +510,16 @@ where
         )
             .await;
 
-        let response = a
-            .map_err(|_e| Error::ConnectTimeoutError)??
-            .ok_or(CliError(None, "No response from boundary".to_string()))?;
+        let Some(response) = a.map_err(|_e| Error::ConnectTimeoutError)?? else {
+            let error = self.connect_error(&mut child).await;
+            if is_bind_failure(&error) {
+                return Err(Error::PortNotAvailable(port));
+            }
+            return Err(error);
+        };
 
-        let response: ConnectResponse = serde_json::from_str(&response)?;
+        let response: ConnectResponse = serde_json::from_str(&response)
+            .map_err(|e| unexpected_response(response.as_bytes(), e))?;
 
         Ok((response, child))
     }
@@ -262,25 +528,48 @@ where
         let args = vec!["sessions", "cancel", "-id", session_id, "-format", "json"];
         let mut command = tokio::process::Command::new(&self.bin_path);
         let configured_command = command.args(&args);
-        let output = self.command_runner.output(configured_command).await?;
+        let output = self.output(configured_command).await?;
         let _: IgnoredAny = self.get_result_from_output(&output)?;
         Ok(())
     }
 
     async fn authenticate(&self) -> Result<AuthenticateResponse, Error> {
-        let args = vec!["authenticate", "-format", "json"];
+        let args = self.authenticate_args();
         let mut command = tokio::process::Command::new(&self.bin_path);
-        let configured_command = command.args(&args);
-        let output = self.command_runner.output(configured_command).await?;
-        let result = self.get_result_from_output(&output);
-        result.map(|auth_resp: ItemResponse<AuthenticateResponse>| auth_resp.item)
+        // A prompt for credentials fails right away instead of waiting for input that never comes
+        let configured_command = command.args(&args).stdin(Stdio::null());
+        let output = self.output(configured_command).await?;
+        self.authenticated(self.get_result_from_output(&output))
+    }
+
+    async fn authenticate_with_password(
+        &self,
+        login_name: &str,
+        password: &str,
+    ) -> Result<AuthenticateResponse, Error> {
+        let args = self.password_authenticate_args(login_name);
+        let mut command = tokio::process::Command::new(&self.bin_path);
+        let configured_command = command
+            .args(&args)
+            .env(PASSWORD_ENV_VAR, password)
+            .stdin(Stdio::null());
+        let output = self.output(configured_command).await?;
+        self.authenticated(self.get_result_from_output(&output))
+    }
+
+    fn set_token(&self, token: &str) {
+        *self.token.lock().unwrap() = Some(token.to_string());
+    }
+
+    fn last_command(&self) -> Option<String> {
+        self.last_command.as_ref()?.lock().unwrap().clone()
     }
 
     async fn validate_token(&self, token_id: &str) -> Result<(), Error> {
         let args = vec!["auth-tokens", "read", "-id", token_id, "-format", "json"];
         let mut command = tokio::process::Command::new(&self.bin_path);
         let configured_command = command.args(&args);
-        let output = self.command_runner.output(configured_command).await?;
+        let output = self.output(configured_command).await?;
         let _: IgnoredAny = self.get_result_from_output(&output)?;
         Ok(())
     }
@@ -290,6 +579,7 @@ where
 mod test {
     use crate::boundary::client::cli::command_runner::mock::{MockChild, MockCommandRunner};
     use crate::boundary::client::response::ListResponse;
+    use super::command_line;
     use crate::boundary::{ApiClient, CliClient, ConnectResponse, Error, Scope};
     use chrono::{TimeDelta, Utc};
     use std::net::TcpListener;
@@ -315,14 +605,214 @@ mod test {
         let mock_result = MockChild::new(Ok(0), Some(std_out));
         let command_runner = MockCommandRunner::new(vec![mock_result].into());
 
-        let client = CliClient {
+        let client = client_with_runner(command_runner);
+
+        let scopes = client.get_scopes(None, false).await.unwrap();
+        assert_eq!(scopes, response.items.unwrap());
+    }
+
+    fn scope_with_sessions(id: &str) -> Scope {
+        Scope::builder()
+            .name(id.to_string())
+            .id(id.to_string())
+            .description("".to_string())
+            .type_name("project".to_string())
+            .authorized_collection_actions(std::collections::HashMap::from([(
+                "sessions".to_string(),
+                vec!["list".to_string()],
+            )]))
+            .build()
+    }
+
+    fn json_child<T: serde::Serialize>(value: &T) -> MockChild {
+        let json = serde_json::to_string(value).unwrap();
+        MockChild::new(Ok(0), Some(Builder::new().read(json.as_bytes()).build()))
+    }
+
+    fn sessions_child(session_id: &str, user_id: &str) -> MockChild {
+        let json = format!(
+            r#"{{"items":[{{"id":"{session_id}","target_id":"ttcp_1","type":"tcp","created_time":"2025-01-01T00:00:00Z","status":"active","user_id":"{user_id}"}}]}}"#
+        );
+        MockChild::new(Ok(0), Some(Builder::new().read(json.as_bytes()).build()))
+    }
+
+    fn failing_child() -> MockChild {
+        MockChild::new(Ok(2 << 8), None)
+    }
+
+    pub(super) fn client_with_runner(command_runner: MockCommandRunner) -> CliClient<MockCommandRunner> {
+        CliClient {
             bin_path: "boundary".to_string(),
             command_runner,
             cached_version: Arc::new(tokio::sync::OnceCell::new()),
+            last_command: None,
+            auth_method_id: None,
+            login_name: None,
+            check_port: true,
+            token: Arc::new(std::sync::Mutex::new(None)),
+        }
+    }
+
+    fn client_with(children: Vec<MockChild>) -> CliClient<MockCommandRunner> {
+        client_with_runner(MockCommandRunner::new(children.into()))
+    }
+
+    #[tokio::test]
+    async fn test_recorded_command_matches_the_executed_one() {
+        let scopes = ListResponse {
+            items: Some(vec![scope_with_sessions("p_1")]),
         };
+        let client = client_with(vec![json_child(&scopes), json_child(&scopes)]);
+        assert_eq!(client.last_command(), None);
+        client.get_scopes(Some("o_1"), true).await.unwrap();
+        assert_eq!(client.last_command(), None, "Commands are only recorded if enabled");
 
-        let scopes = client.get_scopes(None, false).await.unwrap();
-        assert_eq!(scopes, response.items.unwrap());
+        let client = client.with_command_recording();
+        client.get_scopes(Some("o_1"), true).await.unwrap();
+
+        let executed = client.command_runner.executed.lock().unwrap().last().unwrap().join(" ");
+        assert_eq!(
+            executed,
+            "boundary scopes list -format json -scope-id o_1 -recursive"
+        );
+        assert_eq!(client.last_command(), Some(executed));
+    }
+
+    #[tokio::test]
+    async fn test_get_session_reads_the_session_by_id() {
+        let json = r#"{"item":{"id":"s_1","target_id":"ttcp_1","type":"tcp","created_time":"2025-01-01T00:00:00Z","status":"active"}}"#;
+        let client = client_with(vec![MockChild::new(
+            Ok(0),
+            Some(Builder::new().read(json.as_bytes()).build()),
+        )]);
+
+        let session = client.get_session("s_1").await.unwrap();
+
+        assert_eq!(session.target_id, "ttcp_1");
+        let executed = client.command_runner.executed.lock().unwrap().last().unwrap().join(" ");
+        assert_eq!(executed, "boundary sessions read -id s_1 -format json");
+    }
+
+    fn authenticate_child() -> MockChild {
+        let json = r#"{"item":{"attributes":{"id":"at_1","user_id":"u_1","token":"secret","expiration_time":"2030-01-01T00:00:00Z"}}}"#;
+        MockChild::new(Ok(0), Some(Builder::new().read(json.as_bytes()).build()))
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_uses_the_primary_auth_method_by_default() {
+        let client = client_with(vec![authenticate_child()]);
+        let response = client.authenticate().await.unwrap();
+        assert_eq!(response.attributes.user_id, "u_1");
+        let executed = client.command_runner.executed.lock().unwrap().last().unwrap().join(" ");
+        assert_eq!(executed, "boundary authenticate -format json");
+    }
+
+    #[tokio::test]
+    async fn test_commands_run_with_the_token_of_the_last_authentication() {
+        let scopes = ListResponse {
+            items: Some(vec![scope_with_sessions("p_1")]),
+        };
+        let client = client_with(vec![json_child(&scopes), authenticate_child(), json_child(&scopes)])
+            .with_command_recording();
+        let token = |index: usize| {
+            client.command_runner.envs.lock().unwrap()[index]
+                .iter()
+                .find(|(key, _)| key == "BOUNDARY_TOKEN")
+                .map(|(_, value)| value.clone())
+        };
+
+        client.get_scopes(None, false).await.unwrap();
+        assert_eq!(token(0), None);
+        client.authenticate().await.unwrap();
+        client.get_scopes(None, false).await.unwrap();
+        assert_eq!(token(2), Some("secret".to_string()));
+        assert!(!client.last_command().unwrap().contains("secret"));
+
+        client.set_token("restored");
+        assert_eq!(client.token.lock().unwrap().as_deref(), Some("restored"));
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_passes_the_configured_auth_method_and_login_name() {
+        let client = client_with(vec![authenticate_child()])
+            .with_auth_method_id(Some("ampw_1234567890".to_string()))
+            .with_login_name(Some("jane".to_string()));
+        client.authenticate().await.unwrap();
+        let executed = client.command_runner.executed.lock().unwrap().last().unwrap().join(" ");
+        assert_eq!(
+            executed,
+            "boundary authenticate -auth-method-id ampw_1234567890 -login-name jane -format json"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_with_password_passes_the_password_in_the_environment() {
+        let client = client_with(vec![authenticate_child()])
+            .with_auth_method_id(Some("ampw_1234567890".to_string()))
+            .with_command_recording();
+        let response = client.authenticate_with_password("jane", "s3cret").await.unwrap();
+        assert_eq!(response.attributes.user_id, "u_1");
+        let executed = client.command_runner.executed.lock().unwrap().last().unwrap().join(" ");
+        assert_eq!(
+            executed,
+            "boundary authenticate password -auth-method-id ampw_1234567890 -login-name jane \
+             -password env://BOUNTUI_BOUNDARY_PASSWORD -format json"
+        );
+        assert!(!client.last_command().unwrap().contains("s3cret"));
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_with_password_uses_ldap_for_ldap_auth_methods() {
+        let client = client_with(vec![authenticate_child()])
+            .with_auth_method_id(Some("amldap_1234567890".to_string()));
+        client.authenticate_with_password("jane", "s3cret").await.unwrap();
+        let executed = client.command_runner.executed.lock().unwrap().last().unwrap().clone();
+        assert_eq!(executed[1..3], ["authenticate", "ldap"]);
+    }
+
+    #[test]
+    fn test_command_line_quotes_arguments_for_the_shell() {
+        let mut command = tokio::process::Command::new("boundary");
+        command.args(["targets", "list", "-filter", "\"/item/name\" == \"it's\"", ""]);
+        assert_eq!(
+            command_line(&command),
+            r#"boundary targets list -filter '"/item/name" == "it'\''s"' ''"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_user_sessions_keeps_sessions_of_working_scopes() {
+        let scopes = ListResponse {
+            items: Some(vec![
+                scope_with_sessions("p_1"),
+                scope_with_sessions("p_2"),
+                scope_with_sessions("p_3"),
+            ]),
+        };
+        let client = client_with(vec![
+            json_child(&scopes),
+            sessions_child("s_1", "u_1"),
+            failing_child(),
+            sessions_child("s_3", "u_1"),
+        ]);
+
+        let user_sessions = client.get_user_sessions("u_1").await.unwrap();
+        let session_ids: Vec<&str> = user_sessions.sessions.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(session_ids, vec!["s_1", "s_3"]);
+        assert_eq!(user_sessions.failed_scopes.len(), 1);
+        assert_eq!(user_sessions.failed_scopes[0].0, "p_2");
+        assert_eq!(user_sessions.failed_scopes[0].1.short_reason(), "exit code 2");
+    }
+
+    #[tokio::test]
+    async fn test_get_user_sessions_fails_if_no_scope_works() {
+        let scopes = ListResponse {
+            items: Some(vec![scope_with_sessions("p_1"), scope_with_sessions("p_2")]),
+        };
+        let client = client_with(vec![json_child(&scopes), failing_child(), failing_child()]);
+
+        let result = client.get_user_sessions("u_1").await;
+        assert!(matches!(result, Err(Error::CliError(..))));
     }
 
     #[tokio::test]
@@ -349,21 +839,17 @@ mod test {
                 .into(),
         );
 
-        let sut = CliClient {
-            bin_path: "boundary".to_string(),
-            command_runner,
-            cached_version: Arc::new(tokio::sync::OnceCell::new()),
-        };
+        let sut = client_with_runner(command_runner);
 
         let tcp_listener = TcpListener::bind("127.0.0.1:0").unwrap();
         let port = tcp_listener.local_addr().unwrap().port();
-        let response = sut.connect("target_id", port).await;
+        let response = sut.connect("target_id", None, port).await;
         assert!(
             matches!(response, Err(Error::PortNotAvailable(p)) if p == port),
             "connect did not return PortNotAvailable error while the port is already in use"
         );
         drop(tcp_listener);
-        let result = sut.connect("target_id", port).await;
+        let result = sut.connect("target_id", None, port).await;
         assert_ok!(&result, "connect should return Ok");
         let (response, _) = result.unwrap();
         assert_eq!(
@@ -372,6 +858,88 @@ mod test {
         );
     }
 
+    fn text_child(json: &str) -> MockChild {
+        MockChild::new(Ok(0), Some(Builder::new().read(json.as_bytes()).build()))
+    }
+
+    #[tokio::test]
+    async fn test_get_target_hosts_lists_the_hosts_of_the_host_sets() {
+        let client = client_with(vec![
+            text_child(r#"{"item":{"id":"ttcp_1","host_sources":[{"id":"hsst_1","host_catalog_id":"hcst_1"}]}}"#),
+            text_child(r#"{"item":{"id":"hsst_1","host_catalog_id":"hcst_1","host_ids":["hst_1","hst_2"]}}"#),
+            text_child(
+                r#"{"items":[
+                    {"id":"hst_1","name":"web 1","type":"static","attributes":{"address":"10.0.0.1"}},
+                    {"id":"hst_2","name":"web 2","type":"plugin","ip_addresses":["10.0.0.2","10.0.0.3"]},
+                    {"id":"hst_3","name":"db","type":"static","attributes":{"address":"10.0.0.4"}}
+                ]}"#,
+            ),
+        ]);
+
+        let hosts = client.get_target_hosts("ttcp_1").await.unwrap();
+        let hosts: Vec<(&str, String)> = hosts.iter().map(|h| (h.id.as_str(), h.address())).collect();
+        assert_eq!(
+            hosts,
+            vec![
+                ("hst_1", "10.0.0.1".to_string()),
+                ("hst_2", "10.0.0.2, 10.0.0.3".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_target_hosts_of_target_without_host_sources() {
+        let client = client_with(vec![text_child(r#"{"item":{"id":"ttcp_1"}}"#)]);
+
+        assert_eq!(client.get_target_hosts("ttcp_1").await.unwrap(), vec![]);
+    }
+
+    #[tokio::test]
+    async fn test_connect_reports_the_error_output_of_boundary() {
+        let error_json =
+            r#"{"status_code":400,"api_error":{"message":"Multiple hosts found, specify one with -host-id"}}"#;
+        let client = client_with(vec![
+            text_child("Version Number: 0.20.0\n"),
+            MockChild::new(Ok(1 << 8), Some(Builder::new().build()))
+                .with_stderr(Builder::new().read(error_json.as_bytes()).build()),
+        ]);
+        let tcp_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = tcp_listener.local_addr().unwrap().port();
+        drop(tcp_listener);
+
+        let error = client.connect("ttcp_1", None, port).await.err().unwrap();
+        assert!(matches!(error, Error::ApiError(400, _)), "Unexpected error {error:?}");
+        assert!(error.needs_host_id());
+    }
+
+    #[tokio::test]
+    async fn test_connect_without_port_check_runs_boundary_on_a_port_in_use() {
+        let client = client_with(vec![
+            text_child("Version Number: 0.20.0\n"),
+            text_child(r#"{"session_id":"s_1","expiration":"2025-09-07T14:24:03Z"}"#),
+        ])
+        .with_port_check(false);
+        let tcp_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = tcp_listener.local_addr().unwrap().port();
+
+        let (response, _) = client.connect("ttcp_1", None, port).await.unwrap();
+        assert_eq!(response.session_id, "s_1");
+    }
+
+    #[tokio::test]
+    async fn test_connect_without_port_check_reports_the_bind_error_of_boundary() {
+        let bind_error = "Error starting listening port: listen tcp 127.0.0.1:5432: bind: address already in use";
+        let client = client_with(vec![
+            text_child("Version Number: 0.20.0\n"),
+            MockChild::new(Ok(1 << 8), Some(Builder::new().build()))
+                .with_stderr(Builder::new().read(bind_error.as_bytes()).build()),
+        ])
+        .with_port_check(false);
+
+        let error = client.connect("ttcp_1", None, 5432).await.err().unwrap();
+        assert!(matches!(error, Error::PortNotAvailable(5432)), "Unexpected error {error:?}");
+    }
+
     #[tokio::test]
     async fn test_cancel_session_success() {
         // JSON returned by boundary sessions cancel -format json
@@ -424,11 +992,7 @@ mod test {
         );
         let command_runner = MockCommandRunner::new(vec![child].into());
 
-        let client = CliClient {
-            bin_path: "boundary".to_string(),
-            command_runner,
-            cached_version: Arc::new(tokio::sync::OnceCell::new()),
-        };
+        let client = client_with_runner(command_runner);
 
         let result = client.cancel_session("id").await;
         assert_ok!(
@@ -459,17 +1023,13 @@ mod test {
         let command_runner =
             MockCommandRunner::new(vec![version_number_child, connect_child].into());
 
-        let sut = CliClient {
-            bin_path: "boundary".to_string(),
-            command_runner,
-            cached_version: Arc::new(tokio::sync::OnceCell::new()),
-        };
+        let sut = client_with_runner(command_runner);
 
         let tcp_listener = TcpListener::bind("127.0.0.1:0").unwrap();
         let port = tcp_listener.local_addr().unwrap().port();
         drop(tcp_listener);
 
-        let result = sut.connect("target_id", port).await;
+        let result = sut.connect("target_id", None, port).await;
         assert_ok!(&result, "connect should return Ok with version >= 0.21.0");
         let (response, _) = result.unwrap();
         assert_eq!(
@@ -478,15 +1038,164 @@ mod test {
         );
     }
 
+    fn client_with_stdout(json: &str) -> CliClient<MockCommandRunner> {
+        let child = MockChild::new(Ok(0), Some(Builder::new().read(json.as_bytes()).build()));
+        client_with(vec![child])
+    }
+
+    #[tokio::test]
+    async fn test_boundary_version() {
+        let response_json = r#"{
+  "build_date": "2025-01-23T16:40:11Z",
+  "revision": "d7f9f9a3c8c4b1f5f4d1c9e6a1b2c3d4e5f60718",
+  "version": "0.19.0"
+}"#;
+        let version = client_with_stdout(response_json)
+            .boundary_version()
+            .await
+            .unwrap();
+        assert_eq!(version.version, "0.19.0");
+        assert_eq!(version.revision, "d7f9f9a3c8c4b1f5f4d1c9e6a1b2c3d4e5f60718");
+        assert_eq!(version.build_date, "2025-01-23T16:40:11Z");
+    }
+
+    #[tokio::test]
+    async fn test_get_targets_with_omitted_fields() {
+        // Newer Boundary versions paginate lists and omit empty fields like the description
+        let response_json = r#"{
+   "items":[
+      {
+         "id":"ttcp_1234567890",
+         "scope_id":"p_1234567890",
+         "name":"database",
+         "created_time":"2025-09-07T06:24:03.179388Z",
+         "updated_time":"2025-09-07T06:24:03.179388Z",
+         "version":1,
+         "type":"tcp",
+         "session_max_seconds":28800,
+         "session_connection_limit":-1,
+         "authorized_actions":["read","authorize-session"]
+      }
+   ],
+   "response_type":"complete",
+   "list_token":"token",
+   "sort_by":"created_time",
+   "sort_dir":"desc",
+   "est_item_count":1
+}"#;
+        let targets = client_with_stdout(response_json)
+            .get_targets(Some("p_1234567890"))
+            .await
+            .unwrap();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].description, "");
+        assert_eq!(targets[0].attributes, None);
+        assert!(targets[0].can_connect());
+    }
+
+    #[tokio::test]
+    async fn test_get_scopes_without_items() {
+        // Boundary omits `items` when a list is empty
+        let response_json = r#"{"response_type":"complete","list_token":"token","est_item_count":0}"#;
+        let scopes = client_with_stdout(response_json)
+            .get_scopes(Some("o_1234567890"), false)
+            .await
+            .unwrap();
+        assert!(scopes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_sessions_without_authorized_actions() {
+        // Older Boundary versions do not return the authorized actions of sessions
+        let response_json = r#"{
+   "items":[
+      {
+         "id":"s_1234567890",
+         "target_id":"ttcp_1234567890",
+         "scope_id":"p_1234567890",
+         "created_time":"2025-09-07T06:24:03.179388Z",
+         "type":"tcp",
+         "status":"active",
+         "user_id":"u_1234567890"
+      }
+   ]
+}"#;
+        let sessions = client_with_stdout(response_json)
+            .get_sessions("p_1234567890")
+            .await
+            .unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert!(!sessions[0].can_cancel());
+        assert_eq!(sessions[0].expiration_time, None);
+        assert_eq!(sessions[0].user_id, "u_1234567890");
+    }
+
+    #[tokio::test]
+    async fn test_get_sessions_without_user_id() {
+        let response_json = r#"{
+   "items":[
+      {
+         "id":"s_1234567890",
+         "target_id":"ttcp_1234567890",
+         "scope_id":"p_1234567890",
+         "created_time":"2025-09-07T06:24:03.179388Z",
+         "type":"tcp",
+         "status":"active"
+      }
+   ]
+}"#;
+        let sessions = client_with_stdout(response_json)
+            .get_sessions("p_1234567890")
+            .await
+            .unwrap();
+        assert_eq!(sessions[0].user_id, "");
+    }
+
+    #[tokio::test]
+    async fn test_get_sessions_with_expiration_time() {
+        let response_json = r#"{
+   "items":[
+      {
+         "id":"s_1234567890",
+         "target_id":"ttcp_1234567890",
+         "scope_id":"p_1234567890",
+         "created_time":"2025-09-07T06:24:03.179388Z",
+         "expiration_time":"2025-09-07T14:24:03.179388Z",
+         "type":"tcp",
+         "status":"active",
+         "user_id":"u_1234567890"
+      }
+   ]
+}"#;
+        let sessions = client_with_stdout(response_json)
+            .get_sessions("p_1234567890")
+            .await
+            .unwrap();
+        assert_eq!(
+            sessions[0].expiration_time,
+            Some("2025-09-07T14:24:03.179388Z".parse().unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unexpected_response_contains_raw_json() {
+        let response_json = r#"{"items":{"id":"p_1234567890"}}"#;
+        let result = client_with_stdout(response_json).get_scopes(None, false).await;
+        match result {
+            Err(Error::UnexpectedResponse(_, snippet)) => assert_eq!(snippet, response_json),
+            other => panic!("expected UnexpectedResponse, got {other:?}"),
+        }
+    }
+
     mod parse_boundary_version_tests {
         use super::super::parse_boundary_version;
         use crate::boundary;
         use crate::boundary::client::cli::command_runner::mock::{MockChild, MockCommandRunner};
         use crate::boundary::client::cli::CONNECT_TIMEOUT_MS;
-        use crate::boundary::{ApiClient, CliClient};
+        use super::client_with_runner;
+        use crate::boundary::ApiClient;
         use semver::Version;
         use std::net::TcpListener;
-        use std::sync::Arc;
         use tokio_test::io::Builder;
 
         #[test]
@@ -556,17 +1265,13 @@ mod test {
                     .into(),
             );
 
-            let sut = CliClient {
-                bin_path: "boundary".to_string(),
-                command_runner,
-                cached_version: Arc::new(tokio::sync::OnceCell::new()),
-            };
+            let sut = client_with_runner(command_runner);
 
             let tcp_listener = TcpListener::bind("127.0.0.1:0").unwrap();
             let port = tcp_listener.local_addr().unwrap().port();
             drop(tcp_listener);
 
-            let result = sut.connect("target_id", port).await;
+            let result = sut.connect("target_id", None, port).await;
             match result {
                 Ok(_) => panic!("connect should have failed due to timeout, but it succeeded"),
                 Err(boundary::Error::ConnectTimeoutError { .. }) => {}
@@ -1,12 +1,13 @@
 use crate::boundary::client::response::{AuthenticateAttributes, AuthenticateResponse};
 use crate::boundary::{
-    ApiClient, BoundaryConnectionHandle, ConnectResponse, Error, Scope, Session, Target,
+    ApiClient, BoundaryConnectionHandle, ConnectResponse, CredentialEntry, Error, Host, Scope,
+    Session, Target, UserSessions,
 };
 use bon::Builder;
 use chrono::{Duration, Utc};
 use std::collections::HashMap;
 use std::future::Future;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::sync::{Mutex, Notify};
 
@@ -24,17 +25,49 @@ pub struct MockClient {
     authenticate_error_status: u16,
     #[builder(default = "denied".to_string())]
     authenticate_error_message: String,
+    /// If set `authenticate` fails asking for credentials, `authenticate_with_password` succeeds
+    /// with this password only.
+    password: Option<String>,
     #[builder(default)]
     validate_token_should_fail: bool,
     #[builder(default = 401)]
     validate_token_error_status: u16,
     scopes: HashMap<Option<String>, Vec<Scope>>,
+    /// Number of upcoming `get_scopes` calls that fail.
+    #[builder(default)]
+    get_scopes_failures: Arc<AtomicUsize>,
+    /// Status codes of the errors listing the child scopes of these scopes fails with.
+    #[builder(default)]
+    failing_scopes: HashMap<String, u16>,
     #[builder(default)]
     targets: HashMap<Option<String>, Vec<Target>>,
+    /// Number of upcoming `get_targets` calls that fail.
+    #[builder(default)]
+    get_targets_failures: Arc<AtomicUsize>,
+    /// Number of `get_targets` calls so far.
+    #[builder(default)]
+    get_targets_calls: Arc<AtomicUsize>,
     #[builder(default)]
     sessions: Arc<Mutex<HashMap<String, Vec<Session>>>>,
+    /// Hosts by target id, connecting to a target with several hosts requires a host id.
+    #[builder(default)]
+    target_hosts: HashMap<String, Vec<Host>>,
+    /// Host id of every successful `connect` call so far.
+    #[builder(default)]
+    connected_host_ids: Arc<Mutex<Vec<Option<String>>>>,
     #[builder(default)]
     connection_handles: Arc<Mutex<HashMap<String, MockConnectionHandle>>>,
+    /// Number of upcoming `cancel_session` calls that fail.
+    #[builder(default)]
+    cancel_session_failures: Arc<AtomicUsize>,
+    /// Ids of all sessions `cancel_session` succeeded for.
+    #[builder(default)]
+    canceled_sessions: Arc<Mutex<Vec<String>>>,
+    /// Returned by `last_command`, as if commands were recorded.
+    last_command: Option<String>,
+    /// Returned by every `connect` call.
+    #[builder(default)]
+    credentials: Vec<CredentialEntry>,
 }
 
 impl ApiClient for MockClient {
@@ -46,6 +79,12 @@ impl ApiClient for MockClient {
         recursive: bool,
     ) -> impl Future<Output = Result<Vec<Scope>, Error>> + Send {
         Box::pin(async move {
+            if Self::take_failure(&self.get_scopes_failures) {
+                return Err(Error::ApiError(500, "failed to list scopes".to_string()));
+            }
+            if let Some(status) = parent.and_then(|p| self.failing_scopes.get(p)) {
+                return Err(Error::ApiError(*status, "failed to list scopes".to_string()));
+            }
             let scopes = match parent {
                 Some(parent) => self
                     .scopes
@@ -60,6 +99,7 @@ impl ApiClient for MockClient {
                 let mut scopes_aac = Vec::new();
                 for scope in scopes {
                     let child_scopes = self.get_scopes(Some(&scope.id), true).await?;
+                    scopes_aac.push(scope);
                     scopes_aac.extend(child_scopes);
                 }
                 Ok(scopes_aac)
@@ -68,6 +108,10 @@ impl ApiClient for MockClient {
     }
 
     async fn get_targets(&self, scope: Option<&str>) -> Result<Vec<Target>, Error> {
+        self.get_targets_calls.fetch_add(1, Ordering::SeqCst);
+        if Self::take_failure(&self.get_targets_failures) {
+            return Err(Error::ApiError(500, "failed to list targets".to_string()));
+        }
         let targets = match scope {
             Some(scope) => self
                 .targets
@@ -89,8 +133,36 @@ impl ApiClient for MockClient {
             .unwrap_or_default())
     }
 
-    async fn get_user_sessions(&self, user_id: &str) -> Result<Vec<Session>, Error> {
-        let user_sessions = self
+    async fn get_scope(&self, id: &str) -> Result<Scope, Error> {
+        self.scopes
+            .values()
+            .flatten()
+            .find(|s| s.id == id)
+            .cloned()
+            .ok_or_else(|| Error::ApiError(404, format!("no scope with id: {}", id)))
+    }
+
+    async fn get_target(&self, id: &str) -> Result<Target, Error> {
+        self.get_all_targets()
+            .into_iter()
+            .find(|t| t.id == id)
+            .cloned()
+            .ok_or_else(|| Error::ApiError(404, format!("no target with id: {}", id)))
+    }
+
+    async fn get_session(&self, id: &str) -> Result<Session, Error> {
+        self.sessions
+            .lock()
+            .await
+            .values()
+            .flatten()
+            .find(|s| s.id == id)
+            .cloned()
+            .ok_or_else(|| Error::ApiError(404, format!("no session with id: {}", id)))
+    }
+
+    async fn get_user_sessions(&self, user_id: &str) -> Result<UserSessions, Error> {
+        let sessions = self
             .sessions
             .lock()
             .await
@@ -99,12 +171,20 @@ impl ApiClient for MockClient {
             .filter(|s| s.user_id == user_id)
             .cloned()
             .collect();
-        Ok(user_sessions)
+        Ok(UserSessions {
+            sessions,
+            failed_scopes: vec![],
+        })
+    }
+
+    async fn get_target_hosts(&self, target_id: &str) -> Result<Vec<Host>, Error> {
+        Ok(self.target_hosts.get(target_id).cloned().unwrap_or_default())
     }
 
     async fn connect(
         &self,
         target_id: &str,
+        host_id: Option<&str>,
         _port: u16,
     ) -> Result<(ConnectResponse, Self::ConnectionHandle), Error> {
         let all_targets = self.get_all_targets();
@@ -112,6 +192,23 @@ impl ApiClient for MockClient {
             .iter()
             .find(|t| t.id == target_id)
             .ok_or_else(|| Error::ApiError(404, format!("no target with id: {}", target_id)))?;
+        let hosts = self.target_hosts.get(target_id).cloned().unwrap_or_default();
+        match host_id {
+            None if hosts.len() > 1 => {
+                return Err(Error::ApiError(
+                    400,
+                    "the target has several hosts, pick one with -host-id".to_string(),
+                ))
+            }
+            Some(host_id) if !hosts.iter().any(|h| h.id == host_id) => {
+                return Err(Error::ApiError(404, format!("no host with id: {}", host_id)))
+            }
+            _ => {}
+        }
+        self.connected_host_ids
+            .lock()
+            .await
+            .push(host_id.map(str::to_string));
         let session_id = uuid::Uuid::new_v4();
         self.sessions
             .lock()
@@ -121,8 +218,10 @@ impl ApiClient for MockClient {
             .push(Session {
                 id: session_id.to_string(),
                 target_id: target_id.to_string(),
+                scope_id: target.scope_id.clone(),
                 session_type: "".to_string(),
                 created_time: Default::default(),
+                expiration_time: None,
                 status: "".to_string(),
                 authorized_actions: vec![],
                 user_id: "".to_string(),
@@ -136,7 +235,7 @@ impl ApiClient for MockClient {
 
         Ok((
             ConnectResponse {
-                credentials: vec![],
+                credentials: self.credentials.clone(),
                 session_id: session_id.to_string(),
                 expiration: Utc::now() + self.session_lifetime,
             },
@@ -145,7 +244,11 @@ impl ApiClient for MockClient {
     }
 
     async fn cancel_session(&self, session_id: &str) -> Result<(), Error> {
+        if Self::take_failure(&self.cancel_session_failures) {
+            return Err(Error::ApiError(403, "failed to cancel session".to_string()));
+        }
         self.sessions.lock().await.remove(session_id);
+        self.canceled_sessions.lock().await.push(session_id.to_string());
         Ok(())
     }
 
@@ -156,16 +259,24 @@ impl ApiClient for MockClient {
                 self.authenticate_error_message.clone(),
             ));
         }
+        if self.password.is_some() {
+            return Err(Error::CliError(
+                Some(2),
+                "Error reading password: inappropriate ioctl for device".to_string(),
+            ));
+        }
+        Ok(self.authenticate_response())
+    }
 
-        let token_id = uuid::Uuid::new_v4().to_string();
-        Ok(AuthenticateResponse {
-            attributes: AuthenticateAttributes {
-                id: token_id,
-                user_id: self.user_id.to_string(),
-                token: format!("token_for_{}", self.user_id),
-                expiration_time: Utc::now() + self.token_lifetime,
-            },
-        })
+    async fn authenticate_with_password(
+        &self,
+        _login_name: &str,
+        password: &str,
+    ) -> Result<AuthenticateResponse, Error> {
+        if self.password.as_deref() != Some(password) {
+            return Err(Error::ApiError(401, "Unauthenticated".to_string()));
+        }
+        Ok(self.authenticate_response())
     }
 
     async fn validate_token(&self, _token_id: &str) -> Result<(), Error> {
@@ -177,9 +288,50 @@ impl ApiClient for MockClient {
         }
         Ok(())
     }
+
+    // The mock accepts any command, with or without a token
+    fn set_token(&self, _token: &str) {}
+
+    fn last_command(&self) -> Option<String> {
+        self.last_command.clone()
+    }
 }
 
 impl MockClient {
+    fn authenticate_response(&self) -> AuthenticateResponse {
+        AuthenticateResponse {
+            attributes: AuthenticateAttributes {
+                id: uuid::Uuid::new_v4().to_string(),
+                user_id: self.user_id.to_string(),
+                token: format!("token_for_{}", self.user_id),
+                expiration_time: Utc::now() + self.token_lifetime,
+            },
+        }
+    }
+
+    fn take_failure(failures: &AtomicUsize) -> bool {
+        failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok()
+    }
+
+    pub async fn canceled_sessions(&self) -> Vec<String> {
+        self.canceled_sessions.lock().await.clone()
+    }
+
+    /// Changes the status of the session, e.g. to end it as if Boundary terminated it.
+    pub async fn set_session_status(&self, session_id: &str, status: &str) {
+        for session in self.sessions.lock().await.values_mut().flatten() {
+            if session.id == session_id {
+                session.status = status.to_string();
+            }
+        }
+    }
+
+    pub async fn connected_host_ids(&self) -> Vec<Option<String>> {
+        self.connected_host_ids.lock().await.clone()
+    }
+
     fn get_all_targets(&self) -> Vec<&Target> {
         self.targets.values().flatten().collect()
     }
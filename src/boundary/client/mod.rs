@@ -2,16 +2,24 @@ pub mod cli;
 #[cfg(test)]
 pub mod mock;
 pub mod response;
+pub mod scope_filter;
 
 use crate::boundary::client::response::AuthenticateResponse;
 use crate::boundary::error::Error;
-use crate::boundary::models::{ConnectResponse, SessionWithTarget, Target};
+use crate::boundary::models::{
+    ConnectResponse, Host, ScopeListing, SessionWithTarget, Target, UserSessions,
+};
 use crate::boundary::{Scope, Session};
 use std::fmt::{Debug, Display};
+use futures::StreamExt;
 use std::future::Future;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+/// How many scopes are listed at once when all scopes are walked, more at once overload the
+/// controller on large installations.
+pub const SCOPE_LISTING_CONCURRENCY: usize = 8;
+
 pub trait ApiClient {
     type ConnectionHandle: BoundaryConnectionHandle;
 
@@ -28,41 +36,75 @@ pub trait ApiClient {
     fn get_sessions(
         &self,
         scope: &str,
-    ) -> impl Future<Output = Result<Vec<Session>, Error>> + Send + Sync;
+    ) -> impl Future<Output = Result<Vec<Session>, Error>> + Send;
+
+    /// Reads a single scope by its id, fails with a 404 if there is none.
+    fn get_scope(&self, id: &str) -> impl Future<Output = Result<Scope, Error>> + Send;
+
+    /// Reads a single target by its id, fails with a 404 if there is none.
+    fn get_target(&self, id: &str) -> impl Future<Output = Result<Target, Error>> + Send;
+
+    /// Reads a single session by its id, fails with a 404 if there is none.
+    fn get_session(&self, id: &str) -> impl Future<Output = Result<Session, Error>> + Send;
 
-    #[warn(dead_code)]
+    /// Lists the sessions of the user in all scopes. Fails only if no scope could be listed.
     fn get_user_sessions(
         &self,
         user_id: &str,
-    ) -> impl Future<Output = Result<Vec<Session>, Error>> + Send + Sync;
+    ) -> impl Future<Output = Result<UserSessions, Error>> + Send;
+
+    /// Lists the hosts of the host sets of the target, one of them can be passed to `connect`.
+    fn get_target_hosts(
+        &self,
+        target_id: &str,
+    ) -> impl Future<Output = Result<Vec<Host>, Error>> + Send;
 
-    async fn connect(
+    /// Connects to the target, to the host with `host_id` if Boundary cannot pick one itself.
+    fn connect(
         &self,
         target_id: &str,
+        host_id: Option<&str>,
         port: u16,
-    ) -> Result<(ConnectResponse, Self::ConnectionHandle), Error>;
+    ) -> impl Future<Output = Result<(ConnectResponse, Self::ConnectionHandle), Error>> + Send;
 
-    async fn cancel_session(&self, session_id: &str) -> Result<(), Error>;
+    fn cancel_session(&self, session_id: &str) -> impl Future<Output = Result<(), Error>> + Send;
 
     fn authenticate(&self) -> impl Future<Output = Result<AuthenticateResponse, Error>> + Send;
 
+    /// Authenticates with the login name and password collected by bountui, for when
+    /// `authenticate` needs credentials it cannot prompt for.
+    fn authenticate_with_password(
+        &self,
+        login_name: &str,
+        password: &str,
+    ) -> impl Future<Output = Result<AuthenticateResponse, Error>> + Send;
+
     /// Validate a cached auth token by its ID against the Boundary API.
     /// Returns `Ok(())` if the token is still valid, `Err` if it's expired/revoked.
     fn validate_token(&self, token_id: &str) -> impl Future<Output=Result<(), Error>> + Send;
+
+    /// Runs the commands from now on with the token, e.g. one restored from the auth cache.
+    /// Authenticating sets the token it got as well.
+    fn set_token(&self, token: &str);
+
+    /// The last boundary command run, `None` if nothing was run yet or commands are not recorded.
+    fn last_command(&self) -> Option<String> {
+        None
+    }
 }
 
 pub trait ApiClientExt: ApiClient + Sync {
     fn combine_sessions_with_target(
         sessions: Vec<Session>,
-        targets: Vec<Target>,
+        targets: &[Target],
     ) -> Vec<SessionWithTarget> {
+        // Sessions of targets that are not listed anymore are kept, they still count
         sessions
             .into_iter()
             .map(|s| {
                 let target = targets.iter().find(|t| s.target_id == t.id).cloned();
-                target.map(|t| SessionWithTarget::new(s, t))
+                SessionWithTarget::new(s, target)
             })
-            .flatten()
             .collect()
     }
 
@@ -73,18 +115,49 @@ pub trait ApiClientExt: ApiClient + Sync {
         async {
             let targets = self.get_targets(Some(scope)).await?;
             let sessions = self.get_sessions(scope).await?;
-            Ok(Self::combine_sessions_with_target(sessions, targets))
+            Ok(Self::combine_sessions_with_target(sessions, &targets))
         }
     }
 
-    fn get_user_sessions_with_target(
+    /// Lists the scopes below `parent` one level at a time instead of with a single recursive
+    /// request, sending the number of scopes discovered so far to `progress` after each level.
+    /// Only scopes that allow listing their child scopes are descended into. Scopes whose child
+    /// scopes fail to list are skipped, only listing `parent` itself has to succeed.
+    fn get_scopes_with_progress(
         &self,
-        user_id: &str,
-    ) -> impl Future<Output = Result<Vec<SessionWithTarget>, Error>> + Send {
-        async {
-            let targets = self.get_targets(None).await?;
-            let user_sessions = self.get_user_sessions(user_id).await?;
-            Ok(Self::combine_sessions_with_target(user_sessions, targets))
+        parent: Option<&str>,
+        progress: tokio::sync::mpsc::UnboundedSender<usize>,
+    ) -> impl Future<Output = Result<ScopeListing, Error>> + Send {
+        async move {
+            let mut listing = ScopeListing::default();
+            let mut level = self.get_scopes(parent, false).await?;
+            while !level.is_empty() {
+                let parents: Vec<String> = level
+                    .iter()
+                    .filter(|s| s.can_list_child_scopes())
+                    .map(|s| s.id.clone())
+                    .collect();
+                listing.scopes.extend(level);
+                // Nobody may be waiting for the progress anymore, the scopes are still wanted
+                let _ = progress.send(listing.scopes.len());
+                let children: Vec<(String, Result<Vec<Scope>, Error>)> =
+                    futures::stream::iter(parents)
+                        .map(|id| async move {
+                            let result = self.get_scopes(Some(&id), false).await;
+                            (id, result)
+                        })
+                        .buffer_unordered(SCOPE_LISTING_CONCURRENCY)
+                        .collect()
+                        .await;
+                level = Vec::new();
+                for (parent_id, result) in children {
+                    match result {
+                        Ok(children) => level.extend(children),
+                        Err(e) => listing.failed_scopes.push((parent_id, e)),
+                    }
+                }
+            }
+            Ok(listing)
         }
     }
 }
@@ -134,34 +207,166 @@ impl<T: ApiClient> ApiClient for Arc<T> {
     fn get_sessions(
         &self,
         scope: &str,
-    ) -> impl Future<Output = Result<Vec<Session>, Error>> + Send + Sync {
+    ) -> impl Future<Output = Result<Vec<Session>, Error>> + Send {
         T::get_sessions(self, scope)
     }
 
+    fn get_scope(&self, id: &str) -> impl Future<Output = Result<Scope, Error>> + Send {
+        T::get_scope(self, id)
+    }
+
+    fn get_target(&self, id: &str) -> impl Future<Output = Result<Target, Error>> + Send {
+        T::get_target(self, id)
+    }
+
+    fn get_session(&self, id: &str) -> impl Future<Output = Result<Session, Error>> + Send {
+        T::get_session(self, id)
+    }
+
     fn get_user_sessions(
         &self,
         user_id: &str,
-    ) -> impl Future<Output = Result<Vec<Session>, Error>> + Send + Sync {
+    ) -> impl Future<Output = Result<UserSessions, Error>> + Send {
         T::get_user_sessions(self, user_id)
     }
 
-    async fn connect(
+    fn get_target_hosts(
+        &self,
+        target_id: &str,
+    ) -> impl Future<Output = Result<Vec<Host>, Error>> + Send {
+        T::get_target_hosts(self, target_id)
+    }
+
+    fn connect(
         &self,
         target_id: &str,
+        host_id: Option<&str>,
         port: u16,
-    ) -> Result<(ConnectResponse, Self::ConnectionHandle), Error> {
-        T::connect(self, target_id, port).await
+    ) -> impl Future<Output = Result<(ConnectResponse, Self::ConnectionHandle), Error>> + Send {
+        T::connect(self, target_id, host_id, port)
     }
 
-    async fn cancel_session(&self, session_id: &str) -> Result<(), Error> {
-        T::cancel_session(self, session_id).await
+    fn cancel_session(&self, session_id: &str) -> impl Future<Output = Result<(), Error>> + Send {
+        T::cancel_session(self, session_id)
     }
 
     fn authenticate(&self) -> impl Future<Output = Result<AuthenticateResponse, Error>> + Send {
         T::authenticate(self)
     }
 
+    fn authenticate_with_password(
+        &self,
+        login_name: &str,
+        password: &str,
+    ) -> impl Future<Output = Result<AuthenticateResponse, Error>> + Send {
+        T::authenticate_with_password(self, login_name, password)
+    }
+
     fn validate_token(&self, token_id: &str) -> impl Future<Output=Result<(), Error>> + Send {
         T::validate_token(self, token_id)
     }
+
+    fn set_token(&self, token: &str) {
+        T::set_token(self, token)
+    }
+
+    fn last_command(&self) -> Option<String> {
+        T::last_command(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::fixtures::{listing, scope, session, target};
+    use crate::boundary::MockClient;
+
+    #[test]
+    fn sessions_of_unlisted_targets_are_kept() {
+        let combined = MockClient::combine_sessions_with_target(
+            vec![session("s_1", "ttcp_1", ""), session("s_2", "ttcp_gone", "")],
+            &[target("ttcp_1", "database", "p_1")],
+        );
+
+        assert_eq!(combined.len(), 2);
+        assert_eq!(combined[0].target_name(), "database");
+        assert!(combined[1].target.is_none());
+        assert_eq!(combined[1].target_id(), "ttcp_gone");
+        assert_eq!(combined[1].target_name(), "unknown (ttcp_gone)");
+    }
+
+    #[tokio::test]
+    async fn recursive_scopes_report_their_progress_per_level() {
+        let client = MockClient::builder()
+            .scopes(std::collections::HashMap::from([
+                (None, vec![listing(scope("o_1", "o_1", "global"), "scopes"), listing(scope("o_2", "o_2", "global"), "scopes")]),
+                (Some("o_1".to_string()), vec![scope("p_1", "p_1", "o_1"), scope("p_2", "p_2", "o_1")]),
+                (Some("o_2".to_string()), vec![scope("p_3", "p_3", "o_2")]),
+                (Some("p_1".to_string()), vec![scope("p_hidden", "p_hidden", "p_1")]),
+            ]))
+            .build();
+        let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let scopes = client.get_scopes_with_progress(None, progress_tx).await.unwrap().scopes;
+
+        let mut ids: Vec<_> = scopes.iter().map(|s| s.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["o_1", "o_2", "p_1", "p_2", "p_3"]);
+        let mut progress = Vec::new();
+        while let Ok(count) = progress_rx.try_recv() {
+            progress.push(count);
+        }
+        assert_eq!(progress, vec![2, 5]);
+    }
+
+    #[tokio::test]
+    async fn recursive_scopes_skip_the_scopes_that_fail_to_list() {
+        let client = MockClient::builder()
+            .scopes(std::collections::HashMap::from([
+                (
+                    None,
+                    vec![
+                        listing(scope("o_1", "o_1", "global"), "scopes"),
+                        listing(scope("o_2", "o_2", "global"), "scopes"),
+                        listing(scope("o_3", "o_3", "global"), "scopes"),
+                    ],
+                ),
+                (Some("o_1".to_string()), vec![scope("p_1", "p_1", "o_1")]),
+                (Some("o_2".to_string()), vec![scope("p_2", "p_2", "o_2")]),
+            ]))
+            .failing_scopes(std::collections::HashMap::from([
+                ("o_2".to_string(), 403),
+                ("o_3".to_string(), 500),
+            ]))
+            .build();
+        let (progress_tx, _progress_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let listing = client.get_scopes_with_progress(None, progress_tx).await.unwrap();
+
+        let ids: Vec<_> = listing.scopes.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["o_1", "o_2", "o_3", "p_1"]);
+        let failed: Vec<_> = listing
+            .failed_scopes
+            .iter()
+            .map(|(id, e)| (id.as_str(), e.short_reason()))
+            .collect();
+        assert_eq!(
+            failed,
+            vec![("o_2", "403".to_string()), ("o_3", "500".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn recursive_scopes_fail_if_the_parent_cannot_be_listed() {
+        let client = MockClient::builder()
+            .scopes(std::collections::HashMap::new())
+            .failing_scopes(std::collections::HashMap::from([("o_1".to_string(), 403)]))
+            .build();
+        let (progress_tx, _progress_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        assert!(client
+            .get_scopes_with_progress(Some("o_1"), progress_tx)
+            .await
+            .is_err());
+    }
 }
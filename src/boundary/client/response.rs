@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ListResponse<T> {
+    // Boundary omits `items` for empty lists
     pub items: Option<Vec<T>>,
 }
 
@@ -11,17 +12,40 @@ pub struct ItemResponse<T> {
     pub item: T,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct ApiError {
+    #[serde(default)]
     pub message: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ErrorResponse {
+    #[serde(alias = "status")]
     pub status_code: u16,
+    #[serde(default, alias = "error")]
     pub api_error: ApiError,
 }
 
+/// The host sources of a target, read with `targets read`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TargetHostSources {
+    #[serde(default)]
+    pub host_sources: Vec<HostSource>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HostSource {
+    pub id: String,
+}
+
+/// A host set read with `host-sets read`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HostSet {
+    pub host_catalog_id: String,
+    #[serde(default)]
+    pub host_ids: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct AuthenticateResponse {
     pub attributes: AuthenticateAttributes,
@@ -34,3 +58,13 @@ pub struct AuthenticateAttributes {
     pub token: String,
     pub expiration_time: DateTime<Utc>,
 }
+
+/// Output of `boundary version -format json`.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct VersionResponse {
+    pub version: String,
+    #[serde(default)]
+    pub revision: String,
+    #[serde(default)]
+    pub build_date: String,
+}
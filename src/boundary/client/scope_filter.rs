@@ -0,0 +1,434 @@
+use crate::boundary::client::response::AuthenticateResponse;
+use crate::boundary::error::Error;
+use crate::boundary::models::{ConnectResponse, Host, Target, UserSessions};
+use crate::boundary::{ApiClient, Scope, Session};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+/// Scopes to hide or restrict, each entry is a scope id or a name pattern where `*` matches
+/// any text, e.g. `"prod-*"`.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScopeRules {
+    /// If not empty, only matching scopes are listed.
+    pub allow: Vec<String>,
+    /// Matching scopes are never listed.
+    pub deny: Vec<String>,
+    /// Matching scopes are listed but cannot be opened.
+    pub restricted: Vec<String>,
+}
+
+impl ScopeRules {
+    /// Whether the scope is listed at all, given the scopes above it, its parent first. Scopes below
+    /// an allowed scope are allowed as well, those below a denied scope are denied as well.
+    pub fn is_visible_below(&self, scope: &Scope, ancestors: &[Scope]) -> bool {
+        let lineage = || std::iter::once(scope).chain(ancestors);
+        (self.allow.is_empty() || lineage().any(|s| Self::any_matches(&self.allow, s)))
+            && !lineage().any(|s| Self::any_matches(&self.deny, s))
+    }
+
+    pub fn is_restricted(&self, scope: &Scope) -> bool {
+        Self::any_matches(&self.restricted, scope)
+    }
+
+    /// Whether no scope is hidden, the listings can be passed on as they are.
+    fn hides_nothing(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    fn any_matches(patterns: &[String], scope: &Scope) -> bool {
+        patterns
+            .iter()
+            .any(|p| p == &scope.id || matches_pattern(p, &scope.name))
+    }
+}
+
+/// Matches `value` against `pattern`, where `*` stands for any text.
+pub fn matches_pattern(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = value.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.is_empty()
+}
+
+/// Wraps an [`ApiClient`] and leaves out the scopes hidden by the [`ScopeRules`], along with
+/// the targets and sessions of these scopes.
+#[derive(Clone)]
+pub struct ScopeFilterClient<C> {
+    inner: C,
+    rules: Arc<ScopeRules>,
+    // The scopes read so far by id, to look up the parents of the filtered scopes
+    known_scopes: Arc<Mutex<HashMap<String, Scope>>>,
+}
+
+impl<C> ScopeFilterClient<C> {
+    pub fn new(inner: C, rules: ScopeRules) -> Self {
+        Self {
+            inner,
+            rules: Arc::new(rules),
+            known_scopes: Arc::default(),
+        }
+    }
+
+    fn remember(&self, scopes: &[Scope]) {
+        let mut known_scopes = self.known_scopes.lock().unwrap();
+        for scope in scopes {
+            known_scopes.insert(scope.id.clone(), scope.clone());
+        }
+    }
+
+    fn known_scope(&self, id: &str) -> Option<Scope> {
+        self.known_scopes.lock().unwrap().get(id).cloned()
+    }
+}
+
+// The global scope is above all others and cannot be hidden
+fn is_global(scope_id: &str) -> bool {
+    scope_id.is_empty() || scope_id == "global"
+}
+
+impl<C: ApiClient + Sync> ScopeFilterClient<C> {
+    /// The scope with the id and the scopes above it, the scope first, reading the ones not seen
+    /// yet. The lineage ends early at a scope that cannot be read.
+    async fn lineage(&self, scope_id: &str) -> Vec<Scope> {
+        let mut lineage: Vec<Scope> = Vec::new();
+        let mut id = scope_id.to_string();
+        // Stops at a cycle of parents, which Boundary does not allow anyway
+        while !is_global(&id) && !lineage.iter().any(|s| s.id == id) {
+            let scope = match self.known_scope(&id) {
+                Some(scope) => scope,
+                None => match self.inner.get_scope(&id).await {
+                    Ok(scope) => {
+                        self.remember(std::slice::from_ref(&scope));
+                        scope
+                    }
+                    Err(e) => {
+                        log::warn!("Failed to read scope {id} to filter the scopes below it: {e}");
+                        break;
+                    }
+                },
+            };
+            id = scope.scope_id.clone();
+            lineage.push(scope);
+        }
+        lineage
+    }
+
+    async fn is_visible(&self, scope: &Scope) -> bool {
+        let ancestors = self.lineage(&scope.scope_id).await;
+        self.rules.is_visible_below(scope, &ancestors)
+    }
+
+    async fn is_scope_id_visible(&self, scope_id: &str) -> bool {
+        let lineage = self.lineage(scope_id).await;
+        match lineage.split_first() {
+            Some((scope, ancestors)) => self.rules.is_visible_below(scope, ancestors),
+            // Items whose scope is not known are kept, like those of the global scope
+            None => true,
+        }
+    }
+
+    /// The ids of the scopes that are visible among the given ones. Scopes not seen yet are
+    /// listed all at once if there are several of them, instead of reading them one by one.
+    async fn visible_scope_ids<'a>(
+        &self,
+        scope_ids: impl Iterator<Item = &'a str>,
+    ) -> HashSet<&'a str> {
+        let scope_ids: HashSet<&str> = scope_ids.collect();
+        let unknown = scope_ids
+            .iter()
+            .filter(|id| !is_global(id) && self.known_scope(id).is_none())
+            .count();
+        if unknown > 1 {
+            match self.inner.get_scopes(None, true).await {
+                Ok(scopes) => self.remember(&scopes),
+                Err(e) => log::warn!("Failed to list the scopes to filter the items by: {e}"),
+            }
+        }
+        let mut visible = HashSet::new();
+        for id in scope_ids {
+            if self.is_scope_id_visible(id).await {
+                visible.insert(id);
+            }
+        }
+        visible
+    }
+
+    async fn filter_scopes(&self, scopes: Vec<Scope>) -> Vec<Scope> {
+        self.remember(&scopes);
+        let mut visible = Vec::new();
+        for scope in scopes {
+            if self.is_visible(&scope).await {
+                visible.push(scope);
+            }
+        }
+        visible
+    }
+
+    async fn filter_by_scope<T>(&self, items: Vec<T>, scope_id: impl Fn(&T) -> &str) -> Vec<T> {
+        let visible: HashSet<String> = self
+            .visible_scope_ids(items.iter().map(&scope_id))
+            .await
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        items
+            .into_iter()
+            .filter(|item| visible.contains(scope_id(item)))
+            .collect()
+    }
+
+    fn hidden(what: &str, id: &str) -> Error {
+        Error::ApiError(404, format!("{} {} is hidden", what, id))
+    }
+}
+
+impl<C: ApiClient + Sync> ApiClient for ScopeFilterClient<C> {
+    type ConnectionHandle = C::ConnectionHandle;
+
+    async fn get_scopes(&self, parent: Option<&str>, recursive: bool) -> Result<Vec<Scope>, Error> {
+        let scopes = self.inner.get_scopes(parent, recursive).await?;
+        if self.rules.hides_nothing() {
+            return Ok(scopes);
+        }
+        Ok(self.filter_scopes(scopes).await)
+    }
+
+    async fn get_targets(&self, scope: Option<&str>) -> Result<Vec<Target>, Error> {
+        let targets = self.inner.get_targets(scope).await?;
+        if self.rules.hides_nothing() {
+            return Ok(targets);
+        }
+        Ok(self.filter_by_scope(targets, |t| t.scope_id.as_str()).await)
+    }
+
+    async fn get_sessions(&self, scope: &str) -> Result<Vec<Session>, Error> {
+        if !self.rules.hides_nothing() && !self.is_scope_id_visible(scope).await {
+            return Ok(vec![]);
+        }
+        self.inner.get_sessions(scope).await
+    }
+
+    async fn get_scope(&self, id: &str) -> Result<Scope, Error> {
+        let scope = self.inner.get_scope(id).await?;
+        if !self.rules.hides_nothing() && !self.is_visible(&scope).await {
+            return Err(Self::hidden("scope", id));
+        }
+        Ok(scope)
+    }
+
+    async fn get_target(&self, id: &str) -> Result<Target, Error> {
+        let target = self.inner.get_target(id).await?;
+        if !self.rules.hides_nothing() && !self.is_scope_id_visible(&target.scope_id).await {
+            return Err(Self::hidden("target", id));
+        }
+        Ok(target)
+    }
+
+    async fn get_session(&self, id: &str) -> Result<Session, Error> {
+        let session = self.inner.get_session(id).await?;
+        if !self.rules.hides_nothing() && !self.is_scope_id_visible(&session.scope_id).await {
+            return Err(Self::hidden("session", id));
+        }
+        Ok(session)
+    }
+
+    async fn get_user_sessions(&self, user_id: &str) -> Result<UserSessions, Error> {
+        let mut user_sessions = self.inner.get_user_sessions(user_id).await?;
+        if !self.rules.hides_nothing() {
+            let sessions = std::mem::take(&mut user_sessions.sessions);
+            user_sessions.sessions = self.filter_by_scope(sessions, |s| s.scope_id.as_str()).await;
+        }
+        Ok(user_sessions)
+    }
+
+    fn get_target_hosts(
+        &self,
+        target_id: &str,
+    ) -> impl Future<Output = Result<Vec<Host>, Error>> + Send {
+        self.inner.get_target_hosts(target_id)
+    }
+
+    fn connect(
+        &self,
+        target_id: &str,
+        host_id: Option<&str>,
+        port: u16,
+    ) -> impl Future<Output = Result<(ConnectResponse, Self::ConnectionHandle), Error>> + Send {
+        self.inner.connect(target_id, host_id, port)
+    }
+
+    async fn cancel_session(&self, session_id: &str) -> Result<(), Error> {
+        self.inner.cancel_session(session_id).await
+    }
+
+    fn authenticate(&self) -> impl Future<Output = Result<AuthenticateResponse, Error>> + Send {
+        self.inner.authenticate()
+    }
+
+    fn authenticate_with_password(
+        &self,
+        login_name: &str,
+        password: &str,
+    ) -> impl Future<Output = Result<AuthenticateResponse, Error>> + Send {
+        self.inner.authenticate_with_password(login_name, password)
+    }
+
+    fn validate_token(&self, token_id: &str) -> impl Future<Output = Result<(), Error>> + Send {
+        self.inner.validate_token(token_id)
+    }
+
+    fn set_token(&self, token: &str) {
+        self.inner.set_token(token)
+    }
+
+    fn last_command(&self) -> Option<String> {
+        self.inner.last_command()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::fixtures::{scope, session, target};
+    use crate::boundary::MockClient;
+    use std::collections::HashMap;
+
+    #[test]
+    fn pattern_matching() {
+        assert!(matches_pattern("prod", "prod"));
+        assert!(!matches_pattern("prod", "production"));
+        assert!(matches_pattern("prod-*", "prod-eu"));
+        assert!(matches_pattern("*-eu", "prod-eu"));
+        assert!(matches_pattern("p*d-*u", "prod-eu"));
+        assert!(!matches_pattern("p*d-*u", "prod-us"));
+        assert!(matches_pattern("*", ""));
+    }
+
+    #[test]
+    fn allow_deny_and_restricted() {
+        let rules = ScopeRules {
+            allow: vec!["team-*".to_string(), "p_shared".to_string()],
+            deny: vec!["team-secret".to_string()],
+            restricted: vec!["*-prod".to_string()],
+        };
+        assert!(rules.is_visible_below(&scope("p_1", "team-dev", ""), &[]));
+        assert!(rules.is_visible_below(&scope("p_shared", "shared", ""), &[]));
+        assert!(!rules.is_visible_below(&scope("p_2", "other", ""), &[]));
+        assert!(!rules.is_visible_below(&scope("p_3", "team-secret", ""), &[]));
+        assert!(rules.is_restricted(&scope("p_4", "team-prod", "")));
+        assert!(!rules.is_restricted(&scope("p_1", "team-dev", "")));
+        assert!(ScopeRules::default().is_visible_below(&scope("p_2", "other", ""), &[]));
+    }
+
+    #[tokio::test]
+    async fn denied_scopes_are_left_out() {
+        let client = MockClient::builder()
+            .scopes(HashMap::from([(
+                None,
+                vec![scope("o_1", "public", ""), scope("o_2", "secret", "")],
+            )]))
+            .build();
+        let rules = ScopeRules {
+            deny: vec!["o_2".to_string()],
+            ..ScopeRules::default()
+        };
+        let client = ScopeFilterClient::new(client, rules);
+
+        let scopes = client.get_scopes(None, false).await.unwrap();
+        assert_eq!(
+            scopes.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(),
+            vec!["o_1"]
+        );
+    }
+
+    // The orgs team-a and secret with a project each
+    fn nested_client(rules: ScopeRules) -> ScopeFilterClient<MockClient> {
+        let client = MockClient::builder()
+            .user_id("u_1".to_string())
+            .scopes(HashMap::from([
+                (
+                    None,
+                    vec![scope("o_1", "team-a", "global"), scope("o_2", "secret", "global")],
+                ),
+                (Some("o_1".to_string()), vec![scope("p_1", "api", "o_1")]),
+                (Some("o_2".to_string()), vec![scope("p_2", "vault", "o_2")]),
+            ]))
+            .targets(HashMap::from([(
+                None,
+                vec![target("ttcp_1", "ttcp_1", "p_1"), target("ttcp_2", "ttcp_2", "p_2")],
+            )]))
+            .sessions(Arc::new(tokio::sync::Mutex::new(HashMap::from([
+                ("p_1".to_string(), vec![session("s_1", "ttcp_1", "p_1")]),
+                ("p_2".to_string(), vec![session("s_2", "ttcp_1", "p_2")]),
+            ]))))
+            .build();
+        ScopeFilterClient::new(client, rules)
+    }
+
+    #[tokio::test]
+    async fn scopes_below_denied_scopes_are_left_out() {
+        let client = nested_client(ScopeRules {
+            deny: vec!["secret".to_string()],
+            ..ScopeRules::default()
+        });
+
+        assert!(client.get_scopes(Some("o_2"), false).await.unwrap().is_empty());
+        let scopes = client.get_scopes(None, true).await.unwrap();
+        assert_eq!(
+            scopes.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(),
+            vec!["o_1", "p_1"]
+        );
+        assert!(client.get_scope("p_2").await.unwrap_err().is_not_found());
+    }
+
+    #[tokio::test]
+    async fn scopes_below_allowed_scopes_are_listed() {
+        let client = nested_client(ScopeRules {
+            allow: vec!["team-*".to_string()],
+            ..ScopeRules::default()
+        });
+
+        let scopes = client.get_scopes(Some("o_1"), false).await.unwrap();
+        assert_eq!(
+            scopes.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(),
+            vec!["p_1"]
+        );
+        assert!(client.get_scopes(Some("o_2"), false).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn targets_and_sessions_of_hidden_scopes_are_left_out() {
+        let client = nested_client(ScopeRules {
+            deny: vec!["o_2".to_string()],
+            ..ScopeRules::default()
+        });
+
+        let targets = client.get_targets(None).await.unwrap();
+        assert_eq!(
+            targets.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(),
+            vec!["ttcp_1"]
+        );
+        assert!(client.get_target("ttcp_2").await.unwrap_err().is_not_found());
+        assert!(client.get_sessions("p_2").await.unwrap().is_empty());
+        let user_sessions = client.get_user_sessions("u_1").await.unwrap();
+        assert_eq!(
+            user_sessions.sessions.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(),
+            vec!["s_1"]
+        );
+    }
+}
@@ -10,6 +10,8 @@ pub enum Error {
     ApiError(u16, String),
     #[error("An error occurred while parsing JSON: {0}")]
     JsonError(#[from] serde_json::Error),
+    #[error("Unexpected response from boundary ({0}): {1}")]
+    UnexpectedResponse(String, String),
     #[error("Failed to parse Boundary version: {0}")]
     VersionParseError(String),
     #[error("Boundary failed to connect in time")]
@@ -17,3 +19,58 @@ pub enum Error {
     #[error("Port {0} is not available")]
     PortNotAvailable(u16),
 }
+
+impl Error {
+    /// Whether Boundary refused to connect because the target has several hosts and one has to
+    /// be picked with `-host-id`.
+    pub fn needs_host_id(&self) -> bool {
+        let message = match self {
+            Error::ApiError(_, message)
+            | Error::CliError(_, message)
+            | Error::UnexpectedResponse(_, message) => message.to_lowercase(),
+            _ => return false,
+        };
+        message.contains("host-id") || message.contains("host id")
+    }
+
+    /// Whether `boundary authenticate` failed because it wanted to prompt for the login name or
+    /// password, which cannot be answered while the terminal is used by bountui.
+    pub fn needs_credentials(&self) -> bool {
+        let message = match self {
+            Error::ApiError(_, message)
+            | Error::CliError(_, message)
+            | Error::UnexpectedResponse(_, message) => message.to_lowercase(),
+            _ => return false,
+        };
+        ["password", "login name", "login-name", "not a terminal", "inappropriate ioctl"]
+            .iter()
+            .any(|word| message.contains(word))
+    }
+
+    /// Whether Boundary has nothing with the id that was read.
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Error::ApiError(404, _))
+    }
+
+    /// A few words on why a request failed, e.g. `403` or `timeout`, for listing several
+    /// failures in one line.
+    pub fn short_reason(&self) -> String {
+        let message = self.to_string().to_lowercase();
+        if message.contains("timeout")
+            || message.contains("timed out")
+            || message.contains("deadline exceeded")
+        {
+            return "timeout".to_string();
+        }
+        match self {
+            Error::ApiError(status, _) => status.to_string(),
+            Error::CliError(Some(code), _) => format!("exit code {}", code),
+            Error::CliError(None, _) => "terminated".to_string(),
+            Error::Io(e) => e.kind().to_string(),
+            Error::JsonError(_) | Error::UnexpectedResponse(_, _) => {
+                "unexpected response".to_string()
+            }
+            e => e.to_string(),
+        }
+    }
+}
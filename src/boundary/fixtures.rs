@@ -0,0 +1,64 @@
+use crate::boundary::{Credential, CredentialEntry, CredentialSource, Scope, Session, Target};
+use std::collections::HashMap;
+
+/// A project scope below `parent` that allows nothing to be listed, see [`listing`].
+pub fn scope(id: &str, name: &str, parent: &str) -> Scope {
+    Scope::builder()
+        .id(id.to_string())
+        .name(name.to_string())
+        .description(String::new())
+        .type_name("project".to_string())
+        .authorized_collection_actions(HashMap::new())
+        .scope_id(parent.to_string())
+        .build()
+}
+
+/// The scope allowing its `collection`, e.g. "scopes" or "targets", to be listed.
+pub fn listing(mut scope: Scope, collection: &str) -> Scope {
+    scope
+        .authorized_collection_actions
+        .insert(collection.to_string(), vec!["list".to_string()]);
+    scope
+}
+
+/// A tcp target in the scope without host or attributes.
+pub fn target(id: &str, name: &str, scope_id: &str) -> Target {
+    Target {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: String::new(),
+        type_name: "tcp".to_string(),
+        authorized_collection_actions: HashMap::new(),
+        authorized_actions: vec![],
+        scope_id: scope_id.to_string(),
+        attributes: None,
+    }
+}
+
+/// An active tcp session of the user `u_1`.
+pub fn session(id: &str, target_id: &str, scope_id: &str) -> Session {
+    Session {
+        id: id.to_string(),
+        target_id: target_id.to_string(),
+        scope_id: scope_id.to_string(),
+        session_type: "tcp".to_string(),
+        created_time: chrono::DateTime::UNIX_EPOCH,
+        expiration_time: None,
+        status: "active".to_string(),
+        authorized_actions: vec![],
+        user_id: "u_1".to_string(),
+    }
+}
+
+/// A username and password credential from the "vault" credential source.
+pub fn credential_entry(username: &str, password: &str) -> CredentialEntry {
+    CredentialEntry {
+        credential: Credential {
+            username: username.to_string(),
+            password: password.to_string(),
+        },
+        credential_source: CredentialSource {
+            name: "vault".to_string(),
+        },
+    }
+}
@@ -1,11 +1,14 @@
 pub mod client;
 mod error;
+#[cfg(test)]
+pub(crate) mod fixtures;
 mod models;
 
 pub use client::cli::CliClient;
 #[cfg(test)]
 pub use client::mock::*;
 pub use client::response::AuthenticateResponse;
+pub use client::scope_filter::{matches_pattern, ScopeFilterClient, ScopeRules};
 pub use client::{ApiClient, ApiClientExt, BoundaryConnectionHandle};
 pub use error::Error;
 pub use models::*;
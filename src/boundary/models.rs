@@ -1,3 +1,4 @@
+use crate::boundary::Error;
 use bon::Builder;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -7,11 +8,17 @@ use std::collections::HashMap;
 pub struct Scope {
     pub id: String,
     pub name: String,
+    // Boundary omits empty fields
+    #[serde(default)]
     pub description: String,
     #[serde(rename = "type")]
     pub type_name: String,
     #[serde(default)]
     pub authorized_collection_actions: HashMap<String, Vec<String>>,
+    /// Id of the parent scope, empty for the global scope.
+    #[serde(default)]
+    #[builder(default)]
+    pub scope_id: String,
 }
 
 impl Scope {
@@ -28,12 +35,20 @@ impl Scope {
             .map(|actions| actions.contains(&"list".to_string()))
             .unwrap_or(false)
     }
+
+    pub fn can_list_sessions(&self) -> bool {
+        self.authorized_collection_actions
+            .get("sessions")
+            .map(|actions| actions.contains(&"list".to_string()))
+            .unwrap_or(false)
+    }
 }
 
 #[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
 pub struct Target {
     pub id: String,
     pub name: String,
+    #[serde(default)]
     pub description: String,
     #[serde(rename = "type")]
     pub type_name: String,
@@ -67,6 +82,35 @@ pub struct TargetAttributes {
     pub default_client_port: Option<u16>,
 }
 
+/// A host one of the host sets of a target points to.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct Host {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub attributes: Option<HostAttributes>,
+    /// Addresses of hosts from dynamic host catalogs.
+    #[serde(default)]
+    pub ip_addresses: Vec<String>,
+}
+
+impl Host {
+    /// The address of a static host or the addresses of a dynamic one, comma separated.
+    pub fn address(&self) -> String {
+        match self.attributes.as_ref().and_then(|a| a.address.as_ref()) {
+            Some(address) => address.clone(),
+            None => self.ip_addresses.join(", "),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct HostAttributes {
+    pub address: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Credential {
     pub username: String,
@@ -96,11 +140,19 @@ pub struct ConnectResponse {
 pub struct Session {
     pub id: String,
     pub target_id: String,
+    // The project scope of the session, empty if Boundary left it out
+    #[serde(default)]
+    pub scope_id: String,
     #[serde(rename = "type")]
     pub session_type: String,
     pub created_time: DateTime<Utc>,
+    // Not returned by older Boundary versions
+    #[serde(default)]
+    pub expiration_time: Option<DateTime<Utc>>,
     pub status: String,
+    #[serde(default)]
     pub authorized_actions: Vec<String>,
+    #[serde(default)]
     pub user_id: String,
 }
 
@@ -108,16 +160,53 @@ impl Session {
     pub fn can_cancel(&self) -> bool {
         self.authorized_actions.contains(&"cancel:self".to_string())
     }
+
+    /// Whether the session is in a terminal state or about to be.
+    pub fn is_ended(&self) -> bool {
+        matches!(
+            self.status.as_str(),
+            "terminated" | "canceling" | "canceled"
+        )
+    }
+}
+
+/// Scopes found by walking down the scope hierarchy.
+#[derive(Debug, Default)]
+pub struct ScopeListing {
+    pub scopes: Vec<Scope>,
+    /// Scopes whose child scopes could not be listed, with the reason.
+    pub failed_scopes: Vec<(String, Error)>,
+}
+
+/// Sessions of a user across all scopes.
+#[derive(Debug, Default)]
+pub struct UserSessions {
+    pub sessions: Vec<Session>,
+    /// Scopes whose sessions could not be listed, with the reason.
+    pub failed_scopes: Vec<(String, Error)>,
 }
 
 #[derive(Debug, Clone)]
 pub struct SessionWithTarget {
     pub session: Session,
-    pub target: Target,
+    /// `None` if the target is not listed anymore, e.g. it was deleted or is no longer permitted.
+    pub target: Option<Target>,
 }
 
 impl SessionWithTarget {
-    pub fn new(session: Session, target: Target) -> SessionWithTarget {
+    pub fn new(session: Session, target: Option<Target>) -> SessionWithTarget {
         SessionWithTarget { session, target }
     }
+
+    pub fn target_id(&self) -> &str {
+        &self.session.target_id
+    }
+
+    /// Name of the target, "unknown (<target id>)" if it is not listed anymore.
+    pub fn target_name(&self) -> String {
+        match &self.target {
+            Some(target) => target.name.clone(),
+            None => format!("unknown ({})", self.session.target_id),
+        }
+    }
 }
@@ -0,0 +1,101 @@
+use std::collections::VecDeque;
+
+/// The alerts waiting to be read, shown one at a time in the order they were raised.
+#[derive(Default)]
+pub struct Alerts {
+    queue: VecDeque<(String, String)>,
+    // Alerts dismissed since the queue was last empty, to count the shown one among them
+    dismissed: usize,
+}
+
+impl Alerts {
+    /// Queues the alert behind the others, unless it is the same as the last one queued.
+    pub fn push(&mut self, alert: (String, String)) {
+        if self.queue.back() != Some(&alert) {
+            self.queue.push_back(alert);
+        }
+    }
+
+    /// The alert shown, by title and message.
+    pub fn current(&self) -> Option<&(String, String)> {
+        self.queue.front()
+    }
+
+    /// Dismisses the shown alert, revealing the next one.
+    pub fn dismiss(&mut self) {
+        if self.queue.pop_front().is_some() {
+            self.dismissed += 1;
+        }
+        if self.queue.is_empty() {
+            self.dismissed = 0;
+        }
+    }
+
+    /// The title of the shown alert, e.g. "Error (1/3)" while more alerts are queued or were
+    /// dismissed before it.
+    pub fn current_title(&self) -> Option<String> {
+        let (title, _) = self.current()?;
+        let total = self.dismissed + self.queue.len();
+        if total > 1 {
+            Some(format!("{title} ({}/{total})", self.dismissed + 1))
+        } else {
+            Some(title.clone())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert(message: &str) -> (String, String) {
+        ("Error".to_string(), message.to_string())
+    }
+
+    fn shown(alerts: &Alerts) -> Option<(String, String)> {
+        let (_, message) = alerts.current()?;
+        Some((alerts.current_title().unwrap(), message.clone()))
+    }
+
+    #[test]
+    fn alerts_are_shown_one_at_a_time_in_order() {
+        let mut alerts = Alerts::default();
+        alerts.push(alert("refresh failed"));
+        alerts.push(alert("clipboard failed"));
+        alerts.push(alert("stop failed"));
+
+        assert_eq!(shown(&alerts), Some(("Error (1/3)".to_string(), "refresh failed".to_string())));
+        alerts.dismiss();
+        assert_eq!(shown(&alerts), Some(("Error (2/3)".to_string(), "clipboard failed".to_string())));
+        alerts.dismiss();
+        assert_eq!(shown(&alerts), Some(("Error (3/3)".to_string(), "stop failed".to_string())));
+        alerts.dismiss();
+        assert_eq!(shown(&alerts), None);
+    }
+
+    #[test]
+    fn single_alert_has_no_counter() {
+        let mut alerts = Alerts::default();
+        alerts.push(alert("refresh failed"));
+        alerts.push(alert("clipboard failed"));
+        alerts.dismiss();
+        alerts.dismiss();
+
+        alerts.push(alert("stop failed"));
+        assert_eq!(shown(&alerts), Some(("Error".to_string(), "stop failed".to_string())));
+    }
+
+    #[test]
+    fn identical_consecutive_alerts_are_queued_once() {
+        let mut alerts = Alerts::default();
+        alerts.push(alert("refresh failed"));
+        alerts.push(alert("refresh failed"));
+        alerts.push(alert("clipboard failed"));
+        alerts.push(alert("refresh failed"));
+
+        assert_eq!(alerts.current_title(), Some("Error (1/3)".to_string()));
+        alerts.dismiss();
+        alerts.dismiss();
+        assert_eq!(alerts.current(), Some(&alert("refresh failed")), "Not consecutive");
+    }
+}
@@ -0,0 +1,103 @@
+use crate::bountui::components::table::{Action, FilterItems, SortItems, TableColumn};
+use crate::bountui::components::TablePage;
+use crossterm::event::Event;
+use ratatui::layout::{Constraint, Flex};
+use ratatui::prelude::{Alignment, Stylize};
+use ratatui::widgets::{Block, BorderType, Borders, Clear};
+use ratatui::Frame;
+use std::rc::Rc;
+
+/// Outcome of connecting to one of several targets at once.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BulkConnectRow {
+    pub target_name: String,
+    pub port: Option<u16>,
+    /// Session id on success, error message otherwise.
+    pub outcome: Result<String, String>,
+}
+
+/// Summarizes a bulk connect with one row per target.
+pub struct BulkConnectDialog {
+    table: TablePage<BulkConnectRow>,
+}
+
+impl BulkConnectDialog {
+    pub fn new(rows: Vec<BulkConnectRow>) -> Self {
+        let columns = vec![
+            TableColumn::new(
+                "Target".to_string(),
+                Constraint::Ratio(1, 3),
+                Box::new(|r: &BulkConnectRow| r.target_name.clone()),
+            ),
+            TableColumn::new(
+                "Port".to_string(),
+                Constraint::Ratio(1, 6),
+                Box::new(|r: &BulkConnectRow| r.port.map(|p| p.to_string()).unwrap_or_default()),
+            ),
+            TableColumn::new(
+                "Session / Error".to_string(),
+                Constraint::Ratio(1, 2),
+                Box::new(|r: &BulkConnectRow| match &r.outcome {
+                    Ok(session_id) => session_id.clone(),
+                    Err(e) => format!("Failed: {e}"),
+                }),
+            ),
+        ];
+
+        let actions = vec![Action::new(
+            "Close".to_string(),
+            "ESC".to_string(),
+            Box::new(|_: Option<&BulkConnectRow>| true),
+        )];
+
+        let failed = rows.iter().filter(|r| r.outcome.is_err()).count();
+        let title = format!("Bulk Connect ({} connected, {} failed)", rows.len() - failed, failed);
+        let table = TablePage::new(title, columns, rows, actions, false);
+
+        Self { table }
+    }
+
+    #[cfg(test)]
+    pub fn rows(&self) -> Vec<BulkConnectRow> {
+        self.table.items().cloned().collect()
+    }
+
+    pub fn view(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let vertical =
+            ratatui::layout::Layout::vertical([Constraint::Percentage(50)]).flex(Flex::Center);
+        let horizontal =
+            ratatui::layout::Layout::horizontal([Constraint::Percentage(70)]).flex(Flex::Center);
+        let [area] = vertical.areas(area);
+        let [area] = horizontal.areas(area);
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .light_blue()
+            .on_black();
+
+        let inner_area = block.inner(area);
+        frame.render_widget(block, area);
+        self.table.view(frame, inner_area);
+    }
+
+    pub async fn handle_event(&mut self, event: &Event) {
+        self.table.handle_event(event).await;
+    }
+}
+
+impl SortItems<BulkConnectRow> for TablePage<BulkConnectRow> {
+    fn sort(_: &mut Vec<Rc<BulkConnectRow>>) {
+        // Keep the order the targets were connected in
+    }
+}
+
+impl FilterItems<BulkConnectRow> for TablePage<BulkConnectRow> {
+    fn matches(item: &BulkConnectRow, search: &str) -> bool {
+        Self::match_str(&item.target_name, search)
+    }
+}
@@ -0,0 +1,324 @@
+use crate::boundary::Target;
+use crate::bountui::goto::{unknown_id_message, GotoId};
+use crate::bountui::components::table::EnabledAction;
+use crate::bountui::recent_pages::RecentPage;
+use crate::bountui::Message;
+use crossterm::event::KeyEvent;
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub const SCOPE_TREE: &str = "scope-tree";
+pub const MY_SESSIONS: &str = "my-sessions";
+pub const TREE: &str = "tree";
+pub const REFRESH: &str = "refresh";
+pub const DISCONNECT_ALL: &str = "disconnect-all";
+pub const RECONNECT: &str = "reconnect";
+pub const LAST_COMMAND: &str = "last-command";
+pub const RECENT: &str = "recent";
+pub const QUIT: &str = "quit";
+// Followed by the id of a scope, target or session, e.g. `goto ttcp_1234567890`
+pub const GOTO: &str = "goto";
+// Followed by the name of a target, e.g. `open db-prod`
+pub const OPEN: &str = "open";
+// Followed by the id of a target and optionally the local port, e.g. `connect ttcp_1234567890 5432`
+pub const CONNECT: &str = "connect";
+
+// Named in the hint of the empty palette, in this order, for those new to it
+const HINTED_COMMANDS: [&str; 4] = [SCOPE_TREE, MY_SESSIONS, RECENT, QUIT];
+
+/// What running a command of the palette does.
+#[derive(Debug, Clone)]
+pub enum CommandAction {
+    /// Sends the message, e.g. to navigate to a page.
+    Send(fn() -> Message),
+    /// Sends the message built from the text typed after the command name, e.g. `goto <id>`.
+    WithArgument(fn(&str) -> Message),
+    /// Presses the shortcut of an action of the shown page.
+    Key(KeyEvent),
+    /// Shows a page shown before again, listed by the `recent` command.
+    OpenPage(Box<RecentPage>),
+    /// Shows the targets of the scope with the target selected, one of those `open` found.
+    SelectTarget { scope_id: String, target_id: String },
+}
+
+/// Values the argument of a command can be completed to, each with a name it is found by as
+/// well, e.g. the id and name of a target.
+pub type ArgumentCandidates = Vec<(String, String)>;
+
+/// Loads the candidates for the argument of a command, called once the argument is typed.
+pub type ArgumentCompleter = Arc<dyn Fn() -> BoxFuture<'static, ArgumentCandidates> + Send + Sync>;
+
+/// An entry of the command palette.
+#[derive(Debug, Clone)]
+pub struct Command {
+    pub label: String,
+    pub hint: String,
+    pub action: CommandAction,
+}
+
+impl Command {
+    /// The text typed after the name of a command taking an argument, `None` for other commands.
+    pub fn argument<'a>(&self, value: &'a str) -> Option<&'a str> {
+        match self.action {
+            CommandAction::WithArgument(_) => value
+                .strip_prefix(self.label.as_str())
+                .filter(|rest| rest.starts_with(' '))
+                .map(str::trim),
+            _ => None,
+        }
+    }
+}
+
+/// The commands of the palette, the global ones followed by those the shown page contributes.
+pub struct CommandRegistry {
+    commands: Vec<Command>,
+    // By the label of the command whose argument they complete
+    completers: HashMap<String, ArgumentCompleter>,
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        let mut registry = CommandRegistry::empty();
+        registry.register(SCOPE_TREE, "Show the root scopes", CommandAction::Send(|| {
+            Message::NavigateToScopeTree
+        }));
+        registry.register(MY_SESSIONS, "Show your sessions of all scopes", CommandAction::Send(|| {
+            Message::NavigateToMySessions
+        }));
+        registry.register(TREE, "Show all scopes as a tree", CommandAction::Send(|| {
+            Message::ShowScopeTree { replace_history: true }
+        }));
+        registry.register(REFRESH, "Reload the shown page", CommandAction::Send(|| {
+            Message::RefreshPage
+        }));
+        registry.register(
+            DISCONNECT_ALL,
+            "Stop all connections made by bountui",
+            CommandAction::Send(|| Message::StopAllConnections),
+        );
+        registry.register(
+            RECONNECT,
+            "Check the connections and reconnect the dead ones, e.g. after a suspend",
+            CommandAction::Send(|| Message::CheckConnections),
+        );
+        registry.register(RECENT, "List the pages shown recently to jump back to one", CommandAction::Send(|| {
+            Message::ShowRecentPages
+        }));
+        registry.register(
+            LAST_COMMAND,
+            "Show the last boundary command to copy it (requires --debug)",
+            CommandAction::Send(|| Message::ShowLastCommand),
+        );
+        registry.register(QUIT, "Stop all connections and exit", CommandAction::Send(|| {
+            Message::Quit
+        }));
+        registry.register(
+            GOTO,
+            "Show the scope, target or session with the id",
+            CommandAction::WithArgument(goto),
+        );
+        registry.register(
+            OPEN,
+            "Show the target named closest to the name in any scope",
+            CommandAction::WithArgument(open),
+        );
+        registry.register(
+            CONNECT,
+            "Connect to the target with the id, on the port if given",
+            CommandAction::WithArgument(connect),
+        );
+        registry
+    }
+}
+
+impl CommandRegistry {
+    fn empty() -> Self {
+        CommandRegistry {
+            commands: vec![],
+            completers: HashMap::new(),
+        }
+    }
+
+    /// Lists the pages, the most recent first, instead of the commands.
+    pub fn recent_pages(pages: Vec<RecentPage>) -> Self {
+        let mut registry = CommandRegistry::empty();
+        for page in pages {
+            let (label, id) = (page.label(), page.id().to_string());
+            registry.register(label, id, CommandAction::OpenPage(Box::new(page)));
+        }
+        registry
+    }
+
+    /// Lists the targets by name, with their id and scope to tell those named alike apart.
+    pub fn target_choices(targets: Vec<Target>) -> Self {
+        let mut registry = CommandRegistry::empty();
+        for target in targets {
+            registry.register(
+                target.name,
+                format!("{} in {}", target.id, target.scope_id),
+                CommandAction::SelectTarget {
+                    scope_id: target.scope_id,
+                    target_id: target.id,
+                },
+            );
+        }
+        registry
+    }
+
+    pub fn register<L: Into<String>, H: Into<String>>(&mut self, label: L, hint: H, action: CommandAction) {
+        self.commands.push(Command {
+            label: label.into(),
+            hint: hint.into(),
+            action,
+        });
+    }
+
+    /// Registers the actions of the shown page that have a key to press, others like quitting
+    /// are left out.
+    pub fn register_page_actions(&mut self, actions: Vec<EnabledAction>) {
+        for action in actions {
+            if let Some(key) = action.key {
+                self.register(action.name, action.shortcut, CommandAction::Key(key));
+            }
+        }
+    }
+
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    /// Completes the argument of the command with the label, e.g. the id after `connect`.
+    pub fn register_completer<L: Into<String>>(&mut self, label: L, completer: ArgumentCompleter) {
+        self.completers.insert(label.into(), completer);
+    }
+
+    pub fn completer(&self, label: &str) -> Option<&ArgumentCompleter> {
+        self.completers.get(label)
+    }
+
+    /// The labels of the commands worth trying first, none for the palettes listing pages or
+    /// targets.
+    pub fn hinted_labels(&self) -> Vec<&str> {
+        HINTED_COMMANDS
+            .into_iter()
+            .filter(|label| self.commands.iter().any(|c| c.label == *label))
+            .collect()
+    }
+}
+
+fn goto(id: &str) -> Message {
+    match GotoId::parse(id) {
+        Some(goto_id) => Message::Goto(goto_id),
+        None => Message::ShowAlert("Unknown id".to_string(), unknown_id_message(id)),
+    }
+}
+
+fn open(name: &str) -> Message {
+    if name.is_empty() {
+        return Message::ShowAlert(
+            "Cannot open".to_string(),
+            "Type the name of a target after open, e.g. open db-prod".to_string(),
+        );
+    }
+    Message::OpenTarget(name.to_string())
+}
+
+fn connect(argument: &str) -> Message {
+    match parse_connect(argument) {
+        Ok((target_id, port)) => Message::ConnectById {
+            target_id: target_id.to_string(),
+            port,
+        },
+        Err(reason) => Message::ShowAlert("Cannot connect".to_string(), reason),
+    }
+}
+
+/// Splits `<target-id> [port]` typed after `connect`.
+fn parse_connect(argument: &str) -> Result<(&str, Option<u16>), String> {
+    let mut parts = argument.split_whitespace();
+    let target_id = parts
+        .next()
+        .ok_or_else(|| "Type the id of the target after connect, e.g. connect ttcp_1234567890 5432".to_string())?;
+    let port = match parts.next() {
+        None => None,
+        Some(port) => match port.parse::<u16>() {
+            Ok(port) if port > 0 => Some(port),
+            _ => return Err(format!("'{port}' is not a port, use a number from 1 to 65535")),
+        },
+    };
+    if let Some(extra) = parts.next() {
+        return Err(format!("Unexpected '{extra}', connect takes a target id and a port"));
+    }
+    Ok((target_id, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyCode;
+
+    #[test]
+    fn pages_contribute_their_actions_after_the_global_commands() {
+        let mut registry = CommandRegistry::default();
+        registry.register_page_actions(vec![
+            EnabledAction {
+                name: "Connect".to_string(),
+                shortcut: "c".to_string(),
+                key: Some(KeyEvent::from(KeyCode::Char('c'))),
+            },
+            EnabledAction {
+                name: "Quit".to_string(),
+                shortcut: "q".to_string(),
+                key: None,
+            },
+        ]);
+        let labels: Vec<&str> = registry.commands().iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            vec![SCOPE_TREE, MY_SESSIONS, TREE, REFRESH, DISCONNECT_ALL, RECONNECT, RECENT, LAST_COMMAND, QUIT, GOTO, OPEN, CONNECT, "Connect"]
+        );
+    }
+
+    #[test]
+    fn hinted_commands_are_the_registered_ones() {
+        assert_eq!(CommandRegistry::default().hinted_labels(), vec![SCOPE_TREE, MY_SESSIONS, RECENT, QUIT]);
+        let recent = CommandRegistry::recent_pages(vec![RecentPage::Scopes(None)]);
+        assert!(recent.hinted_labels().is_empty());
+    }
+
+    #[test]
+    fn only_commands_with_an_argument_take_the_typed_text() {
+        let registry = CommandRegistry::default();
+        let command = |label: &str| registry.commands().iter().find(|c| c.label == label).unwrap();
+        assert_eq!(command(GOTO).argument("goto  p_1 "), Some("p_1"));
+        assert_eq!(command(GOTO).argument("gotop_1"), None);
+        assert_eq!(command(TREE).argument("tree x"), None);
+    }
+
+    #[test]
+    fn connect_takes_a_target_id_and_an_optional_port() {
+        assert_eq!(parse_connect("ttcp_abc123 5432"), Ok(("ttcp_abc123", Some(5432))));
+        assert_eq!(parse_connect("  ttcp_abc123   5432 "), Ok(("ttcp_abc123", Some(5432))));
+        assert_eq!(parse_connect("ttcp_abc123"), Ok(("ttcp_abc123", None)));
+    }
+
+    #[test]
+    fn connect_rejects_missing_ids_invalid_ports_and_extra_arguments() {
+        assert!(parse_connect("").is_err());
+        assert!(parse_connect("   ").is_err());
+        assert!(parse_connect("ttcp_abc123 0").is_err());
+        assert!(parse_connect("ttcp_abc123 65536").is_err());
+        assert!(parse_connect("ttcp_abc123 pg").is_err());
+        assert!(parse_connect("ttcp_abc123 5432 extra").is_err());
+    }
+
+    #[test]
+    fn connect_sends_the_parsed_target_and_port() {
+        assert!(matches!(
+            connect("ttcp_abc123 5432"),
+            Message::ConnectById { target_id, port: Some(5432) } if target_id == "ttcp_abc123"
+        ));
+        assert!(matches!(connect("ttcp_abc123 x"), Message::ShowAlert(title, _) if title == "Cannot connect"));
+    }
+}
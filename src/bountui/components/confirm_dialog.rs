@@ -0,0 +1,67 @@
+use crossterm::event::{Event, KeyCode};
+use ratatui::layout::{Alignment, Constraint, Flex, Layout};
+use ratatui::prelude::Stylize;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, Paragraph, Wrap};
+use ratatui::Frame;
+
+/// Asks the user to confirm an action with <y> or to decline it with <n> / <Esc>.
+pub struct ConfirmDialog {
+    title: String,
+    message: String,
+}
+
+impl ConfirmDialog {
+    pub fn new(title: String, message: String) -> Self {
+        Self { title, message }
+    }
+
+    pub fn view(&self, frame: &mut Frame) {
+        let vertical = Layout::vertical([Constraint::Percentage(25)]).flex(Flex::Center);
+        let horizontal = Layout::horizontal([Constraint::Percentage(40)]).flex(Flex::Center);
+        let [area] = vertical.areas(frame.area());
+        let [area] = horizontal.areas(area);
+
+        let block = Block::bordered()
+            .light_blue()
+            .on_black()
+            .title_alignment(Alignment::Center)
+            .title(Span::from(format!(" {} ", self.title)).bold());
+
+        let [_, text_area, _, button_area, _] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(block.inner(area));
+
+        let message = Paragraph::new(self.message.as_str())
+            .alignment(Alignment::Center)
+            .wrap(Wrap::default());
+        let buttons = Paragraph::new(Line::from(vec![
+            Span::from("  Yes <y>  ").bold().reversed(),
+            Span::from("    "),
+            Span::from("  No <n>  ").bold().reversed(),
+        ]))
+        .alignment(Alignment::Center);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+        frame.render_widget(message, text_area);
+        frame.render_widget(buttons, button_area);
+    }
+
+    /// Returns the decision once the user made one, `None` for unrelated keys.
+    pub fn handle_event(&self, event: &Event) -> Option<bool> {
+        if let Event::Key(key_event) = event {
+            match key_event.code {
+                KeyCode::Char('y') | KeyCode::Enter => return Some(true),
+                KeyCode::Char('n') | KeyCode::Esc => return Some(false),
+                _ => {}
+            }
+        }
+        None
+    }
+}
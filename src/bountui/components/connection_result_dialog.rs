@@ -1,10 +1,15 @@
 use crate::boundary;
 use crate::bountui::components::credential_table::CredentialTable;
+use crate::bountui::components::table::local_address;
+use crate::bountui::connect_command::OpenCommand;
 use crate::bountui::Message;
-use crossterm::event::Event;
+use crate::event_ext::EventExt;
+use crate::util::MpscSenderExt;
+use crossterm::event::{Event, KeyCode, KeyModifiers};
 use ratatui::layout::Flex;
 use ratatui::prelude::{Alignment, Stylize};
-use ratatui::widgets::Clear;
+use ratatui::text::Line;
+use ratatui::widgets::{Clear, Paragraph};
 use ratatui::{
     layout::{Constraint, Layout},
     widgets::{Block, BorderType, Borders},
@@ -14,18 +19,43 @@ use tokio::sync::mpsc;
 
 pub struct ConnectionEstablishedDialog {
     credential_table: CredentialTable,
+    // Without credentials a message with the local address is shown instead of the table
+    has_credentials: bool,
+    local_address: Option<String>,
+    command: Option<String>,
+    open_command: Option<OpenCommand>,
+    message_tx: mpsc::Sender<Message>,
 }
 
 impl ConnectionEstablishedDialog {
+    /// `target` is the type and local port of the connected target, if known.
     pub fn new(
         credentials: Vec<boundary::CredentialEntry>,
+        command: Option<String>,
         message_tx: mpsc::Sender<Message>,
+        target: Option<(String, u16)>,
     ) -> Self {
         Self {
-            credential_table: CredentialTable::new(credentials, message_tx),
+            has_credentials: !credentials.is_empty(),
+            local_address: target.as_ref().map(|(_, port)| local_address(*port)),
+            credential_table: CredentialTable::new(credentials, message_tx.clone(), target),
+            command,
+            open_command: None,
+            message_tx,
         }
     }
 
+    /// Offers to open the connected port with the given user-configured command.
+    pub fn with_open_command(mut self, open_command: Option<OpenCommand>) -> Self {
+        self.open_command = open_command;
+        self
+    }
+
+    #[cfg(test)]
+    pub fn command(&self) -> Option<&str> {
+        self.command.as_deref()
+    }
+
     pub fn view(&self, frame: &mut Frame) {
         let area = frame.area();
         let vertical = Layout::vertical([Constraint::Percentage(70)]).flex(Flex::Center);
@@ -45,10 +75,175 @@ impl ConnectionEstablishedDialog {
 
         let inner_area = block.inner(area);
         frame.render_widget(block, area);
-        self.credential_table.view(frame, inner_area)
+
+        let mut command_lines = Vec::new();
+        if let Some(command) = &self.command {
+            command_lines.push(Line::from(format!("Run: {command}   (copy with <x>)")));
+        }
+        if let Some(open_command) = &self.open_command {
+            command_lines.push(Line::from(format!(
+                "Open with {}   (press <o>)",
+                open_command.program
+            )));
+        }
+        command_lines.push(Line::from("Label the connection   (press <l>)"));
+        if !self.has_credentials {
+            let mut lines = vec![Line::from("Connected — no credentials returned").bold()];
+            if let Some(local_address) = &self.local_address {
+                lines.push(Line::from(format!("Local address: {local_address}")));
+            }
+            lines.push(Line::default());
+            lines.extend(command_lines);
+            lines.push(Line::default());
+            lines.push(Line::from(" Close ").reversed());
+            let paragraph = Paragraph::new(lines).alignment(Alignment::Center);
+            frame.render_widget(paragraph, inner_area);
+            return;
+        }
+        let command_height = command_lines.len() as u16 + 1;
+        let [command_area, table_area] = Layout::vertical([
+            Constraint::Length(command_height),
+            Constraint::Fill(1),
+        ])
+        .areas(inner_area);
+        let paragraph = Paragraph::new(command_lines).alignment(Alignment::Center);
+        frame.render_widget(paragraph, command_area);
+        self.credential_table.view(frame, table_area)
+    }
+
+    /// Whether the event left unhandled closes the dialog, `Esc` or `Enter` on the close button
+    /// shown without credentials.
+    pub fn closes_on(&self, event: &Event) -> bool {
+        event.is_esc() || (!self.has_credentials && event.is_enter())
     }
 
-    pub async fn handle_event(&mut self, event: &Event) {
-        self.credential_table.handle_event(event).await;
+    /// Returns whether the event was handled, see `closes_on` for unhandled events.
+    pub async fn handle_event(&mut self, event: &Event) -> bool {
+        if self.has_credentials && self.credential_table.handle_event(event).await {
+            return true;
+        }
+        if let Event::Key(key_event) = event {
+            if key_event.code == KeyCode::Char('x') && key_event.modifiers == KeyModifiers::NONE {
+                self.copy_command_to_clipboard().await;
+                return true;
+            }
+            if key_event.code == KeyCode::Char('o') && key_event.modifiers == KeyModifiers::NONE {
+                self.open().await;
+                return true;
+            }
+        }
+        false
+    }
+
+    async fn copy_command_to_clipboard(&self) {
+        if let Some(command) = &self.command {
+            self
+                .message_tx
+                .send_or_log(Message::SetClipboard {
+                    text: command.clone(),
+                    on_success: Some(Box::new(Message::toast("Command copied"))),
+                    on_error: Some(Box::new(Message::toast("Failed to copy command"))),
+                })
+                .await;
+        }
+    }
+
+    async fn open(&self) {
+        if let Some(open_command) = &self.open_command {
+            if let Err(e) = open_command.spawn() {
+                self
+                    .message_tx
+                    .send_or_log(Message::ShowAlert(
+                        "Failed to open".to_string(),
+                        format!("Could not start '{}': {}", open_command.program, e),
+                    ))
+                    .await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::fixtures::credential_entry;
+    use crossterm::event::KeyEvent;
+
+    fn rendered_text(sut: &ConnectionEstablishedDialog) -> String {
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| sut.view(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer.content().iter().map(|cell| cell.symbol()).collect()
+    }
+
+    #[test]
+    fn without_credentials_a_message_with_the_local_address_is_shown() {
+        let (message_tx, _message_rx) = mpsc::channel(10);
+        let sut = ConnectionEstablishedDialog::new(
+            vec![],
+            None,
+            message_tx,
+            Some(("tcp".to_string(), 8080)),
+        );
+
+        let text = rendered_text(&sut);
+        assert!(text.contains("Connected — no credentials returned"));
+        assert!(text.contains("Local address: 127.0.0.1:8080"));
+        assert!(text.contains("Close"));
+        assert!(!text.contains("Copy Password"), "No credential actions without credentials");
+        assert!(sut.closes_on(&Event::Key(KeyEvent::from(KeyCode::Enter))));
+    }
+
+    #[test]
+    fn with_credentials_enter_does_not_close_the_dialog() {
+        let (message_tx, _message_rx) = mpsc::channel(10);
+        let sut = ConnectionEstablishedDialog::new(vec![credential_entry("admin", "secret")], None, message_tx, None);
+
+        assert!(rendered_text(&sut).contains("admin"));
+        assert!(!sut.closes_on(&Event::Key(KeyEvent::from(KeyCode::Enter))));
+        assert!(sut.closes_on(&Event::Key(KeyEvent::from(KeyCode::Esc))));
+    }
+
+    #[tokio::test]
+    async fn test_filter_input_takes_precedence_over_dialog_keys() {
+        let (message_tx, mut message_rx) = mpsc::channel(10);
+        let mut sut =
+            ConnectionEstablishedDialog::new(vec![credential_entry("admin", "secret")], Some("psql".to_string()), message_tx, None);
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+
+        assert!(sut.handle_event(&key(KeyCode::Char('/'))).await);
+        assert!(sut.handle_event(&key(KeyCode::Char('x'))).await);
+        assert!(message_rx.try_recv().is_err(), "x is part of the search");
+
+        assert!(sut.handle_event(&key(KeyCode::Esc)).await, "Esc resets the filter");
+        assert!(!sut.handle_event(&key(KeyCode::Esc)).await, "Esc closes the dialog");
+        assert!(sut.handle_event(&key(KeyCode::Char('x'))).await);
+        assert!(matches!(message_rx.try_recv(), Ok(Message::SetClipboard { text, .. }) if text == "psql"));
+    }
+
+    #[tokio::test]
+    async fn test_open_reports_spawn_error_as_alert() {
+        let (message_tx, mut message_rx) = mpsc::channel(10);
+        let mut sut = ConnectionEstablishedDialog::new(vec![], None, message_tx, None).with_open_command(
+            Some(OpenCommand {
+                program: "bountui-does-not-exist".to_string(),
+                args: vec!["5432".to_string()],
+            }),
+        );
+
+        sut.handle_event(&Event::Key(KeyEvent::new(
+            KeyCode::Char('o'),
+            KeyModifiers::NONE,
+        )))
+        .await;
+
+        match message_rx.try_recv() {
+            Ok(Message::ShowAlert(title, message)) => {
+                assert_eq!(title, "Failed to open");
+                assert!(message.contains("bountui-does-not-exist"));
+            }
+            _ => panic!("expected an alert"),
+        }
     }
 }
@@ -20,9 +20,10 @@ impl CredentialDialog {
     pub fn new(
         credentials: Vec<boundary::CredentialEntry>,
         message_tx: mpsc::Sender<Message>,
+        database: Option<(String, u16)>,
     ) -> Self {
         Self {
-            credential_table: CredentialTable::new(credentials, message_tx),
+            credential_table: CredentialTable::new(credentials, message_tx, database),
         }
     }
 
@@ -48,7 +49,8 @@ impl CredentialDialog {
         self.credential_table.view(frame, inner_area)
     }
 
-    pub async fn handle_event(&mut self, event: &Event) {
-        self.credential_table.handle_event(event).await;
+    /// Returns whether the event was handled, an unhandled `Esc` closes the dialog.
+    pub async fn handle_event(&mut self, event: &Event) -> bool {
+        self.credential_table.handle_event(event).await
     }
 }
@@ -0,0 +1,156 @@
+use crate::boundary::Scope;
+use crate::bountui::components::table::{Action, FilterItems, SortItems, TableColumn};
+use crate::bountui::components::TablePage;
+use crossterm::event::{Event, KeyCode};
+use ratatui::layout::{Constraint, Flex};
+use ratatui::prelude::{Alignment, Stylize};
+use ratatui::widgets::{Block, BorderType, Borders, Clear};
+use ratatui::Frame;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// A scope with the scopes leading to it from the top down, the scope itself being the last one.
+#[derive(Clone)]
+struct ScopePath {
+    scopes: Vec<Scope>,
+    // Names of the scopes, e.g. "org / project"
+    path: String,
+}
+
+impl ScopePath {
+    fn scope(&self) -> &Scope {
+        self.scopes.last().expect("A path contains at least the scope itself")
+    }
+}
+
+/// Searches all scopes below the global scope by their path, to open one without knowing which
+/// scopes it is nested in.
+pub struct DeepSearchDialog {
+    table: TablePage<ScopePath>,
+}
+
+impl DeepSearchDialog {
+    /// Opens the dialog with the search input shown, the scopes are passed once they are loaded.
+    pub fn new() -> Self {
+        let columns = vec![
+            TableColumn::new(
+                "Scope".to_string(),
+                Constraint::Ratio(6, 10),
+                Box::new(|p: &ScopePath| p.path.clone()),
+            ),
+            TableColumn::new(
+                "Type".to_string(),
+                Constraint::Ratio(1, 10),
+                Box::new(|p: &ScopePath| p.scope().type_name.clone()),
+            ),
+            TableColumn::new(
+                "ID".to_string(),
+                Constraint::Ratio(3, 10),
+                Box::new(|p: &ScopePath| p.scope().id.clone()),
+            ),
+        ];
+
+        let actions = vec![
+            Action::new(
+                "Close".to_string(),
+                "ESC".to_string(),
+                Box::new(|_: Option<&ScopePath>| true),
+            ),
+            Action::new(
+                "Open".to_string(),
+                "⏎".to_string(),
+                Box::new(|item: Option<&ScopePath>| item.is_some()),
+            ),
+        ];
+
+        let mut table = TablePage::new(
+            "Search All Scopes".to_string(),
+            columns,
+            Vec::new(),
+            actions,
+            true,
+        );
+        table.show_filter();
+
+        Self { table }
+    }
+
+    /// Shows the scopes whose parents are all listed as well, others cannot be opened by path.
+    pub fn set_scopes(&mut self, scopes: Vec<Scope>) {
+        let by_id: HashMap<&str, &Scope> = scopes.iter().map(|s| (s.id.as_str(), s)).collect();
+        let paths = scopes
+            .iter()
+            .filter_map(|scope| {
+                let mut path = vec![scope.clone()];
+                let mut parent_id = scope.scope_id.as_str();
+                // Giving up after as many steps as there are scopes in case the parents form a cycle
+                for _ in 0..scopes.len() {
+                    if parent_id == "global" {
+                        path.reverse();
+                        let names: Vec<&str> = path.iter().map(|s| s.name.as_str()).collect();
+                        let path_name = names.join(" / ");
+                        return Some(ScopePath {
+                            scopes: path,
+                            path: path_name,
+                        });
+                    }
+                    let parent = by_id.get(parent_id)?;
+                    parent_id = parent.scope_id.as_str();
+                    path.push((*parent).clone());
+                }
+                None
+            })
+            .collect();
+        self.table.set_items(paths);
+        self.table.loading = false;
+    }
+
+    pub fn view(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let vertical =
+            ratatui::layout::Layout::vertical([Constraint::Percentage(60)]).flex(Flex::Center);
+        let horizontal =
+            ratatui::layout::Layout::horizontal([Constraint::Percentage(70)]).flex(Flex::Center);
+        let [area] = vertical.areas(area);
+        let [area] = horizontal.areas(area);
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .light_blue()
+            .on_black();
+
+        let inner_area = block.inner(area);
+        frame.render_widget(block, area);
+        self.table.view(frame, inner_area);
+    }
+
+    /// Returns the path of the selected scope from the top down once the user confirmed it with
+    /// Enter, also while still typing the search.
+    pub async fn handle_event(&mut self, event: &Event) -> Option<Vec<Scope>> {
+        if let Event::Key(key_event) = event {
+            if key_event.code == KeyCode::Enter {
+                if let Some(path) = self.table.selected_item() {
+                    return Some(path.scopes.clone());
+                }
+            }
+        }
+        self.table.handle_event(event).await;
+        None
+    }
+}
+
+impl SortItems<ScopePath> for TablePage<ScopePath> {
+    fn sort(items: &mut Vec<Rc<ScopePath>>) {
+        items.sort_by(|a, b| a.path.cmp(&b.path));
+    }
+}
+
+impl FilterItems<ScopePath> for TablePage<ScopePath> {
+    fn matches(item: &ScopePath, search: &str) -> bool {
+        Self::match_str(&item.path, search) || Self::match_str(&item.scope().id, search)
+    }
+}
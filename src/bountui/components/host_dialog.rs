@@ -0,0 +1,120 @@
+use crate::boundary::Host;
+use crate::bountui::components::table::{Action, FilterItems, SortItems, TableColumn};
+use crate::bountui::components::TablePage;
+use crossterm::event::{Event, KeyCode};
+use ratatui::layout::{Constraint, Flex};
+use ratatui::prelude::{Alignment, Stylize};
+use ratatui::widgets::{Block, BorderType, Borders, Clear};
+use ratatui::Frame;
+use std::rc::Rc;
+
+/// Lists the hosts of a target Boundary cannot pick a host of itself, to connect to one of them.
+pub struct HostDialog {
+    table: TablePage<Host>,
+    /// The connect request to retry with the picked host.
+    pub target_id: String,
+    pub port: u16,
+    pub keep_alive: bool,
+}
+
+impl HostDialog {
+    pub fn new(target_id: String, port: u16, keep_alive: bool, hosts: Vec<Host>) -> Self {
+        let columns = vec![
+            TableColumn::new(
+                "Name".to_string(),
+                Constraint::Ratio(1, 3),
+                Box::new(|h: &Host| h.name.clone()),
+            ),
+            TableColumn::new(
+                "Address".to_string(),
+                Constraint::Ratio(1, 3),
+                Box::new(|h: &Host| h.address()),
+            ),
+            TableColumn::new(
+                "ID".to_string(),
+                Constraint::Ratio(1, 3),
+                Box::new(|h: &Host| h.id.clone()),
+            ),
+        ];
+
+        let actions = vec![
+            Action::new(
+                "Cancel".to_string(),
+                "ESC".to_string(),
+                Box::new(|_: Option<&Host>| true),
+            ),
+            Action::new(
+                "Connect".to_string(),
+                "⏎".to_string(),
+                Box::new(|item: Option<&Host>| item.is_some()),
+            ),
+        ];
+
+        let notice = hosts
+            .is_empty()
+            .then(|| "The target has no hosts you are allowed to list".to_string());
+        let mut table = TablePage::new(
+            format!("Hosts of {}", target_id),
+            columns,
+            hosts,
+            actions,
+            false,
+        );
+        table.notice = notice;
+
+        Self {
+            table,
+            target_id,
+            port,
+            keep_alive,
+        }
+    }
+
+    pub fn view(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let vertical =
+            ratatui::layout::Layout::vertical([Constraint::Percentage(40)]).flex(Flex::Center);
+        let horizontal =
+            ratatui::layout::Layout::horizontal([Constraint::Percentage(60)]).flex(Flex::Center);
+        let [area] = vertical.areas(area);
+        let [area] = horizontal.areas(area);
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .light_blue()
+            .on_black();
+
+        let inner_area = block.inner(area);
+        frame.render_widget(block, area);
+        self.table.view(frame, inner_area);
+    }
+
+    /// Returns the id of the selected host once the user confirmed it with Enter.
+    pub async fn handle_event(&mut self, event: &Event) -> Option<String> {
+        if let Event::Key(key_event) = event {
+            if key_event.code == KeyCode::Enter {
+                return self.table.selected_item().map(|h| h.id.clone());
+            }
+        }
+        self.table.handle_event(event).await;
+        None
+    }
+}
+
+impl SortItems<Host> for TablePage<Host> {
+    fn sort(items: &mut Vec<Rc<Host>>) {
+        items.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+    }
+}
+
+impl FilterItems<Host> for TablePage<Host> {
+    fn matches(item: &Host, search: &str) -> bool {
+        Self::match_str(&item.name, search)
+            || Self::match_str(&item.address(), search)
+            || Self::match_str(&item.id, search)
+    }
+}
@@ -13,6 +13,8 @@ pub struct InputField<InputId>
     pub id: InputId,
     pub title: String,
     pub value: Input,
+    // Shows a bullet per typed character instead of the value, e.g. for passwords
+    masked: bool,
 }
 
 
@@ -56,8 +58,14 @@ where
             id,
             title: title.into(),
             value: Input::new(value.into()),
+            masked: false,
         }
     }
+
+    pub fn masked(mut self) -> Self {
+        self.masked = true;
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -74,6 +82,7 @@ pub struct InputDialog<FieldId, ButtonId>
     width: Constraint,
     height: Constraint,
     selected_item: SelectedItem,
+    hint: Option<String>,
 }
 
 impl<FieldId, ButtonId> InputDialog<FieldId, ButtonId>
@@ -93,8 +102,14 @@ impl<FieldId, ButtonId> InputDialog<FieldId, ButtonId>
             buttons,
             width,
             height,
+            hint: None,
         }
     }
+
+    pub fn with_hint<T: Into<String>>(mut self, hint: T) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
     
 }
 
@@ -177,8 +192,13 @@ impl<FieldId, ButtonId> InputDialog<FieldId, ButtonId> where FieldId: Clone + Eq
             .iter()
             .flat_map(|field| {
                 let white_space = " ".repeat(max_title_len - field.title.len());
+                let value = if field.masked {
+                    "•".repeat(field.value.value().chars().count())
+                } else {
+                    field.value.to_string()
+                };
                 vec![
-                    Line::from(format!("{}:{} {}", field.title, white_space, field.value)).bold(),
+                    Line::from(format!("{}:{} {}", field.title, white_space, value)).bold(),
                     Line::raw(""),
                 ]
             })
@@ -228,8 +248,10 @@ impl<FieldId, ButtonId> InputDialog<FieldId, ButtonId> where FieldId: Clone + Eq
             .title(self.title.to_string());
         let inner_area = block.inner(area);
 
-        let [input_area, _, button_area, _] = Layout::vertical([
+        let hint_height = if self.hint.is_some() { 1 } else { 0 };
+        let [input_area, hint_area, _, button_area, _] = Layout::vertical([
             Constraint::Fill(1),
+            Constraint::Length(hint_height),
             Constraint::Length(1),
             Constraint::Length(1),
             Constraint::Length(1),
@@ -248,6 +270,12 @@ impl<FieldId, ButtonId> InputDialog<FieldId, ButtonId> where FieldId: Clone + Eq
         frame.render_widget(Clear, area);
         frame.render_widget(block, area);
         frame.render_widget(self.inputs(max_title_len), input_area);
+        if let Some(hint) = &self.hint {
+            frame.render_widget(
+                Paragraph::new(Line::from(hint.as_str()).dark_gray()).alignment(Alignment::Left),
+                hint_area,
+            );
+        }
         frame.render_widget(self.buttons(), button_area);
     }
 
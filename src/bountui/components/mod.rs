@@ -1,14 +1,27 @@
+mod bulk_connect_dialog;
+mod command_registry;
+mod confirm_dialog;
+mod deep_search_dialog;
+mod host_dialog;
 mod input_dialog;
 pub mod table;
 mod connection_result_dialog;
 pub mod credential_table;
 pub mod credential_dialog;
 pub mod target_detail_dialog;
+mod target_type_dialog;
 mod navigation_input;
 pub mod toaster;
-mod util;
+pub mod util;
 
 pub use table::TablePage;
+pub use bulk_connect_dialog::{BulkConnectDialog, BulkConnectRow};
+pub use confirm_dialog::ConfirmDialog;
+pub use input_dialog::{Button, InputDialog, InputField};
+pub use deep_search_dialog::DeepSearchDialog;
 pub use connection_result_dialog::ConnectionEstablishedDialog;
+pub use host_dialog::HostDialog;
 pub use target_detail_dialog::TargetDetailDialog;
+pub use target_type_dialog::TargetTypeDialog;
 pub use navigation_input::*;
+pub use command_registry::{ArgumentCandidates, ArgumentCompleter, CONNECT, GOTO, OPEN};
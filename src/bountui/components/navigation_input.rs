@@ -6,51 +6,397 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Paragraph};
 use tui_input::backend::crossterm::EventHandler;
 use tui_input::Input;
+use crate::bountui::components::command_registry::{
+    ArgumentCandidates, ArgumentCompleter, Command, CommandAction, CommandRegistry,
+};
+use crate::bountui::components::table::EnabledAction;
+use crate::boundary::Target;
+use crate::bountui::recent_pages::RecentPage;
 use crate::bountui::Message;
+use crate::util::MpscSenderExt;
+use std::collections::{HashMap, HashSet};
 
-const SCOPE_TREE: &str = "scope-tree";
-const MY_SESSIONS: &str = "my-sessions";
+// Number of matching commands listed below the input, the others are reached by typing more
+const MAX_SHOWN_COMMANDS: usize = 8;
 
-const OPTIONS: [&'static str; 2] = [SCOPE_TREE, MY_SESSIONS];
+// Added to the score of commands starting with the input, so they always rank first
+const PREFIX_BONUS: i32 = 1000;
+
+/// Scores how well `query` matches `label` as a subsequence, ignoring case, `None` if it does
+/// not. Consecutive characters and word starts score higher, skipped leading characters lower.
+fn fuzzy_score(label: &str, query: &str) -> Option<i32> {
+    let label: Vec<char> = label.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    for q in query.to_lowercase().chars() {
+        let index = position + label[position..].iter().position(|c| *c == q)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == index) {
+            score += 5;
+        }
+        if index == 0 || matches!(label[index - 1], ' ' | '-' | '/' | '(') {
+            score += 3;
+        }
+        if previous.is_none() {
+            score -= index as i32;
+        }
+        previous = Some(index);
+        position = index + 1;
+    }
+    Some(score)
+}
+
+/// Ranks `label` for the typed `value`, commands starting with it rank above all others.
+pub fn rank(label: &str, value: &str) -> Option<i32> {
+    let score = fuzzy_score(label, value)?;
+    if label.to_lowercase().starts_with(&value.to_lowercase()) {
+        Some(score + PREFIX_BONUS)
+    } else {
+        Some(score)
+    }
+}
+
+/// The rest of `option` shown after the typed text when it starts with it, ignoring case.
+/// Compared by characters, so typed accents or emoji never split a character of the option.
+fn ghost_suffix(typed: &str, option: &str) -> Option<String> {
+    let mut option_chars = option.chars();
+    for typed_char in typed.chars() {
+        let option_char = option_chars.next()?;
+        if !typed_char.to_lowercase().eq(option_char.to_lowercase()) {
+            return None;
+        }
+    }
+    Some(option_chars.collect())
+}
 
 pub struct NavigationInput {
     pub input: Input,
-    // Cached matching option for current input value
-    pub matching_option: Option<&'static str>,
+    // Cached matching command label for current input value
+    pub matching_option: Option<String>,
     pub message_tx: tokio::sync::mpsc::Sender<Message>,
+    commands: CommandRegistry,
+    // Indices and ranks of the commands matching the input, best match first
+    matches: Vec<(usize, i32)>,
+    selected: usize,
+    // Loaded argument candidates by command label, and the commands they were requested for
+    argument_candidates: HashMap<String, ArgumentCandidates>,
+    requested_candidates: HashSet<String>,
+    // Completions of the typed argument with their ranks, e.g. `connect ttcp_1`, best first
+    argument_matches: Vec<(String, i32)>,
+    // The best ranked commands Tab cycles through, with the one it completed last
+    completions: Vec<String>,
+    completion_index: usize,
+    // Commands accepted before, the oldest first, recalled with Up from the top of the list
+    history: Vec<String>,
+    // The recalled entry of `history` while browsing it
+    history_index: Option<usize>,
+    // The text typed before browsing the history, restored when going past the newest entry
+    draft: String,
+    // The command run last, taken by the app to add it to the history
+    accepted: Option<String>,
+    // Shown in the input line after confirming text no command matches, until typing on
+    error: Option<String>,
 }
 
 impl NavigationInput {
     pub fn new(message_tx: tokio::sync::mpsc::Sender<Message>) -> Self {
-        NavigationInput {
+        let mut navigation_input = NavigationInput {
             input: Input::default(),
             matching_option: None,
+            message_tx,
+            commands: CommandRegistry::default(),
+            matches: vec![],
+            selected: 0,
+            argument_candidates: HashMap::new(),
+            requested_candidates: HashSet::new(),
+            argument_matches: vec![],
+            completions: vec![],
+            completion_index: 0,
+            history: vec![],
+            history_index: None,
+            draft: String::new(),
+            accepted: None,
+            error: None,
+        };
+        navigation_input.recompute_matching_option();
+        navigation_input
+    }
+
+    /// Lists the pages shown before to jump to one, the most recent first, instead of the
+    /// commands.
+    pub fn recent_pages(message_tx: tokio::sync::mpsc::Sender<Message>, pages: Vec<RecentPage>) -> Self {
+        Self::with_commands(message_tx, CommandRegistry::recent_pages(pages))
+    }
+
+    /// Lists the targets to pick the one to show, when several are named like the name typed
+    /// after `open`.
+    pub fn target_choices(message_tx: tokio::sync::mpsc::Sender<Message>, targets: Vec<Target>) -> Self {
+        Self::with_commands(message_tx, CommandRegistry::target_choices(targets))
+    }
+
+    fn with_commands(message_tx: tokio::sync::mpsc::Sender<Message>, commands: CommandRegistry) -> Self {
+        let mut navigation_input = NavigationInput::new(message_tx);
+        navigation_input.commands = commands;
+        navigation_input.recompute_matching_option();
+        navigation_input
+    }
+
+    /// Adds the actions of the shown page to the palette.
+    pub fn with_page_actions(mut self, actions: Vec<EnabledAction>) -> Self {
+        self.commands.register_page_actions(actions);
+        self.recompute_matching_option();
+        self
+    }
+
+    /// Completes the argument of the command with the label from the candidates the completer
+    /// loads once the argument is typed.
+    pub fn with_argument_completer(mut self, label: &str, completer: ArgumentCompleter) -> Self {
+        self.commands.register_completer(label, completer);
+        self.recompute_matching_option();
+        self
+    }
+
+    /// The candidates loaded for the argument of the command, see `with_argument_completer`.
+    pub fn set_argument_candidates(&mut self, command: &str, candidates: ArgumentCandidates) {
+        self.argument_candidates.insert(command.to_string(), candidates);
+        self.recompute_matching_option();
+    }
+
+    // Loads the candidates in the background, they are passed back with `set_argument_candidates`
+    fn request_argument_candidates(&mut self, label: &str) {
+        if self.argument_candidates.contains_key(label) || self.requested_candidates.contains(label) {
+            return;
+        }
+        let Some(completer) = self.commands.completer(label) else {
+            return;
+        };
+        self.requested_candidates.insert(label.to_string());
+        let candidates = completer();
+        let message_tx = self.message_tx.clone();
+        let command = label.to_string();
+        tokio::spawn(async move {
+            let candidates = candidates.await;
             message_tx
+                .send_or_log(Message::ArgumentCandidates { command, candidates })
+                .await;
+        });
+    }
+
+    /// Sets the commands accepted before, the oldest first.
+    pub fn with_history(mut self, history: Vec<String>) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// The command run last, to remember it and close the palette.
+    pub fn take_accepted_command(&mut self) -> Option<String> {
+        self.accepted.take()
+    }
+
+    fn set_input(&mut self, value: String) {
+        self.input = Input::new(value);
+        self.recompute_matching_option();
+    }
+
+    /// Recalls the previous command, staying at the oldest one.
+    fn history_previous(&mut self) {
+        let index = match self.history_index {
+            _ if self.history.is_empty() => return,
+            None => {
+                self.draft = self.input.value().to_string();
+                self.history.len() - 1
+            }
+            Some(index) => index.saturating_sub(1),
+        };
+        self.history_index = Some(index);
+        self.set_input(self.history[index].clone());
+    }
+
+    /// Recalls the next command, going past the newest one restores the typed text.
+    fn history_next(&mut self) {
+        let Some(index) = self.history_index else {
+            return;
+        };
+        if index + 1 < self.history.len() {
+            self.history_index = Some(index + 1);
+            self.set_input(self.history[index + 1].clone());
+        } else {
+            self.history_index = None;
+            let draft = std::mem::take(&mut self.draft);
+            self.set_input(draft);
         }
     }
 
-    fn compute_matching_option(value: &str) -> Option<&'static str> {
-        if value.is_empty() {
+    // The best ranked command, completed by Tab and shown as ghost text
+    fn compute_matching_option(&self) -> Option<String> {
+        if self.input.value().is_empty() {
             return None;
         }
-        OPTIONS.iter()
-            .find(|opt| opt.starts_with(value))
-            .map(|opt| *opt)
+        if let Some((command, _)) = self.command_with_argument() {
+            let completion = self.argument_matches.first().map(|(completion, _)| completion);
+            return Some(completion.unwrap_or(&command.label).clone());
+        }
+        self.matching_commands().next().map(|command| command.label.clone())
+    }
+
+    // The best ranked completions of the argument or else of the command
+    fn best_completions(&self) -> Vec<String> {
+        let ranked: Vec<(String, i32)> = if self.command_with_argument().is_some() {
+            self.argument_matches.clone()
+        } else {
+            self.matches
+                .iter()
+                .map(|(i, rank)| (self.commands.commands()[*i].label.clone(), *rank))
+                .collect()
+        };
+        let best = ranked.first().map(|(_, rank)| *rank);
+        ranked
+            .into_iter()
+            .take_while(|(_, rank)| Some(*rank) == best)
+            .map(|(completion, _)| completion)
+            .collect()
+    }
+
+    /// Completes the best ranked command, further Tabs cycle through the commands ranked the
+    /// same.
+    fn complete(&mut self) {
+        let cycling = self
+            .completions
+            .get(self.completion_index)
+            .is_some_and(|completion| completion == self.input.value());
+        if cycling {
+            self.completion_index = (self.completion_index + 1) % self.completions.len();
+        } else {
+            self.completions = self.best_completions();
+            self.completion_index = 0;
+        }
+        if let Some(completion) = self.completions.get(self.completion_index).cloned() {
+            self.history_index = None;
+            self.set_input(completion);
+        }
+    }
+
+    /// Whether something was typed that none of the commands matches.
+    pub fn has_no_match(&self) -> bool {
+        self.matching_option.is_none() && self.matches.is_empty() && !self.input.value().is_empty()
+    }
+
+    /// The commands matching the input, best match first.
+    pub fn matching_commands(&self) -> impl Iterator<Item = &Command> {
+        self.matches.iter().map(|(i, _)| &self.commands.commands()[*i])
+    }
+
+    /// Rows needed to show the input, the hint and the matching commands below it.
+    pub fn height(&self) -> u16 {
+        3 + u16::from(self.hint().is_some()) + self.matches.len().min(MAX_SHOWN_COMMANDS) as u16
+    }
+
+    // Shown below the input until typing starts, naming the commands to try first
+    fn hint(&self) -> Option<String> {
+        let labels = self.commands.hinted_labels();
+        if !self.input.value().is_empty() || labels.is_empty() {
+            return None;
+        }
+        Some(format!("{} — Tab to complete, Esc to close", labels.join(" · ")))
+    }
+
+    // The command whose argument is being typed, e.g. `goto <id>`, with the argument
+    fn command_with_argument(&self) -> Option<(&Command, &str)> {
+        let value = self.input.value();
+        self.commands
+            .commands()
+            .iter()
+            .find_map(|command| command.argument(value).map(|argument| (command, argument)))
     }
 
     fn recompute_matching_option(&mut self) {
-        self.matching_option = Self::compute_matching_option(self.input.value());
+        let argument = self
+            .command_with_argument()
+            .map(|(command, argument)| (command.label.clone(), argument.to_string()));
+        self.argument_matches = match argument {
+            Some((label, argument)) => {
+                self.request_argument_candidates(&label);
+                self.rank_argument_candidates(&label, &argument)
+            }
+            None => vec![],
+        };
+        let value = self.input.value();
+        let mut ranked: Vec<(usize, i32)> = if self.command_with_argument().is_some() {
+            vec![]
+        } else {
+            self.commands
+                .commands()
+                .iter()
+                .enumerate()
+                .filter_map(|(i, c)| rank(&c.label, value).map(|rank| (i, rank)))
+                .collect()
+        };
+        // Stable, commands ranking the same keep their order
+        ranked.sort_by_key(|(_, rank)| std::cmp::Reverse(*rank));
+        self.matches = ranked;
+        self.matching_option = self.compute_matching_option();
+        self.selected = 0;
+    }
+
+    // Completions of the argument by how well it matches the value or name of each candidate,
+    // none once a second argument is typed
+    fn rank_argument_candidates(&self, label: &str, argument: &str) -> Vec<(String, i32)> {
+        if argument.contains(char::is_whitespace) {
+            return vec![];
+        }
+        let Some(candidates) = self.argument_candidates.get(label) else {
+            return vec![];
+        };
+        let mut ranked: Vec<(String, i32)> = candidates
+            .iter()
+            .filter_map(|(value, name)| {
+                let best = rank(value, argument).max(rank(name, argument))?;
+                Some((format!("{label} {value}"), best))
+            })
+            .collect();
+        ranked.sort_by_key(|(_, rank)| std::cmp::Reverse(*rank));
+        // Targets in different scopes may share a name, each name is completed once
+        let mut seen = HashSet::new();
+        ranked.retain(|(completion, _)| seen.insert(completion.clone()));
+        ranked
     }
 
-    async fn handle_confirm(&self) {
-        match self.input.value() {
-            SCOPE_TREE => {
-                self.message_tx.send(Message::NavigateToScopeTree).await.unwrap();
+    async fn handle_confirm(&mut self) {
+        if let Some((command, argument)) = self.command_with_argument() {
+            if let CommandAction::WithArgument(message) = command.action {
+                let message = message(argument);
+                self.accepted = Some(format!("{} {}", command.label, argument));
+                self.message_tx.send_or_log(message).await;
+            }
+            return;
+        }
+        let Some(command) = self.matching_commands().nth(self.selected).cloned() else {
+            self.error = Some(format!("unknown command: {}", self.input.value().trim()));
+            return;
+        };
+        match command.action {
+            CommandAction::Send(message) => {
+                self.accepted = Some(command.label);
+                self.message_tx.send_or_log(message()).await;
             },
-            MY_SESSIONS => {
-                self.message_tx.send(Message::NavigateToMySessions).await.unwrap();
+            CommandAction::WithArgument(_) => {
+                // The argument still has to be typed or pasted
+                self.set_input(format!("{} ", command.label));
+            },
+            CommandAction::Key(key) => {
+                self.accepted = Some(command.label);
+                self.message_tx.send_or_log(Message::PageAction(key)).await;
+            },
+            // Not kept in the history, the page may not be listed next time
+            CommandAction::OpenPage(page) => {
+                self.message_tx.send_or_log(Message::OpenRecentPage(*page)).await;
+            },
+            CommandAction::SelectTarget { scope_id, target_id } => {
+                self.message_tx
+                    .send_or_log(Message::ShowTargetsForTarget { scope_id, target_id })
+                    .await;
             },
-            _ => {}
         }
     }
 
@@ -62,15 +408,30 @@ impl NavigationInput {
                     return;
                 }
                 KeyCode::Tab => {
-                    if let Some(opt) = self.matching_option {
-                        self.input = Input::new(opt.to_string());
-                        self.recompute_matching_option();
+                    self.complete();
+                    return;
+                }
+                KeyCode::Up => {
+                    if self.history_index.is_some() || self.selected == 0 {
+                        self.history_previous();
+                    } else {
+                        self.selected -= 1;
+                    }
+                    return;
+                }
+                KeyCode::Down => {
+                    if self.history_index.is_some() {
+                        self.history_next();
+                    } else if self.selected + 1 < self.matches.len().min(MAX_SHOWN_COMMANDS) {
+                        self.selected += 1;
                     }
                     return;
                 }
                 _ => {}
             }
         }
+        self.history_index = None;
+        self.error = None;
         self.input.handle_event(event);
         self.recompute_matching_option();
     }
@@ -79,14 +440,44 @@ impl NavigationInput {
         let block = Block::bordered().cyan().on_black();
         let inner_area = block.inner(area);
         let typed = self.input.value();
-        let mut spans: Vec<Span> = vec![Span::raw("> "), Span::raw(typed.to_string())];
-        if let Some(opt) = self.matching_option {
-            if typed.len() < opt.len() {
-                let rest = &opt[typed.len()..];
-                spans.push(Span::raw(rest).dark_gray());
+        let mut spans: Vec<Span> = vec![Span::raw("> ")];
+        if let Some(error) = &self.error {
+            spans.push(Span::raw(typed.to_string()).red());
+            spans.push(Span::raw(format!("  {error}")).red().italic());
+        } else if self.has_no_match() {
+            spans.push(Span::raw(typed.to_string()).yellow());
+            spans.push(Span::raw("  no match").dark_gray().italic());
+        } else {
+            spans.push(Span::raw(typed.to_string()));
+        }
+        if let Some(opt) = &self.matching_option {
+            match ghost_suffix(typed, opt) {
+                Some(rest) => spans.push(Span::raw(rest).dark_gray()),
+                // A fuzzy match, the whole command is shown next to the typed text
+                None if opt.chars().count() > typed.chars().count() => {
+                    spans.push(Span::raw(format!("  → {opt}")).dark_gray())
+                }
+                None => {}
             }
         }
-        let paragraph = Paragraph::new(Line::from(spans))
+        let mut lines = vec![Line::from(spans)];
+        if let Some(hint) = self.hint() {
+            lines.push(Line::from(Span::raw(hint).dark_gray().italic()));
+        }
+        lines.extend(self.matching_commands().take(MAX_SHOWN_COMMANDS).enumerate().map(
+            |(i, command)| {
+                let line = Line::from(vec![
+                    Span::raw(format!("  {}", command.label)),
+                    Span::raw(format!("  {}", command.hint)).dark_gray(),
+                ]);
+                if i == self.selected {
+                    line.reversed()
+                } else {
+                    line
+                }
+            },
+        ));
+        let paragraph = Paragraph::new(lines)
             .block(block)
             .alignment(Alignment::Left);
         frame.render_widget(paragraph, area);
@@ -101,6 +492,14 @@ impl NavigationInput {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bountui::components::command_registry::{
+        CONNECT, DISCONNECT_ALL, GOTO, LAST_COMMAND, MY_SESSIONS, OPEN, QUIT, RECENT, RECONNECT,
+        REFRESH, SCOPE_TREE, TREE,
+    };
+    use crate::bountui::goto::GotoId;
+    use futures::FutureExt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
     use crossterm::event::{KeyEvent, KeyEventKind, KeyEventState, KeyModifiers};
 
     fn key_char(c: char) -> Event {
@@ -143,8 +542,473 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn unknown_input_has_no_match() {
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        let mut nav = NavigationInput::new(tx);
+        assert!(!nav.has_no_match(), "Empty input is not a miss");
+
+        for c in "xyz".chars() {
+            nav.handle_event(&key_char(c)).await;
+        }
+        assert_eq!(nav.matching_option, None);
+        assert!(nav.has_no_match());
+
+        nav.handle_event(&key_tab()).await;
+        assert_eq!(nav.input.value(), "xyz", "Tab must not complete anything");
+    }
+
+    #[tokio::test]
+    async fn confirming_unknown_input_shows_an_error_until_typing_on() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let mut nav = NavigationInput::new(tx);
+        for c in "foo".chars() {
+            nav.handle_event(&key_char(c)).await;
+        }
+
+        nav.handle_event(&Event::Key(KeyEvent::from(KeyCode::Enter))).await;
+        assert!(rx.try_recv().is_err());
+        assert_eq!(nav.error.as_deref(), Some("unknown command: foo"));
+        assert_eq!(nav.take_accepted_command(), None, "The palette stays open");
+
+        nav.handle_event(&Event::Key(KeyEvent::from(KeyCode::Backspace))).await;
+        assert_eq!(nav.error, None);
+    }
+
     autocomplete_tests! {
         autocomplete_accepts_scope_tree_on_tab: ("sco", "scope-tree"),
         autocomplete_accepts_my_sessions_on_tab: ("my-", "my-sessions"),
+        autocomplete_accepts_a_fuzzy_match_on_tab: ("sessions", "my-sessions"),
+        autocomplete_prefers_a_prefix_match_on_tab: ("tre", "tree"),
+        autocomplete_accepts_an_abbreviation_on_tab: ("lstcmd", "last-command"),
+    }
+
+    #[tokio::test]
+    async fn tab_cycles_through_equally_ranked_matches() {
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        let mut nav = NavigationInput::new(tx).with_page_actions(page_actions());
+        type_text(&mut nav, "s").await;
+
+        let mut completed = vec![];
+        for _ in 0..4 {
+            nav.handle_event(&key_tab()).await;
+            completed.push(nav.input.value().to_string());
+        }
+        assert_eq!(
+            completed,
+            vec![SCOPE_TREE, "Show Sessions", "Search All Scopes", SCOPE_TREE]
+        );
+    }
+
+    async fn confirm(typed: &str) -> Option<Message> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let mut nav = NavigationInput::new(tx);
+        for c in typed.chars() {
+            nav.handle_event(&key_char(c)).await;
+        }
+        nav.handle_event(&Event::Key(KeyEvent::from(KeyCode::Enter))).await;
+        rx.try_recv().ok()
+    }
+
+    #[tokio::test]
+    async fn goto_sends_the_pasted_id() {
+        match confirm("goto ttcp_1234567890").await {
+            Some(Message::Goto(goto_id)) => {
+                assert_eq!(goto_id, GotoId::Target("ttcp_1234567890".to_string()))
+            }
+            _ => panic!("Expected a goto message"),
+        }
+        match confirm("goto  p_1 ").await {
+            Some(Message::Goto(goto_id)) => assert_eq!(goto_id, GotoId::Scope("p_1".to_string())),
+            _ => panic!("Expected a goto message"),
+        }
+    }
+
+    #[tokio::test]
+    async fn goto_with_an_unknown_prefix_is_alerted() {
+        match confirm("goto u_1").await {
+            Some(Message::ShowAlert(title, _)) => assert_eq!(title, "Unknown id"),
+            _ => panic!("Expected an alert"),
+        }
+    }
+
+    #[tokio::test]
+    async fn goto_with_an_id_is_a_match() {
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        let mut nav = NavigationInput::new(tx);
+        for c in "goto s_1".chars() {
+            nav.handle_event(&key_char(c)).await;
+        }
+        assert_eq!(nav.matching_option.as_deref(), Some(GOTO));
+        assert!(!nav.has_no_match());
+    }
+
+    async fn type_text(nav: &mut NavigationInput, text: &str) {
+        for c in text.chars() {
+            nav.handle_event(&key_char(c)).await;
+        }
+    }
+
+    fn page_actions() -> Vec<EnabledAction> {
+        [
+            ("Connect", "c", Some(KeyEvent::from(KeyCode::Char('c')))),
+            ("Show Sessions", "s", Some(KeyEvent::from(KeyCode::Char('s')))),
+            ("Quit", "Ctrl + C", None),
+            ("Previous/Next Scope", "←/→", None),
+            (
+                "Search All Scopes",
+                "Ctrl+/",
+                Some(KeyEvent::new(KeyCode::Char('/'), KeyModifiers::CONTROL)),
+            ),
+        ]
+        .into_iter()
+        .map(|(name, shortcut, key)| EnabledAction {
+            name: name.to_string(),
+            shortcut: shortcut.to_string(),
+            key,
+        })
+        .collect()
+    }
+
+    #[tokio::test]
+    async fn palette_lists_the_page_actions_that_can_be_pressed() {
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        let nav = NavigationInput::new(tx).with_page_actions(page_actions());
+
+        let labels: Vec<&str> = nav.matching_commands().map(|c| c.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            vec![
+                SCOPE_TREE,
+                MY_SESSIONS,
+                TREE,
+                REFRESH,
+                DISCONNECT_ALL,
+                RECONNECT,
+                RECENT,
+                LAST_COMMAND,
+                QUIT,
+                GOTO,
+                OPEN,
+                CONNECT,
+                "Connect",
+                "Show Sessions",
+                "Search All Scopes"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn fuzzy_search_ranks_the_closest_command_first() {
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        let mut nav = NavigationInput::new(tx).with_page_actions(page_actions());
+
+        type_text(&mut nav, "tree").await;
+        assert_eq!(nav.matching_commands().next().unwrap().label, TREE);
+
+        nav.input = Input::default();
+        type_text(&mut nav, "shows").await;
+        let labels: Vec<&str> = nav.matching_commands().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, vec!["Show Sessions"]);
+    }
+
+    #[tokio::test]
+    async fn selecting_a_page_action_presses_its_shortcut() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let mut nav = NavigationInput::new(tx).with_page_actions(page_actions());
+
+        type_text(&mut nav, "conn").await;
+        // Below the `connect <id>` command, which is registered first
+        nav.handle_event(&Event::Key(KeyEvent::from(KeyCode::Down))).await;
+        nav.handle_event(&Event::Key(KeyEvent::from(KeyCode::Enter))).await;
+        match rx.try_recv() {
+            Ok(Message::PageAction(key)) => assert_eq!(key, KeyEvent::from(KeyCode::Char('c'))),
+            _ => panic!("Expected the connect shortcut"),
+        }
+    }
+
+    #[tokio::test]
+    async fn arrow_keys_select_among_the_matches() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let mut nav = NavigationInput::new(tx).with_page_actions(page_actions());
+
+        type_text(&mut nav, "s").await;
+        nav.handle_event(&Event::Key(KeyEvent::from(KeyCode::Down))).await;
+        let second = nav.matching_commands().nth(1).unwrap().clone();
+        nav.handle_event(&Event::Key(KeyEvent::from(KeyCode::Enter))).await;
+        let message = rx.try_recv().ok();
+        match second.action {
+            CommandAction::Send(_) if second.label == MY_SESSIONS => {
+                assert!(matches!(message, Some(Message::NavigateToMySessions)))
+            }
+            CommandAction::Key(key) => {
+                assert!(matches!(message, Some(Message::PageAction(k)) if k == key))
+            }
+            _ => panic!("Unexpected second match {}", second.label),
+        }
+    }
+
+    #[tokio::test]
+    async fn selecting_goto_asks_for_the_id() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let mut nav = NavigationInput::new(tx);
+
+        type_text(&mut nav, "got").await;
+        nav.handle_event(&Event::Key(KeyEvent::from(KeyCode::Enter))).await;
+        assert!(rx.try_recv().is_err());
+        assert_eq!(nav.input.value(), "goto ");
+    }
+
+    #[tokio::test]
+    async fn global_commands_send_their_message() {
+        assert!(matches!(confirm("quit").await, Some(Message::Quit)));
+        assert!(matches!(confirm("disconnect").await, Some(Message::StopAllConnections)));
+        assert!(matches!(confirm("refresh").await, Some(Message::RefreshPage)));
+    }
+
+    #[tokio::test]
+    async fn nothing_is_sent_without_a_match() {
+        assert!(confirm("xyz").await.is_none());
+    }
+
+    fn history_input(history: &[&str]) -> NavigationInput {
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        NavigationInput::new(tx).with_history(history.iter().map(|c| c.to_string()).collect())
+    }
+
+    async fn press(nav: &mut NavigationInput, code: KeyCode) {
+        nav.handle_event(&Event::Key(KeyEvent::from(code))).await;
+    }
+
+    #[tokio::test]
+    async fn up_without_history_keeps_the_input() {
+        let mut nav = history_input(&[]);
+        type_text(&mut nav, "tr").await;
+        press(&mut nav, KeyCode::Up).await;
+        assert_eq!(nav.input.value(), "tr");
+        press(&mut nav, KeyCode::Down).await;
+        assert_eq!(nav.input.value(), "tr");
+    }
+
+    #[tokio::test]
+    async fn history_is_browsed_from_the_newest_command() {
+        let mut nav = history_input(&["tree", "goto p_1"]);
+        type_text(&mut nav, "my").await;
+
+        press(&mut nav, KeyCode::Up).await;
+        assert_eq!(nav.input.value(), "goto p_1");
+        press(&mut nav, KeyCode::Up).await;
+        assert_eq!(nav.input.value(), "tree");
+        press(&mut nav, KeyCode::Up).await;
+        assert_eq!(nav.input.value(), "tree", "The oldest command stays recalled");
+
+        press(&mut nav, KeyCode::Down).await;
+        assert_eq!(nav.input.value(), "goto p_1");
+        press(&mut nav, KeyCode::Down).await;
+        assert_eq!(nav.input.value(), "my", "Going past the newest command restores the typed text");
+        press(&mut nav, KeyCode::Down).await;
+        assert_eq!(nav.input.value(), "my");
+    }
+
+    #[tokio::test]
+    async fn up_selects_the_previous_match_before_recalling_the_history() {
+        let mut nav = history_input(&["tree"]).with_page_actions(page_actions());
+        type_text(&mut nav, "s").await;
+        press(&mut nav, KeyCode::Down).await;
+        press(&mut nav, KeyCode::Up).await;
+        assert_eq!(nav.input.value(), "s");
+        press(&mut nav, KeyCode::Up).await;
+        assert_eq!(nav.input.value(), "tree");
+    }
+
+    #[tokio::test]
+    async fn run_commands_are_accepted_for_the_history() {
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        let mut nav = NavigationInput::new(tx);
+        type_text(&mut nav, "got").await;
+        press(&mut nav, KeyCode::Enter).await;
+        assert_eq!(nav.take_accepted_command(), None, "The id still has to be typed");
+
+        type_text(&mut nav, "p_1").await;
+        press(&mut nav, KeyCode::Enter).await;
+        assert_eq!(nav.take_accepted_command().as_deref(), Some("goto p_1"));
+        assert_eq!(nav.take_accepted_command(), None);
+    }
+
+    fn stub_completer(calls: Arc<AtomicUsize>) -> ArgumentCompleter {
+        Arc::new(move || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async {
+                vec![
+                    ("ttcp_1".to_string(), "db".to_string()),
+                    ("ttcp_2".to_string(), "dashboard".to_string()),
+                    ("tssh_3".to_string(), "web".to_string()),
+                ]
+            }
+            .boxed()
+        })
+    }
+
+    // Passes the loaded candidates back like the app does
+    async fn deliver_candidates(nav: &mut NavigationInput, rx: &mut tokio::sync::mpsc::Receiver<Message>) {
+        match tokio::time::timeout(std::time::Duration::from_secs(1), rx.recv()).await {
+            Ok(Some(Message::ArgumentCandidates { command, candidates })) => {
+                nav.set_argument_candidates(&command, candidates)
+            }
+            _ => panic!("Expected the argument candidates"),
+        }
+    }
+
+    #[tokio::test]
+    async fn argument_candidates_are_loaded_once_the_argument_is_typed() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut nav =
+            NavigationInput::new(tx).with_argument_completer(CONNECT, stub_completer(calls.clone()));
+
+        type_text(&mut nav, "connect").await;
+        assert_eq!(calls.load(Ordering::SeqCst), 0, "Nothing is loaded for the command name");
+        type_text(&mut nav, " t").await;
+        deliver_candidates(&mut nav, &mut rx).await;
+        type_text(&mut nav, "t").await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1, "The candidates are loaded once");
+        assert_eq!(nav.matching_option.as_deref(), Some("connect ttcp_1"));
+    }
+
+    #[tokio::test]
+    async fn tab_cycles_through_the_argument_candidates() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        let mut nav = NavigationInput::new(tx)
+            .with_argument_completer(GOTO, stub_completer(Arc::new(AtomicUsize::new(0))));
+        type_text(&mut nav, "goto ").await;
+        deliver_candidates(&mut nav, &mut rx).await;
+
+        type_text(&mut nav, "t").await;
+        let mut completed = vec![];
+        for _ in 0..4 {
+            nav.handle_event(&key_tab()).await;
+            completed.push(nav.input.value().to_string());
+        }
+        assert_eq!(
+            completed,
+            vec!["goto ttcp_1", "goto ttcp_2", "goto tssh_3", "goto ttcp_1"]
+        );
+    }
+
+    #[tokio::test]
+    async fn argument_candidates_are_found_by_name() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        let mut nav = NavigationInput::new(tx)
+            .with_argument_completer(CONNECT, stub_completer(Arc::new(AtomicUsize::new(0))));
+        type_text(&mut nav, "connect ").await;
+        deliver_candidates(&mut nav, &mut rx).await;
+
+        type_text(&mut nav, "web").await;
+        nav.handle_event(&key_tab()).await;
+        assert_eq!(nav.input.value(), "connect tssh_3");
+
+        // The port is not completed
+        type_text(&mut nav, " 54").await;
+        assert!(nav.argument_matches.is_empty());
+    }
+
+    #[tokio::test]
+    async fn tab_cycles_through_the_matching_names_in_order() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        let names: ArgumentCompleter = Arc::new(|| {
+            async {
+                vec![
+                    ("db-prod".to_string(), "ttcp_1".to_string()),
+                    ("web".to_string(), "ttcp_2".to_string()),
+                    ("db-staging".to_string(), "ttcp_3".to_string()),
+                    // Another scope has a target of the same name
+                    ("db-prod".to_string(), "ttcp_4".to_string()),
+                ]
+            }
+            .boxed()
+        });
+        let mut nav = NavigationInput::new(tx).with_argument_completer(OPEN, names);
+        type_text(&mut nav, "open ").await;
+        deliver_candidates(&mut nav, &mut rx).await;
+
+        type_text(&mut nav, "db").await;
+        assert_eq!(nav.matching_option.as_deref(), Some("open db-prod"), "Shown as ghost text");
+        let mut completed = vec![];
+        for _ in 0..3 {
+            nav.handle_event(&key_tab()).await;
+            completed.push(nav.input.value().to_string());
+        }
+        assert_eq!(completed, vec!["open db-prod", "open db-staging", "open db-prod"]);
+    }
+
+    // The rows of the palette as rendered, the input line first
+    fn rendered_rows(nav: &NavigationInput) -> Vec<String> {
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(80, 16)).unwrap();
+        terminal
+            .draw(|frame| nav.view(frame, Rect::new(0, 0, 80, nav.height())))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (1..nav.height() - 1)
+            .map(|y| (1..79).map(|x| buffer[(x, y)].symbol()).collect::<String>().trim_end().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn ghost_suffix_compares_characters_ignoring_case() {
+        assert_eq!(ghost_suffix("TR", "tree"), Some("ee".to_string()));
+        assert_eq!(ghost_suffix("targets — t", "Targets — team-a"), Some("eam-a".to_string()));
+        assert_eq!(ghost_suffix("é", "tree"), None);
+        assert_eq!(ghost_suffix("tree🚀", "tree"), None, "Typed text longer than the option");
+        assert_eq!(ghost_suffix("tree", "tree"), Some(String::new()));
+    }
+
+    #[tokio::test]
+    async fn non_ascii_input_is_rendered_without_panicking() {
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        for text in ["é", "trè", "🚀", "tree🚀", "ü-scope", "日本"] {
+            let mut nav = NavigationInput::new(tx.clone());
+            type_text(&mut nav, text).await;
+            nav.handle_event(&key_tab()).await;
+            let line = rendered_rows(&nav).remove(0);
+            assert!(line.starts_with("> "), "{text}: {line}");
+        }
+    }
+
+    #[tokio::test]
+    async fn ghost_text_follows_typed_multi_byte_characters() {
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        let pages = vec![RecentPage::Scopes(None)];
+        let mut nav = NavigationInput::recent_pages(tx, pages);
+
+        type_text(&mut nav, "scopes — g").await;
+        assert_eq!(rendered_rows(&nav)[0], "> scopes — global");
+
+        nav.handle_event(&key_tab()).await;
+        assert_eq!(nav.input.value(), "Scopes — global");
+        assert_eq!(rendered_rows(&nav)[0], "> Scopes — global");
+    }
+
+    #[tokio::test]
+    async fn empty_input_shows_a_hint_until_typing_starts() {
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        let mut nav = NavigationInput::new(tx);
+        let hint = "scope-tree · my-sessions · recent · quit — Tab to complete, Esc to close";
+        assert_eq!(rendered_rows(&nav)[1], hint);
+
+        type_text(&mut nav, "t").await;
+        let rows = rendered_rows(&nav);
+        assert!(!rows.iter().any(|row| row == hint), "{rows:?}");
+        assert_eq!(rows.len() - 1, nav.matching_commands().count().min(MAX_SHOWN_COMMANDS));
+
+        nav.handle_event(&Event::Key(KeyEvent::from(KeyCode::Backspace))).await;
+        assert_eq!(rendered_rows(&nav)[1], hint);
+    }
+
+    #[tokio::test]
+    async fn palettes_listing_pages_have_no_hint() {
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        let nav = NavigationInput::recent_pages(tx, vec![RecentPage::Scopes(None)]);
+        assert_eq!(rendered_rows(&nav)[1..], ["  Scopes — global  global"]);
     }
 }
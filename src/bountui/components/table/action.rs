@@ -1,20 +1,50 @@
 
+use crossterm::event::KeyEvent;
+
+/// Decides by the selected item, if any, e.g. whether an action is enabled.
+pub type Predicate<T> = Box<dyn Fn(Option<&T>) -> bool>;
+
 pub struct Action<T> {
     pub name: String,
     pub shortcut: String,
-    pub enabled: Box<dyn Fn(Option<&T>) -> bool>,
+    pub enabled: Predicate<T>,
+    /// Shown in bold, e.g. the one of several actions sharing a shortcut that the shortcut runs.
+    pub highlighted: Option<Predicate<T>>,
+    /// The key of the shortcut, pressed when the action is run from the command palette. `None`
+    /// for shortcuts standing for several keys like "←/→" and for quitting.
+    pub key: Option<KeyEvent>,
+}
+
+/// An action enabled for the selected item, as listed in the legend and the command palette.
+#[derive(Debug, Clone)]
+pub struct EnabledAction {
+    pub name: String,
+    pub shortcut: String,
+    pub key: Option<KeyEvent>,
 }
 
 impl<T> Action<T> {
     pub fn new(
         name: String,
         shortcut: String,
-        enabled: Box<dyn Fn(Option<&T>) -> bool>,
+        enabled: Predicate<T>,
     ) -> Self {
         Self {
             name,
             shortcut,
             enabled,
+            highlighted: None,
+            key: None,
         }
     }
+
+    pub fn with_key<K: Into<KeyEvent>>(mut self, key: K) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    pub fn with_highlight(mut self, highlighted: Predicate<T>) -> Self {
+        self.highlighted = Some(highlighted);
+        self
+    }
 }
@@ -1,30 +1,33 @@
 mod action;
 mod filter;
 pub mod scope;
+pub mod scope_tree;
 pub mod sessions;
 pub mod target;
 mod util;
 
-use crossterm::event::{Event, KeyCode};
+use crossterm::event::{Event, KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::layout::{Alignment, Constraint, Layout};
 use ratatui::style::{Color, Style, Stylize};
 use std::cell::{Cell, RefCell};
-use std::cmp::{max, min};
+use std::cmp::{max, min, Ordering};
+use std::mem;
 
 use crate::bountui::components::table::filter::Filter;
 use crate::bountui::components::util::center;
-use crate::bountui::Message;
-use crate::bountui::Message::GoBack;
-pub use action::Action;
+pub use action::{Action, EnabledAction};
+pub use util::local_address;
 use ratatui::prelude::Rect;
 use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{Block, Paragraph, Row, Table, TableState};
 use ratatui::Frame;
 use std::rc::Rc;
-use tokio::sync::mpsc;
 use tui_input::backend::crossterm::EventHandler;
 use tui_input::Input;
 
+// Shown in the footer instead of the actions while the filter is typed, as the keys apply to it
+const FILTER_INSTRUCTIONS: [(&str, &str); 2] = [("Apply Filter", "⏎"), ("Clear Filter", "Esc")];
+
 pub trait SortItems<T> {
     fn sort(items: &mut Vec<Rc<T>>);
 }
@@ -37,10 +40,33 @@ pub trait FilterItems<T> {
     fn matches(item: &T, search: &str) -> bool;
 }
 
+type CompareItems<T> = Box<dyn Fn(&T, &T) -> Ordering>;
+type ViewFilter<T> = Box<dyn Fn(&T) -> bool>;
+type CellStyle<T> = Box<dyn Fn(&T) -> Style>;
+type ColumnsResized = Box<dyn Fn(Vec<u16>)>;
+
+// Weight added to or removed from the focused column per key press
+const RESIZE_STEP: u16 = 5;
+
+/// A persistent filter shown next to the table title, applied in addition to the text filter.
+pub struct FilterChip<T> {
+    label: String,
+    predicate: Box<dyn Fn(&T) -> bool>,
+}
+
+impl<T> FilterChip<T> {
+    pub fn new(label: String, predicate: Box<dyn Fn(&T) -> bool>) -> Self {
+        FilterChip { label, predicate }
+    }
+}
+
 pub struct TableColumn<T> {
     header: String,
     width: Constraint,
     get_value: Box<dyn Fn(&T) -> String>,
+    get_style: Option<CellStyle<T>>,
+    // Hidden columns are left out of the table until they are shown again
+    hidden: bool,
 }
 
 impl<T> TableColumn<T> {
@@ -49,6 +75,37 @@ impl<T> TableColumn<T> {
             header,
             width,
             get_value,
+            get_style: None,
+            hidden: false,
+        }
+    }
+
+    /// Hides the column until it is shown with [`TablePage::toggle_column`], e.g. for ids.
+    pub fn hidden(mut self) -> Self {
+        self.hidden = true;
+        self
+    }
+
+    /// Styles the cells of this column depending on the item, e.g. to highlight a value.
+    pub fn with_style(mut self, get_style: CellStyle<T>) -> Self {
+        self.get_style = Some(get_style);
+        self
+    }
+
+    fn default_weight(&self) -> u16 {
+        match self.width {
+            Constraint::Ratio(n, d) if d > 0 => (n * 100 / d) as u16,
+            Constraint::Percentage(p) | Constraint::Fill(p) => p,
+            Constraint::Length(n) | Constraint::Min(n) => n,
+            _ => RESIZE_STEP,
+        }
+    }
+
+    fn cell(&self, item: &T) -> ratatui::widgets::Cell<'static> {
+        let cell = ratatui::widgets::Cell::from((self.get_value)(item));
+        match &self.get_style {
+            Some(get_style) => cell.style(get_style(item)),
+            None => cell,
         }
     }
 }
@@ -60,13 +117,35 @@ pub struct TablePage<T> {
     visible_items: Vec<Rc<T>>,
     table_state: RefCell<TableState>,
     filter: Filter,
-    message_tx: mpsc::Sender<Message>,
     actions: Vec<Action<T>>,
     page_size: Cell<usize>,
-    pub loading: bool
+    // Where the table was rendered last, to map mouse clicks to rows
+    table_area: Cell<Rect>,
+    pub loading: bool,
+    // Shown instead of "Loading..." while loading, e.g. how many items were found so far
+    pub progress: Option<String>,
+    // Shown in place of the rows, e.g. when loading the items failed
+    pub notice: Option<String>,
+    // Shown in front of the actions in the footer, e.g. that a page is paused
+    pub status: Option<String>,
+    // Applied after `SortItems::sort` when the user picked a different order at runtime
+    sort_override: Option<CompareItems<T>>,
+    filter_chip: Option<FilterChip<T>>,
+    // Hides items independent of the user filters, e.g. ended sessions
+    view_filter: Option<ViewFilter<T>>,
+    // Highlights rows depending on the item, e.g. the sessions of the current user
+    row_style: Option<CellStyle<T>>,
+    // Rows marked with <Space> when multi selection is enabled
+    multi_select: bool,
+    marked: Vec<Rc<T>>,
+    // Relative column widths once the user resized a column, replacing the column constraints
+    column_weights: Option<Vec<u16>>,
+    // Column resized with Alt + </>, chosen with Alt + Left/Right
+    focused_column: Option<usize>,
+    on_columns_resized: Option<ColumnsResized>,
 }
 impl<T> TablePage<T> where Self: SortItems<T> {
-    pub fn new(title: String, columns: Vec<TableColumn<T>>, items: Vec<T>, actions: Vec<Action<T>>, message_tx: mpsc::Sender<Message>, loading: bool) -> Self {
+    pub fn new(title: String, columns: Vec<TableColumn<T>>, items: Vec<T>, actions: Vec<Action<T>>, loading: bool) -> Self {
         let mut items: Vec<Rc<T>> = items.into_iter().map(Rc::new).collect();
         Self::sort(&mut items);
         let visible_items: Vec<Rc<T>> = items.iter().cloned().collect();
@@ -78,9 +157,21 @@ impl<T> TablePage<T> where Self: SortItems<T> {
             table_state: RefCell::new(TableState::default()),
             filter: Filter::Disabled,
             actions,
-            message_tx,
             page_size: Cell::new(0),
-            loading
+            table_area: Cell::new(Rect::default()),
+            loading,
+            progress: None,
+            notice: None,
+            status: None,
+            sort_override: None,
+            filter_chip: None,
+            view_filter: None,
+            row_style: None,
+            multi_select: false,
+            marked: Vec::new(),
+            column_weights: None,
+            focused_column: None,
+            on_columns_resized: None,
         };
         table_page.select_first_or_none();
         table_page
@@ -90,13 +181,48 @@ impl<T> TablePage<T> where Self: SortItems<T> {
         self.table_state.borrow_mut().select(if self.visible_items.is_empty() { None } else { Some(0) });
     }
 
-    pub fn set_items(&mut self, items: Vec<T>) {
-        self.items = items.into_iter().map(Rc::new).collect();
-        Self::sort(&mut self.items);
-        self.visible_items = self.items.iter().cloned().collect();
+    fn sort_items(&self, items: &mut Vec<Rc<T>>) {
+        Self::sort(items);
+        if let Some(compare) = &self.sort_override {
+            items.sort_by(|a, b| compare(a, b));
+        }
+    }
+
+    /// Replaces the title, e.g. to show an active filter.
+    pub fn set_title(&mut self, title: String) {
+        self.title = title;
+    }
+
+    /// Replaces the header of a column, e.g. to show the current sort order.
+    pub fn set_column_header(&mut self, column: usize, header: String) {
+        if let Some(column) = self.columns.get_mut(column) {
+            column.header = header;
+        }
+    }
+
+    /// Orders the items with `compare` instead of the default order, or restores the default
+    /// order when `None` is passed.
+    pub fn set_sort_override(&mut self, compare: Option<CompareItems<T>>) {
+        self.sort_override = compare;
+        let mut items = mem::take(&mut self.items);
+        self.sort_items(&mut items);
+        self.items = items;
+        let mut visible_items = mem::take(&mut self.visible_items);
+        self.sort_items(&mut visible_items);
+        self.visible_items = visible_items;
+        self.select_first_or_none();
+    }
+
+    pub fn set_items(&mut self, items: Vec<T>) where Self: FilterItems<T> {
+        let mut items = items.into_iter().map(Rc::new).collect();
+        self.sort_items(&mut items);
+        self.items = items;
+        self.marked.clear();
+        // Re-apply the filter so a refresh doesn't drop the filtered view
+        self.refresh_visible_items();
         let selected_optional = self.table_state.borrow().selected();
         if let Some(selected) = selected_optional {
-            if selected >= self.items.len() {
+            if selected >= self.visible_items.len() {
                 self.select_first_or_none();
             }
         } else {
@@ -105,33 +231,238 @@ impl<T> TablePage<T> where Self: SortItems<T> {
 
     }
 
+    /// Like [`TablePage::set_items`], but keeps the same item selected, identified by `key`,
+    /// if it is still visible after the update.
+    pub fn set_items_keeping_selection<K: PartialEq>(&mut self, items: Vec<T>, key: impl Fn(&T) -> K)
+    where
+        Self: FilterItems<T>,
+    {
+        let selected_key = self.selected_item().map(|item| key(&item));
+        self.set_items(items);
+        let position = selected_key
+            .and_then(|selected_key| self.visible_items.iter().position(|i| key(i) == selected_key));
+        if let Some(position) = position {
+            self.table_state.borrow_mut().select(Some(position));
+        }
+    }
+
+    /// Selects the first visible item matching `predicate`, returns whether there was one.
+    pub fn select_where(&mut self, predicate: impl Fn(&T) -> bool) -> bool {
+        let position = self.visible_items.iter().position(|i| predicate(i));
+        if position.is_some() {
+            self.table_state.borrow_mut().select(position);
+        }
+        position.is_some()
+    }
+
     pub fn selected_item(&self) -> Option<Rc<T>> {
         self.table_state.borrow_mut().selected()
             .map(|i| self.visible_items.get(i).cloned())
             .flatten()
     }
 
-    fn reset_filter(&mut self) {
+    fn filter_value(&self) -> Option<&str> {
+        match &self.filter {
+            Filter::Disabled => None,
+            Filter::Input(input) => Some(input.value()),
+            Filter::Value(value) => Some(value.as_str()),
+        }
+    }
+
+    fn refresh_visible_items(&mut self) where Self: FilterItems<T> {
+        let search = self.filter_value().unwrap_or_default();
+        let chip = self.filter_chip.as_ref();
+        self.visible_items = self
+            .items
+            .iter()
+            .filter(|i| self.view_filter.as_ref().is_none_or(|f| f(i.as_ref())))
+            .filter(|i| chip.is_none_or(|c| (c.predicate)(i.as_ref())))
+            .filter(|i| search.is_empty() || Self::matches(i.as_ref(), search))
+            .map(Rc::clone)
+            .collect();
+    }
+
+    /// Sets or clears the filter chip and updates the visible items accordingly.
+    pub fn set_filter_chip(&mut self, chip: Option<FilterChip<T>>) where Self: FilterItems<T> {
+        self.filter_chip = chip;
+        self.refresh_visible_items();
+        self.select_first_or_none();
+    }
+
+    /// Sets or clears the view filter, which is applied before the filter chip and the text filter.
+    pub fn set_view_filter(&mut self, view_filter: Option<ViewFilter<T>>) where Self: FilterItems<T> {
+        self.view_filter = view_filter;
+        self.refresh_visible_items();
+        self.select_first_or_none();
+    }
+
+    /// Styles whole rows depending on the item, marked rows keep their highlight.
+    pub fn set_row_style(&mut self, row_style: CellStyle<T>) {
+        self.row_style = Some(row_style);
+    }
+
+    /// Lets the user resize the columns, `on_resized` is called with the new widths after each change.
+    pub fn enable_column_resizing(&mut self, on_resized: ColumnsResized) {
+        self.on_columns_resized = Some(on_resized);
+    }
+
+    /// Restores widths previously passed to the resize callback, ignored if they don't fit the columns.
+    pub fn set_column_widths(&mut self, widths: &[u16]) {
+        if widths.len() == self.columns.len() && widths.iter().all(|w| *w > 0) {
+            self.column_weights = Some(widths.to_vec());
+        }
+    }
+
+    fn column_weights(&self) -> Vec<u16> {
+        self.column_weights.clone().unwrap_or_else(|| {
+            self.columns
+                .iter()
+                .map(TableColumn::default_weight)
+                .collect()
+        })
+    }
+
+    fn focus_column(&mut self, forward: bool) {
+        let shown: Vec<usize> = self.shown_columns().map(|(i, _)| i).collect();
+        let focused = match self.focused_column {
+            None => shown.first(),
+            Some(i) if forward => shown.iter().find(|c| **c > i).or(shown.last()),
+            Some(i) => shown.iter().rev().find(|c| **c < i).or(shown.first()),
+        };
+        self.focused_column = focused.copied();
+    }
+
+    /// Shows the hidden column with the given header or hides it if it is shown.
+    pub fn toggle_column(&mut self, header: &str) {
+        if let Some(column) = self.columns.iter_mut().find(|c| c.header == header) {
+            column.hidden = !column.hidden;
+        }
+        if self.focused_column.is_some_and(|i| self.columns[i].hidden) {
+            self.focused_column = None;
+        }
+    }
+
+    /// The columns that are not hidden, with their index among all columns.
+    fn shown_columns(&self) -> impl Iterator<Item = (usize, &TableColumn<T>)> {
+        self.columns.iter().enumerate().filter(|(_, c)| !c.hidden)
+    }
+
+    fn resize_focused_column(&mut self, grow: bool) {
+        let Some(focused) = self
+            .focused_column
+            .or_else(|| self.shown_columns().next().map(|(i, _)| i))
+        else {
+            return;
+        };
+        self.focused_column = Some(focused);
+        let mut weights = self.column_weights();
+        if let Some(weight) = weights.get_mut(focused) {
+            *weight = if grow {
+                weight.saturating_add(RESIZE_STEP)
+            } else {
+                max(weight.saturating_sub(RESIZE_STEP), 1)
+            };
+        }
+        self.column_weights = Some(weights.clone());
+        if let Some(on_resized) = &self.on_columns_resized {
+            on_resized(weights);
+        }
+    }
+
+    fn handle_resize_event(&mut self, event: &Event) -> bool {
+        let Event::Key(key_event) = event else {
+            return false;
+        };
+        if self.on_columns_resized.is_none() || !key_event.modifiers.contains(KeyModifiers::ALT) {
+            return false;
+        }
+        match key_event.code {
+            KeyCode::Left => self.focus_column(false),
+            KeyCode::Right => self.focus_column(true),
+            KeyCode::Char('<') | KeyCode::Char(',') => self.resize_focused_column(false),
+            KeyCode::Char('>') | KeyCode::Char('.') => self.resize_focused_column(true),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Number of items hidden by the view filter.
+    pub fn hidden_count(&self) -> usize {
+        self.view_filter.as_ref().map_or(0, |f| {
+            self.items.iter().filter(|i| !f(i.as_ref())).count()
+        })
+    }
+
+    /// Lets the user mark several rows with <Space>.
+    pub fn enable_multi_select(&mut self) {
+        self.multi_select = true;
+    }
+
+    /// The marked rows in table order.
+    pub fn marked_items(&self) -> Vec<Rc<T>> {
+        self.items
+            .iter()
+            .filter(|i| self.is_marked(i))
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+    }
+
+    fn is_marked(&self, item: &Rc<T>) -> bool {
+        self.marked.iter().any(|m| Rc::ptr_eq(m, item))
+    }
+
+    fn toggle_mark(&mut self) {
+        if let Some(item) = self.selected_item() {
+            if self.is_marked(&item) {
+                self.marked.retain(|m| !Rc::ptr_eq(m, &item));
+            } else {
+                self.marked.push(item);
+            }
+        }
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = &T> {
+        self.items.iter().map(Rc::as_ref)
+    }
+
+    /// The items left after filtering, in the order they are shown.
+    pub fn shown_items(&self) -> impl Iterator<Item = &T> {
+        self.visible_items.iter().map(Rc::as_ref)
+    }
+
+    fn reset_filter(&mut self) where Self: FilterItems<T> {
         self.filter = Filter::Disabled;
-        self.visible_items = self.items.iter().cloned().collect();
+        self.refresh_visible_items();
         self.select_first_or_none();
     }
 
     fn update_filter(&mut self, event: &Event) where TablePage<T>: FilterItems<T>  {
         if let Filter::Input(filter_input) = &mut self.filter {
             filter_input.handle_event(event);
-            let value = filter_input.value().to_string();
-            self.visible_items = self
-                .items
-                .iter()
-                .filter(|i| Self::matches(i.as_ref(), &value))
-                .map(Rc::clone)
-                .collect();
+            self.refresh_visible_items();
             self.select_first_or_none();
         }
     }
 
-    fn show_filter(&mut self) {
+    /// The actions enabled for the selected item.
+    pub fn enabled_actions(&self) -> Vec<EnabledAction> {
+        let selected = self.selected_item();
+        self.actions
+            .iter()
+            .filter(|a| (a.enabled)(selected.as_deref()))
+            .map(|a| EnabledAction {
+                name: a.name.clone(),
+                shortcut: a.shortcut.clone(),
+                key: a.key,
+            })
+            .collect()
+    }
+
+    pub fn show_filter(&mut self) {
         self.filter =  if let Filter::Value(filter_value) = &self.filter {
             Filter::Input(Input::new(filter_value.to_string()))
         }
@@ -148,6 +479,9 @@ impl<T> TablePage<T> where Self: SortItems<T> {
     }
 
     fn next_page(&self) {
+        if self.visible_items.is_empty() {
+            return;
+        }
         let mut table_state = self.table_state.borrow_mut();
         let new_selected = min(table_state.offset() + self.page_size.get(), self.visible_items.len() - 1);
         *table_state.offset_mut() = min(new_selected, self.visible_items.len().saturating_sub(self.page_size.get()
@@ -161,20 +495,81 @@ impl<T> TablePage<T> where Self: SortItems<T> {
         table_state.select(Some(new_selected));
     }
 
+    fn select_next(&self) {
+        let current = self.table_state.borrow().selected().unwrap_or(0);
+        if current + 1 < self.visible_items.len() {
+            self.table_state.borrow_mut().select_next();
+        }
+    }
+
+    /// Index of the visible item in the given terminal row, if any.
+    fn item_at_row(&self, row: u16) -> Option<usize> {
+        let area = self.table_area.get();
+        // The rows start below the top border and the header, the bottom border follows them
+        let first_row = area.y + 2;
+        if row < first_row || row + 1 >= area.bottom() {
+            return None;
+        }
+        let index = self.table_state.borrow().offset() + (row - first_row) as usize;
+        (index < self.visible_items.len()).then_some(index)
+    }
+
+    fn handle_mouse_event(&mut self, mouse_event: &MouseEvent) -> bool {
+        match mouse_event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let area = self.table_area.get();
+                if mouse_event.column < area.x || mouse_event.column >= area.right() {
+                    return false;
+                }
+                match self.item_at_row(mouse_event.row) {
+                    Some(index) => {
+                        self.table_state.borrow_mut().select(Some(index));
+                        true
+                    }
+                    None => false,
+                }
+            }
+            MouseEventKind::ScrollDown => {
+                self.select_next();
+                true
+            }
+            MouseEventKind::ScrollUp => {
+                self.table_state.borrow_mut().select_previous();
+                true
+            }
+            _ => false,
+        }
+    }
+
     fn instructions(&'_ self) -> Line<'_>
     {
-        let spans: Vec<Span> = self
+        let mut spans: Vec<Span> = Vec::new();
+        if let Some(status) = &self.status {
+            spans.push(Span::from(format!(" {status} ")).fg(Color::Yellow).reversed());
+        }
+        if self.filter.is_input() {
+            spans.extend(
+                FILTER_INSTRUCTIONS
+                    .iter()
+                    .map(|(name, shortcut)| Span::from(format!("  {name}<{shortcut}>  "))),
+            );
+            return Line::from(spans);
+        }
+        spans.extend(self
             .actions
             .iter()
             .map(|c| {
                 let span = Span::from(format!("  {}<{}>  ", c.name, c.shortcut));
-                if (c.enabled)(self.selected_item().as_deref()) {
-                    span
+                let selected = self.selected_item();
+                if (c.enabled)(selected.as_deref()) {
+                    match &c.highlighted {
+                        Some(highlighted) if highlighted(selected.as_deref()) => span.bold(),
+                        _ => span,
+                    }
                 } else {
                     span.fg(Color::DarkGray)
                 }
-            })
-            .collect();
+            }));
 
         Line::from(spans)
     }
@@ -184,10 +579,17 @@ impl<T> TablePage<T> where Self: SortItems<T> {
             .visible_items
             .iter()
             .map(|i| {
-                self.columns
-                    .iter()
-                    .map(|c| (c.get_value)(i.as_ref()))
-                    .collect()
+                let row: Row = self
+                    .shown_columns()
+                    .map(|(_, c)| c.cell(i.as_ref()))
+                    .collect();
+                if self.is_marked(i) {
+                    row.fg(Color::Yellow).bold()
+                } else if let Some(row_style) = &self.row_style {
+                    row.style(row_style(i.as_ref()))
+                } else {
+                    row
+                }
             })
             .collect()
     }
@@ -198,30 +600,48 @@ impl<T> TablePage<T> where Self: SortItems<T> {
 
         let rows: Vec<Row> = self.rows();
 
-        let block = Block::bordered()
+        let mut block = Block::bordered()
             .title(title.centered())
             .title_bottom(self.instructions().centered())
             .light_blue()
             .bg(Color::Black);
+        if let Some(chip) = &self.filter_chip {
+            block = block.title(Line::from(format!(" [{}] <Esc> ", chip.label).reversed()).right_aligned());
+        }
         let header_items: Vec<Span> = self
-            .columns
-            .iter()
-            .map(|c| c.header.clone().bold().fg(Color::White))
+            .shown_columns()
+            .map(|(i, c)| {
+                let header = c.header.clone().bold().fg(Color::White);
+                if self.focused_column == Some(i) {
+                    header.underlined()
+                } else {
+                    header
+                }
+            })
             .collect();
         let header = Row::new(header_items);
 
-        let width_constraints: Vec<Constraint> = self.columns.iter().map(|c| c.width).collect();
+        let width_constraints: Vec<Constraint> = match &self.column_weights {
+            Some(weights) => self
+                .shown_columns()
+                .map(|(i, _)| Constraint::Fill(weights[i]))
+                .collect(),
+            None => self.shown_columns().map(|(_, c)| c.width).collect(),
+        };
         Table::new(rows, width_constraints)
             .header(header)
             .row_highlight_style(Style::new().reversed())
             .block(block)
     }
 
-    async fn go_back(&self) {
-        self.message_tx.send(GoBack).await.unwrap()
-    }
-
     pub async fn handle_event(&mut self, event: &Event) -> bool where TablePage<T>: FilterItems<T> {
+        // Typed into the filter, some layouts need Alt to type characters
+        if !self.filter.is_input() && self.handle_resize_event(event) {
+            return true;
+        }
+        if let Event::Mouse(mouse_event) = event {
+            return self.handle_mouse_event(mouse_event);
+        }
         if self.filter.is_input() {
             match event {
                 Event::Key(key_event) => {
@@ -249,13 +669,21 @@ impl<T> TablePage<T> where Self: SortItems<T> {
 
         if let Event::Key(key_event) = event {
             match key_event.code {
-                KeyCode::Esc => {
-                    if self.filter.is_active() {
-                        self.reset_filter();
-                    }
-                    else {
-                        self.go_back().await;
-                    }
+                // Esc only resets filters, navigating back is up to the page
+                KeyCode::Esc if self.filter.is_active() => {
+                    self.reset_filter();
+                    return true
+                }
+                KeyCode::Esc if self.filter_chip.is_some() => {
+                    self.set_filter_chip(None);
+                    return true
+                }
+                KeyCode::Esc if !self.marked.is_empty() => {
+                    self.clear_marks();
+                    return true
+                }
+                KeyCode::Char(' ') if self.multi_select => {
+                    self.toggle_mark();
                     return true
                 }
                 KeyCode::Up => {
@@ -263,10 +691,7 @@ impl<T> TablePage<T> where Self: SortItems<T> {
                     return true;
                 }
                 KeyCode::Down => {
-                    let current = self.table_state.borrow().selected().unwrap_or(0);
-                    if current + 1 < self.visible_items.len() {
-                        self.table_state.borrow_mut().select_next();
-                    }
+                    self.select_next();
                     return true;
                 },
                 KeyCode::PageDown => {
@@ -300,6 +725,7 @@ impl<T> TablePage<T> where Self: SortItems<T> {
         let [search_area, table_area] = Layout::vertical(layout_constraints).areas(area);
 
         self.page_size.set(table_area.height as usize - 3);
+        self.table_area.set(table_area);
 
         if let Filter::Input(search) = &self.filter {
             let block = Block::bordered().light_blue().on_black();
@@ -313,12 +739,19 @@ impl<T> TablePage<T> where Self: SortItems<T> {
         frame.render_stateful_widget(self.table(), table_area, &mut self.table_state.borrow_mut());
 
         if self.loading {
-            let loading_text = Text::raw("Loading...");
+            let loading_text = Text::raw(self.progress.as_deref().unwrap_or("Loading..."));
             let width = loading_text.width() + 2;
             let loading = Paragraph::new(loading_text)
                 .block(Block::bordered().light_blue().on_black());
             let loading_area = center(table_area, Constraint::Length(width as u16), Constraint::Length(3));
             frame.render_widget(loading, loading_area);
+        } else if let Some(notice) = &self.notice {
+            let notice_text = Text::raw(notice.as_str());
+            let width = notice_text.width() + 2;
+            let notice = Paragraph::new(notice_text)
+                .block(Block::bordered().light_blue().on_black());
+            let notice_area = center(table_area, Constraint::Length(width as u16), Constraint::Length(3));
+            frame.render_widget(notice, notice_area);
         }
 
     }
@@ -327,14 +760,13 @@ impl<T> TablePage<T> where Self: SortItems<T> {
 
 #[cfg(test)]
 mod test {
-    use crate::bountui::components::table::{FilterItems, SortItems, TableColumn};
+    use crate::bountui::components::table::{Action, FilterChip, FilterItems, SortItems, TableColumn};
     use crate::bountui::components::TablePage;
-    use crate::bountui::Message;
-    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
     use ratatui::prelude::Constraint;
+    use std::cell::RefCell;
     use std::rc::Rc;
-    use tokio::sync::mpsc;
-
+    
     struct TestItem {
         col_a: String,
         col_b: i32
@@ -351,7 +783,7 @@ mod test {
         }
     }
 
-    fn create_table_page(message_tx: mpsc::Sender<Message>) ->TablePage<TestItem> {
+    fn create_table_page() ->TablePage<TestItem> {
 
         let cols: Vec<TableColumn<TestItem>> = vec![
             TableColumn::new("Col A".to_string(), Constraint::Ratio(1, 2), Box::new(|i| i.col_a.to_string())),
@@ -374,7 +806,6 @@ mod test {
             cols,
             items,
             vec![],
-            message_tx,
             false
         )
     }
@@ -382,8 +813,7 @@ mod test {
 
     #[tokio::test]
     async fn test_cancel_filter() {
-        let (message_tx, _message_rx) = mpsc::channel(1);
-        let mut sut = create_table_page(message_tx);
+        let mut sut = create_table_page();
         sut.handle_event(&Event::Key(KeyEvent::new(KeyCode::Char('/'), KeyModifiers::NONE))).await;
         assert_eq!(sut.filter.is_active(), true);
         sut.handle_event(&Event::Key(KeyEvent::new(KeyCode::Char('o'), KeyModifiers::NONE))).await;
@@ -395,5 +825,206 @@ mod test {
         assert_eq!(sut.filter.is_active(), false);
     }
 
+    fn alt(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::ALT))
+    }
+
+    #[tokio::test]
+    async fn test_resize_focused_column() {
+        let mut sut = create_table_page();
+        let resized: Rc<RefCell<Vec<Vec<u16>>>> = Rc::default();
+        let resized_for_callback = resized.clone();
+        sut.enable_column_resizing(Box::new(move |w| resized_for_callback.borrow_mut().push(w)));
+
+        assert!(sut.handle_event(&alt(KeyCode::Right)).await);
+        assert!(sut.handle_event(&alt(KeyCode::Right)).await);
+        assert_eq!(sut.focused_column, Some(1));
+        sut.handle_event(&alt(KeyCode::Char('>'))).await;
+        sut.handle_event(&alt(KeyCode::Char('>'))).await;
+        assert_eq!(sut.column_weights, Some(vec![50, 60]));
+
+        sut.handle_event(&alt(KeyCode::Left)).await;
+        sut.handle_event(&alt(KeyCode::Char('<'))).await;
+        assert_eq!(sut.column_weights, Some(vec![45, 60]));
+        assert_eq!(resized.borrow().last(), Some(&vec![45, 60]), "Every change is reported");
+        assert_eq!(resized.borrow().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_hidden_columns_are_not_focused() {
+        let mut sut = create_table_page();
+        sut.enable_column_resizing(Box::new(|_| {}));
+        sut.toggle_column("Col A");
+
+        sut.handle_event(&alt(KeyCode::Char('>'))).await;
+        assert_eq!(sut.focused_column, Some(1), "The first shown column is resized");
+        sut.handle_event(&alt(KeyCode::Left)).await;
+        assert_eq!(sut.focused_column, Some(1));
 
-}
\ No newline at end of file
+        sut.toggle_column("Col A");
+        sut.handle_event(&alt(KeyCode::Left)).await;
+        assert_eq!(sut.focused_column, Some(0));
+        sut.toggle_column("Col A");
+        assert_eq!(sut.focused_column, None, "Hiding the focused column drops the focus");
+    }
+
+    #[tokio::test]
+    async fn test_resize_keys_are_typed_into_the_filter() {
+        let mut sut = create_table_page();
+        sut.enable_column_resizing(Box::new(|_| {}));
+        sut.handle_event(&Event::Key(KeyEvent::new(KeyCode::Char('/'), KeyModifiers::NONE))).await;
+
+        sut.handle_event(&alt(KeyCode::Char('.'))).await;
+        sut.handle_event(&alt(KeyCode::Char(','))).await;
+        assert_eq!(sut.column_weights, None);
+        assert!(sut.filter.is_input());
+    }
+
+    #[tokio::test]
+    async fn test_resize_keeps_columns_visible() {
+        let mut sut = create_table_page();
+        sut.enable_column_resizing(Box::new(|_| {}));
+        for _ in 0..20 {
+            sut.handle_event(&alt(KeyCode::Char('<'))).await;
+        }
+        assert_eq!(sut.column_weights, Some(vec![1, 50]));
+    }
+
+    #[tokio::test]
+    async fn test_resize_requires_enabled_resizing() {
+        let mut sut = create_table_page();
+        assert!(!sut.handle_event(&alt(KeyCode::Char('>'))).await);
+        assert_eq!(sut.column_weights, None);
+    }
+
+    #[test]
+    fn test_set_column_widths() {
+        let mut sut = create_table_page();
+        sut.set_column_widths(&[10, 20, 30]);
+        assert_eq!(sut.column_weights, None, "Widths of other columns are ignored");
+        sut.set_column_widths(&[10, 20]);
+        assert_eq!(sut.column_weights, Some(vec![10, 20]));
+    }
+
+    #[tokio::test]
+    async fn test_set_items_keeps_filter() {
+        let mut sut = create_table_page();
+        sut.handle_event(&Event::Key(KeyEvent::new(KeyCode::Char('/'), KeyModifiers::NONE))).await;
+        sut.handle_event(&Event::Key(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE))).await;
+        sut.handle_event(&Event::Key(KeyEvent::new(KeyCode::Char('w'), KeyModifiers::NONE))).await;
+        assert_eq!(sut.visible_items.len(), 1);
+
+        let refreshed = || vec![
+            TestItem { col_a: "one".to_string(), col_b: 1 },
+            TestItem { col_a: "two".to_string(), col_b: 2 },
+            TestItem { col_a: "twenty".to_string(), col_b: 20 },
+        ];
+        sut.set_items(refreshed());
+        assert!(sut.filter.is_active());
+        assert_eq!(
+            sut.visible_items.iter().map(|i| i.col_a.as_str()).collect::<Vec<_>>(),
+            vec!["two", "twenty"]
+        );
+
+        // Also when the filter input was closed with Enter
+        sut.handle_event(&Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))).await;
+        sut.set_items(refreshed());
+        assert_eq!(sut.visible_items.len(), 2);
+        assert_eq!(sut.selected_item().map(|i| i.col_a.clone()), Some("two".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_filter_chip_combines_with_text_filter() {
+        let mut sut = create_table_page();
+        sut.set_filter_chip(Some(FilterChip::new(
+            "two".to_string(),
+            Box::new(|i: &TestItem| i.col_a == "two"),
+        )));
+        assert_eq!(sut.visible_items.len(), 1);
+        sut.handle_event(&Event::Key(KeyEvent::new(KeyCode::Char('/'), KeyModifiers::NONE))).await;
+        sut.handle_event(&Event::Key(KeyEvent::new(KeyCode::Char('o'), KeyModifiers::NONE))).await;
+        sut.handle_event(&Event::Key(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE))).await;
+        assert_eq!(sut.visible_items.len(), 0, "Text filter and chip should both apply");
+        sut.handle_event(&Event::Key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE))).await;
+        assert_eq!(sut.visible_items.len(), 1, "Esc should clear the text filter first");
+        sut.handle_event(&Event::Key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE))).await;
+        assert_eq!(sut.visible_items.len(), 2, "Esc should clear the chip next");
+        assert!(sut.filter_chip.is_none());
+    }
+
+    fn mouse(kind: MouseEventKind, column: u16, row: u16) -> Event {
+        Event::Mouse(MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        })
+    }
+
+    fn render(sut: &TablePage<TestItem>) {
+        let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(40, 10)).unwrap();
+        terminal.draw(|frame| sut.view(frame, frame.area())).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_click_selects_row() {
+        let mut sut = create_table_page();
+        render(&sut);
+        let click = |row| mouse(MouseEventKind::Down(MouseButton::Left), 5, row);
+
+        // Border in row 0, header in row 1, items from row 2
+        assert!(sut.handle_event(&click(3)).await);
+        assert_eq!(sut.selected_item().map(|i| i.col_a.clone()), Some("two".to_string()));
+        assert!(sut.handle_event(&click(2)).await);
+        assert_eq!(sut.selected_item().map(|i| i.col_a.clone()), Some("one".to_string()));
+
+        assert!(!sut.handle_event(&click(1)).await, "Clicking the header selects nothing");
+        assert!(!sut.handle_event(&click(4)).await, "Clicking below the items selects nothing");
+        assert_eq!(sut.selected_item().map(|i| i.col_a.clone()), Some("one".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_wheel_moves_selection() {
+        let mut sut = create_table_page();
+        render(&sut);
+        sut.handle_event(&mouse(MouseEventKind::ScrollDown, 5, 5)).await;
+        assert_eq!(sut.selected_item().map(|i| i.col_a.clone()), Some("two".to_string()));
+        sut.handle_event(&mouse(MouseEventKind::ScrollDown, 5, 5)).await;
+        assert_eq!(sut.selected_item().map(|i| i.col_a.clone()), Some("two".to_string()), "Stays on the last item");
+        sut.handle_event(&mouse(MouseEventKind::ScrollUp, 5, 5)).await;
+        assert_eq!(sut.selected_item().map(|i| i.col_a.clone()), Some("one".to_string()));
+    }
+
+    // The footer of the table as rendered
+    fn rendered_instructions(sut: &TablePage<TestItem>) -> String {
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(80, 8)).unwrap();
+        terminal.draw(|frame| sut.view(frame, frame.area())).unwrap();
+        let buffer = terminal.backend().buffer();
+        (1..79)
+            .map(|x| buffer[(x, 7)].symbol())
+            .collect::<String>()
+            .trim_matches('─')
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_instructions_follow_the_filter_input() {
+        let mut sut = create_table_page();
+        sut.actions = vec![Action::new(
+            "Open".to_string(),
+            "⏎".to_string(),
+            Box::new(|item: Option<&TestItem>| item.is_some()),
+        )];
+        assert_eq!(rendered_instructions(&sut), "  Open<⏎>  ");
+
+        sut.handle_event(&Event::Key(KeyEvent::new(KeyCode::Char('/'), KeyModifiers::NONE))).await;
+        assert_eq!(rendered_instructions(&sut), "  Apply Filter<⏎>    Clear Filter<Esc>  ");
+        sut.handle_event(&Event::Key(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE))).await;
+        assert_eq!(rendered_instructions(&sut), "  Apply Filter<⏎>    Clear Filter<Esc>  ");
+
+        // The applied filter no longer takes the keys
+        sut.handle_event(&Event::Key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))).await;
+        assert_eq!(rendered_instructions(&sut), "  Open<⏎>  ");
+    }
+}
@@ -1,52 +1,105 @@
 use crate::boundary;
-use crate::boundary::{ApiClient, Scope};
+use crate::boundary::{ApiClient, Scope, ScopeRules, Target};
 use crate::bountui::components::table::action::Action;
-use crate::bountui::components::table::{FilterItems, SortItems, TableColumn};
-use crate::bountui::components::TablePage;
-use crate::bountui::{Message};
-use crossterm::event::{Event, KeyCode};
-use ratatui::layout::{Constraint, Rect};
+use crate::bountui::components::table::{EnabledAction, FilterItems, SortItems, TableColumn};
+use crate::bountui::components::{DeepSearchDialog, TablePage};
+use crate::bountui::known_items::KnownItems;
+use crate::bountui::{failure_reasons, web_ui, Message};
+use crate::event_ext::EventExt;
+use crate::util::MpscSenderExt;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
+use ratatui::style::{Color, Style};
 use ratatui::Frame;
+use std::cell::RefCell;
 use std::rc::Rc;
 use futures::FutureExt;
-use crate::bountui::components::table::util::format_title_with_parent;
+use crate::bountui::components::table::util::{columns_resized_sender, format_title_with_parent};
+use crate::bountui::components::util::wrap_text;
+use std::collections::{HashMap, HashSet};
 
-pub struct ScopesPage {
+pub struct ScopesPage<C> {
     table_page: TablePage<boundary::Scope>,
-    send_message: tokio::sync::mpsc::Sender<Message>
+    send_message: tokio::sync::mpsc::Sender<Message>,
+    boundary_client: C,
+    parent_id: Option<String>,
+    // Restricted scopes are shown grayed and cannot be opened
+    scope_rules: ScopeRules,
+    select_after_load: Option<String>,
+    // Child scope and target counts per scope id, None while they are loading
+    counts: Rc<RefCell<Option<ChildCounts>>>,
+    // Searches the scopes of all levels, only offered on the root scopes
+    deep_search: Option<DeepSearchDialog>,
+    // When the listed scopes were loaded, None until the first load finished
+    loaded_at: Option<tokio::time::Instant>,
+    // The scopes and targets of all levels, shared with the other pages that show them
+    known: KnownItems,
 }
 
+/// Number of child scopes and of targets (including those of nested scopes) per scope id.
+pub type ChildCounts = HashMap<String, (usize, usize)>;
+
+// Key of the scopes table in the column widths of the config
+const COLUMN_WIDTHS_KEY: &str = "scopes";
+// Lines of the description of the selected scope shown below the table at most
+const DESCRIPTION_LINES: usize = 3;
+
 pub enum ScopesPageMessage {
     ScopesLoaded(Vec<Scope>),
+    LoadScopesFailed,
+    CountsLoaded(ChildCounts),
+    /// All scopes below the global scope, loaded for the deep search.
+    DeepSearchScopesLoaded(Vec<Scope>),
+    DeepSearchFailed,
 }
 
+const LOAD_SCOPES_FAILED: &str = "Failed to load scopes — press r to retry";
+
 impl From<ScopesPageMessage> for Message {
     fn from(value: ScopesPageMessage) -> Self {
         Message::Scopes(value)
     }
 }
 
-impl ScopesPage {
-    pub async fn new<C: ApiClient + Send + 'static>(parent_scope: Option<&Scope>, message_tx: tokio::sync::mpsc::Sender<Message>, boundary_client: C) -> Self {
+impl<C: ApiClient + Clone + Send + Sync + 'static> ScopesPage<C> {
+    pub async fn new(parent_scope: Option<&Scope>, message_tx: tokio::sync::mpsc::Sender<Message>, boundary_client: C) -> Self {
+        let counts: Rc<RefCell<Option<ChildCounts>>> = Rc::default();
+        let scope_counts = counts.clone();
+        let target_counts = counts.clone();
+        let is_root = parent_scope.is_none();
         let columns = vec![
             TableColumn::new(
                 "Name".to_string(),
-                Constraint::Ratio(3, 8),
+                Constraint::Ratio(3, 10),
                 Box::new(|s: &boundary::Scope| s.name.clone()),
             ),
             TableColumn::new(
                 "Description".to_string(),
-                Constraint::Ratio(3, 8),
+                Constraint::Ratio(3, 10),
                 Box::new(|s| s.description.clone()),
             ),
             TableColumn::new(
                 "Type".to_string(),
-                Constraint::Ratio(1, 8),
-                Box::new(|s| s.type_name.clone()),
+                Constraint::Ratio(1, 10),
+                Box::new(|s: &boundary::Scope| s.type_name.clone()),
+            )
+            .with_style(Box::new(|s: &boundary::Scope| type_style(&s.type_name))),
+            TableColumn::new(
+                "Scopes".to_string(),
+                Constraint::Ratio(1, 10),
+                Box::new(move |s: &boundary::Scope| format_count(&scope_counts.borrow(), &s.id, |(scopes, _)| scopes)),
+            ),
+            TableColumn::new(
+                "Targets".to_string(),
+                Constraint::Ratio(1, 10),
+                Box::new(move |s: &boundary::Scope| format_count(&target_counts.borrow(), &s.id, |(_, targets)| targets)),
             ),
             TableColumn::new(
                 "ID".to_string(),
-                Constraint::Ratio(1, 8),
+                Constraint::Ratio(1, 10),
                 Box::new(|s| s.id.clone()),
             ),
         ];
@@ -54,82 +107,328 @@ impl ScopesPage {
         let actions = vec![
             Action::new(
                 "Quit".to_string(),
-                "Ctrl + C".to_string(),
+                "q".to_string(),
                 Box::new(|_: Option<&Scope>| true),
             ),
             Action::new(
                 "Back".to_string(),
                 "ESC".to_string(),
                 Box::new(|_: Option<&Scope>| true),
-            ),
+            )
+            .with_key(KeyCode::Esc),
             Action::new(
                 "List Scopes".to_string(),
-                "⏎".to_string(),
+                "⏎/s".to_string(),
                 Box::new(|item: Option<&Scope>| item.map_or(false, |s| s.can_list_child_scopes())),
-            ),
+            )
+            .with_key(KeyCode::Char('s'))
+            .with_highlight(Box::new(|item: Option<&Scope>| {
+                item.and_then(enter_listing) == Some(Listing::ChildScopes)
+            })),
             Action::new(
                 "List Targets".to_string(),
-                "⏎".to_string(),
+                "⏎/t".to_string(),
                 Box::new(|item: Option<&Scope>| item.map_or(false, |s| s.can_list_targets())),
-            ),
+            )
+            .with_key(KeyCode::Char('t'))
+            .with_highlight(Box::new(|item: Option<&Scope>| {
+                item.and_then(enter_listing) == Some(Listing::Targets)
+            })),
+            Action::new(
+                "Reload".to_string(),
+                "r".to_string(),
+                Box::new(|_: Option<&Scope>| true),
+            )
+            .with_key(KeyCode::Char('r')),
+            Action::new(
+                "Open Web UI".to_string(),
+                "w".to_string(),
+                Box::new(|item: Option<&Scope>| item.is_some()),
+            )
+            .with_key(KeyCode::Char('w')),
+            Action::new(
+                "Scope Tree".to_string(),
+                "T".to_string(),
+                Box::new(|_: Option<&Scope>| true),
+            )
+            .with_key(KeyCode::Char('T')),
+            Action::new(
+                "Search All Scopes".to_string(),
+                "Ctrl+/".to_string(),
+                Box::new(move |_: Option<&Scope>| is_root),
+            )
+            .with_key(KeyEvent::new(KeyCode::Char('/'), KeyModifiers::CONTROL)),
         ];
         
         let parent_id = parent_scope.map(|s| s.id.clone());
-        Self::load_scopes(parent_id, &message_tx, boundary_client).await;
+        Self::load_scopes(parent_id.clone(), &message_tx, boundary_client.clone()).await;
         let title = format_title_with_parent("Scopes", parent_scope.map(|s| s.name.as_str()));
-        let table_page = TablePage::new(
+        let mut table_page = TablePage::new(
             title,
             columns,
             Vec::new(),
             actions,
-            message_tx.clone(),
             true
         );
+        table_page.enable_column_resizing(columns_resized_sender(COLUMN_WIDTHS_KEY, message_tx.clone()));
+        table_page.set_row_style(row_style(ScopeRules::default()));
 
         ScopesPage {
             table_page,
-            send_message: message_tx
+            send_message: message_tx,
+            boundary_client,
+            parent_id,
+            scope_rules: ScopeRules::default(),
+            select_after_load: None,
+            counts,
+            deep_search: None,
+            loaded_at: None,
+            known: KnownItems::default(),
         }
     }
 
-    async fn load_scopes<C: ApiClient + Send + 'static>(parent_id: Option<String>, message_tx: &tokio::sync::mpsc::Sender<Message>, boundary_client: C) {
+    pub fn with_column_widths(mut self, column_widths: &HashMap<String, Vec<u16>>) -> Self {
+        if let Some(widths) = column_widths.get(COLUMN_WIDTHS_KEY) {
+            self.table_page.set_column_widths(widths);
+        }
+        self
+    }
+
+    /// Selects the scope with the given id once the scopes are loaded.
+    pub fn with_selected_scope(mut self, scope_id: Option<String>) -> Self {
+        self.select_after_load = scope_id;
+        self
+    }
+
+    pub fn with_scope_rules(mut self, scope_rules: &ScopeRules) -> Self {
+        self.table_page.set_row_style(row_style(scope_rules.clone()));
+        self.scope_rules = scope_rules.clone();
+        self
+    }
+
+    /// Shares the scopes and targets of all levels with the other pages.
+    pub fn with_known_items(mut self, known: &KnownItems) -> Self {
+        self.known = known.clone();
+        self
+    }
+
+    async fn load_scopes(parent_id: Option<String>, message_tx: &tokio::sync::mpsc::Sender<Message>, boundary_client: C) {
         let message_tx_clone = message_tx.clone();
-        let _ = message_tx.send(Message::RunFuture(async move {
+        message_tx.send_or_log(Message::RunFuture(async move {
             let result = boundary_client.get_scopes(parent_id.as_ref().map(|i| i.as_str()), false).await;
             let message = match result {
                 Ok(scopes) => {
                     ScopesPageMessage::ScopesLoaded(scopes).into()
                 },
                 Err(e) => {
+                    message_tx_clone.send_or_log(ScopesPageMessage::LoadScopesFailed.into()).await;
                     Message::ShowAlert("Error".to_string(), format!("Failed to load scopes: {}", e))
                 }
             };
-            message_tx_clone.send(message).await.unwrap();
+            message_tx_clone.send_or_log(message).await;
         }.boxed())).await;
     }
 
+    // Counts the child scopes and targets of the listed scopes in the background, from the
+    // scopes and targets of all levels that are only listed once for all pages.
+    // Failures are only logged and leave the count columns blank.
+    async fn load_counts(&self, scope_ids: Vec<String>) {
+        let parent_id = self.parent_id.clone();
+        let boundary_client = self.boundary_client.clone();
+        let message_tx = self.send_message.clone();
+        let known = self.known.clone();
+        self.send_message.send_or_log(Message::RunFuture(async move {
+            let result = futures::try_join!(known.scopes(&boundary_client), known.targets(&boundary_client));
+            let counts = match result {
+                Ok((scopes, targets)) => {
+                    let subtree = subtree_scope_ids(parent_id.as_deref(), &scopes);
+                    let targets: Vec<Target> = targets
+                        .iter()
+                        .filter(|t| subtree.contains(t.scope_id.as_str()))
+                        .cloned()
+                        .collect();
+                    count_children(&scope_ids, &scopes, &targets)
+                }
+                Err(e) => {
+                    log::warn!("Failed to count the children of the scopes: {}", e);
+                    HashMap::new()
+                }
+            };
+            message_tx.send_or_log(ScopesPageMessage::CountsLoaded(counts).into()).await;
+        }.boxed())).await;
+    }
+
+    /// Reloads the scopes, as `r` does.
+    pub async fn reload_scopes(&mut self) {
+        self.table_page.notice = None;
+        self.table_page.loading = true;
+        self.known.forget();
+        *self.counts.borrow_mut() = None;
+        Self::load_scopes(self.parent_id.clone(), &self.send_message, self.boundary_client.clone()).await;
+    }
+
+    /// Reloads the scopes if they were loaded more than `max_age` ago, e.g. when the page is
+    /// shown again. The listed scopes stay shown until the reload finished.
+    pub async fn reload_if_stale(&mut self, max_age: std::time::Duration) {
+        if self.loaded_at.is_some_and(|loaded_at| loaded_at.elapsed() > max_age) {
+            // Not reloaded again while this reload is running
+            self.loaded_at = None;
+            Self::load_scopes(self.parent_id.clone(), &self.send_message, self.boundary_client.clone()).await;
+        }
+    }
+
+    /// Name and shortcut of the actions enabled for the selected item, for the command palette.
+    pub fn enabled_actions(&self) -> Vec<EnabledAction> {
+        self.table_page.enabled_actions()
+    }
+
     pub fn view(&self, frame: &mut Frame, area: Rect) {
-        self.table_page.view(frame, area);
+        // The full description of the selected scope, the column often cuts it
+        let description = self
+            .table_page
+            .selected_item()
+            .map(|scope| {
+                wrap_text(&scope.description, area.width.saturating_sub(2) as usize, DESCRIPTION_LINES)
+            })
+            .unwrap_or_default();
+        let [table_area, description_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(description.len() as u16),
+        ])
+        .areas(area);
+        self.table_page.view(frame, table_area);
+        let lines: Vec<Line> = description.into_iter().map(|line| Line::from(format!(" {line}"))).collect();
+        frame.render_widget(Paragraph::new(lines).gray(), description_area);
+        if let Some(deep_search) = &self.deep_search {
+            deep_search.view(frame);
+        }
+    }
+
+    async fn open_deep_search(&mut self) {
+        self.deep_search = Some(DeepSearchDialog::new());
+        let boundary_client = self.boundary_client.clone();
+        let message_tx = self.send_message.clone();
+        let known = self.known.clone();
+        self.send_message.send_or_log(Message::RunFuture(async move {
+            // Listing level by level skips the scopes that fail instead of failing the whole search
+            let (progress_tx, _) = tokio::sync::mpsc::unbounded_channel();
+            match known.scopes_with_progress(&boundary_client, progress_tx).await {
+                Ok(listing) => {
+                    message_tx.send_or_log(ScopesPageMessage::DeepSearchScopesLoaded(listing.scopes).into()).await;
+                    if !listing.failed_scopes.is_empty() {
+                        let failed_scopes = failure_reasons(&listing.failed_scopes);
+                        message_tx.send_or_log(Message::scopes_failed(&failed_scopes)).await;
+                    }
+                }
+                Err(e) => {
+                    message_tx.send_or_log(ScopesPageMessage::DeepSearchFailed.into()).await;
+                    message_tx.send_or_log(Message::show_error("Failed to search the scopes", e)).await;
+                }
+            }
+        }.boxed())).await;
+    }
+
+    // Opens the pages of the scopes on the path, the last one with its targets if its child
+    // scopes cannot be listed
+    async fn open_scope_path(&mut self, scopes: Vec<Scope>) {
+        let Some(scope) = scopes.last() else {
+            return;
+        };
+        let message = if let Some(restricted) = scopes.iter().find(|s| self.scope_rules.is_restricted(s)) {
+            Message::ShowAlert("Restricted scope".to_string(), restricted_message(restricted))
+        } else if is_dead_end(scope) {
+            Message::ShowAlert("No access".to_string(), no_access_message(scope, None))
+        } else {
+            let targets = !scope.can_list_child_scopes();
+            Message::ShowScopePath { scopes, targets }
+        };
+        self.send_message.send_or_log(message).await;
+    }
+
+    // Opens the child scopes or the targets of the selected scope, whichever Enter picks if
+    // `listing` is not given
+    async fn open_selected(&self, listing: Option<Listing>) {
+        let Some(scope) = self.table_page.selected_item() else {
+            return;
+        };
+        let message = if self.scope_rules.is_restricted(&scope) {
+            Message::ShowAlert("Restricted scope".to_string(), restricted_message(&scope))
+        } else {
+            let allowed = match listing {
+                None => enter_listing(&scope),
+                Some(Listing::ChildScopes) => scope.can_list_child_scopes().then_some(Listing::ChildScopes),
+                Some(Listing::Targets) => scope.can_list_targets().then_some(Listing::Targets),
+            };
+            match allowed {
+                Some(Listing::ChildScopes) => Message::ShowScopes {
+                    parent: Some((*scope).clone()),
+                    select_scope_id: None,
+                },
+                Some(Listing::Targets) => Message::ShowTargets {
+                    parent: (*scope).clone(),
+                    select_target_id: None,
+                },
+                None => Message::ShowAlert(
+                    "No access".to_string(),
+                    no_access_message(&scope, listing),
+                ),
+            }
+        };
+        self.send_message.send_or_log(message).await;
+    }
+
+    async fn open_in_web_ui(&self) {
+        if let Some(scope) = self.table_page.selected_item() {
+            let message = match web_ui::controller_addr() {
+                Some(addr) => Message::OpenInBrowser(web_ui::scope_url(&addr, &scope)),
+                None => Message::ShowAlert("Web UI".to_string(), web_ui::MISSING_ADDR.to_string()),
+            };
+            self.send_message.send_or_log(message).await;
+        }
     }
 
     pub async fn handle_event(&mut self, event: &Event) {
+        if let Some(deep_search) = &mut self.deep_search {
+            if event.is_esc() {
+                self.deep_search = None;
+            } else if let Some(scopes) = deep_search.handle_event(event).await {
+                self.deep_search = None;
+                self.open_scope_path(scopes).await;
+            }
+            return;
+        }
+        if self.parent_id.is_none() && is_deep_search_key(event) {
+            self.open_deep_search().await;
+            return;
+        }
         if self.table_page.handle_event(event).await {
             return;
         }
         if let Event::Key(key_event) = event {
             match key_event.code {
                 KeyCode::Enter => {
-                    if let Some(scope) = self.table_page.selected_item() {
-                        if scope.can_list_child_scopes() {
-                            self.send_message.send(Message::ShowScopes {
-                                parent: Some((*scope).clone())
-                            }).await.unwrap();
-                        } else if scope.can_list_targets() {
-                            self.send_message.send(Message::ShowTargets {
-                                parent: (*scope).clone()
-                            }).await.unwrap();
-                        }
-                    }
+                    self.open_selected(None).await;
+                }
+                KeyCode::Char('s') => {
+                    self.open_selected(Some(Listing::ChildScopes)).await;
+                }
+                KeyCode::Char('t') => {
+                    self.open_selected(Some(Listing::Targets)).await;
+                }
+                KeyCode::Char('r') => {
+                    self.reload_scopes().await;
+                }
+                KeyCode::Char('w') => {
+                    self.open_in_web_ui().await;
+                }
+                KeyCode::Char('T') => {
+                    self.send_message.send_or_log(Message::ShowScopeTree { replace_history: false }).await;
+                }
+                KeyCode::Char('q') => {
+                    self.send_message.send_or_log(Message::Quit).await;
+                }
+                KeyCode::Esc => {
+                    self.send_message.send_or_log(Message::GoBack).await;
                 }
                 _ => {}
             }
@@ -139,13 +438,178 @@ impl ScopesPage {
     pub async fn handle_message(&mut self, message: ScopesPageMessage) {
         match message {
             ScopesPageMessage::ScopesLoaded(scopes) => {
-                self.table_page.set_items(scopes);
+                self.table_page.notice = None;
+                let scope_ids: Vec<String> = scopes.iter().map(|s| s.id.clone()).collect();
+                self.table_page.set_items_keeping_selection(scopes, |s| s.id.clone());
                 self.table_page.loading = false;
+                self.loaded_at = Some(tokio::time::Instant::now());
+                if let Some(scope_id) = self.select_after_load.take() {
+                    self.table_page.select_where(|s| s.id == scope_id);
+                }
+                // The counts stay until the page is reloaded, unless new scopes are listed
+                let counted = self
+                    .counts
+                    .borrow()
+                    .as_ref()
+                    .is_some_and(|counts| scope_ids.iter().all(|id| counts.contains_key(id)));
+                if !counted {
+                    *self.counts.borrow_mut() = None;
+                    self.load_counts(scope_ids).await;
+                }
+            }
+            ScopesPageMessage::LoadScopesFailed => {
+                self.table_page.loading = false;
+                self.table_page.notice = Some(LOAD_SCOPES_FAILED.to_string());
+            }
+            ScopesPageMessage::CountsLoaded(counts) => {
+                *self.counts.borrow_mut() = Some(counts);
+            }
+            ScopesPageMessage::DeepSearchScopesLoaded(scopes) => {
+                if let Some(deep_search) = &mut self.deep_search {
+                    deep_search.set_scopes(scopes);
+                }
+            }
+            ScopesPageMessage::DeepSearchFailed => {
+                self.deep_search = None;
             }
         }
     }
 }
 
+/// Counts the direct child scopes and all targets below each of the given scopes.
+pub fn count_children(scope_ids: &[String], scopes: &[Scope], targets: &[Target]) -> ChildCounts {
+    let mut counts: ChildCounts = scope_ids.iter().map(|id| (id.clone(), (0, 0))).collect();
+    let parents: HashMap<&str, &str> = scopes
+        .iter()
+        .map(|s| (s.id.as_str(), s.scope_id.as_str()))
+        .collect();
+    for scope in scopes {
+        if let Some((child_scopes, _)) = counts.get_mut(&scope.scope_id) {
+            *child_scopes += 1;
+        }
+    }
+    for target in targets {
+        // Walk up from the scope of the target until one of the listed scopes is reached,
+        // giving up after as many steps as there are scopes in case the parents form a cycle
+        let mut scope_id = target.scope_id.as_str();
+        for _ in 0..=scopes.len() {
+            if let Some((_, child_targets)) = counts.get_mut(scope_id) {
+                *child_targets += 1;
+                break;
+            }
+            match parents.get(scope_id) {
+                Some(parent_id) => scope_id = parent_id,
+                None => break,
+            }
+        }
+    }
+    counts
+}
+
+// Ids of the scopes below the parent, all scopes below the global scope if there is no parent
+fn subtree_scope_ids<'a>(parent_id: Option<&'a str>, scopes: &'a [Scope]) -> HashSet<&'a str> {
+    let Some(parent_id) = parent_id else {
+        return scopes.iter().map(|s| s.id.as_str()).collect();
+    };
+    let mut subtree = HashSet::from([parent_id]);
+    let mut pending = vec![parent_id];
+    while let Some(id) = pending.pop() {
+        for child in scopes.iter().filter(|s| s.scope_id == id) {
+            if subtree.insert(child.id.as_str()) {
+                pending.push(child.id.as_str());
+            }
+        }
+    }
+    subtree
+}
+
+fn format_count(counts: &Option<ChildCounts>, scope_id: &str, pick: impl Fn((usize, usize)) -> usize) -> String {
+    match counts {
+        None => "…".to_string(),
+        Some(counts) => counts
+            .get(scope_id)
+            .map(|c| pick(*c).to_string())
+            .unwrap_or_default(),
+    }
+}
+
+// Tells orgs, projects and the global scope apart at a glance, other types are not styled
+fn type_style(type_name: &str) -> Style {
+    match type_name {
+        "global" => Style::default().fg(Color::LightYellow),
+        "org" => Style::default().fg(Color::LightCyan),
+        "project" => Style::default().fg(Color::LightMagenta),
+        _ => Style::default(),
+    }
+}
+
+// Restricted scopes and scopes that cannot be opened at all are grayed
+fn row_style(rules: ScopeRules) -> Box<dyn Fn(&Scope) -> Style> {
+    Box::new(move |s: &Scope| {
+        if rules.is_restricted(s) || is_dead_end(s) {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default()
+        }
+    })
+}
+
+// Terminals report Ctrl+/ either as such or as Ctrl+7, which sends the same control character
+fn is_deep_search_key(event: &Event) -> bool {
+    match event {
+        Event::Key(key_event) => {
+            key_event.modifiers.contains(KeyModifiers::CONTROL)
+                && matches!(key_event.code, KeyCode::Char('/') | KeyCode::Char('7'))
+        }
+        _ => false,
+    }
+}
+
+// Neither the child scopes nor the targets of the scope can be listed
+fn is_dead_end(scope: &Scope) -> bool {
+    !scope.can_list_child_scopes() && !scope.can_list_targets()
+}
+
+/// What of a scope is opened.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Listing {
+    ChildScopes,
+    Targets,
+}
+
+/// Enter opens the child scopes of a scope if they can be listed, its targets otherwise.
+fn enter_listing(scope: &Scope) -> Option<Listing> {
+    if scope.can_list_child_scopes() {
+        Some(Listing::ChildScopes)
+    } else if scope.can_list_targets() {
+        Some(Listing::Targets)
+    } else {
+        None
+    }
+}
+
+/// Explains which permission is missing to open `listing` of the scope, or either of them.
+fn no_access_message(scope: &Scope, listing: Option<Listing>) -> String {
+    let (what, permission) = match listing {
+        None => ("the child scopes or the targets", "scopes or targets"),
+        Some(Listing::ChildScopes) => ("the child scopes", "scopes"),
+        Some(Listing::Targets) => ("the targets", "targets"),
+    };
+    format!(
+        "You are not allowed to list {} of scope '{}', \
+         it needs the list permission on {} — ask your Boundary admin",
+        what, scope.name, permission
+    )
+}
+
+/// Explains why a restricted scope cannot be opened.
+pub fn restricted_message(scope: &Scope) -> String {
+    format!(
+        "The scope '{}' is restricted in the scopes section of the config and cannot be opened",
+        scope.name
+    )
+}
+
 impl SortItems<Scope> for TablePage<Scope> {
     fn sort(items: &mut Vec<Rc<Scope>>) {
         items.sort_by(|a, b| a.name.cmp(&b.name));
@@ -160,3 +624,582 @@ impl FilterItems<Scope> for TablePage<Scope> {
     }
 }
 
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn create_scope() -> Scope {
+        Scope::builder()
+            .id("scope-1".to_string())
+            .name("scope 1".to_string())
+            .description("scope 1".to_string())
+            .type_name("org".to_string())
+            .authorized_collection_actions(HashMap::new())
+            .build()
+    }
+
+    async fn process_messages<C: ApiClient + Clone + Send + Sync + 'static>(
+        sut: &mut ScopesPage<C>,
+        msg_rx: &mut tokio::sync::mpsc::Receiver<Message>,
+    ) {
+        while let Ok(message) = msg_rx.try_recv() {
+            match message {
+                Message::RunFuture(future) => future.await,
+                Message::Scopes(message) => sut.handle_message(message).await,
+                _ => {}
+            }
+        }
+    }
+
+    fn scope_named(id: &str, name: &str) -> Scope {
+        Scope {
+            id: id.to_string(),
+            name: name.to_string(),
+            ..create_scope()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_denied_scopes_are_not_listed() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let scopes = HashMap::from([(
+            None,
+            vec![scope_named("o_dev", "dev"), scope_named("o_prod", "prod")],
+        )]);
+        let client = boundary::ScopeFilterClient::new(
+            boundary::MockClient::builder().scopes(scopes).build(),
+            ScopeRules {
+                deny: vec!["prod".to_string()],
+                ..ScopeRules::default()
+            },
+        );
+        let mut sut = ScopesPage::new(None, msg_tx, client).await;
+        process_messages(&mut sut, &mut msg_rx).await;
+        assert_eq!(
+            sut.table_page.items().map(|s| s.id.as_str()).collect::<Vec<_>>(),
+            vec!["o_dev"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restricted_scope_cannot_be_opened() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let mut scope = scope_named("o_prod", "prod");
+        scope
+            .authorized_collection_actions
+            .insert("scopes".to_string(), vec!["list".to_string()]);
+        let client = boundary::MockClient::builder()
+            .scopes(HashMap::from([(None, vec![scope])]))
+            .build();
+        let rules = ScopeRules {
+            restricted: vec!["o_prod".to_string()],
+            ..ScopeRules::default()
+        };
+        let mut sut = ScopesPage::new(None, msg_tx, client).await.with_scope_rules(&rules);
+        process_messages(&mut sut, &mut msg_rx).await;
+
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Enter))).await;
+        match msg_rx.try_recv() {
+            Ok(Message::ShowAlert(title, _)) => assert_eq!(title, "Restricted scope"),
+            _ => panic!("Expected an alert instead of navigating"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_failed_load() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let mut scopes = HashMap::new();
+        scopes.insert(None, vec![create_scope()]);
+        let client = boundary::MockClient::builder()
+            .scopes(scopes)
+            .get_scopes_failures(Arc::new(AtomicUsize::new(1)))
+            .build();
+        let mut sut = ScopesPage::new(None, msg_tx, client).await;
+        process_messages(&mut sut, &mut msg_rx).await;
+        assert_eq!(
+            sut.table_page.notice.as_deref(),
+            Some(LOAD_SCOPES_FAILED),
+            "Loading the scopes should have failed"
+        );
+        assert!(sut.table_page.selected_item().is_none());
+
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('r')))).await;
+        process_messages(&mut sut, &mut msg_rx).await;
+        assert_eq!(sut.table_page.notice, None, "Retrying should succeed");
+        assert_eq!(sut.table_page.selected_item().map(|s| s.id.clone()), Some("scope-1".to_string()));
+    }
+
+    fn scope_in(id: &str, name: &str, parent_id: &str) -> Scope {
+        Scope {
+            scope_id: parent_id.to_string(),
+            ..scope_named(id, name)
+        }
+    }
+
+    fn target_in(id: &str, scope_id: &str) -> Target {
+        Target {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: "".to_string(),
+            type_name: "tcp".to_string(),
+            authorized_collection_actions: HashMap::new(),
+            authorized_actions: vec![],
+            scope_id: scope_id.to_string(),
+            attributes: None,
+        }
+    }
+
+    fn counted_client(get_targets_failures: usize, get_targets_calls: Arc<AtomicUsize>) -> boundary::MockClient {
+        boundary::MockClient::builder()
+            .scopes(HashMap::from([
+                (
+                    None,
+                    vec![scope_in("o_dev", "dev", "global"), scope_in("o_prod", "prod", "global")],
+                ),
+                (
+                    Some("o_dev".to_string()),
+                    vec![scope_in("p_api", "api", "o_dev"), scope_in("p_web", "web", "o_dev")],
+                ),
+            ]))
+            .targets(HashMap::from([(
+                None,
+                vec![
+                    target_in("t_1", "p_api"),
+                    target_in("t_2", "p_api"),
+                    target_in("t_3", "p_web"),
+                    target_in("t_4", "o_prod"),
+                ],
+            )]))
+            .get_targets_failures(Arc::new(AtomicUsize::new(get_targets_failures)))
+            .get_targets_calls(get_targets_calls)
+            .build()
+    }
+
+    fn row_of(sut: &ScopesPage<boundary::MockClient>, name: &str) -> String {
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(100, 8)).unwrap();
+        terminal.draw(|frame| sut.view(frame, frame.area())).unwrap();
+        let buffer = terminal.backend().buffer().clone();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol().to_string())
+                    .collect::<String>()
+            })
+            .find(|line| cells(line).first() == Some(&name))
+            .unwrap_or_else(|| panic!("No row for {}", name))
+    }
+
+    fn cells(row: &str) -> Vec<&str> {
+        row.split(|c: char| c.is_whitespace() || c == '│')
+            .filter(|c| !c.is_empty())
+            .collect()
+    }
+
+    #[test]
+    fn test_count_children() {
+        let scopes = vec![
+            scope_in("o_dev", "dev", "global"),
+            scope_in("p_api", "api", "o_dev"),
+            scope_in("p_web", "web", "o_dev"),
+        ];
+        let targets = vec![target_in("t_1", "p_api"), target_in("t_2", "o_dev"), target_in("t_3", "o_other")];
+        let counts = count_children(&["o_dev".to_string(), "p_api".to_string()], &scopes, &targets);
+        assert_eq!(counts["o_dev"], (2, 1));
+        assert_eq!(counts["p_api"], (0, 1));
+    }
+
+    #[tokio::test]
+    async fn test_counts_land_on_their_rows() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let mut sut = ScopesPage::new(None, msg_tx, counted_client(0, Arc::default())).await;
+        process_messages(&mut sut, &mut msg_rx).await;
+
+        assert!(cells(&row_of(&sut, "dev")).ends_with(&["2", "3", "o_dev"]));
+        assert!(cells(&row_of(&sut, "prod")).ends_with(&["0", "1", "o_prod"]));
+    }
+
+    #[tokio::test]
+    async fn test_counts_are_shown_as_pending_until_loaded() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let mut sut = ScopesPage::new(None, msg_tx, counted_client(0, Arc::default())).await;
+        while let Ok(message) = msg_rx.try_recv() {
+            match message {
+                Message::RunFuture(future) => future.await,
+                Message::Scopes(message @ ScopesPageMessage::ScopesLoaded(_)) => sut.handle_message(message).await,
+                _ => {}
+            }
+        }
+
+        assert!(cells(&row_of(&sut, "dev")).ends_with(&["…", "…", "o_dev"]));
+    }
+
+    #[tokio::test]
+    async fn test_failed_counts_leave_the_columns_blank() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let mut sut = ScopesPage::new(None, msg_tx, counted_client(1, Arc::default())).await;
+        process_messages(&mut sut, &mut msg_rx).await;
+
+        assert!(cells(&row_of(&sut, "dev")).ends_with(&["org", "o_dev"]));
+    }
+
+    #[tokio::test]
+    async fn test_counts_are_kept_until_the_page_is_reloaded() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let get_targets_calls = Arc::new(AtomicUsize::new(0));
+        let client = counted_client(0, get_targets_calls.clone());
+        let parent = scope_in("o_dev", "dev", "global");
+        let mut sut = ScopesPage::new(Some(&parent), msg_tx, client).await;
+        process_messages(&mut sut, &mut msg_rx).await;
+        assert!(cells(&row_of(&sut, "api")).ends_with(&["0", "2", "p_api"]));
+
+        sut.reload_if_stale(std::time::Duration::ZERO).await;
+        process_messages(&mut sut, &mut msg_rx).await;
+        assert_eq!(get_targets_calls.load(Ordering::SeqCst), 1);
+
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('r')))).await;
+        process_messages(&mut sut, &mut msg_rx).await;
+        assert_eq!(get_targets_calls.load(Ordering::SeqCst), 2);
+        assert!(cells(&row_of(&sut, "web")).ends_with(&["0", "1", "p_web"]));
+    }
+
+    #[tokio::test]
+    async fn test_scope_is_selected_once_loaded() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let mut sut = ScopesPage::new(None, msg_tx, counted_client(0, Arc::default()))
+            .await
+            .with_selected_scope(Some("o_prod".to_string()));
+        process_messages(&mut sut, &mut msg_rx).await;
+
+        assert_eq!(sut.table_page.selected_item().map(|s| s.id.clone()), Some("o_prod".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_scopes_that_cannot_be_opened_are_grayed_and_explained() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let mut dev = scope_named("o_dev", "dev");
+        dev.authorized_collection_actions
+            .insert("scopes".to_string(), vec!["list".to_string()]);
+        let client = boundary::MockClient::builder()
+            .scopes(HashMap::from([(None, vec![dev, scope_named("o_prod", "prod")])]))
+            .build();
+        let mut sut = ScopesPage::new(None, msg_tx, client).await;
+        process_messages(&mut sut, &mut msg_rx).await;
+
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(100, 8)).unwrap();
+        terminal.draw(|frame| sut.view(frame, frame.area())).unwrap();
+        let buffer = terminal.backend().buffer();
+        // The first row is selected and highlighted, the second one shows the row style
+        assert_eq!(buffer[(1, 2)].symbol(), "d");
+        assert_eq!(buffer[(1, 3)].symbol(), "p");
+        assert_eq!(buffer[(1, 3)].fg, Color::DarkGray);
+
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Down))).await;
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Enter))).await;
+        match msg_rx.try_recv() {
+            Ok(Message::ShowAlert(title, message)) => {
+                assert_eq!(title, "No access");
+                assert!(message.contains("'prod'"));
+            }
+            _ => panic!("Expected an alert explaining the missing permissions"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_type_cells_are_colored_by_scope_type() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let typed = |id: &str, type_name: &str| {
+            let mut scope = scope_named(id, id);
+            scope.type_name = type_name.to_string();
+            scope
+                .authorized_collection_actions
+                .insert("scopes".to_string(), vec!["list".to_string()]);
+            scope
+        };
+        let client = boundary::MockClient::builder()
+            .scopes(HashMap::from([(
+                None,
+                vec![
+                    typed("a", "global"),
+                    typed("b", "org"),
+                    typed("c", "project"),
+                    typed("d", "custom"),
+                ],
+            )]))
+            .build();
+        let mut sut = ScopesPage::new(None, msg_tx, client).await;
+        process_messages(&mut sut, &mut msg_rx).await;
+
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(100, 8)).unwrap();
+        terminal.draw(|frame| sut.view(frame, frame.area())).unwrap();
+        let buffer = terminal.backend().buffer();
+        let type_x = (0..100)
+            .find(|x| buffer[(*x, 1)].symbol() == "T" && buffer[(*x + 1, 1)].symbol() == "y")
+            .expect("The Type header should be shown");
+        let expected = [
+            ("g", Color::LightYellow),
+            ("o", Color::LightCyan),
+            ("p", Color::LightMagenta),
+        ];
+        for (row, (symbol, color)) in expected.into_iter().enumerate() {
+            let cell = &buffer[(type_x, 2 + row as u16)];
+            assert_eq!(cell.symbol(), symbol);
+            assert_eq!(cell.fg, color, "Unexpected color of the type {symbol}");
+        }
+        // Unknown types keep the color of the rest of the row
+        assert_eq!(buffer[(type_x, 5)].symbol(), "c");
+        assert_eq!(buffer[(type_x, 5)].fg, buffer[(1, 5)].fg);
+    }
+
+    fn listing(scope: Scope, collection: &str) -> Scope {
+        let mut scope = scope;
+        scope
+            .authorized_collection_actions
+            .insert(collection.to_string(), vec!["list".to_string()]);
+        scope
+    }
+
+    fn deep_search_client() -> boundary::MockClient {
+        boundary::MockClient::builder()
+            .scopes(HashMap::from([
+                (None, vec![listing(scope_in("o_acme", "acme", "global"), "scopes")]),
+                (
+                    Some("o_acme".to_string()),
+                    vec![
+                        listing(scope_in("p_payments", "payments", "o_acme"), "targets"),
+                        listing(scope_in("p_web", "web", "o_acme"), "targets"),
+                    ],
+                ),
+            ]))
+            .build()
+    }
+
+    fn type_text(text: &str) -> Vec<Event> {
+        text.chars()
+            .map(|c| Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char(c))))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_deep_search_opens_the_path_of_the_matching_scope() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let mut sut = ScopesPage::new(None, msg_tx, deep_search_client()).await;
+        process_messages(&mut sut, &mut msg_rx).await;
+
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::new(
+            KeyCode::Char('/'),
+            KeyModifiers::CONTROL,
+        )))
+        .await;
+        assert!(sut.deep_search.is_some(), "Deep search should be open");
+        // The query can be typed while the scopes are still loading
+        for event in type_text("pay") {
+            sut.handle_event(&event).await;
+        }
+        process_messages(&mut sut, &mut msg_rx).await;
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Enter))).await;
+
+        assert!(sut.deep_search.is_none(), "Deep search should be closed");
+        match msg_rx.try_recv() {
+            Ok(Message::ShowScopePath { scopes, targets }) => {
+                let ids: Vec<&str> = scopes.iter().map(|s| s.id.as_str()).collect();
+                assert_eq!(ids, vec!["o_acme", "p_payments"]);
+                assert!(targets, "The targets of a project should be shown");
+            }
+            _ => panic!("Expected the path of the payments project"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deep_search_is_only_offered_on_the_root_scopes() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let parent = listing(scope_in("o_acme", "acme", "global"), "scopes");
+        let mut sut = ScopesPage::new(Some(&parent), msg_tx, deep_search_client()).await;
+        process_messages(&mut sut, &mut msg_rx).await;
+
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::new(
+            KeyCode::Char('/'),
+            KeyModifiers::CONTROL,
+        )))
+        .await;
+        assert!(sut.deep_search.is_none());
+    }
+
+    fn scope_with_scopes_and_targets(id: &str, name: &str) -> Scope {
+        let mut scope = scope_named(id, name);
+        scope.authorized_collection_actions.insert("scopes".to_string(), vec!["list".to_string()]);
+        scope.authorized_collection_actions.insert("targets".to_string(), vec!["list".to_string()]);
+        scope
+    }
+
+    // Whether the footer shows the action in bold
+    fn footer_action_is_bold(buffer: &ratatui::buffer::Buffer, action: &str) -> bool {
+        let area = buffer.area;
+        (area.top()..area.bottom()).find_map(|y| {
+            let line: String = (area.left()..area.right())
+                .map(|x| buffer[(x, y)].symbol().to_string())
+                .collect();
+            line.find(action).map(|byte| {
+                let x = area.left() + line[..byte].chars().count() as u16;
+                buffer[(x, y)].modifier.contains(ratatui::style::Modifier::BOLD)
+            })
+        })
+        .expect("The action should be shown")
+    }
+
+    async fn press(sut: &mut ScopesPage<boundary::MockClient>, code: KeyCode) {
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(code))).await;
+    }
+
+    #[tokio::test]
+    async fn test_enter_lists_child_scopes_of_a_scope_with_both_collections() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let client = boundary::MockClient::builder()
+            .scopes(HashMap::from([(None, vec![scope_with_scopes_and_targets("o_dev", "dev")])]))
+            .build();
+        let mut sut = ScopesPage::new(None, msg_tx, client).await;
+        process_messages(&mut sut, &mut msg_rx).await;
+
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(250, 8)).unwrap();
+        terminal.draw(|frame| sut.view(frame, frame.area())).unwrap();
+        let buffer = terminal.backend().buffer();
+        assert!(footer_action_is_bold(buffer, "List Scopes<⏎/s>"));
+        assert!(!footer_action_is_bold(buffer, "List Targets<⏎/t>"));
+
+        press(&mut sut, KeyCode::Enter).await;
+        assert!(matches!(
+            msg_rx.try_recv(),
+            Ok(Message::ShowScopes { parent: Some(parent), .. }) if parent.id == "o_dev"
+        ));
+        press(&mut sut, KeyCode::Char('t')).await;
+        assert!(matches!(
+            msg_rx.try_recv(),
+            Ok(Message::ShowTargets { parent, .. }) if parent.id == "o_dev"
+        ));
+        press(&mut sut, KeyCode::Char('s')).await;
+        assert!(matches!(
+            msg_rx.try_recv(),
+            Ok(Message::ShowScopes { parent: Some(parent), .. }) if parent.id == "o_dev"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_enter_lists_targets_if_child_scopes_cannot_be_listed() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let mut project = scope_named("p_web", "web");
+        project
+            .authorized_collection_actions
+            .insert("targets".to_string(), vec!["list".to_string()]);
+        let client = boundary::MockClient::builder()
+            .scopes(HashMap::from([(None, vec![project])]))
+            .build();
+        let mut sut = ScopesPage::new(None, msg_tx, client).await;
+        process_messages(&mut sut, &mut msg_rx).await;
+
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(250, 8)).unwrap();
+        terminal.draw(|frame| sut.view(frame, frame.area())).unwrap();
+        assert!(footer_action_is_bold(terminal.backend().buffer(), "List Targets<⏎/t>"));
+
+        press(&mut sut, KeyCode::Char('s')).await;
+        match msg_rx.try_recv() {
+            Ok(Message::ShowAlert(title, message)) => {
+                assert_eq!(title, "No access");
+                assert!(message.contains("list the child scopes of scope 'web'"));
+            }
+            _ => panic!("Expected an alert explaining the missing permission"),
+        }
+        press(&mut sut, KeyCode::Enter).await;
+        assert!(matches!(
+            msg_rx.try_recv(),
+            Ok(Message::ShowTargets { parent, .. }) if parent.id == "p_web"
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stale_scopes_are_reloaded_keeping_the_selection() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let client = boundary::MockClient::builder()
+            .scopes(HashMap::from([(
+                None,
+                vec![scope_named("o_dev", "dev"), scope_named("o_prod", "prod")],
+            )]))
+            .build();
+        let mut sut = ScopesPage::new(None, msg_tx, client).await;
+        process_messages(&mut sut, &mut msg_rx).await;
+        press(&mut sut, KeyCode::Down).await;
+
+        sut.reload_if_stale(std::time::Duration::from_secs(60)).await;
+        assert!(msg_rx.try_recv().is_err(), "Scopes loaded just now are not reloaded");
+
+        tokio::time::advance(std::time::Duration::from_secs(61)).await;
+        sut.reload_if_stale(std::time::Duration::from_secs(60)).await;
+        assert!(!sut.table_page.loading, "The listed scopes stay shown while reloading");
+        process_messages(&mut sut, &mut msg_rx).await;
+        assert_eq!(sut.table_page.selected_item().unwrap().id, "o_prod");
+    }
+
+    fn buffer_lines(buffer: &ratatui::buffer::Buffer) -> Vec<String> {
+        let area = buffer.area;
+        (area.top()..area.bottom())
+            .map(|y| {
+                (area.left()..area.right())
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    async fn render_with_description(description: &str) -> Vec<String> {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let scope = Scope {
+            description: description.to_string(),
+            ..scope_named("o_ops", "ops")
+        };
+        let client = boundary::MockClient::builder()
+            .scopes(HashMap::from([(None, vec![scope])]))
+            .build();
+        let mut sut = ScopesPage::new(None, msg_tx, client).await;
+        process_messages(&mut sut, &mut msg_rx).await;
+
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(40, 12)).unwrap();
+        terminal.draw(|frame| sut.view(frame, frame.area())).unwrap();
+        buffer_lines(terminal.backend().buffer())
+    }
+
+    #[tokio::test]
+    async fn test_long_description_of_the_selected_scope_is_wrapped_below_the_table() {
+        let lines = render_with_description(
+            "Owned by the payments team, on-call: #payments-oncall, escalate to \
+             payments-leads@example.com during business hours, otherwise page the SRE on duty",
+        )
+        .await;
+
+        assert_eq!(
+            lines[9..],
+            [
+                " Owned by the payments team, on-call:",
+                " #payments-oncall, escalate to",
+                " payments-leads@example.com during…",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_empty_description_takes_no_space() {
+        let with_description = render_with_description("Owned by the payments team").await;
+        assert_eq!(with_description[11], " Owned by the payments team");
+
+        let lines = render_with_description("   ").await;
+        assert_eq!(lines[11], with_description[10], "The table takes the line of the description");
+    }
+}
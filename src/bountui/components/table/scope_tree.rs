@@ -0,0 +1,722 @@
+use crate::boundary::{ApiClient, Scope, ScopeRules};
+use crate::bountui::components::table::action::Action;
+use crate::bountui::components::table::util::columns_resized_sender;
+use crate::bountui::components::table::{EnabledAction, FilterItems, SortItems, TableColumn};
+use crate::bountui::components::TablePage;
+use crate::bountui::components::table::scope::restricted_message;
+use crate::bountui::known_items::KnownItems;
+use crate::bountui::{failure_reasons, Message};
+use crate::util::MpscSenderExt;
+use crossterm::event::{Event, KeyCode};
+use futures::FutureExt;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::Frame;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+// Key of the scope tree table in the column widths of the config
+const COLUMN_WIDTHS_KEY: &str = "scope_tree";
+
+const LOAD_SCOPES_FAILED: &str = "Failed to load scopes — press r to retry";
+
+/// A scope of the tree, listed below the scopes it belongs to.
+#[derive(Debug, Clone)]
+pub struct ScopeTreeRow {
+    pub scope: Scope,
+    // Ids of the scopes above, the root first
+    ancestors: Vec<String>,
+    // Names from the root down to the scope itself, matched by the filter
+    path: Vec<String>,
+    has_children: bool,
+    // Position in the tree, the table keeps this order
+    position: usize,
+}
+
+impl ScopeTreeRow {
+    pub fn path(&self) -> String {
+        self.path.join(" / ")
+    }
+
+    pub fn depth(&self) -> usize {
+        self.ancestors.len()
+    }
+}
+
+/// Orders the flat list of scopes depth first with the children of a scope sorted by name.
+/// Scopes whose parent is not part of the list, e.g. the orgs below the global scope, are roots.
+pub fn build_scope_tree(scopes: Vec<Scope>) -> Vec<ScopeTreeRow> {
+    let ids: HashSet<String> = scopes.iter().map(|s| s.id.clone()).collect();
+    let mut children: HashMap<Option<String>, Vec<Scope>> = HashMap::new();
+    for scope in scopes {
+        let parent = Some(scope.scope_id.clone()).filter(|id| ids.contains(id) && *id != scope.id);
+        children.entry(parent).or_default().push(scope);
+    }
+    for siblings in children.values_mut() {
+        siblings.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+    }
+
+    let mut rows = Vec::new();
+    let mut pending: Vec<(Scope, Vec<String>, Vec<String>)> = children
+        .remove(&None)
+        .unwrap_or_default()
+        .into_iter()
+        .rev()
+        .map(|scope| (scope, vec![], vec![]))
+        .collect();
+    while let Some((scope, ancestors, mut path)) = pending.pop() {
+        path.push(scope.name.clone());
+        let scope_children = children.remove(&Some(scope.id.clone())).unwrap_or_default();
+        let mut child_ancestors = ancestors.clone();
+        child_ancestors.push(scope.id.clone());
+        for child in scope_children.iter().rev() {
+            pending.push((child.clone(), child_ancestors.clone(), path.clone()));
+        }
+        rows.push(ScopeTreeRow {
+            scope,
+            ancestors,
+            path,
+            has_children: !scope_children.is_empty(),
+            position: rows.len(),
+        });
+    }
+    rows
+}
+
+/// Whether the row is shown, i.e. none of the scopes above it is collapsed.
+pub fn is_expanded_to(row: &ScopeTreeRow, collapsed: &HashSet<String>) -> bool {
+    !row.ancestors.iter().any(|id| collapsed.contains(id))
+}
+
+// Shows only the scopes without a collapsed scope above them
+fn hide_collapsed(collapsed: Rc<RefCell<HashSet<String>>>) -> Box<dyn Fn(&ScopeTreeRow) -> bool> {
+    Box::new(move |row: &ScopeTreeRow| is_expanded_to(row, &collapsed.borrow()))
+}
+
+pub enum ScopeTreeMessage {
+    ScopesLoaded(Vec<Scope>),
+    LoadScopesFailed,
+    // Number of scopes found so far while loading, the tree can take a while on big orgs
+    ScopesDiscovered(usize),
+}
+
+impl From<ScopeTreeMessage> for Message {
+    fn from(value: ScopeTreeMessage) -> Self {
+        Message::ScopeTree(value)
+    }
+}
+
+/// All scopes at once as a tree, scopes are collapsed and expanded with Left and Right.
+pub struct ScopeTreePage<C> {
+    table_page: TablePage<ScopeTreeRow>,
+    message_tx: tokio::sync::mpsc::Sender<Message>,
+    boundary_client: C,
+    // Ids of the collapsed scopes, their descendants are hidden
+    collapsed: Rc<RefCell<HashSet<String>>>,
+    scope_rules: ScopeRules,
+    // The scopes of all levels, shared with the other pages that show them
+    known: KnownItems,
+}
+
+impl<C: ApiClient + Clone + Send + Sync + 'static> ScopeTreePage<C> {
+    pub async fn new(
+        message_tx: tokio::sync::mpsc::Sender<Message>,
+        boundary_client: C,
+        known: KnownItems,
+    ) -> Self {
+        let collapsed: Rc<RefCell<HashSet<String>>> = Rc::default();
+        let collapsed_for_column = collapsed.clone();
+        let columns = vec![
+            TableColumn::new(
+                "Name".to_string(),
+                Constraint::Ratio(3, 8),
+                Box::new(move |row: &ScopeTreeRow| {
+                    let marker = if !row.has_children {
+                        " "
+                    } else if collapsed_for_column.borrow().contains(&row.scope.id) {
+                        "▸"
+                    } else {
+                        "▾"
+                    };
+                    format!("{}{marker} {}", "  ".repeat(row.depth()), row.scope.name)
+                }),
+            ),
+            TableColumn::new(
+                "Description".to_string(),
+                Constraint::Ratio(3, 8),
+                Box::new(|row: &ScopeTreeRow| row.scope.description.clone()),
+            ),
+            TableColumn::new(
+                "Type".to_string(),
+                Constraint::Ratio(1, 8),
+                Box::new(|row: &ScopeTreeRow| row.scope.type_name.clone()),
+            ),
+            TableColumn::new(
+                "ID".to_string(),
+                Constraint::Ratio(1, 8),
+                Box::new(|row: &ScopeTreeRow| row.scope.id.clone()),
+            ),
+        ];
+
+        let actions = vec![
+            Action::new(
+                "Quit".to_string(),
+                "q".to_string(),
+                Box::new(|_: Option<&ScopeTreeRow>| true),
+            ),
+            Action::new(
+                "Back".to_string(),
+                "ESC".to_string(),
+                Box::new(|_: Option<&ScopeTreeRow>| true),
+            )
+            .with_key(KeyCode::Esc),
+            Action::new(
+                "List Targets".to_string(),
+                "⏎".to_string(),
+                Box::new(|item: Option<&ScopeTreeRow>| {
+                    item.is_some_and(|row| row.scope.can_list_targets())
+                }),
+            )
+            .with_key(KeyCode::Enter),
+            Action::new(
+                "Collapse/Expand".to_string(),
+                "←/→".to_string(),
+                Box::new(|item: Option<&ScopeTreeRow>| item.is_some_and(|row| row.has_children)),
+            ),
+            Action::new(
+                "Toggle".to_string(),
+                "Space".to_string(),
+                Box::new(|item: Option<&ScopeTreeRow>| item.is_some_and(|row| row.has_children)),
+            )
+            .with_key(KeyCode::Char(' ')),
+            Action::new(
+                "Expand/Collapse All".to_string(),
+                "+/-".to_string(),
+                Box::new(|_: Option<&ScopeTreeRow>| true),
+            ),
+            Action::new(
+                "Scopes List".to_string(),
+                "T".to_string(),
+                Box::new(|_: Option<&ScopeTreeRow>| true),
+            )
+            .with_key(KeyCode::Char('T')),
+            Action::new(
+                "Reload".to_string(),
+                "r".to_string(),
+                Box::new(|_: Option<&ScopeTreeRow>| true),
+            )
+            .with_key(KeyCode::Char('r')),
+        ];
+
+        let mut table_page = TablePage::new(
+            "Scope Tree".to_string(),
+            columns,
+            Vec::new(),
+            actions,
+            true,
+        );
+        table_page.enable_column_resizing(columns_resized_sender(COLUMN_WIDTHS_KEY, message_tx.clone()));
+        table_page.set_view_filter(Some(hide_collapsed(collapsed.clone())));
+
+        let page = ScopeTreePage {
+            table_page,
+            message_tx,
+            boundary_client,
+            collapsed,
+            scope_rules: ScopeRules::default(),
+            known,
+        };
+        page.load_scopes().await;
+        page
+    }
+
+    pub fn with_column_widths(mut self, column_widths: &HashMap<String, Vec<u16>>) -> Self {
+        if let Some(widths) = column_widths.get(COLUMN_WIDTHS_KEY) {
+            self.table_page.set_column_widths(widths);
+        }
+        self
+    }
+
+    pub fn with_scope_rules(mut self, scope_rules: &ScopeRules) -> Self {
+        let rules = scope_rules.clone();
+        self.table_page.set_row_style(Box::new(move |row: &ScopeTreeRow| {
+            if rules.is_restricted(&row.scope) {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default()
+            }
+        }));
+        self.scope_rules = scope_rules.clone();
+        self
+    }
+
+    async fn load_scopes(&self) {
+        let boundary_client = self.boundary_client.clone();
+        let message_tx = self.message_tx.clone();
+        let known = self.known.clone();
+        let future = async move {
+            let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+            let report_progress = async {
+                while let Some(count) = progress_rx.recv().await {
+                    message_tx
+                        .send_or_log(ScopeTreeMessage::ScopesDiscovered(count).into())
+                        .await;
+                }
+            };
+            // The progress channel closes once the scopes are loaded, ending the reporting
+            let (result, ()) = futures::join!(
+                known.scopes_with_progress(&boundary_client, progress_tx),
+                report_progress
+            );
+            match result {
+                Ok(listing) => {
+                    message_tx
+                        .send_or_log(ScopeTreeMessage::ScopesLoaded(listing.scopes).into())
+                        .await;
+                    // The tree shows the scopes that could be listed, the others are only warned about
+                    if !listing.failed_scopes.is_empty() {
+                        let failed_scopes = failure_reasons(&listing.failed_scopes);
+                        message_tx
+                            .send_or_log(Message::scopes_failed(&failed_scopes))
+                            .await;
+                    }
+                }
+                Err(e) => {
+                    message_tx
+                        .send_or_log(ScopeTreeMessage::LoadScopesFailed.into())
+                        .await;
+                    message_tx
+                        .send_or_log(Message::show_error("Failed to load scopes", e))
+                        .await;
+                }
+            }
+        }
+        .boxed();
+        self.message_tx.send_or_log(Message::RunFuture(future)).await;
+    }
+
+    /// Reloads the scopes, as `r` does.
+    pub async fn reload_scopes(&mut self) {
+        self.table_page.notice = None;
+        self.table_page.progress = None;
+        self.table_page.loading = true;
+        self.known.forget();
+        self.load_scopes().await;
+    }
+
+    /// Names of the scopes from the root down to the selected scope.
+    pub fn selected_path(&self) -> Option<Vec<String>> {
+        self.table_page.selected_item().map(|row| row.path.clone())
+    }
+
+    /// Ids of the scopes from the root down to the selected scope, matching `selected_path`.
+    pub fn selected_ids(&self) -> Option<Vec<String>> {
+        self.table_page.selected_item().map(|row| {
+            let mut ids = row.ancestors.clone();
+            ids.push(row.scope.id.clone());
+            ids
+        })
+    }
+
+    /// Name and shortcut of the actions enabled for the selected item, for the command palette.
+    pub fn enabled_actions(&self) -> Vec<EnabledAction> {
+        self.table_page.enabled_actions()
+    }
+
+    pub fn view(&self, frame: &mut Frame, area: Rect) {
+        self.table_page.view(frame, area);
+    }
+
+    /// Collapses or expands the selected scope, the selection stays on it.
+    fn set_collapsed(&mut self, collapse: bool) {
+        let Some(row) = self.table_page.selected_item() else {
+            return;
+        };
+        if !row.has_children {
+            return;
+        }
+        let changed = if collapse {
+            self.collapsed.borrow_mut().insert(row.scope.id.clone())
+        } else {
+            self.collapsed.borrow_mut().remove(&row.scope.id)
+        };
+        if changed {
+            self.table_page
+                .set_view_filter(Some(hide_collapsed(self.collapsed.clone())));
+            self.table_page.select_where(|r| r.scope.id == row.scope.id);
+        }
+    }
+
+    fn toggle_collapsed(&mut self) {
+        let Some(row) = self.table_page.selected_item() else {
+            return;
+        };
+        let collapse = !self.collapsed.borrow().contains(&row.scope.id);
+        self.set_collapsed(collapse);
+    }
+
+    /// Collapses or expands every scope with children. The selection stays on the selected
+    /// scope, or moves to the closest scope above it that is still shown.
+    fn set_all_collapsed(&mut self, collapse: bool) {
+        let selected = self.table_page.selected_item();
+        {
+            let mut collapsed = self.collapsed.borrow_mut();
+            collapsed.clear();
+            if collapse {
+                collapsed.extend(
+                    self.table_page
+                        .items()
+                        .filter(|row| row.has_children)
+                        .map(|row| row.scope.id.clone()),
+                );
+            }
+        }
+        self.table_page
+            .set_view_filter(Some(hide_collapsed(self.collapsed.clone())));
+        if let Some(row) = selected {
+            let _ = std::iter::once(&row.scope.id)
+                .chain(row.ancestors.iter().rev())
+                .any(|id| self.table_page.select_where(|r| &r.scope.id == id));
+        }
+    }
+
+    async fn open_selected(&mut self) {
+        let Some(row) = self.table_page.selected_item() else {
+            return;
+        };
+        if self.scope_rules.is_restricted(&row.scope) {
+            self.message_tx
+                .send_or_log(Message::ShowAlert(
+                    "Restricted scope".to_string(),
+                    restricted_message(&row.scope),
+                ))
+                .await;
+        } else if row.scope.can_list_targets() {
+            self.message_tx
+                .send_or_log(Message::ShowTargets {
+                    parent: row.scope.clone(),
+                    select_target_id: None,
+                })
+                .await;
+        } else {
+            self.toggle_collapsed();
+        }
+    }
+
+    pub async fn handle_event(&mut self, event: &Event) {
+        if self.table_page.handle_event(event).await {
+            return;
+        }
+        if let Event::Key(key_event) = event {
+            match key_event.code {
+                KeyCode::Enter => self.open_selected().await,
+                KeyCode::Left => self.set_collapsed(true),
+                KeyCode::Right => self.set_collapsed(false),
+                KeyCode::Char(' ') => self.toggle_collapsed(),
+                KeyCode::Char('+') => self.set_all_collapsed(false),
+                KeyCode::Char('-') => self.set_all_collapsed(true),
+                KeyCode::Char('T') => {
+                    self.message_tx.send_or_log(Message::CloseScopeTree).await;
+                }
+                KeyCode::Char('r') => self.reload_scopes().await,
+                KeyCode::Char('q') => {
+                    self.message_tx.send_or_log(Message::Quit).await;
+                }
+                KeyCode::Esc => {
+                    self.message_tx.send_or_log(Message::GoBack).await;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub fn handle_message(&mut self, message: ScopeTreeMessage) {
+        match message {
+            ScopeTreeMessage::ScopesLoaded(scopes) => {
+                self.table_page.notice = None;
+                self.table_page.set_items(build_scope_tree(scopes));
+                self.table_page.loading = false;
+                self.table_page.progress = None;
+            }
+            ScopeTreeMessage::LoadScopesFailed => {
+                self.table_page.loading = false;
+                self.table_page.progress = None;
+                self.table_page.notice = Some(LOAD_SCOPES_FAILED.to_string());
+            }
+            ScopeTreeMessage::ScopesDiscovered(count) => {
+                self.table_page.progress = Some(format!("Discovered {} scopes…", count));
+            }
+        }
+    }
+}
+
+impl SortItems<ScopeTreeRow> for TablePage<ScopeTreeRow> {
+    fn sort(items: &mut Vec<Rc<ScopeTreeRow>>) {
+        items.sort_by_key(|row| row.position);
+    }
+}
+
+impl FilterItems<ScopeTreeRow> for TablePage<ScopeTreeRow> {
+    fn matches(item: &ScopeTreeRow, search: &str) -> bool {
+        Self::match_str(&item.path(), search) || Self::match_str(&item.scope.id, search)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::fixtures::{listing, scope};
+    use crossterm::event::KeyEvent;
+
+    fn names(rows: &[ScopeTreeRow]) -> Vec<(usize, &str)> {
+        rows.iter().map(|r| (r.depth(), r.scope.name.as_str())).collect()
+    }
+
+    #[test]
+    fn tree_lists_children_below_their_parent_by_name() {
+        let rows = build_scope_tree(vec![
+            scope("p_2", "platform", "o_1"),
+            scope("o_2", "zeta", "global"),
+            scope("p_1", "billing", "o_1"),
+            scope("o_1", "acme", "global"),
+            scope("p_3", "team-a", "p_2"),
+            scope("p_4", "web", "o_2"),
+        ]);
+
+        assert_eq!(
+            names(&rows),
+            vec![
+                (0, "acme"),
+                (1, "billing"),
+                (1, "platform"),
+                (2, "team-a"),
+                (0, "zeta"),
+                (1, "web"),
+            ]
+        );
+        assert_eq!(rows[3].path(), "acme / platform / team-a");
+        assert!(rows[2].has_children);
+        assert!(!rows[3].has_children);
+    }
+
+    #[test]
+    fn scopes_with_unknown_parents_are_roots() {
+        let rows = build_scope_tree(vec![
+            scope("p_1", "orphan", "o_missing"),
+            scope("o_1", "acme", "global"),
+            scope("o_2", "self", "o_2"),
+        ]);
+
+        assert_eq!(names(&rows), vec![(0, "acme"), (0, "orphan"), (0, "self")]);
+    }
+
+    async fn make_page() -> (ScopeTreePage<crate::boundary::MockClient>, tokio::sync::mpsc::Receiver<Message>) {
+        let (message_tx, mut message_rx) = tokio::sync::mpsc::channel(10);
+        let client = crate::boundary::MockClient::builder()
+            .scopes(HashMap::from([
+                (None, vec![listing(scope("o_1", "acme", "global"), "scopes")]),
+                (Some("o_1".to_string()), vec![listing(scope("p_1", "platform", "o_1"), "scopes")]),
+                (Some("p_1".to_string()), vec![scope("p_2", "team-a", "p_1")]),
+            ]))
+            .build();
+        let mut page = ScopeTreePage::new(message_tx, client, KnownItems::default()).await;
+        process_messages(&mut page, &mut message_rx, |_| {}).await;
+        (page, message_rx)
+    }
+
+    // Also hands each message to `inspect`, before the page handles it
+    async fn process_messages(
+        page: &mut ScopeTreePage<crate::boundary::MockClient>,
+        message_rx: &mut tokio::sync::mpsc::Receiver<Message>,
+        mut inspect: impl FnMut(&ScopeTreeMessage),
+    ) {
+        while let Ok(message) = message_rx.try_recv() {
+            match message {
+                Message::RunFuture(future) => future.await,
+                Message::ScopeTree(message) => {
+                    inspect(&message);
+                    page.handle_message(message)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn shown(page: &ScopeTreePage<crate::boundary::MockClient>) -> Vec<&str> {
+        page.table_page.shown_items().map(|r| r.scope.name.as_str()).collect()
+    }
+
+    async fn press(page: &mut ScopeTreePage<crate::boundary::MockClient>, code: KeyCode) {
+        page.handle_event(&Event::Key(KeyEvent::from(code))).await;
+    }
+
+    #[tokio::test]
+    async fn left_collapses_and_right_expands_the_selected_scope() {
+        let (mut page, _message_rx) = make_page().await;
+        assert_eq!(shown(&page), vec!["acme", "platform", "team-a"]);
+
+        press(&mut page, KeyCode::Down).await;
+        press(&mut page, KeyCode::Left).await;
+        assert_eq!(shown(&page), vec!["acme", "platform"]);
+        assert_eq!(page.selected_path(), Some(vec!["acme".to_string(), "platform".to_string()]));
+
+        press(&mut page, KeyCode::Up).await;
+        press(&mut page, KeyCode::Left).await;
+        assert_eq!(shown(&page), vec!["acme"]);
+
+        press(&mut page, KeyCode::Right).await;
+        assert_eq!(shown(&page), vec!["acme", "platform"], "platform stays collapsed");
+    }
+
+    #[tokio::test]
+    async fn filter_matches_the_path() {
+        let (mut page, _message_rx) = make_page().await;
+        for c in "/platform".chars() {
+            press(&mut page, KeyCode::Char(c)).await;
+        }
+
+        assert_eq!(shown(&page), vec!["platform", "team-a"]);
+    }
+
+    #[tokio::test]
+    async fn enter_shows_the_targets_of_the_selected_scope() {
+        let (mut page, mut message_rx) = make_page().await;
+        let mut targets_scope = scope("p_2", "team-a", "p_1");
+        targets_scope
+            .authorized_collection_actions
+            .insert("targets".to_string(), vec!["list".to_string()]);
+        page.handle_message(ScopeTreeMessage::ScopesLoaded(vec![
+            scope("o_1", "acme", "global"),
+            targets_scope,
+        ]));
+
+        press(&mut page, KeyCode::Down).await;
+        press(&mut page, KeyCode::Enter).await;
+
+        match message_rx.try_recv() {
+            Ok(Message::ShowTargets { parent, .. }) => assert_eq!(parent.id, "p_2"),
+            _ => panic!("Expected ShowTargets"),
+        }
+    }
+
+    #[tokio::test]
+    async fn reloading_reports_the_discovered_scopes() {
+        let (mut page, mut message_rx) = make_page().await;
+        press(&mut page, KeyCode::Char('r')).await;
+
+        let mut discovered = Vec::new();
+        process_messages(&mut page, &mut message_rx, |message| {
+            if let ScopeTreeMessage::ScopesDiscovered(count) = message {
+                discovered.push(*count);
+            }
+        })
+        .await;
+
+        assert_eq!(discovered, vec![1, 2, 3]);
+        assert_eq!(page.table_page.progress, None);
+        assert_eq!(shown(&page), vec!["acme", "platform", "team-a"]);
+    }
+
+    #[tokio::test]
+    async fn discovered_scopes_are_shown_while_loading() {
+        let (mut page, _message_rx) = make_page().await;
+        page.handle_message(ScopeTreeMessage::ScopesDiscovered(120));
+
+        assert_eq!(page.table_page.progress.as_deref(), Some("Discovered 120 scopes…"));
+    }
+
+    fn shown_with(rows: &[ScopeTreeRow], collapsed: &[&str]) -> Vec<String> {
+        let collapsed: HashSet<String> = collapsed.iter().map(|id| id.to_string()).collect();
+        rows.iter()
+            .filter(|row| is_expanded_to(row, &collapsed))
+            .map(|row| row.scope.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn collapsed_scopes_hide_all_their_descendants() {
+        let rows = build_scope_tree(vec![
+            scope("o_1", "acme", "global"),
+            scope("p_1", "platform", "o_1"),
+            scope("p_2", "team-a", "p_1"),
+            scope("p_3", "billing", "o_1"),
+            scope("o_2", "zeta", "global"),
+            scope("p_4", "web", "o_2"),
+        ]);
+
+        assert_eq!(
+            shown_with(&rows, &[]),
+            vec!["acme", "billing", "platform", "team-a", "zeta", "web"]
+        );
+        assert_eq!(
+            shown_with(&rows, &["p_1"]),
+            vec!["acme", "billing", "platform", "zeta", "web"]
+        );
+        assert_eq!(shown_with(&rows, &["o_1", "o_2"]), vec!["acme", "zeta"]);
+        assert_eq!(
+            shown_with(&rows, &["o_1", "p_1"]),
+            vec!["acme", "zeta", "web"],
+            "a collapsed scope inside a collapsed scope stays hidden"
+        );
+    }
+
+    #[tokio::test]
+    async fn collapse_all_keeps_the_closest_shown_scope_selected() {
+        let (mut page, _message_rx) = make_page().await;
+        press(&mut page, KeyCode::Down).await;
+        press(&mut page, KeyCode::Down).await;
+        assert_eq!(page.selected_path().unwrap().last().unwrap(), "team-a");
+
+        press(&mut page, KeyCode::Char('-')).await;
+        assert_eq!(shown(&page), vec!["acme"]);
+        assert_eq!(page.selected_path(), Some(vec!["acme".to_string()]));
+
+        press(&mut page, KeyCode::Char('+')).await;
+        assert_eq!(shown(&page), vec!["acme", "platform", "team-a"]);
+        assert_eq!(page.selected_path(), Some(vec!["acme".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn space_toggles_the_selected_scope() {
+        let (mut page, _message_rx) = make_page().await;
+        press(&mut page, KeyCode::Char(' ')).await;
+        assert_eq!(shown(&page), vec!["acme"]);
+
+        press(&mut page, KeyCode::Char(' ')).await;
+        assert_eq!(shown(&page), vec!["acme", "platform", "team-a"]);
+    }
+
+    #[tokio::test]
+    async fn scopes_that_fail_to_list_are_skipped_with_a_warning() {
+        let (message_tx, mut message_rx) = tokio::sync::mpsc::channel(10);
+        let client = crate::boundary::MockClient::builder()
+            .scopes(HashMap::from([
+                (
+                    None,
+                    vec![
+                        listing(scope("o_1", "acme", "global"), "scopes"),
+                        listing(scope("o_2", "globex", "global"), "scopes"),
+                    ],
+                ),
+                (Some("o_1".to_string()), vec![scope("p_1", "platform", "o_1")]),
+            ]))
+            .failing_scopes(HashMap::from([("o_2".to_string(), 403)]))
+            .build();
+        let mut page = ScopeTreePage::new(message_tx, client, KnownItems::default()).await;
+
+        let mut alerts = Vec::new();
+        while let Ok(message) = message_rx.try_recv() {
+            match message {
+                Message::RunFuture(future) => future.await,
+                Message::ScopeTree(message) => page.handle_message(message),
+                Message::ShowAlert(_, text) => alerts.push(text),
+                _ => {}
+            }
+        }
+
+        assert_eq!(shown(&page), vec!["acme", "platform", "globex"]);
+        assert_eq!(page.table_page.notice, None);
+        assert_eq!(alerts, vec!["1 scope could not be listed: o_2 (403)"]);
+    }
+}
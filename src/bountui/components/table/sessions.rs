@@ -1,32 +1,88 @@
 use crate::boundary;
-use crate::boundary::{ApiClient, ApiClientExt, CredentialEntry, Error, SessionWithTarget};
+use crate::boundary::{
+    ApiClient, ApiClientExt, CredentialEntry, Error, SessionWithTarget, Target, UserSessions,
+};
 use crate::bountui::components::credential_dialog::CredentialDialog;
 use crate::bountui::components::table::action::Action;
-use crate::bountui::components::table::util::format_title_with_parent;
-use crate::bountui::components::table::{FilterItems, SortItems, TableColumn};
-use crate::bountui::components::TablePage;
-use crate::bountui::Message;
-use crossterm::event::{Event, KeyCode, KeyModifiers};
+use crate::bountui::components::table::util::{
+    columns_resized_sender, format_remaining, format_title_with_parent, local_address,
+};
+use crate::bountui::components::table::{EnabledAction, FilterItems, SortItems, TableColumn, ViewFilter};
+use crate::bountui::components::{toaster, ConfirmDialog, TablePage};
+use crate::bountui::session_export::{self, ExportedSession};
+use crate::bountui::{failed_scopes_summary, failure_reasons, Message};
+use crate::event_ext::EventExt;
+use crate::util::MpscSenderExt;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 use futures::FutureExt;
-use ratatui::layout::{Constraint, Rect};
+use chrono::{TimeDelta, Utc};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
+use ratatui::style::{Color, Style};
 use ratatui::Frame;
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::future::Future;
 use std::rc::Rc;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::select;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tokio::sync::mpsc::Sender;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
 use tokio_util::sync::CancellationToken;
 
 pub struct SessionsPage<R: LoadSessions + Send + 'static> {
     table_page: TablePage<boundary::SessionWithTarget>,
     message_tx: mpsc::Sender<Message>,
     reload_now_tx: mpsc::Sender<()>,
+    // Whether the refresh loop is paused, by the user or because the page is not shown
+    refresh_paused_tx: watch::Sender<bool>,
+    paused: bool,
+    hidden: bool,
+    // The terminal lost the focus, see `set_focused`
+    unfocused: bool,
+    show_all: bool,
+    only_mine: bool,
+    // Only sessions of this type are listed, picked with <t>
+    session_type: Option<String>,
+    // Title without the session type filter
+    title: String,
+    user_id: String,
     marker: std::marker::PhantomData<R>,
     cancellation_token: CancellationToken,
-    credentials: Rc<HashMap<String, Vec<CredentialEntry>>>,
+    // The connections of this bountui, replaced by the app whenever the sessions are reloaded
+    credentials: Rc<RefCell<HashMap<String, Vec<CredentialEntry>>>>,
+    // Ports of the local proxies of sessions managed by this bountui instance
+    local_ports: Rc<RefCell<HashMap<String, u16>>>,
     credential_dialog: Option<CredentialDialog>,
+    // Asks before stopping the session with the given id
+    confirm_stop: Option<(ConfirmDialog, String)>,
+    // Whether all listed sessions can be stopped at once with Ctrl+Shift+d
+    bulk_stop: bool,
+    // Asks before stopping the sessions with the given ids
+    confirm_bulk_stop: Option<(ConfirmDialog, Vec<String>)>,
+    // Sessions a stop was requested for, shown as stopping until the next reload
+    stopping: Rc<RefCell<HashSet<String>>>,
+    // Stopped sessions whose stopping state is cleared by the next reload
+    stopped: HashSet<String>,
+    oldest_first: bool,
+    // Counts by status of all loaded sessions, shown above the table
+    summary: String,
+    // Shown next to the summary when some scopes could not be loaded
+    load_warning: Option<String>,
+    // Labels of the connections of this bountui by session id, shown with the target name
+    connection_labels: Rc<RefCell<HashMap<String, String>>>,
+    refresh_interval: Duration,
+    // When the sessions were last loaded successfully, shown as their age next to the summary
+    last_loaded: Option<Instant>,
+    // Whether the last reload failed, only the first of consecutive failures is alerted
+    load_failing: bool,
+    select_after_load: Option<String>,
 }
 
 impl<L: LoadSessions + Send + Sync + 'static> SessionsPage<L> {
@@ -35,92 +91,261 @@ impl<L: LoadSessions + Send + Sync + 'static> SessionsPage<L> {
         load_sessions: L,
         message_tx: mpsc::Sender<Message>,
         credentials: HashMap<String, Vec<CredentialEntry>>,
+        local_ports: HashMap<String, u16>,
+        refresh_interval: Duration,
+        user_id: String,
     ) -> Self {
-        let credentials = Rc::new(credentials);
+        let credentials = Rc::new(RefCell::new(credentials));
+        let local_ports = Rc::new(RefCell::new(local_ports));
+        let local_ports_for_column = local_ports.clone();
+        let stopping: Rc<RefCell<HashSet<String>>> = Rc::new(RefCell::new(HashSet::new()));
+        let stopping_for_column = stopping.clone();
+        let connection_labels: Rc<RefCell<HashMap<String, String>>> = Rc::default();
+        let connection_labels_for_column = connection_labels.clone();
 
         let columns = vec![
             TableColumn::new(
                 "Id".to_string(),
-                Constraint::Ratio(1, 6),
+                Constraint::Length(12),
                 Box::new(|s: &boundary::SessionWithTarget| s.session.id.clone()),
-            ),
+            )
+            .hidden(),
             TableColumn::new(
                 "Target name".to_string(),
-                Constraint::Ratio(1, 6),
-                Box::new(|s| s.target.name.clone()),
+                Constraint::Fill(20),
+                Box::new(move |s: &SessionWithTarget| {
+                    match connection_labels_for_column.borrow().get(&s.session.id) {
+                        Some(label) => format!("{} [{label}]", s.target_name()),
+                        None => s.target_name(),
+                    }
+                }),
             ),
             TableColumn::new(
                 "Target".to_string(),
-                Constraint::Ratio(1, 6),
-                Box::new(|s| s.target.id.clone()),
+                Constraint::Fill(12),
+                Box::new(|s| s.target_id().to_string()),
+            ),
+            TableColumn::new(
+                "User".to_string(),
+                Constraint::Fill(10),
+                Box::new(|s| s.session.user_id.clone()),
             ),
             TableColumn::new(
                 "Type".to_string(),
-                Constraint::Ratio(1, 6),
+                Constraint::Length(5),
                 Box::new(|s| s.session.session_type.clone()),
             ),
             TableColumn::new(
                 "Status".to_string(),
-                Constraint::Ratio(1, 6),
-                Box::new(|s| s.session.status.clone()),
+                Constraint::Length(10),
+                Box::new(move |s: &SessionWithTarget| {
+                    if stopping_for_column.borrow().contains(&s.session.id) {
+                        "stopping…".to_string()
+                    } else {
+                        s.session.status.clone()
+                    }
+                }),
             ),
             TableColumn::new(
-                "Created Time".to_string(),
-                Constraint::Ratio(1, 6),
-                Box::new(|s| s.session.created_time.to_string()),
+                created_time_header(false),
+                Constraint::Length(19),
+                Box::new(|s| s.session.created_time.format("%Y-%m-%d %H:%M:%S").to_string()),
             ),
+            TableColumn::new(
+                "Local".to_string(),
+                Constraint::Length(15),
+                Box::new(move |s: &SessionWithTarget| {
+                    local_ports_for_column
+                        .borrow()
+                        .get(&s.session.id)
+                        .map(|port| local_address(*port))
+                        .unwrap_or_default()
+                }),
+            ),
+            TableColumn::new(
+                "Expires".to_string(),
+                Constraint::Length(7),
+                Box::new(|s: &SessionWithTarget| {
+                    s.session
+                        .expiration_time
+                        .map(|e| format_remaining(e - Utc::now()))
+                        .unwrap_or_else(|| "-".to_string())
+                }),
+            )
+            .with_style(Box::new(|s: &SessionWithTarget| {
+                if expires_soon(s) {
+                    Style::default().fg(Color::Red)
+                } else {
+                    Style::default()
+                }
+            })),
         ];
 
         let credentials_for_action = credentials.clone();
+        let credentials_for_copy = credentials.clone();
+        let local_ports_for_action = local_ports.clone();
         let actions = vec![
             Action::new(
                 "Quit".to_string(),
-                "Ctrl + C".to_string(),
+                "q".to_string(),
                 Box::new(|_: Option<&SessionWithTarget>| true),
             ),
             Action::new(
                 "Back".to_string(),
                 "ESC".to_string(),
                 Box::new(|_: Option<&SessionWithTarget>| true),
-            ),
+            )
+            .with_key(KeyCode::Esc),
+            Action::new(
+                "Show Target".to_string(),
+                "⏎".to_string(),
+                Box::new(|item: Option<&SessionWithTarget>| item.is_some_and(|s| s.target.is_some())),
+            )
+            .with_key(KeyCode::Enter),
             Action::new(
                 "Stop Session".to_string(),
                 "Ctrl + d".to_string(),
                 Box::new(|item: Option<&SessionWithTarget>| {
                     item.map_or(false, |s| s.session.can_cancel())
                 }),
-            ),
+            )
+            .with_key(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL)),
             Action::new(
                 "Show Credentials".to_string(),
                 "v".to_string(),
                 Box::new(move |item: Option<&SessionWithTarget>| {
                     item.map_or(false, |s| {
-                        credentials_for_action.contains_key(&s.session.id)
+                        credentials_for_action.borrow().contains_key(&s.session.id)
                     })
                 }),
-            ),
+            )
+            .with_key(KeyCode::Char('v')),
+            Action::new(
+                "Copy Credentials".to_string(),
+                "c".to_string(),
+                Box::new(move |item: Option<&SessionWithTarget>| {
+                    item.is_some_and(|s| credentials_for_copy.borrow().contains_key(&s.session.id))
+                }),
+            )
+            .with_key(KeyCode::Char('c')),
+            Action::new(
+                "Copy Local Address".to_string(),
+                "y".to_string(),
+                Box::new(move |item: Option<&SessionWithTarget>| {
+                    item.is_some_and(|s| local_ports_for_action.borrow().contains_key(&s.session.id))
+                }),
+            )
+            .with_key(KeyCode::Char('y')),
+            Action::new(
+                "Session Info".to_string(),
+                "i".to_string(),
+                Box::new(|item: Option<&SessionWithTarget>| item.is_some()),
+            )
+            .with_key(KeyCode::Char('i')),
+            Action::new(
+                "Copy Id".to_string(),
+                "Y".to_string(),
+                Box::new(|item: Option<&SessionWithTarget>| item.is_some()),
+            )
+            .with_key(KeyCode::Char('Y')),
+            Action::new(
+                "Show/Hide Id".to_string(),
+                "I".to_string(),
+                Box::new(|_: Option<&SessionWithTarget>| true),
+            )
+            .with_key(KeyCode::Char('I')),
+            Action::new(
+                "Export".to_string(),
+                "e".to_string(),
+                Box::new(|_: Option<&SessionWithTarget>| true),
+            )
+            .with_key(KeyCode::Char('e')),
+            Action::new(
+                "Refresh".to_string(),
+                "r".to_string(),
+                Box::new(|_: Option<&SessionWithTarget>| true),
+            )
+            .with_key(KeyCode::Char('r')),
+            Action::new(
+                "Show All".to_string(),
+                "a".to_string(),
+                Box::new(|_: Option<&SessionWithTarget>| true),
+            )
+            .with_key(KeyCode::Char('a')),
+            Action::new(
+                "Only Mine".to_string(),
+                "m".to_string(),
+                Box::new(|_: Option<&SessionWithTarget>| true),
+            )
+            .with_key(KeyCode::Char('m')),
+            Action::new(
+                "Pause/Resume".to_string(),
+                "p".to_string(),
+                Box::new(|_: Option<&SessionWithTarget>| true),
+            )
+            .with_key(KeyCode::Char('p')),
+            Action::new(
+                "Newest/Oldest First".to_string(),
+                "s".to_string(),
+                Box::new(|_: Option<&SessionWithTarget>| true),
+            )
+            .with_key(KeyCode::Char('s')),
+            Action::new(
+                "Filter Type".to_string(),
+                "t".to_string(),
+                Box::new(|_: Option<&SessionWithTarget>| true),
+            )
+            .with_key(KeyCode::Char('t')),
         ];
 
-        let table_page = TablePage::new(
-            format_title_with_parent("Sessions", parent_name),
+        let title = format_title_with_parent("Sessions", parent_name);
+        let mut table_page = TablePage::new(
+            title.clone(),
             columns,
             Vec::new(),
             actions,
-            message_tx.clone(),
             true,
         );
+        table_page.enable_column_resizing(columns_resized_sender(
+            COLUMN_WIDTHS_KEY,
+            message_tx.clone(),
+        ));
+        {
+            let user_id = user_id.clone();
+            table_page.set_row_style(Box::new(move |s: &SessionWithTarget| {
+                if s.session.user_id == user_id {
+                    Style::default().fg(Color::LightGreen)
+                } else {
+                    Style::default()
+                }
+            }));
+        }
 
         let (reload_now_tx, mut reload_now_rx) = mpsc::channel(1);
+        let (refresh_paused_tx, mut refresh_paused_rx) = watch::channel(false);
 
         let cancellation_token = CancellationToken::new();
         {
             let cancellation_token = cancellation_token.clone();
             let refresh_future = async move {
+                let mut paused = false;
+                let mut refresh = true;
                 loop {
-                    load_sessions.update_sessions().await;
+                    if refresh {
+                        load_sessions.update_sessions().await;
+                    }
                     select! {
-                        _ = reload_now_rx.recv() => {}
-                        _ = tokio::time::sleep(Duration::from_secs(5)) => {}
+                        _ = reload_now_rx.recv() => {
+                            refresh = true;
+                        }
+                        Ok(()) = refresh_paused_rx.changed() => {
+                            paused = *refresh_paused_rx.borrow_and_update();
+                            // Catch up right away instead of waiting for the next interval
+                            refresh = !paused;
+                        }
+                        _ = tokio::time::sleep(refresh_interval), if !paused => {
+                            refresh = true;
+                        }
                         _ = cancellation_token.cancelled() => {
                                 break;
                             }
@@ -129,59 +354,445 @@ impl<L: LoadSessions + Send + Sync + 'static> SessionsPage<L> {
             }
             .boxed();
 
-            let _ = message_tx.send(Message::RunFuture(refresh_future)).await;
+            message_tx.send_or_log(Message::RunFuture(refresh_future)).await;
         }
 
-        SessionsPage {
+        let mut page = SessionsPage {
             table_page,
             message_tx,
             reload_now_tx,
+            refresh_paused_tx,
+            paused: false,
+            hidden: false,
+            unfocused: false,
+            show_all: false,
+            only_mine: false,
+            session_type: None,
+            title,
+            user_id,
             cancellation_token,
             marker: std::marker::PhantomData,
             credentials,
+            local_ports,
             credential_dialog: None,
+            confirm_stop: None,
+            bulk_stop: false,
+            confirm_bulk_stop: None,
+            stopping,
+            stopped: HashSet::new(),
+            oldest_first: false,
+            summary: String::new(),
+            load_warning: None,
+            connection_labels,
+            refresh_interval,
+            last_loaded: None,
+            load_failing: false,
+            select_after_load: None,
+        };
+        page.update_view_filter();
+        page
+    }
+
+    pub fn with_column_widths(mut self, column_widths: &HashMap<String, Vec<u16>>) -> Self {
+        if let Some(widths) = column_widths.get(COLUMN_WIDTHS_KEY) {
+            self.table_page.set_column_widths(widths);
         }
+        self
+    }
+
+    /// Labels chosen for the connections of this bountui, by session id.
+    pub fn with_connection_labels(self, labels: HashMap<String, String>) -> Self {
+        *self.connection_labels.borrow_mut() = labels;
+        self
+    }
+
+    /// Replaces the credentials, local ports and labels of the connections of this bountui, so
+    /// connections made or stopped while the page is shown are reflected.
+    pub fn set_connections(
+        &mut self,
+        credentials: HashMap<String, Vec<CredentialEntry>>,
+        local_ports: HashMap<String, u16>,
+        labels: HashMap<String, String>,
+    ) {
+        *self.credentials.borrow_mut() = credentials;
+        *self.local_ports.borrow_mut() = local_ports;
+        *self.connection_labels.borrow_mut() = labels;
+    }
+
+    /// Selects the session with the given id once the sessions are loaded.
+    pub fn with_selected_session(mut self, session_id: Option<String>) -> Self {
+        self.select_after_load = session_id;
+        self
     }
 
-    async fn stop_session(&self) {
+    /// Allows stopping all sessions matching the filter and status view at once.
+    pub fn with_bulk_stop(mut self) -> Self {
+        self.bulk_stop = true;
+        self
+    }
+
+    // The sessions as listed, leaving out the ones hidden by the filters
+    fn exported_sessions(&self) -> Vec<ExportedSession> {
+        self.table_page
+            .shown_items()
+            .map(|s| ExportedSession::new(s, &self.local_ports.borrow()))
+            .collect()
+    }
+
+    async fn export_sessions(&self) {
+        let sessions = self.exported_sessions();
+        let result = session_export::default_export_path()
+            .and_then(|path| session_export::write_sessions(&path, &sessions).map(|_| path));
+        let message = match result {
+            Ok(path) => Message::Toaster(toaster::Message::ShowToast {
+                text: format!("Exported {} sessions to {}", sessions.len(), path.display()),
+                duration: Duration::from_secs(5),
+            }),
+            Err(e) => Message::ShowAlert("Export failed".to_string(), format!("{e:#}")),
+        };
+        self.message_tx.send_or_log(message).await;
+    }
+
+    async fn confirm_stop_session(&mut self) {
         if let Some(session) = self.table_page.selected_item() {
-            self.message_tx
-                .send(Message::StopSession {
-                    session_id: session.session.id.clone(),
-                    notify_stopped_tx: self.reload_now_tx.clone(),
+            if let Some(reason) = stop_unavailable_reason(&session.session) {
+                self.message_tx
+                    .send_or_log(Message::ShowAlert("Stop Session".to_string(), reason))
+                    .await;
+                return;
+            }
+            let dialog = ConfirmDialog::new(
+                "Stop Session".to_string(),
+                format!(
+                    "Cancel session {} on target {}?",
+                    session.session.id,
+                    session.target_name()
+                ),
+            );
+            self.confirm_stop = Some((dialog, session.session.id.clone()));
+        }
+    }
+
+    fn confirm_bulk_stop(&mut self) {
+        let session_ids: Vec<String> = self
+            .table_page
+            .shown_items()
+            .filter(|s| {
+                stop_unavailable_reason(&s.session).is_none()
+                    && !self.stopping.borrow().contains(&s.session.id)
+            })
+            .map(|s| s.session.id.clone())
+            .collect();
+        if session_ids.is_empty() {
+            return;
+        }
+        let dialog = ConfirmDialog::new(
+            "Stop Sessions".to_string(),
+            format!("Cancel all {} listed sessions?", session_ids.len()),
+        );
+        self.confirm_bulk_stop = Some((dialog, session_ids));
+    }
+
+    async fn stop_sessions(&mut self, session_ids: Vec<String>) {
+        self.stopping
+            .borrow_mut()
+            .extend(session_ids.iter().cloned());
+        self.message_tx
+            .send_or_log(Message::StopSessions {
+                session_ids,
+                notify_stopped_tx: self.reload_now_tx.clone(),
+            })
+            .await;
+    }
+
+    async fn stop_session(&mut self, session_id: String) {
+        self.stopping.borrow_mut().insert(session_id.clone());
+        self.message_tx
+            .send_or_log(Message::StopSession {
+                session_id,
+                notify_stopped_tx: self.reload_now_tx.clone(),
+            })
+            .await;
+    }
+
+    fn update_view_filter(&mut self) {
+        let show_all = self.show_all;
+        let user_id = self.only_mine.then(|| self.user_id.clone());
+        let session_type = self.session_type.clone();
+        let view_filter: Option<ViewFilter<SessionWithTarget>> =
+            if show_all && user_id.is_none() && session_type.is_none() {
+                None
+            } else {
+                Some(Box::new(move |s: &SessionWithTarget| {
+                    (show_all || !s.session.is_ended())
+                        && user_id.as_ref().is_none_or(|u| &s.session.user_id == u)
+                        && session_type.as_ref().is_none_or(|t| &s.session.session_type == t)
+                }))
+            };
+        self.table_page.set_view_filter(view_filter);
+        self.update_status();
+    }
+
+    /// Switches to the next session type of the loaded sessions, after the last one all are shown.
+    fn cycle_session_type(&mut self) {
+        let types: std::collections::BTreeSet<&str> = self
+            .table_page
+            .items()
+            .map(|s| s.session.session_type.as_str())
+            .collect();
+        let next = match &self.session_type {
+            None => types.first(),
+            Some(current) => types.iter().find(|t| **t > current.as_str()),
+        };
+        self.session_type = next.map(|t| t.to_string());
+        let title = match &self.session_type {
+            Some(session_type) => format!("{} [{}]", self.title, session_type),
+            None => self.title.clone(),
+        };
+        self.table_page.set_title(title);
+        self.update_view_filter();
+    }
+
+    fn toggle_show_all(&mut self) {
+        self.show_all = !self.show_all;
+        self.update_view_filter();
+    }
+
+    fn toggle_only_mine(&mut self) {
+        self.only_mine = !self.only_mine;
+        self.update_view_filter();
+    }
+
+    fn toggle_sort_order(&mut self) {
+        self.oldest_first = !self.oldest_first;
+        if self.oldest_first {
+            self.table_page
+                .set_sort_override(Some(Box::new(|a: &SessionWithTarget, b: &SessionWithTarget| {
+                    compare_created_time(b, a)
+                })));
+        } else {
+            self.table_page.set_sort_override(None);
+        }
+        self.table_page
+            .set_column_header(CREATED_TIME_COLUMN, created_time_header(self.oldest_first));
+    }
+
+    fn update_status(&mut self) {
+        let hidden = self.table_page.hidden_count();
+        let mode = match (self.show_all, self.only_mine) {
+            (true, false) => "All sessions".to_string(),
+            (false, false) => format!("Active only, {hidden} hidden"),
+            (true, true) => format!("My sessions, {hidden} hidden"),
+            (false, true) => format!("My active sessions, {hidden} hidden"),
+        };
+        self.table_page.status = Some(if self.paused {
+            format!("{mode} | Paused")
+        } else {
+            mode
+        });
+    }
+
+    /// Reloads the sessions, as `r` does.
+    pub fn refresh_now(&self) {
+        // A pending reload is as good as a new one
+        let _ = self.reload_now_tx.try_send(());
+    }
+
+    /// Shows the targets of the selected session's scope with its target selected.
+    async fn show_target(&self) {
+        if let Some(target) = self
+            .table_page
+            .selected_item()
+            .and_then(|s| s.target.clone())
+        {
+            self
+                .message_tx
+                .send_or_log(Message::ShowTargetsForTarget {
+                    scope_id: target.scope_id.clone(),
+                    target_id: target.id.clone(),
                 })
-                .await
-                .unwrap();
+                .await;
         }
     }
 
+    fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.update_status();
+        self.update_refresh_paused();
+    }
+
+    /// Stops reloading while the page is not shown, e.g. kept in the history, and catches up
+    /// once it is shown again unless the user paused it.
+    pub fn set_hidden(&mut self, hidden: bool) {
+        self.hidden = hidden;
+        self.update_refresh_paused();
+    }
+
+    /// Stops reloading while the terminal is not focused and catches up once it is again, unless
+    /// the user paused it.
+    pub fn set_focused(&mut self, focused: bool) {
+        self.unfocused = !focused;
+        self.update_refresh_paused();
+    }
+
+    fn update_refresh_paused(&self) {
+        let paused = self.paused || self.hidden || self.unfocused;
+        self.refresh_paused_tx.send_if_modified(|current| {
+            let modified = *current != paused;
+            *current = paused;
+            modified
+        });
+    }
+
+    #[cfg(test)]
+    pub fn is_refresh_paused(&self) -> bool {
+        *self.refresh_paused_tx.borrow()
+    }
+
+    async fn copy_local_address(&self) {
+        let Some(session) = self.table_page.selected_item() else {
+            return;
+        };
+        let Some(port) = self.local_ports.borrow().get(&session.session.id).copied() else {
+            return;
+        };
+        self
+            .message_tx
+            .send_or_log(Message::SetClipboard {
+                text: local_address(port),
+                on_success: Some(Box::new(Message::toast("Local address copied"))),
+                on_error: Some(Box::new(Message::toast("Failed to copy local address"))),
+            })
+            .await;
+    }
+
+    async fn copy_session_id(&self) {
+        let Some(session) = self.table_page.selected_item() else {
+            return;
+        };
+        self.message_tx
+            .send_or_log(Message::SetClipboard {
+                text: session.session.id.clone(),
+                on_success: Some(Box::new(Message::toast("Session id copied"))),
+                on_error: Some(Box::new(Message::toast("Failed to copy session id"))),
+            })
+            .await;
+    }
+
+    // Shows the full id, which is hidden with the id column by default
+    async fn show_session_info(&self) {
+        let Some(session) = self.table_page.selected_item() else {
+            return;
+        };
+        let info = format!(
+            "Id: {}\nTarget: {} ({})\nStatus: {}\nCreated: {}",
+            session.session.id,
+            session.target_name(),
+            session.target_id(),
+            session.session.status,
+            session.session.created_time.format("%Y-%m-%d %H:%M:%S"),
+        );
+        self.message_tx
+            .send_or_log(Message::ShowAlert("Session".to_string(), info))
+            .await;
+    }
+
+    // The app copies them from the connection manager, which keeps the whole connect response
+    async fn copy_credentials(&self) {
+        let Some(session) = self.table_page.selected_item() else {
+            return;
+        };
+        if !self.credentials.borrow().contains_key(&session.session.id) {
+            return;
+        }
+        self.message_tx
+            .send_or_log(Message::CopySessionCredentials {
+                session_id: session.session.id.clone(),
+            })
+            .await;
+    }
+
     fn show_credentials(&mut self) {
         if let Some(session) = self.table_page.selected_item() {
-            if let Some(creds) = self.credentials.get(&session.session.id) {
+            if let Some(creds) = self.credentials.borrow().get(&session.session.id) {
+                let database = session
+                    .target
+                    .as_ref()
+                    .zip(self.local_ports.borrow().get(&session.session.id))
+                    .map(|(target, port)| (target.type_name.clone(), *port));
                 self.credential_dialog = Some(CredentialDialog::new(
                     creds.clone(),
                     self.message_tx.clone(),
+                    database,
                 ));
             }
         }
     }
 
+    /// Name and shortcut of the actions enabled for the selected item, for the command palette.
+    pub fn enabled_actions(&self) -> Vec<EnabledAction> {
+        self.table_page.enabled_actions()
+    }
+
     pub fn view(&self, frame: &mut Frame, area: Rect) {
-        self.table_page.view(frame, area);
+        let [summary_area, table_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+        let mut summary = vec![format!(" {}", self.summary).light_blue()];
+        if let Some(warning) = &self.load_warning {
+            summary.push(format!(" | {warning}").yellow());
+        }
+        if let Some(last_loaded) = self.last_loaded {
+            let age = last_loaded.elapsed();
+            let updated = format!("updated {} ago", format_age(age));
+            if !self.paused && age > self.refresh_interval * STALE_AFTER_INTERVALS {
+                summary.push(format!(" | stale data, {updated}").black().on_yellow());
+            } else {
+                summary.push(format!(" | {updated}").dark_gray());
+            }
+        }
+        frame.render_widget(Paragraph::new(Line::from(summary)), summary_area);
+        self.table_page.view(frame, table_area);
         if let Some(dialog) = &self.credential_dialog {
             dialog.view(frame);
         }
+        if let Some((dialog, _)) = &self.confirm_stop {
+            dialog.view(frame);
+        }
+        if let Some((dialog, _)) = &self.confirm_bulk_stop {
+            dialog.view(frame);
+        }
     }
 
     pub async fn handle_event(&mut self, event: &Event) {
-        if let Some(dialog) = &mut self.credential_dialog {
-            if let Event::Key(key_event) = event {
-                if key_event.code == KeyCode::Esc {
-                    self.credential_dialog = None;
-                    return;
+        if let Some((dialog, session_id)) = &self.confirm_stop {
+            match dialog.handle_event(event) {
+                Some(true) => {
+                    let session_id = session_id.clone();
+                    self.confirm_stop = None;
+                    self.stop_session(session_id).await;
+                }
+                Some(false) => self.confirm_stop = None,
+                None => {}
+            }
+            return;
+        }
+        if let Some((dialog, session_ids)) = &self.confirm_bulk_stop {
+            match dialog.handle_event(event) {
+                Some(true) => {
+                    let session_ids = session_ids.clone();
+                    self.confirm_bulk_stop = None;
+                    self.stop_sessions(session_ids).await;
                 }
+                Some(false) => self.confirm_bulk_stop = None,
+                None => {}
+            }
+            return;
+        }
+        if let Some(dialog) = &mut self.credential_dialog {
+            // Esc resets the credentials filter first and closes the dialog otherwise
+            if !dialog.handle_event(event).await && event.is_esc() {
+                self.credential_dialog = None;
             }
-            dialog.handle_event(event).await;
             return;
         }
 
@@ -192,21 +803,133 @@ impl<L: LoadSessions + Send + Sync + 'static> SessionsPage<L> {
             if key_event.code == KeyCode::Char('d')
                 && key_event.modifiers == KeyModifiers::CONTROL
             {
-                self.stop_session().await;
+                self.confirm_stop_session().await;
+            }
+            if matches!(key_event.code, KeyCode::Char('d') | KeyCode::Char('D'))
+                && key_event.modifiers == KeyModifiers::CONTROL | KeyModifiers::SHIFT
+                && self.bulk_stop
+            {
+                self.confirm_bulk_stop();
             }
             if key_event.code == KeyCode::Char('v')
                 && key_event.modifiers == KeyModifiers::NONE
             {
                 self.show_credentials();
             }
+            if key_event.code == KeyCode::Char('c')
+                && key_event.modifiers == KeyModifiers::NONE
+            {
+                self.copy_credentials().await;
+            }
+            if key_event.code == KeyCode::Char('y')
+                && key_event.modifiers == KeyModifiers::NONE
+            {
+                self.copy_local_address().await;
+            }
+            if key_event.code == KeyCode::Char('i')
+                && key_event.modifiers == KeyModifiers::NONE
+            {
+                self.show_session_info().await;
+            }
+            if key_event.code == KeyCode::Char('Y') {
+                self.copy_session_id().await;
+            }
+            if key_event.code == KeyCode::Char('I') {
+                self.table_page.toggle_column("Id");
+            }
+            if key_event.code == KeyCode::Char('e')
+                && key_event.modifiers == KeyModifiers::NONE
+            {
+                self.export_sessions().await;
+            }
+            if key_event.code == KeyCode::Char('r')
+                && key_event.modifiers == KeyModifiers::NONE
+            {
+                self.refresh_now();
+            }
+            if key_event.code == KeyCode::Char('p')
+                && key_event.modifiers == KeyModifiers::NONE
+            {
+                self.toggle_pause();
+            }
+            if key_event.code == KeyCode::Char('a')
+                && key_event.modifiers == KeyModifiers::NONE
+            {
+                self.toggle_show_all();
+            }
+            if key_event.code == KeyCode::Char('m')
+                && key_event.modifiers == KeyModifiers::NONE
+            {
+                self.toggle_only_mine();
+            }
+            if key_event.code == KeyCode::Char('t')
+                && key_event.modifiers == KeyModifiers::NONE
+            {
+                self.cycle_session_type();
+            }
+            if key_event.code == KeyCode::Char('s')
+                && key_event.modifiers == KeyModifiers::NONE
+            {
+                self.toggle_sort_order();
+            }
+            if key_event.code == KeyCode::Char('q')
+                && key_event.modifiers == KeyModifiers::NONE
+            {
+                self.message_tx.send_or_log(Message::Quit).await;
+            }
+            if key_event.code == KeyCode::Enter {
+                self.show_target().await;
+            }
+            if key_event.code == KeyCode::Esc {
+                self.message_tx.send_or_log(Message::GoBack).await;
+            }
         }
     }
 
-    pub fn handle_message(&mut self, message: SessionsPageMessage) {
+    pub async fn handle_message(&mut self, message: SessionsPageMessage) {
         match message {
+            SessionsPageMessage::ScopesFailed(failed_scopes) => {
+                let warning =
+                    (!failed_scopes.is_empty()).then(|| failed_scopes_summary(&failed_scopes));
+                // Alerted once, every reload failing on the same scopes only keeps the warning
+                if warning.is_some() && warning != self.load_warning {
+                    self.message_tx
+                        .send_or_log(Message::scopes_failed(&failed_scopes))
+                        .await;
+                }
+                self.load_warning = warning;
+            }
+            SessionsPageMessage::LoadFailed(error) => {
+                if !self.load_failing {
+                    self.message_tx
+                        .send_or_log(Message::show_error("Error loading sessions", error))
+                        .await;
+                }
+                self.load_failing = true;
+            }
             SessionsPageMessage::SessionsLoaded(sessions) => {
-                self.table_page.set_items(sessions);
+                self.last_loaded = Some(Instant::now());
+                self.load_failing = false;
+                let mut stopping = self.stopping.borrow_mut();
+                for session_id in self.stopped.drain() {
+                    stopping.remove(&session_id);
+                }
+                drop(stopping);
+                self.table_page
+                    .set_items_keeping_selection(sessions, |s| s.session.id.clone());
+                if let Some(session_id) = self.select_after_load.take() {
+                    self.table_page.select_where(|s| s.session.id == session_id);
+                }
                 self.table_page.loading = false;
+                self.summary = summarize_statuses(self.table_page.items());
+                self.update_status();
+            }
+            SessionsPageMessage::StopFinished { session_id, stopped } => {
+                if stopped {
+                    self.stopped.insert(session_id);
+                } else {
+                    self.stopping.borrow_mut().remove(&session_id);
+                }
             }
         }
     }
@@ -215,8 +938,8 @@ impl<L: LoadSessions + Send + Sync + 'static> SessionsPage<L> {
 impl FilterItems<SessionWithTarget> for TablePage<SessionWithTarget> {
     fn matches(item: &SessionWithTarget, search: &str) -> bool {
         Self::match_str(&item.session.id, search)
-            || Self::match_str(&item.target.id, search)
-            || Self::match_str(&item.target.name, search)
+            || Self::match_str(item.target_id(), search)
+            || Self::match_str(&item.target_name(), search)
             || Self::match_str(&item.session.session_type, search)
             || Self::match_str(&item.session.status, search)
             || Self::match_str(&item.session.created_time.to_string(), search)
@@ -225,10 +948,54 @@ impl FilterItems<SessionWithTarget> for TablePage<SessionWithTarget> {
 
 impl SortItems<SessionWithTarget> for TablePage<SessionWithTarget> {
     fn sort(items: &mut Vec<Rc<SessionWithTarget>>) {
-        items.sort_by(|a, b| a.session.created_time.cmp(&b.session.created_time));
+        items.sort_by(|a, b| compare_created_time(a, b));
+    }
+}
+
+/// Newest sessions first, sessions created at the same time by id.
+fn compare_created_time(a: &SessionWithTarget, b: &SessionWithTarget) -> Ordering {
+    b.session
+        .created_time
+        .cmp(&a.session.created_time)
+        .then_with(|| a.session.id.cmp(&b.session.id))
+}
+
+/// Statuses counted on their own in the summary, in the order they are listed.
+const SUMMARY_STATUSES: [&str; 5] = ["active", "pending", "canceling", "canceled", "terminated"];
+
+/// Counts sessions by status, e.g. "3 active, 1 pending, 42 terminated". Statuses are
+/// compared case-insensitively, unknown ones are counted as "other".
+fn summarize_statuses<'a>(sessions: impl Iterator<Item = &'a SessionWithTarget>) -> String {
+    let mut counts = [0usize; SUMMARY_STATUSES.len() + 1];
+    for s in sessions {
+        let status = s.session.status.trim().to_lowercase();
+        let index = SUMMARY_STATUSES
+            .iter()
+            .position(|known| *known == status)
+            .unwrap_or(SUMMARY_STATUSES.len());
+        counts[index] += 1;
+    }
+    let summary = SUMMARY_STATUSES
+        .iter()
+        .chain(["other"].iter())
+        .zip(counts)
+        .filter(|(_, count)| *count > 0)
+        .map(|(status, count)| format!("{count} {status}"))
+        .collect::<Vec<_>>();
+    if summary.is_empty() {
+        "No sessions".to_string()
+    } else {
+        summary.join(", ")
     }
 }
 
+/// Index of the "Created Time" column, its header shows the sort order.
+const CREATED_TIME_COLUMN: usize = 6;
+
+fn created_time_header(oldest_first: bool) -> String {
+    format!("Created Time {}", if oldest_first { "↑" } else { "↓" })
+}
+
 impl<R: LoadSessions> Drop for SessionsPage<R> {
     fn drop(&mut self) {
         self.cancellation_token.cancel();
@@ -242,31 +1009,14 @@ pub trait LoadSessions: Send + Sync + Clone {
 
     fn message_tx(&self) -> &Sender<Message>;
 
-    fn fetch_sessions_or_show_error(
-        &self,
-    ) -> impl Future<Output = Option<Vec<SessionWithTarget>>> + Send {
-        async {
-            match self.fetch_sessions().await {
-                Ok(sessions) => Some(sessions),
-                Err(e) => {
-                    let _ = self
-                        .message_tx()
-                        .send(Message::show_error("Error loading sessions", e))
-                        .await;
-                    None
-                }
-            }
-        }
-    }
-
+    /// Reports the result to the page, which decides whether a failure is worth an alert.
     fn update_sessions(&self) -> impl Future<Output = ()> + Send {
         async move {
-            if let Some(sessions) = self.fetch_sessions_or_show_error().await {
-                self.message_tx()
-                    .send(SessionsPageMessage::SessionsLoaded(sessions).into())
-                    .await
-                    .unwrap();
-            }
+            let message = match self.fetch_sessions().await {
+                Ok(sessions) => SessionsPageMessage::SessionsLoaded(sessions),
+                Err(e) => SessionsPageMessage::LoadFailed(e.to_string()),
+            };
+            self.message_tx().send_or_log(message.into()).await;
         }
     }
 }
@@ -303,7 +1053,7 @@ impl<B: ApiClient + Clone + Send + Sync + 'static> LoadSessions for LoadTargetSe
             .map(|sessions| {
                 sessions
                     .into_iter()
-                    .filter(|s| s.target.id == self.target_id)
+                    .filter(|s| s.target_id() == self.target_id)
                     .collect()
             })
     }
@@ -313,11 +1063,43 @@ impl<B: ApiClient + Clone + Send + Sync + 'static> LoadSessions for LoadTargetSe
     }
 }
 
+// How long the targets of all scopes are reused for the sessions of the user
+const TARGETS_CACHE_LIFETIME: Duration = Duration::from_secs(5 * 60);
+
+struct CachedTargets {
+    targets: Vec<Target>,
+    fetched_at: Instant,
+    // Target ids of sessions that were not in `targets`, e.g. deleted targets
+    missing: HashSet<String>,
+}
+
+impl CachedTargets {
+    fn new(targets: Vec<Target>) -> Self {
+        CachedTargets {
+            targets,
+            fetched_at: Instant::now(),
+            missing: HashSet::new(),
+        }
+    }
+
+    fn knows(&self, target_id: &str) -> bool {
+        self.missing.contains(target_id) || self.targets.iter().any(|t| t.id == target_id)
+    }
+
+    /// Outdated, or a session belongs to a target that was not asked for yet.
+    fn is_stale(&self, sessions: &[boundary::Session]) -> bool {
+        self.fetched_at.elapsed() >= TARGETS_CACHE_LIFETIME
+            || sessions.iter().any(|s| !self.knows(&s.target_id))
+    }
+}
+
 #[derive(Clone)]
 pub struct LoadUserSessions<B: boundary::ApiClient> {
     user_id: String,
     boundary_client: B,
     message_tx: mpsc::Sender<Message>,
+    // Listing the targets of all scopes is slow, so they are not fetched on every refresh
+    targets: Arc<Mutex<Option<CachedTargets>>>,
 }
 
 impl<B: boundary::ApiClient> LoadUserSessions<B> {
@@ -326,15 +1108,36 @@ impl<B: boundary::ApiClient> LoadUserSessions<B> {
             user_id,
             boundary_client,
             message_tx,
+            targets: Arc::new(Mutex::new(None)),
         }
     }
 }
 
 impl<B: boundary::ApiClient + Clone + Send + Sync + 'static> LoadSessions for LoadUserSessions<B> {
     async fn fetch_sessions(&self) -> Result<Vec<SessionWithTarget>, Error> {
-        self.boundary_client
-            .get_user_sessions_with_target(&self.user_id)
-            .await
+        let UserSessions {
+            sessions,
+            failed_scopes,
+        } = self.boundary_client.get_user_sessions(&self.user_id).await?;
+        for (scope_id, e) in &failed_scopes {
+            log::warn!("Failed to load the sessions of scope {}: {}", scope_id, e);
+        }
+        self.message_tx
+            .send_or_log(SessionsPageMessage::ScopesFailed(failure_reasons(&failed_scopes)).into())
+            .await;
+        let mut cache = self.targets.lock().await;
+        let mut cached = match cache.take() {
+            Some(cached) if !cached.is_stale(&sessions) => cached,
+            _ => CachedTargets::new(self.boundary_client.get_targets(None).await?),
+        };
+        for session in &sessions {
+            if !cached.knows(&session.target_id) {
+                cached.missing.insert(session.target_id.clone());
+            }
+        }
+        let sessions = B::combine_sessions_with_target(sessions, &cached.targets);
+        *cache = Some(cached);
+        Ok(sessions)
     }
 
     fn message_tx(&self) -> &Sender<Message> {
@@ -342,9 +1145,52 @@ impl<B: boundary::ApiClient + Clone + Send + Sync + 'static> LoadSessions for Lo
     }
 }
 
+// Key of the sessions tables in the column widths of the config
+const COLUMN_WIDTHS_KEY: &str = "sessions";
+
+// Sessions not reloaded for this many refresh intervals are marked as stale
+const STALE_AFTER_INTERVALS: u32 = 3;
+
+fn format_age(age: Duration) -> String {
+    match age.as_secs() {
+        secs @ 0..60 => format!("{secs}s"),
+        secs @ 60..3600 => format!("{}m", secs / 60),
+        secs => format!("{}h", secs / 3600),
+    }
+}
+
+/// Why the session cannot be stopped, `None` if it can.
+fn stop_unavailable_reason(session: &boundary::Session) -> Option<String> {
+    if session.is_ended() {
+        Some(format!("Session {} is already {}.", session.id, session.status))
+    } else if !session.can_cancel() {
+        Some(format!(
+            "You are not allowed to cancel session {}, the cancel:self permission is missing.",
+            session.id
+        ))
+    } else {
+        None
+    }
+}
+
+const EXPIRES_SOON: TimeDelta = TimeDelta::minutes(5);
+
+fn expires_soon(s: &SessionWithTarget) -> bool {
+    s.session
+        .expiration_time
+        .is_some_and(|e| e - Utc::now() < EXPIRES_SOON)
+}
+
 #[derive(Clone, Debug)]
 pub enum SessionsPageMessage {
     SessionsLoaded(Vec<SessionWithTarget>),
+    /// The last reload failed with the given error.
+    LoadFailed(String),
+    /// Scopes whose sessions could not be loaded by the last reload with the reason, empty if
+    /// all were loaded.
+    ScopesFailed(Vec<(String, String)>),
+    /// Result of a `Message::StopSession` requested by this page.
+    StopFinished { session_id: String, stopped: bool },
 }
 
 impl From<SessionsPageMessage> for Message {
@@ -352,3 +1198,961 @@ impl From<SessionsPageMessage> for Message {
         Message::SessionsPage(msg)
     }
 }
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::boundary::{MockClient, Session, Target};
+    use chrono::Utc;
+    use crossterm::event::KeyEvent;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Clone)]
+    struct CountingLoadSessions {
+        fetches: Arc<AtomicUsize>,
+        message_tx: mpsc::Sender<Message>,
+    }
+
+    impl LoadSessions for CountingLoadSessions {
+        async fn fetch_sessions(&self) -> Result<Vec<SessionWithTarget>, Error> {
+            self.fetches.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![])
+        }
+
+        fn message_tx(&self) -> &Sender<Message> {
+            &self.message_tx
+        }
+    }
+
+    async fn make_page(
+        fetches: Arc<AtomicUsize>,
+    ) -> (SessionsPage<CountingLoadSessions>, mpsc::Receiver<Message>) {
+        make_page_with_ports(fetches, HashMap::new()).await
+    }
+
+    async fn make_page_with_ports(
+        fetches: Arc<AtomicUsize>,
+        local_ports: HashMap<String, u16>,
+    ) -> (SessionsPage<CountingLoadSessions>, mpsc::Receiver<Message>) {
+        let (message_tx, mut message_rx) = mpsc::channel(100);
+        let load_sessions = CountingLoadSessions {
+            fetches,
+            message_tx: message_tx.clone(),
+        };
+        let page = SessionsPage::new(
+            None,
+            load_sessions,
+            message_tx,
+            HashMap::new(),
+            local_ports,
+            Duration::from_secs(5),
+            "u_1".to_string(),
+        )
+        .await;
+        match message_rx.recv().await {
+            Some(Message::RunFuture(future)) => {
+                tokio::spawn(future);
+            }
+            _ => panic!("Expected the refresh future"),
+        }
+        (page, message_rx)
+    }
+
+    async fn press(page: &mut SessionsPage<CountingLoadSessions>, c: char) {
+        page.handle_event(&Event::Key(KeyEvent::from(KeyCode::Char(c))))
+            .await;
+        // Let the refresh loop react
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+
+    pub fn session_with_status(id: &str, status: &str) -> SessionWithTarget {
+        SessionWithTarget::new(
+            Session {
+                id: id.to_string(),
+                target_id: "ttcp_1".to_string(),
+                scope_id: String::new(),
+                session_type: "tcp".to_string(),
+                // Sessions created at the same time are listed by id
+                created_time: chrono::DateTime::UNIX_EPOCH,
+                expiration_time: None,
+                status: status.to_string(),
+                authorized_actions: vec!["cancel:self".to_string()],
+                user_id: "u_1".to_string(),
+            },
+            Some(Target {
+                id: "ttcp_1".to_string(),
+                name: "database".to_string(),
+                description: "".to_string(),
+                type_name: "tcp".to_string(),
+                authorized_collection_actions: Default::default(),
+                authorized_actions: vec![],
+                scope_id: "p_1".to_string(),
+                attributes: None,
+            }),
+        )
+    }
+
+    fn session_expiring_in(id: &str, remaining: TimeDelta) -> SessionWithTarget {
+        let mut session = session_with_status(id, "active");
+        session.session.expiration_time = Some(Utc::now() + remaining);
+        session
+    }
+
+    /// Position of the first cell of `text` in the rendered buffer.
+    fn find_text(buffer: &ratatui::buffer::Buffer, text: &str) -> Option<(u16, u16)> {
+        let area = buffer.area;
+        (area.top()..area.bottom()).find_map(|y| {
+            let line: String = (area.left()..area.right())
+                .map(|x| buffer[(x, y)].symbol().to_string())
+                .collect();
+            line.find(text)
+                .map(|byte| (area.left() + line[..byte].chars().count() as u16, y))
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn expires_column_is_red_under_five_minutes() {
+        let (mut page, _message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![
+            session_expiring_in("s_soon", TimeDelta::seconds(150)),
+            session_expiring_in("s_later", TimeDelta::hours(3) + TimeDelta::seconds(30)),
+            session_with_status("s_unknown", "active"),
+        ])).await;
+        page.table_page.toggle_column("Id");
+
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(210, 12)).unwrap();
+        terminal
+            .draw(|frame| page.view(frame, frame.area()))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+
+        let soon = find_text(buffer, "2m ").expect("countdown of s_soon");
+        assert_eq!(buffer[soon].fg, Color::Red);
+        let later = find_text(buffer, "3h 00m").expect("countdown of s_later");
+        assert_ne!(buffer[later].fg, Color::Red);
+        let (expires_column, _) = find_text(buffer, "Expires").unwrap();
+        let (_, unknown_row) = find_text(buffer, "s_unknown").unwrap();
+        assert_eq!(buffer[(expires_column, unknown_row)].symbol(), "-");
+    }
+
+    fn visible_ids(page: &SessionsPage<CountingLoadSessions>) -> Vec<String> {
+        page.table_page
+            .visible_items
+            .iter()
+            .map(|s| s.session.id.clone())
+            .collect()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn actions_are_run_from_the_palette_with_their_keys() {
+        let (mut page, _message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![session_with_status(
+            "s_1", "active",
+        )])).await;
+
+        let actions = page.enabled_actions();
+        let key = |name: &str| actions.iter().find(|a| a.name == name).and_then(|a| a.key);
+        assert_eq!(
+            key("Stop Session"),
+            Some(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(key("Back"), Some(KeyEvent::from(KeyCode::Esc)));
+        assert_eq!(key("Quit"), None, "The palette quits with its own command");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn export_contains_the_sessions_as_listed() {
+        let (mut page, _message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![
+            session_with_status("s_1", "active"),
+            session_with_status("s_2", "terminated"),
+            session_with_status("s_3", "pending"),
+        ])).await;
+        press(&mut page, 's').await;
+
+        let exported: Vec<String> = page
+            .exported_sessions()
+            .into_iter()
+            .map(|s| s.session_id)
+            .collect();
+        assert_eq!(exported, visible_ids(&page));
+        assert!(!exported.contains(&"s_2".to_string()), "Ended sessions are hidden");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn ended_sessions_are_hidden_until_show_all() {
+        let (mut page, _message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![
+            session_with_status("s_active", "active"),
+            session_with_status("s_pending", "pending"),
+            session_with_status("s_terminated", "terminated"),
+            session_with_status("s_canceling", "canceling"),
+        ])).await;
+        assert_eq!(visible_ids(&page), vec!["s_active", "s_pending"]);
+        assert_eq!(
+            page.table_page.status,
+            Some("Active only, 2 hidden".to_string())
+        );
+
+        press(&mut page, 'a').await;
+        assert_eq!(visible_ids(&page).len(), 4);
+        assert_eq!(page.table_page.status, Some("All sessions".to_string()));
+
+        press(&mut page, 'a').await;
+        assert_eq!(visible_ids(&page), vec!["s_active", "s_pending"]);
+    }
+
+    fn stop_requests(message_rx: &mut mpsc::Receiver<Message>) -> Vec<String> {
+        let mut session_ids = vec![];
+        while let Ok(message) = message_rx.try_recv() {
+            if let Message::StopSession { session_id, .. } = message {
+                session_ids.push(session_id);
+            }
+        }
+        session_ids
+    }
+
+    async fn request_stop(page: &mut SessionsPage<CountingLoadSessions>, confirm: char) {
+        page.handle_event(&Event::Key(KeyEvent::new(
+            KeyCode::Char('d'),
+            KeyModifiers::CONTROL,
+        )))
+        .await;
+        assert!(page.confirm_stop.is_some(), "Stopping must be confirmed");
+        press(page, confirm).await;
+        assert!(page.confirm_stop.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn declined_stop_does_not_stop_session() {
+        let (mut page, mut message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![
+            session_with_status("s_1", "active"),
+        ])).await;
+        request_stop(&mut page, 'n').await;
+        assert!(stop_requests(&mut message_rx).is_empty());
+        assert!(page.stopping.borrow().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn confirmed_stop_shows_stopping_until_reload() {
+        let (mut page, mut message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        let sessions = vec![session_with_status("s_1", "active")];
+        page.handle_message(SessionsPageMessage::SessionsLoaded(sessions.clone())).await;
+        request_stop(&mut page, 'y').await;
+        assert_eq!(stop_requests(&mut message_rx), vec!["s_1"]);
+        assert!(page.stopping.borrow().contains("s_1"));
+
+        page.handle_message(SessionsPageMessage::StopFinished {
+            session_id: "s_1".to_string(),
+            stopped: true,
+        }).await;
+        assert!(page.stopping.borrow().contains("s_1"), "Still stopping until reloaded");
+
+        page.handle_message(SessionsPageMessage::SessionsLoaded(sessions)).await;
+        assert!(page.stopping.borrow().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failed_stop_clears_stopping() {
+        let (mut page, mut message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![
+            session_with_status("s_1", "active"),
+        ])).await;
+        request_stop(&mut page, 'y').await;
+        assert_eq!(stop_requests(&mut message_rx), vec!["s_1"]);
+
+        page.handle_message(SessionsPageMessage::StopFinished {
+            session_id: "s_1".to_string(),
+            stopped: false,
+        }).await;
+        assert!(page.stopping.borrow().is_empty());
+    }
+
+    fn session_of_user(id: &str, user_id: &str, status: &str) -> SessionWithTarget {
+        let mut session = session_with_status(id, status);
+        session.session.user_id = user_id.to_string();
+        session
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn only_mine_shows_sessions_of_current_user() {
+        let (mut page, _message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![
+            session_of_user("s_mine", "u_1", "active"),
+            session_of_user("s_other", "u_2", "active"),
+            session_of_user("s_mine_old", "u_1", "terminated"),
+        ])).await;
+        assert_eq!(visible_ids(&page), vec!["s_mine", "s_other"]);
+
+        press(&mut page, 'm').await;
+        assert_eq!(visible_ids(&page), vec!["s_mine"]);
+        assert_eq!(
+            page.table_page.status,
+            Some("My active sessions, 2 hidden".to_string())
+        );
+
+        press(&mut page, 'a').await;
+        assert_eq!(visible_ids(&page), vec!["s_mine", "s_mine_old"]);
+
+        press(&mut page, 'm').await;
+        assert_eq!(visible_ids(&page).len(), 3);
+    }
+
+    fn local_column_value(page: &SessionsPage<CountingLoadSessions>, session: &SessionWithTarget) -> String {
+        let column = page
+            .table_page
+            .columns
+            .iter()
+            .find(|c| c.header == "Local")
+            .unwrap();
+        (column.get_value)(session)
+    }
+
+    fn clipboard_texts(message_rx: &mut mpsc::Receiver<Message>) -> Vec<String> {
+        let mut texts = vec![];
+        while let Ok(message) = message_rx.try_recv() {
+            if let Message::SetClipboard { text, .. } = message {
+                texts.push(text);
+            }
+        }
+        texts
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn local_address_of_managed_sessions() {
+        let local_ports = HashMap::from([("s_managed".to_string(), 5432)]);
+        let (mut page, mut message_rx) =
+            make_page_with_ports(Arc::new(AtomicUsize::new(0)), local_ports).await;
+        let managed = session_with_status("s_managed", "active");
+        let unmanaged = session_with_status("s_unmanaged", "active");
+        assert_eq!(local_column_value(&page, &managed), "127.0.0.1:5432");
+        assert_eq!(local_column_value(&page, &unmanaged), "");
+
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![managed, unmanaged])).await;
+        clipboard_texts(&mut message_rx);
+        press(&mut page, 'y').await;
+        assert_eq!(clipboard_texts(&mut message_rx), vec!["127.0.0.1:5432"]);
+
+        page.handle_event(&Event::Key(KeyEvent::from(KeyCode::Down)))
+            .await;
+        press(&mut page, 'y').await;
+        assert!(clipboard_texts(&mut message_rx).is_empty(), "Unmanaged sessions have no address");
+    }
+
+    fn session_created_at(id: &str, minute: i64) -> SessionWithTarget {
+        let mut session = session_with_status(id, "active");
+        session.session.created_time = chrono::DateTime::UNIX_EPOCH + TimeDelta::minutes(minute);
+        session
+    }
+
+    fn selected_id(page: &SessionsPage<CountingLoadSessions>) -> Option<String> {
+        page.table_page.selected_item().map(|s| s.session.id.clone())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn refresh_keeps_filter_and_selection() {
+        let (mut page, _message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        let mut sessions = vec![
+            session_created_at("s_alpha_1", 10),
+            session_created_at("s_beta", 20),
+            session_created_at("s_alpha_2", 30),
+        ];
+        page.handle_message(SessionsPageMessage::SessionsLoaded(sessions.clone())).await;
+        for c in "/alpha".chars() {
+            press(&mut page, c).await;
+        }
+        assert_eq!(selected_id(&page), Some("s_alpha_2".to_string()));
+
+        // A refresh lands while the filter is still being typed, with a new session in front
+        sessions.push(session_created_at("s_alpha_3", 40));
+        page.handle_message(SessionsPageMessage::SessionsLoaded(sessions.clone())).await;
+        assert!(page.table_page.filter.is_input());
+        assert_eq!(visible_ids(&page), ["s_alpha_3", "s_alpha_2", "s_alpha_1"]);
+        assert_eq!(selected_id(&page), Some("s_alpha_2".to_string()));
+
+        // And after the filter was confirmed and another session was selected
+        page.handle_event(&Event::Key(KeyEvent::from(KeyCode::Enter)))
+            .await;
+        page.handle_event(&Event::Key(KeyEvent::from(KeyCode::Down)))
+            .await;
+        assert_eq!(selected_id(&page), Some("s_alpha_1".to_string()));
+        sessions.push(session_created_at("s_alpha_4", 50));
+        page.handle_message(SessionsPageMessage::SessionsLoaded(sessions)).await;
+        assert!(page.table_page.filter.is_active());
+        assert_eq!(visible_ids(&page).len(), 4);
+        assert_eq!(selected_id(&page), Some("s_alpha_1".to_string()));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn newest_session_is_first_until_sort_order_is_reversed() {
+        let (mut page, _message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![
+            session_created_at("s_old", 10),
+            session_created_at("s_new", 30),
+            session_created_at("s_same_b", 20),
+            session_created_at("s_same_a", 20),
+        ])).await;
+        assert_eq!(visible_ids(&page), ["s_new", "s_same_a", "s_same_b", "s_old"]);
+        assert_eq!(selected_id(&page), Some("s_new".to_string()));
+        assert_eq!(page.table_page.columns[CREATED_TIME_COLUMN].header, "Created Time ↓");
+
+        press(&mut page, 's').await;
+        assert_eq!(visible_ids(&page), ["s_old", "s_same_b", "s_same_a", "s_new"]);
+        assert_eq!(selected_id(&page), Some("s_old".to_string()));
+        assert_eq!(page.table_page.columns[CREATED_TIME_COLUMN].header, "Created Time ↑");
+
+        press(&mut page, 's').await;
+        assert_eq!(visible_ids(&page), ["s_new", "s_same_a", "s_same_b", "s_old"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn terminal_sessions_are_hidden_by_default() {
+        let (mut page, _message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![
+            session_with_status("s_terminated", "terminated"),
+            session_with_status("s_canceled", "canceled"),
+        ])).await;
+        assert!(visible_ids(&page).is_empty());
+
+        press(&mut page, 'a').await;
+        assert_eq!(visible_ids(&page), vec!["s_canceled", "s_terminated"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn text_filter_applies_on_top_of_active_sessions() {
+        let (mut page, _message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![
+            session_with_status("s_alpha", "active"),
+            session_with_status("s_beta", "active"),
+            session_with_status("s_beta_old", "terminated"),
+        ])).await;
+        press(&mut page, '/').await;
+        for c in "beta".chars() {
+            press(&mut page, c).await;
+        }
+        assert_eq!(visible_ids(&page), vec!["s_beta"]);
+
+        page.handle_event(&Event::Key(KeyEvent::from(KeyCode::Enter)))
+            .await;
+        press(&mut page, 'a').await;
+        assert_eq!(visible_ids(&page), vec!["s_beta", "s_beta_old"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn paused_sessions_page_does_not_fetch() {
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let (mut page, _message_rx) = make_page(fetches.clone()).await;
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        press(&mut page, 'p').await;
+        assert!(page.table_page.status.as_ref().unwrap().ends_with("Paused"));
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        press(&mut page, 'p').await;
+        assert!(!page.table_page.status.as_ref().unwrap().ends_with("Paused"));
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        assert_eq!(fetches.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn summarize_statuses_counts_known_statuses_and_other() {
+        let sessions = [
+            session_with_status("s_1", "active"),
+            session_with_status("s_2", "Active"),
+            session_with_status("s_3", "terminated"),
+            session_with_status("s_4", "PENDING"),
+            session_with_status("s_5", "active"),
+            session_with_status("s_6", "unknown"),
+            session_with_status("s_7", "connecting"),
+        ];
+        assert_eq!(
+            summarize_statuses(sessions.iter()),
+            "3 active, 1 pending, 1 terminated, 2 other"
+        );
+        assert_eq!(summarize_statuses([].iter()), "No sessions");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn summary_counts_sessions_hidden_by_filters() {
+        let (mut page, _message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![
+            session_with_status("s_active", "active"),
+            session_with_status("s_terminated", "terminated"),
+        ])).await;
+        assert_eq!(visible_ids(&page), ["s_active"]);
+        assert_eq!(page.summary, "1 active, 1 terminated");
+
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![
+            session_with_status("s_terminated", "terminated"),
+        ])).await;
+        assert_eq!(page.summary, "1 terminated");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn enter_shows_target_of_selected_session() {
+        let (mut page, mut message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![session_with_status(
+            "s_1", "active",
+        )])).await;
+        while message_rx.try_recv().is_ok() {}
+
+        page.handle_event(&Event::Key(KeyEvent::from(KeyCode::Enter)))
+            .await;
+        match message_rx.try_recv() {
+            Ok(Message::ShowTargetsForTarget {
+                scope_id,
+                target_id,
+            }) => {
+                assert_eq!(scope_id, "p_1");
+                assert_eq!(target_id, "ttcp_1");
+            }
+            _ => panic!("Expected ShowTargetsForTarget message"),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn sessions_of_unknown_targets_can_be_stopped() {
+        let (mut page, mut message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        let mut orphan = session_with_status("s_1", "active");
+        orphan.target = None;
+        let name_column = page
+            .table_page
+            .columns
+            .iter()
+            .find(|c| c.header == "Target name")
+            .unwrap();
+        assert_eq!((name_column.get_value)(&orphan), "unknown (ttcp_1)");
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![orphan])).await;
+        while message_rx.try_recv().is_ok() {}
+
+        page.handle_event(&Event::Key(KeyEvent::from(KeyCode::Enter)))
+            .await;
+        assert!(
+            message_rx.try_recv().is_err(),
+            "There is no target to show"
+        );
+
+        request_stop(&mut page, 'y').await;
+        assert_eq!(stop_requests(&mut message_rx), vec!["s_1"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn keys_after_app_shutdown_do_not_panic() {
+        let (mut page, message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![session_with_status(
+            "s_1", "active",
+        )])).await;
+        drop(message_rx);
+
+        page.handle_event(&Event::Key(KeyEvent::from(KeyCode::Enter)))
+            .await;
+        page.handle_event(&Event::Key(KeyEvent::from(KeyCode::Esc)))
+            .await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn hidden_sessions_page_does_not_fetch() {
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let (mut page, _message_rx) = make_page(fetches.clone()).await;
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        page.set_hidden(true);
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        // Catches up as soon as it is shown again
+        page.set_hidden(false);
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+
+        // Showing a page the user paused keeps it paused
+        press(&mut page, 'p').await;
+        page.set_hidden(true);
+        page.set_hidden(false);
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+        assert!(page.is_refresh_paused());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn unfocused_sessions_page_does_not_fetch() {
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let (mut page, _message_rx) = make_page(fetches.clone()).await;
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        page.set_focused(false);
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        // Catches up as soon as the terminal is focused again
+        page.set_focused(true);
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+        assert!(!page.is_refresh_paused());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn refresh_key_fetches_immediately() {
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let (mut page, _message_rx) = make_page(fetches.clone()).await;
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        press(&mut page, 'r').await;
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+
+    fn user_sessions_loader(
+        sessions: Vec<Session>,
+        get_targets_calls: Arc<AtomicUsize>,
+    ) -> LoadUserSessions<MockClient> {
+        let target = session_with_status("s_0", "active").target.unwrap();
+        let client = MockClient::builder()
+            .scopes(HashMap::new())
+            .targets(HashMap::from([(None, vec![target])]))
+            .sessions(Arc::new(tokio::sync::Mutex::new(HashMap::from([(
+                "p_1".to_string(),
+                sessions,
+            )]))))
+            .get_targets_calls(get_targets_calls)
+            .build();
+        let (message_tx, _message_rx) = mpsc::channel(10);
+        LoadUserSessions::new("u_1".to_string(), client, message_tx)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn user_sessions_reuse_the_targets() {
+        let get_targets_calls = Arc::new(AtomicUsize::new(0));
+        let loader = user_sessions_loader(
+            vec![session_with_status("s_1", "active").session],
+            get_targets_calls.clone(),
+        );
+
+        for _ in 0..3 {
+            let sessions = loader.fetch_sessions().await.unwrap();
+            assert_eq!(sessions[0].target_name(), "database");
+            tokio::time::advance(Duration::from_secs(5)).await;
+        }
+        assert_eq!(get_targets_calls.load(Ordering::SeqCst), 1);
+
+        tokio::time::advance(TARGETS_CACHE_LIFETIME).await;
+        loader.fetch_sessions().await.unwrap();
+        assert_eq!(get_targets_calls.load(Ordering::SeqCst), 2, "Outdated targets are fetched again");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn user_sessions_of_unknown_targets_fetch_the_targets_once() {
+        let get_targets_calls = Arc::new(AtomicUsize::new(0));
+        let mut orphan = session_with_status("s_1", "active").session;
+        orphan.target_id = "ttcp_gone".to_string();
+        let loader = user_sessions_loader(vec![orphan], get_targets_calls.clone());
+
+        for _ in 0..3 {
+            let sessions = loader.fetch_sessions().await.unwrap();
+            assert!(sessions[0].target.is_none());
+        }
+        assert_eq!(get_targets_calls.load(Ordering::SeqCst), 1);
+    }
+
+    fn ctrl_shift_d() -> Event {
+        Event::Key(KeyEvent::new(
+            KeyCode::Char('D'),
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+        ))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn bulk_stop_cancels_listed_sessions() {
+        let (page, mut message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        let mut page = page.with_bulk_stop();
+        let mut not_cancelable = session_with_status("s_5", "active");
+        not_cancelable.session.authorized_actions.clear();
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![
+            session_with_status("s_1", "active"),
+            session_with_status("s_2", "pending"),
+            session_with_status("s_3", "terminated"),
+            session_with_status("s_4", "active"),
+            not_cancelable,
+        ])).await;
+        press(&mut page, '/').await;
+        for c in "active".chars() {
+            press(&mut page, c).await;
+        }
+        page.handle_event(&Event::Key(KeyEvent::from(KeyCode::Enter)))
+            .await;
+        while message_rx.try_recv().is_ok() {}
+
+        page.handle_event(&ctrl_shift_d()).await;
+        let (_, session_ids) = page.confirm_bulk_stop.as_ref().expect("Stopping must be confirmed");
+        assert_eq!(
+            session_ids,
+            &["s_1", "s_4"],
+            "Only the listed sessions the user may cancel are stopped"
+        );
+        press(&mut page, 'y').await;
+        assert!(page.confirm_bulk_stop.is_none());
+
+        let mut requested = None;
+        while let Ok(message) = message_rx.try_recv() {
+            if let Message::StopSessions { session_ids, .. } = message {
+                requested = Some(session_ids);
+            }
+        }
+        assert_eq!(requested, Some(vec!["s_1".to_string(), "s_4".to_string()]));
+        assert!(page.stopping.borrow().contains("s_4"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn bulk_stop_requires_enabling() {
+        let (mut page, _message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![session_with_status(
+            "s_1", "active",
+        )])).await;
+        page.handle_event(&ctrl_shift_d()).await;
+        assert!(page.confirm_bulk_stop.is_none());
+    }
+
+    fn session_of_type(id: &str, status: &str, session_type: &str) -> SessionWithTarget {
+        let mut session = session_with_status(id, status);
+        session.session.session_type = session_type.to_string();
+        session
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn filter_by_session_type() {
+        let (mut page, _message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![
+            session_of_type("s_1", "active", "tcp"),
+            session_of_type("s_2", "active", "ssh"),
+            session_of_type("s_3", "terminated", "ssh"),
+            session_of_type("s_4", "active", "tcp"),
+        ])).await;
+
+        press(&mut page, 't').await;
+        assert_eq!(visible_ids(&page), ["s_2"]);
+        assert_eq!(page.table_page.title, "Sessions [ssh]");
+        press(&mut page, 'a').await;
+        assert_eq!(visible_ids(&page), ["s_2", "s_3"], "Combines with the status view");
+
+        press(&mut page, 't').await;
+        assert_eq!(visible_ids(&page), ["s_1", "s_4"]);
+        assert_eq!(page.table_page.title, "Sessions [tcp]");
+
+        press(&mut page, 't').await;
+        assert_eq!(visible_ids(&page).len(), 4);
+        assert_eq!(page.table_page.title, "Sessions");
+    }
+
+    fn ctrl_d() -> Event {
+        Event::Key(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL))
+    }
+
+    fn alerts(message_rx: &mut mpsc::Receiver<Message>) -> Vec<String> {
+        let mut texts = vec![];
+        while let Ok(message) = message_rx.try_recv() {
+            if let Message::ShowAlert(_, text) = message {
+                texts.push(text);
+            }
+        }
+        texts
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stopping_an_ended_session_explains_why() {
+        let (mut page, mut message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        press(&mut page, 'a').await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![session_with_status(
+            "s_1", "canceling",
+        )])).await;
+        alerts(&mut message_rx);
+
+        page.handle_event(&ctrl_d()).await;
+        assert!(page.confirm_stop.is_none());
+        assert_eq!(alerts(&mut message_rx), ["Session s_1 is already canceling."]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stopping_without_permission_explains_why() {
+        let (mut page, mut message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        let mut session = session_with_status("s_1", "active");
+        session.session.authorized_actions = vec!["read:self".to_string()];
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![session])).await;
+        alerts(&mut message_rx);
+
+        page.handle_event(&ctrl_d()).await;
+        assert!(page.confirm_stop.is_none());
+        assert_eq!(
+            alerts(&mut message_rx),
+            ["You are not allowed to cancel session s_1, the cancel:self permission is missing."]
+        );
+    }
+
+    fn render(page: &SessionsPage<CountingLoadSessions>, width: u16) -> ratatui::buffer::Buffer {
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(width, 12)).unwrap();
+        terminal
+            .draw(|frame| page.view(frame, frame.area()))
+            .unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn id_column_is_hidden_until_shown() {
+        let (mut page, _message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        press(&mut page, 'a').await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![session_with_status(
+            "s_0123456789",
+            "terminated",
+        )])).await;
+
+        for width in [100, 140] {
+            let buffer = render(&page, width);
+            assert!(find_text(&buffer, "s_0123456789").is_none(), "Hidden id at {width}");
+            for value in ["database", "terminated", "1970-01-01 00:00:00", "Created Time"] {
+                assert!(find_text(&buffer, value).is_some(), "{value} at {width}");
+            }
+        }
+
+        press(&mut page, 'I').await;
+        for width in [100, 140] {
+            let buffer = render(&page, width);
+            assert!(find_text(&buffer, "s_0123456789").is_some(), "Shown id at {width}");
+            assert!(find_text(&buffer, "terminated").is_some(), "Status at {width}");
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn full_session_id_is_shown_and_copied() {
+        let (mut page, mut message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![session_with_status(
+            "s_0123456789",
+            "active",
+        )])).await;
+
+        press(&mut page, 'i').await;
+        press(&mut page, 'Y').await;
+        let messages: Vec<Message> = std::iter::from_fn(|| message_rx.try_recv().ok()).collect();
+        assert!(messages.iter().any(|m| matches!(
+            m,
+            Message::ShowAlert(title, info) if title == "Session" && info.starts_with("Id: s_0123456789\n")
+        )));
+        assert!(messages.iter().any(|m| matches!(
+            m,
+            Message::SetClipboard { text, .. } if text == "s_0123456789"
+        )));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn scopes_that_failed_to_load_are_shown() {
+        let (mut page, mut message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        let failed_scopes = vec![
+            ("p_1".to_string(), "403".to_string()),
+            ("p_2".to_string(), "timeout".to_string()),
+        ];
+        page.handle_message(SessionsPageMessage::ScopesFailed(failed_scopes.clone())).await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![session_with_status(
+            "s_1", "active",
+        )])).await;
+
+        let summary = "2 scopes could not be listed: p_1 (403), p_2 (timeout)";
+        page.table_page.toggle_column("Id");
+        let buffer = render(&page, 140);
+        assert!(find_text(&buffer, summary).is_some());
+        assert!(find_text(&buffer, "s_1").is_some(), "Loaded sessions are still listed");
+        assert_eq!(alerts(&mut message_rx), vec![summary]);
+
+        page.handle_message(SessionsPageMessage::ScopesFailed(failed_scopes)).await;
+        assert!(alerts(&mut message_rx).is_empty(), "The same failures are alerted once");
+
+        page.handle_message(SessionsPageMessage::ScopesFailed(vec![])).await;
+        assert!(find_text(&render(&page, 140), "could not be listed").is_none());
+    }
+
+    #[tokio::test]
+    async fn user_sessions_report_failed_scopes() {
+        let (message_tx, mut message_rx) = mpsc::channel(10);
+        let loader = LoadUserSessions::new(
+            "u_1".to_string(),
+            MockClient::builder().scopes(HashMap::new()).build(),
+            message_tx,
+        );
+        loader.fetch_sessions().await.unwrap();
+        assert!(matches!(
+            message_rx.try_recv(),
+            Ok(Message::SessionsPage(SessionsPageMessage::ScopesFailed(scopes))) if scopes.is_empty()
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn consecutive_load_failures_are_alerted_once() {
+        let (mut page, mut message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![])).await;
+
+        for _ in 0..4 {
+            page.handle_message(SessionsPageMessage::LoadFailed("timeout".to_string())).await;
+            tokio::time::advance(Duration::from_secs(5)).await;
+        }
+        assert_eq!(alerts(&mut message_rx), vec!["Error loading sessions: timeout"]);
+        assert!(find_text(&render(&page, 100), "stale data, updated 20s ago").is_some());
+
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![])).await;
+        assert!(find_text(&render(&page, 100), "updated 0s ago").is_some());
+        assert!(find_text(&render(&page, 100), "stale data").is_none());
+
+        page.handle_message(SessionsPageMessage::LoadFailed("timeout".to_string())).await;
+        assert_eq!(alerts(&mut message_rx).len(), 1, "A new failure after a reload is alerted again");
+    }
+
+    #[derive(Clone)]
+    struct FailingLoadSessions {
+        message_tx: mpsc::Sender<Message>,
+    }
+
+    impl LoadSessions for FailingLoadSessions {
+        async fn fetch_sessions(&self) -> Result<Vec<SessionWithTarget>, Error> {
+            Err(Error::ApiError(503, "unavailable".to_string()))
+        }
+
+        fn message_tx(&self) -> &Sender<Message> {
+            &self.message_tx
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_reload_is_reported_to_the_page() {
+        let (message_tx, mut message_rx) = mpsc::channel(10);
+        FailingLoadSessions { message_tx }.update_sessions().await;
+        assert!(matches!(
+            message_rx.try_recv(),
+            Ok(Message::SessionsPage(SessionsPageMessage::LoadFailed(_)))
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn connection_labels_are_shown_with_the_target_name() {
+        let (page, _message_rx) = make_page(Arc::new(AtomicUsize::new(0))).await;
+        let mut page = page.with_connection_labels(HashMap::from([(
+            "s_1".to_string(),
+            "migration".to_string(),
+        )]));
+        page.handle_message(SessionsPageMessage::SessionsLoaded(vec![
+            session_with_status("s_1", "active"),
+            session_with_status("s_2", "active"),
+        ])).await;
+
+        page.table_page.toggle_column("Id");
+        let buffer = render(&page, 140);
+        let (_, labeled_row) = find_text(&buffer, "database [migration]").unwrap();
+        let (_, unlabeled_row) = find_text(&buffer, "s_2").unwrap();
+        assert_eq!(find_text(&buffer, "["), Some((find_text(&buffer, "[migration]").unwrap().0, labeled_row)));
+        assert_ne!(labeled_row, unlabeled_row);
+    }
+}
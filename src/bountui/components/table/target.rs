@@ -1,25 +1,82 @@
 use crate::boundary;
-use crate::boundary::{ApiClient, ConnectResponse, Scope, Target};
+use crate::boundary::{ApiClient, ConnectResponse, Host, Scope, Target};
 use crate::bountui::components::input_dialog::{Button, InputDialog, InputField};
 use crate::bountui::components::table::action::Action;
-use crate::bountui::components::table::util::format_title_with_parent;
-use crate::bountui::components::table::{FilterItems, SortItems, TableColumn};
-use crate::bountui::components::{ConnectionEstablishedDialog, TablePage, TargetDetailDialog};
+use crate::bountui::components::table::util::{
+    columns_resized_sender, format_title_with_parent, local_address,
+};
+use crate::bountui::components::table::{EnabledAction, FilterChip, FilterItems, SortItems, TableColumn};
+use crate::bountui::components::{
+    BulkConnectDialog, BulkConnectRow, ConnectionEstablishedDialog, HostDialog,
+    TablePage, TargetDetailDialog, TargetTypeDialog,
+};
+use crate::bountui::connect_command::{
+    connect_command, connect_tip, OpenCommand, OpenCommandValues,
+};
+use crate::bountui::known_items::KnownItems;
 use crate::bountui::remember_user_input::RememberUserInput;
+use crate::bountui::web_ui;
 use crate::bountui::Message;
 use crate::bountui::Message::GoBack;
 use crate::event_ext::EventExt;
 use crate::util::MpscSenderExt;
+use chrono::{DateTime, Local, Utc};
 use crossterm::event::{Event, KeyCode};
 use futures::FutureExt;
 use ratatui::layout::Rect;
 use ratatui::prelude::Constraint;
 use ratatui::Frame;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::net::TcpListener;
 use std::rc::Rc;
 
 pub enum TargetsPageMessage {
     ConnectedToTarget(ConnectResponse),
     TargetsLoaded(Vec<Target>),
+    LoadTargetsFailed,
+    /// Scopes next to the parent scope, loaded to switch to one of them.
+    SiblingsLoaded {
+        siblings: Vec<Scope>,
+        forward: bool,
+    },
+    /// Hosts of a target Boundary could not pick a host of, the connect request is sent again
+    /// with the host the user picks.
+    HostsLoaded {
+        target_id: String,
+        port: u16,
+        keep_alive: bool,
+        hosts: Vec<Host>,
+    },
+    /// Session id or error of each target of a bulk connect, by target id.
+    BulkConnected(Vec<(String, Result<String, String>)>),
+}
+
+// Key of the targets table in the column widths of the config
+const COLUMN_WIDTHS_KEY: &str = "targets";
+
+const LOAD_TARGETS_FAILED: &str = "Failed to load targets — press r to retry";
+
+/// Shown instead of an empty table, an empty list can also mean that listing is not permitted.
+fn no_targets_notice(scope: &Scope) -> String {
+    if scope.can_list_targets() {
+        format!("There are no targets in scope '{}' — press r to reload", scope.name)
+    } else {
+        format!(
+            "You are not allowed to list the targets of scope '{}' — ask your Boundary admin",
+            scope.name
+        )
+    }
+}
+
+/// Whether the target matches one of the `production_targets` patterns by its id or name, or
+/// by the id or name of its scope.
+pub fn is_production_target(production_targets: &[String], target: &Target, scope: &Scope) -> bool {
+    production_targets.iter().any(|pattern| {
+        [&target.id, &target.name, &scope.id, &scope.name]
+            .iter()
+            .any(|value| boundary::matches_pattern(pattern, value))
+    })
 }
 
 impl From<TargetsPageMessage> for Message {
@@ -37,17 +94,107 @@ pub enum ConnectDialogFields {
 pub enum ConnectDialogButtons {
     Cancel,
     Ok,
+    KeepAlive,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum NoteDialogFields {
+    Note,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum NoteDialogButtons {
+    Cancel,
+    Save,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum LabelDialogFields {
+    Label,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum LabelDialogButtons {
+    Cancel,
+    Save,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ProductionDialogFields {
+    TargetName,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ProductionDialogButtons {
+    Cancel,
+    Connect,
+}
+
+// What is done with a production target once its name is typed
+enum ProductionAction {
+    Connect { port: u16, keep_alive: bool },
+    TestConnection,
+}
+
+// A connect to a production target waiting for the user to type the target name
+struct ProductionConfirm {
+    target: Rc<Target>,
+    action: ProductionAction,
+    dialog: InputDialog<ProductionDialogFields, ProductionDialogButtons>,
+}
+
+// A target of a running bulk connect, `outcome` is set once its connect request finished
+struct BulkConnectRequest {
+    target_id: String,
+    target_name: String,
+    port: Option<u16>,
+    outcome: Option<Result<String, String>>,
 }
 
 pub struct TargetsPage<C, S: RememberUserInput> {
     table_page: TablePage<boundary::Target>,
     connect_dialog: Option<InputDialog<ConnectDialogFields, ConnectDialogButtons>>,
+    production_confirm: Option<ProductionConfirm>,
     connect_result_dialog: Option<ConnectionEstablishedDialog>,
+    // Session of the connection shown by the result dialog, it can be labeled with <l>
+    connected_session_id: Option<String>,
+    label_dialog: Option<InputDialog<LabelDialogFields, LabelDialogButtons>>,
+    note_dialog: Option<InputDialog<NoteDialogFields, NoteDialogButtons>>,
     detail_dialog: Option<TargetDetailDialog>,
+    type_dialog: Option<TargetTypeDialog>,
+    host_dialog: Option<HostDialog>,
+    bulk_connect_dialog: Option<BulkConnectDialog>,
+    bulk_connect: Vec<BulkConnectRequest>,
     message_tx: tokio::sync::mpsc::Sender<Message>,
     boundary_client: C,
     parent_scope: Scope,
     remember_user_input: S,
+    // Target and port of the last connect request, used to suggest a command once connected
+    pending_connect: Option<(Rc<Target>, u16)>,
+    // User-configured command templates by target type to open a connected port with
+    open_commands: HashMap<String, String>,
+    // Whether the local address is copied to the clipboard once connected
+    copy_address_on_connect: bool,
+    // Whether the password is copied instead of showing the result dialog if there is only one
+    copy_single_password_on_connect: bool,
+    // Whether the sessions of a target are shown once its connect result dialog is closed
+    show_sessions_after_connect: bool,
+    // Target or scope id or name patterns of the targets to confirm by name before connecting
+    production_targets: Vec<String>,
+    // Target whose sessions are shown when the open connect result dialog is closed
+    sessions_after_connect: Option<Target>,
+    last_connected: Rc<RefCell<HashMap<String, DateTime<Utc>>>>,
+    // Remembered ports by target id, read once when the targets are loaded
+    local_ports: Rc<RefCell<HashMap<String, u16>>>,
+    sorted_by_last_connected: bool,
+    // Selected once the targets are loaded, e.g. the target of a session
+    select_after_load: Option<String>,
+    // Scopes next to `parent_scope` that have targets, loaded on the first [ or ]
+    siblings: Option<Vec<Scope>>,
+    // When the listed targets were loaded, None until the first load finished
+    loaded_at: Option<tokio::time::Instant>,
+    // The targets of all scopes, forgotten on reload so added targets are found again
+    known: KnownItems,
 }
 
 impl<C, S: RememberUserInput> TargetsPage<C, S> {
@@ -60,74 +207,182 @@ impl<C, S: RememberUserInput> TargetsPage<C, S> {
     where
         C: ApiClient + Clone + Send + 'static,
     {
+        let last_connected: Rc<RefCell<HashMap<String, DateTime<Utc>>>> = Rc::default();
+        let last_connected_for_column = last_connected.clone();
+        let local_ports: Rc<RefCell<HashMap<String, u16>>> = Rc::default();
+        let local_ports_for_column = local_ports.clone();
         let columns = vec![
             TableColumn::new(
                 "Name".to_string(),
-                Constraint::Ratio(3, 8),
+                Constraint::Ratio(3, 10),
                 Box::new(|s: &boundary::Target| s.name.clone()),
             ),
             TableColumn::new(
                 "Description".to_string(),
-                Constraint::Ratio(3, 8),
+                Constraint::Ratio(2, 10),
                 Box::new(|s| s.description.clone()),
             ),
             TableColumn::new(
                 "Type".to_string(),
-                Constraint::Ratio(1, 8),
+                Constraint::Ratio(1, 10),
                 Box::new(|s| s.type_name.clone()),
             ),
             TableColumn::new(
                 "ID".to_string(),
-                Constraint::Ratio(1, 8),
+                Constraint::Ratio(1, 10),
                 Box::new(|s| s.id.clone()),
             ),
+            TableColumn::new(
+                "Port".to_string(),
+                Constraint::Ratio(1, 10),
+                Box::new(move |s| {
+                    local_ports_for_column
+                        .borrow()
+                        .get(&s.id)
+                        .map(|p| p.to_string())
+                        .unwrap_or_default()
+                }),
+            ),
+            TableColumn::new(
+                "Last Connected".to_string(),
+                Constraint::Ratio(2, 10),
+                Box::new(move |s| {
+                    last_connected_for_column
+                        .borrow()
+                        .get(&s.id)
+                        .map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+                        .unwrap_or_default()
+                }),
+            ),
         ];
 
+        let can_list_sessions = parent_scope.can_list_sessions();
         let actions = vec![
             Action::new(
                 "Quit".to_string(),
-                "Ctrl + C".to_string(),
+                "q".to_string(),
                 Box::new(|_: Option<&Target>| true),
             ),
             Action::new(
                 "Back".to_string(),
                 "ESC".to_string(),
                 Box::new(|_: Option<&Target>| true),
-            ),
+            )
+            .with_key(KeyCode::Esc),
             Action::new(
                 "Show Sessions".to_string(),
                 "Shift + C".to_string(),
-                Box::new(|item: Option<&Target>| item.is_some()), // Enabled if any target is selected
-            ),
+                // Enabled if any target is selected and the sessions of the scope can be listed
+                Box::new(move |item: Option<&Target>| item.is_some() && can_list_sessions),
+            )
+            .with_key(KeyCode::Char('C')),
             Action::new(
                 "Connect".to_string(),
                 "c".to_string(),
                 Box::new(|item: Option<&Target>| item.map_or(false, |t| t.can_connect())),
-            ),
+            )
+            .with_key(KeyCode::Char('c')),
+            Action::new(
+                "Test Connection".to_string(),
+                "v".to_string(),
+                Box::new(|item: Option<&Target>| item.is_some_and(|t| t.can_connect())),
+            )
+            .with_key(KeyCode::Char('v')),
             Action::new(
                 "Show Details".to_string(),
                 "d".to_string(),
                 Box::new(|item: Option<&Target>| item.is_some()),
+            )
+            .with_key(KeyCode::Char('d')),
+            Action::new(
+                "Reload".to_string(),
+                "r".to_string(),
+                Box::new(|_: Option<&Target>| true),
+            )
+            .with_key(KeyCode::Char('r')),
+            Action::new(
+                "Filter Type".to_string(),
+                "t".to_string(),
+                Box::new(|_: Option<&Target>| true),
+            )
+            .with_key(KeyCode::Char('t')),
+            Action::new(
+                "Sort by Last Connected".to_string(),
+                "s".to_string(),
+                Box::new(|_: Option<&Target>| true),
+            )
+            .with_key(KeyCode::Char('s')),
+            Action::new(
+                "Mark".to_string(),
+                "Space".to_string(),
+                Box::new(|item: Option<&Target>| item.is_some()),
+            )
+            .with_key(KeyCode::Char(' ')),
+            Action::new(
+                "Bulk Connect".to_string(),
+                "b".to_string(),
+                Box::new(|_: Option<&Target>| true),
+            )
+            .with_key(KeyCode::Char('b')),
+            Action::new(
+                "Open Web UI".to_string(),
+                "w".to_string(),
+                Box::new(|item: Option<&Target>| item.is_some()),
+            )
+            .with_key(KeyCode::Char('w')),
+            Action::new(
+                "Edit Note".to_string(),
+                "n".to_string(),
+                Box::new(|item: Option<&Target>| item.is_some()),
+            )
+            .with_key(KeyCode::Char('n')),
+            Action::new(
+                "Previous/Next Scope".to_string(),
+                "[ ]".to_string(),
+                Box::new(|_: Option<&Target>| true),
             ),
         ];
 
-        let table_page = TablePage::new(
+        let mut table_page = TablePage::new(
             format_title_with_parent("Targets", Some(parent_scope.name.as_str())),
             columns,
             Vec::new(),
             actions,
-            message_tx.clone(),
             true,
         );
+        table_page.enable_multi_select();
+        table_page.enable_column_resizing(columns_resized_sender(COLUMN_WIDTHS_KEY, message_tx.clone()));
         let targets_page = TargetsPage {
             table_page,
             connect_dialog: None,
+            production_confirm: None,
             connect_result_dialog: None,
+            connected_session_id: None,
+            label_dialog: None,
+            note_dialog: None,
             detail_dialog: None,
+            type_dialog: None,
+            host_dialog: None,
+            bulk_connect_dialog: None,
+            bulk_connect: Vec::new(),
             message_tx,
             parent_scope,
             boundary_client,
             remember_user_input,
+            pending_connect: None,
+            open_commands: HashMap::new(),
+            copy_address_on_connect: false,
+            copy_single_password_on_connect: false,
+            show_sessions_after_connect: false,
+            production_targets: Vec::new(),
+            sessions_after_connect: None,
+            known: KnownItems::default(),
+            last_connected,
+            local_ports,
+            sorted_by_last_connected: false,
+            select_after_load: None,
+            siblings: None,
+            loaded_at: None,
         };
         targets_page.load_targets().await;
         targets_page
@@ -144,26 +399,55 @@ impl<C, S: RememberUserInput> TargetsPage<C, S> {
             match boundary_client.get_targets(Some(scope_id.as_str())).await {
                 Ok(targets) => {
                     message_tx
-                        .send(TargetsPageMessage::TargetsLoaded(targets).into())
-                        .await
-                        .unwrap();
+                        .send_or_log(TargetsPageMessage::TargetsLoaded(targets).into())
+                        .await;
                 }
                 Err(e) => {
                     message_tx
-                        .send(Message::ShowAlert(
+                        .send_or_log(TargetsPageMessage::LoadTargetsFailed.into())
+                        .await;
+                    message_tx
+                        .send_or_log(Message::ShowAlert(
                             "Error".to_string(),
                             format!("Failed to load targets: {e}"),
                         ))
-                        .await
-                        .unwrap();
+                        .await;
                 }
             }
         }
         .boxed();
         self.message_tx
-            .send(Message::RunFuture(future))
-            .await
-            .unwrap();
+            .send_or_log(Message::RunFuture(future))
+            .await;
+    }
+
+    /// Reloads the targets, as `r` does.
+    pub async fn reload_targets(&mut self)
+    where
+        C: ApiClient + Clone + Send + 'static,
+    {
+        self.table_page.notice = None;
+        self.table_page.loading = true;
+        self.known.targets.forget();
+        self.load_targets().await;
+    }
+
+    /// Reloads the targets if they were loaded more than `max_age` ago, e.g. when the page is
+    /// shown again. The listed targets stay shown until the reload finished.
+    pub async fn reload_if_stale(&mut self, max_age: std::time::Duration)
+    where
+        C: ApiClient + Clone + Send + 'static,
+    {
+        if self.loaded_at.is_some_and(|loaded_at| loaded_at.elapsed() > max_age) {
+            // Not reloaded again while this reload is running
+            self.loaded_at = None;
+            self.load_targets().await;
+        }
+    }
+
+    /// Name and shortcut of the actions enabled for the selected item, for the command palette.
+    pub fn enabled_actions(&self) -> Vec<EnabledAction> {
+        self.table_page.enabled_actions()
     }
 
     pub fn view(&self, frame: &mut Frame, area: Rect) {
@@ -171,16 +455,44 @@ impl<C, S: RememberUserInput> TargetsPage<C, S> {
         if let Some(connect_dialog) = &self.connect_dialog {
             connect_dialog.view(frame);
         }
+        if let Some(production_confirm) = &self.production_confirm {
+            production_confirm.dialog.view(frame);
+        }
         if let Some(connect_result_dialog) = &self.connect_result_dialog {
             connect_result_dialog.view(frame);
         }
+        if let Some(label_dialog) = &self.label_dialog {
+            label_dialog.view(frame);
+        }
+        if let Some(note_dialog) = &self.note_dialog {
+            note_dialog.view(frame);
+        }
         if let Some(detail_dialog) = &self.detail_dialog {
             detail_dialog.view(frame);
         }
+        if let Some(type_dialog) = &self.type_dialog {
+            type_dialog.view(frame);
+        }
+        if let Some(host_dialog) = &self.host_dialog {
+            host_dialog.view(frame);
+        }
+        if let Some(bulk_connect_dialog) = &self.bulk_connect_dialog {
+            bulk_connect_dialog.view(frame);
+        }
     }
 
-    fn close_connect_result_dialog(&mut self) {
+    async fn close_connect_result_dialog(&mut self) {
         self.connect_result_dialog = None;
+        self.connected_session_id = None;
+        if let Some(target) = self.sessions_after_connect.take() {
+            self.message_tx
+                .send_or_log(Message::ShowSessions {
+                    scope: target.scope_id.clone(),
+                    target,
+                    select_session_id: None,
+                })
+                .await;
+        }
     }
 
     fn open_connect_dialog(&mut self) {
@@ -200,7 +512,7 @@ impl<C, S: RememberUserInput> TargetsPage<C, S> {
             .map(|p| p.to_string())
             .unwrap_or_else(|| "".to_string());
 
-        self.connect_dialog = Some(InputDialog::new(
+        let mut connect_dialog = InputDialog::new(
             "Connect",
             vec![InputField::new(
                 ConnectDialogFields::ListenPort,
@@ -210,22 +522,281 @@ impl<C, S: RememberUserInput> TargetsPage<C, S> {
             vec![
                 Button::new(ConnectDialogButtons::Cancel, "Cancel"),
                 Button::new(ConnectDialogButtons::Ok, "Ok"),
+                Button::new(ConnectDialogButtons::KeepAlive, "Keep Alive"),
             ],
-        ));
+        );
+        if let Some(tip) = connect_tip(&selected_item.type_name) {
+            connect_dialog = connect_dialog.with_hint(tip);
+        }
+        self.connect_dialog = Some(connect_dialog);
     }
 
     fn close_connect_dialog(&mut self) {
         self.connect_dialog = None;
     }
 
-    pub fn connection_establised(&mut self, response: ConnectResponse) {
-        self.connect_result_dialog = Some(ConnectionEstablishedDialog::new(
-            response.credentials,
-            self.message_tx.clone(),
-        ));
+    fn open_note_dialog(&mut self) {
+        if let Some(target) = self.table_page.selected_item() {
+            let note = self
+                .remember_user_input
+                .get_note(&target.id)
+                .unwrap_or(None)
+                .unwrap_or_default();
+            self.note_dialog = Some(
+                InputDialog::new(
+                    &format!("Note for {}", target.name),
+                    vec![InputField::new(NoteDialogFields::Note, "Note", note)],
+                    vec![
+                        Button::new(NoteDialogButtons::Cancel, "Cancel"),
+                        Button::new(NoteDialogButtons::Save, "Save"),
+                    ],
+                )
+                .with_hint("Leave empty to remove the note"),
+            );
+        }
+    }
+
+    fn open_label_dialog(&mut self) {
+        self.label_dialog = Some(
+            InputDialog::new(
+                "Label the connection",
+                vec![InputField::new(LabelDialogFields::Label, "Label", "")],
+                vec![
+                    Button::new(LabelDialogButtons::Cancel, "Cancel"),
+                    Button::new(LabelDialogButtons::Save, "Save"),
+                ],
+            )
+            .with_hint("Shown with the target name on the sessions pages"),
+        );
+    }
+
+    async fn save_label(&mut self) {
+        let label = self
+            .label_dialog
+            .take()
+            .and_then(|d| d.get_value(LabelDialogFields::Label).map(str::to_string));
+        if let (Some(session_id), Some(label)) = (self.connected_session_id.clone(), label) {
+            self.message_tx
+                .send_or_log(Message::SetConnectionLabel { session_id, label })
+                .await;
+        }
+    }
+
+    async fn save_note(&mut self) {
+        let note = self
+            .note_dialog
+            .take()
+            .and_then(|d| d.get_value(NoteDialogFields::Note).map(str::to_string));
+        if let (Some(target), Some(note)) = (self.table_page.selected_item(), note) {
+            if let Err(e) = self.remember_user_input.store_note(target.id.clone(), note) {
+                self.message_tx
+                    .send_or_log(Message::show_error("Failed to save note", format!("{e:#}")))
+                    .await;
+            }
+        }
+    }
+
+    /// Shares the scopes and targets of all levels with the other pages.
+    pub fn with_known_items(mut self, known: &KnownItems) -> Self {
+        self.known = known.clone();
+        self
+    }
+
+    pub fn with_open_commands(mut self, open_commands: HashMap<String, String>) -> Self {
+        self.open_commands = open_commands;
+        self
+    }
+
+    pub fn with_copy_address_on_connect(mut self, copy_address_on_connect: bool) -> Self {
+        self.copy_address_on_connect = copy_address_on_connect;
+        self
+    }
+
+    pub fn with_copy_single_password_on_connect(mut self, copy_single_password_on_connect: bool) -> Self {
+        self.copy_single_password_on_connect = copy_single_password_on_connect;
+        self
+    }
+
+    pub fn with_show_sessions_after_connect(mut self, show_sessions_after_connect: bool) -> Self {
+        self.show_sessions_after_connect = show_sessions_after_connect;
+        self
+    }
+
+    pub fn with_production_targets(mut self, production_targets: Vec<String>) -> Self {
+        self.production_targets = production_targets;
+        self
+    }
+
+    fn is_production(&self, target: &Target) -> bool {
+        is_production_target(&self.production_targets, target, &self.parent_scope)
+    }
+
+    #[cfg(test)]
+    pub fn selected_target_id(&self) -> Option<String> {
+        self.table_page.selected_item().map(|t| t.id.clone())
+    }
+
+    /// Selects the target with the given id once the targets are loaded.
+    pub fn with_selected_target(mut self, target_id: Option<String>) -> Self {
+        self.select_after_load = target_id;
+        self
+    }
+
+    /// Reuses the siblings of the previous page when switching between sibling scopes.
+    pub fn with_siblings(mut self, siblings: Option<Vec<Scope>>) -> Self {
+        self.siblings = siblings;
+        self
+    }
+
+    async fn show_sibling(&mut self, forward: bool)
+    where
+        C: ApiClient + Clone + Send + 'static,
+    {
+        if self.siblings.is_some() {
+            self.switch_to_sibling(forward).await;
+            return;
+        }
+        let boundary_client = self.boundary_client.clone();
+        let message_tx = self.message_tx.clone();
+        let parent_id = self.parent_scope.scope_id.clone();
+        let future = async move {
+            // The global scope has no siblings
+            let siblings = if parent_id.is_empty() {
+                Ok(vec![])
+            } else {
+                boundary_client.get_scopes(Some(&parent_id), false).await
+            };
+            match siblings {
+                Ok(siblings) => {
+                    let siblings = siblings.into_iter().filter(|s| s.can_list_targets()).collect();
+                    message_tx
+                        .send_or_log(TargetsPageMessage::SiblingsLoaded { siblings, forward }.into())
+                        .await;
+                }
+                Err(e) => {
+                    message_tx
+                        .send_or_log(Message::show_error("Failed to load the sibling scopes", e))
+                        .await;
+                }
+            }
+        }
+        .boxed();
+        self.message_tx
+            .send_or_log(Message::RunFuture(future))
+            .await;
+    }
+
+    async fn switch_to_sibling(&self, forward: bool) {
+        let Some(siblings) = &self.siblings else {
+            return;
+        };
+        let Some(position) = siblings.iter().position(|s| s.id == self.parent_scope.id) else {
+            return;
+        };
+        let next = if forward {
+            siblings.get(position + 1)
+        } else {
+            position.checked_sub(1).and_then(|i| siblings.get(i))
+        };
+        if let Some(next) = next {
+            self.message_tx
+                .send_or_log(Message::ShowSiblingTargets {
+                    parent: next.clone(),
+                    siblings: siblings.clone(),
+                })
+                .await;
+        }
+    }
+
+    pub fn with_column_widths(mut self, column_widths: &HashMap<String, Vec<u16>>) -> Self {
+        if let Some(widths) = column_widths.get(COLUMN_WIDTHS_KEY) {
+            self.table_page.set_column_widths(widths);
+        }
+        self
+    }
+
+    async fn copy_local_address(&self, port: u16) {
+        self.message_tx
+            .send_or_log(Message::SetClipboard {
+                text: local_address(port),
+                on_success: Some(Box::new(Message::toast("Local address copied"))),
+                on_error: Some(Box::new(Message::toast("Failed to copy local address"))),
+            })
+            .await;
+    }
+
+    /// Copies the password of the only credential, `false` if there is not exactly one.
+    async fn copy_single_password(&self, response: &ConnectResponse) -> bool {
+        let [entry] = response.credentials.as_slice() else {
+            return false;
+        };
+        self.message_tx
+            .send_or_log(Message::SetClipboard {
+                text: entry.credential.password.clone(),
+                on_success: Some(Box::new(Message::toast(format!(
+                    "Connected, password of {} copied",
+                    entry.credential.username
+                )))),
+                on_error: Some(Box::new(Message::toast("Connected, failed to copy the password"))),
+            })
+            .await;
+        true
+    }
+
+    pub async fn connection_establised(&mut self, response: ConnectResponse) {
+        let pending_connect = self.pending_connect.take();
+        let command = pending_connect
+            .as_ref()
+            .and_then(|(target, port)| connect_command(&target.type_name, *port));
+        // Copying the password replaces the dialog, it also takes the clipboard over the address
+        let password_copied =
+            self.copy_single_password_on_connect && self.copy_single_password(&response).await;
+        if let Some((target, port)) = &pending_connect {
+            self.store_last_connected(&target.id);
+            self.local_ports
+                .borrow_mut()
+                .insert(target.id.clone(), *port);
+            if self.copy_address_on_connect && !password_copied {
+                self.copy_local_address(*port).await;
+            }
+            if self.show_sessions_after_connect && self.parent_scope.can_list_sessions() {
+                self.sessions_after_connect = Some((**target).clone());
+            }
+        }
+        let connected_target = pending_connect
+            .as_ref()
+            .map(|(target, port)| (target.type_name.clone(), *port));
+        let open_command = pending_connect.and_then(|(target, port)| {
+            let template = self.open_commands.get(&target.type_name)?;
+            let credential = response.credentials.first().map(|c| &c.credential);
+            OpenCommand::from_template(
+                template,
+                &OpenCommandValues {
+                    port,
+                    username: credential.map_or("", |c| c.username.as_str()),
+                    password: credential.map_or("", |c| c.password.as_str()),
+                    session_id: &response.session_id,
+                },
+            )
+        });
+        if password_copied {
+            // Nothing to close, the sessions are shown right away if requested
+            self.close_connect_result_dialog().await;
+            return;
+        }
+        self.connected_session_id = Some(response.session_id.clone());
+        self.connect_result_dialog = Some(
+            ConnectionEstablishedDialog::new(
+                response.credentials,
+                command,
+                self.message_tx.clone(),
+                connected_target,
+            )
+            .with_open_command(open_command),
+        );
     }
 
-    async fn connect_to_target(&mut self) {
+    async fn connect_to_target(&mut self, keep_alive: bool) {
         if let Some(target) = self.table_page.selected_item() {
             let port: u16 = self
                 .connect_dialog
@@ -236,18 +807,82 @@ impl<C, S: RememberUserInput> TargetsPage<C, S> {
                 .parse()
                 .unwrap();
             self.store_selected_port(port);
-            let _ = self
-                .message_tx
-                .send(Message::Connect {
-                    target_id: target.id.clone(),
-                    port,
-                })
-                .await
-                .unwrap();
             self.connect_dialog = None;
+            if self.is_production(&target) {
+                self.open_production_confirm(target, ProductionAction::Connect { port, keep_alive });
+            } else {
+                self.send_connect(target, port, keep_alive).await;
+            }
         }
     }
 
+    fn open_production_confirm(&mut self, target: Rc<Target>, action: ProductionAction) {
+        let dialog = InputDialog::new(
+            "Connect to Production",
+            vec![InputField::new(
+                ProductionDialogFields::TargetName,
+                "Target Name",
+                "",
+            )],
+            vec![
+                Button::new(ProductionDialogButtons::Cancel, "Cancel"),
+                Button::new(ProductionDialogButtons::Connect, "Connect"),
+            ],
+        )
+        .with_hint(format!("Type \"{}\" to confirm connecting to it", target.name));
+        self.production_confirm = Some(ProductionConfirm {
+            target,
+            action,
+            dialog,
+        });
+    }
+
+    /// Connects once the typed name matches the target, keeps asking otherwise.
+    async fn confirm_production_connect(&mut self) {
+        let Some(mut confirm) = self.production_confirm.take() else {
+            return;
+        };
+        let typed = confirm
+            .dialog
+            .get_value(ProductionDialogFields::TargetName)
+            .unwrap_or_default();
+        if typed.trim() != confirm.target.name {
+            confirm.dialog = confirm.dialog.with_hint(format!(
+                "The name does not match, type \"{}\" to confirm",
+                confirm.target.name
+            ));
+            self.production_confirm = Some(confirm);
+            return;
+        }
+        match confirm.action {
+            ProductionAction::Connect { port, keep_alive } => {
+                self.send_connect(confirm.target, port, keep_alive).await
+            }
+            ProductionAction::TestConnection => self.send_test_connection(&confirm.target).await,
+        }
+    }
+
+    async fn send_test_connection(&self, target: &Target) {
+        self.message_tx
+            .send_or_log(Message::TestConnection {
+                target_id: target.id.clone(),
+            })
+            .await;
+    }
+
+    async fn send_connect(&mut self, target: Rc<Target>, port: u16, keep_alive: bool) {
+        let target_id = target.id.clone();
+        self.pending_connect = Some((target, port));
+        self.message_tx
+            .send_or_log(Message::Connect {
+                target_id,
+                host_id: None,
+                port,
+                keep_alive,
+            })
+            .await;
+    }
+
     fn store_selected_port(&mut self, port: u16) {
         if let Some(target) = self.table_page.selected_item() {
             let _ = self
@@ -256,19 +891,166 @@ impl<C, S: RememberUserInput> TargetsPage<C, S> {
         }
     }
 
+    fn store_last_connected(&mut self, target_id: &str) {
+        let now = Utc::now();
+        let _ = self
+            .remember_user_input
+            .store_last_connected(target_id.to_string(), now);
+        self.last_connected
+            .borrow_mut()
+            .insert(target_id.to_string(), now);
+    }
+
+    fn load_last_connected(&self) {
+        *self.last_connected.borrow_mut() = self
+            .remember_user_input
+            .get_all_last_connected()
+            .unwrap_or_default();
+    }
+
+    fn load_local_ports(&self) {
+        *self.local_ports.borrow_mut() = self.remember_user_input.get_all_ports().unwrap_or_default();
+    }
+
+    async fn open_in_web_ui(&mut self) {
+        if let Some(target) = self.table_page.selected_item() {
+            let message = match web_ui::controller_addr() {
+                Some(addr) => Message::OpenInBrowser(web_ui::target_url(&addr, &target)),
+                None => Message::ShowAlert("Web UI".to_string(), web_ui::MISSING_ADDR.to_string()),
+            };
+            self.message_tx.send_or_log(message).await;
+        }
+    }
+
+    /// Connects to all marked targets using their remembered port or a free one. The
+    /// summary is shown once every connect request finished.
+    async fn bulk_connect(&mut self) {
+        let targets = self.table_page.marked_items();
+        if targets.is_empty() {
+            self.message_tx
+                .send_or_log(Message::ShowAlert(
+                    "Bulk Connect".to_string(),
+                    "Mark the targets to connect to with <Space> first".to_string(),
+                ))
+                .await;
+            return;
+        }
+        // Keep the picked free ports bound until all are picked, so no port is used twice
+        let mut reserved_ports = Vec::new();
+        let mut connects = Vec::new();
+        for target in targets {
+            let mut request = BulkConnectRequest {
+                target_id: target.id.clone(),
+                target_name: target.name.clone(),
+                port: None,
+                outcome: None,
+            };
+            if !target.can_connect() {
+                request.outcome = Some(Err("Not permitted to connect".to_string()));
+            } else if self.is_production(&target) {
+                request.outcome = Some(Err(
+                    "Production target, connect to it on its own to confirm".to_string(),
+                ));
+            } else {
+                match self.pick_port(&target, &mut reserved_ports) {
+                    Ok(port) => {
+                        request.port = Some(port);
+                        let _ = self
+                            .remember_user_input
+                            .store_local_port(target.id.clone(), port);
+                        connects.push((target.id.clone(), port));
+                    }
+                    Err(e) => request.outcome = Some(Err(e)),
+                }
+            }
+            self.bulk_connect.push(request);
+        }
+        self.table_page.clear_marks();
+        if connects.is_empty() {
+            self.finish_bulk_connect();
+        } else {
+            self.message_tx
+                .send_or_log(Message::BulkConnect(connects))
+                .await;
+        }
+    }
+
+    fn pick_port(&self, target: &Target, reserved_ports: &mut Vec<TcpListener>) -> Result<u16, String> {
+        if let Some(port) = self.local_ports.borrow().get(&target.id) {
+            return Ok(*port);
+        }
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .map_err(|e| format!("No free port available: {e}"))?;
+        let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+        reserved_ports.push(listener);
+        Ok(port)
+    }
+
+    fn finish_bulk_connect(&mut self) {
+        if self.bulk_connect.is_empty() || self.bulk_connect.iter().any(|r| r.outcome.is_none()) {
+            return;
+        }
+        let rows = self
+            .bulk_connect
+            .drain(..)
+            .map(|r| BulkConnectRow {
+                target_name: r.target_name,
+                port: r.port,
+                outcome: r.outcome.unwrap_or_else(|| Err("No result".to_string())),
+            })
+            .collect();
+        self.bulk_connect_dialog = Some(BulkConnectDialog::new(rows));
+    }
+
+    fn filter_by_type(&mut self, type_name: String) {
+        self.table_page.set_filter_chip(Some(FilterChip::new(
+            format!("type: {type_name}"),
+            Box::new(move |target: &Target| target.type_name == type_name),
+        )));
+    }
+
+    fn toggle_sort_by_last_connected(&mut self) {
+        self.sorted_by_last_connected = !self.sorted_by_last_connected;
+        if self.sorted_by_last_connected {
+            let last_connected = self.last_connected.clone();
+            self.table_page.set_sort_override(Some(Box::new(move |a: &Target, b: &Target| {
+                let last_connected = last_connected.borrow();
+                // Most recently connected first, never connected targets last
+                last_connected.get(&b.id).cmp(&last_connected.get(&a.id))
+            })));
+        } else {
+            self.table_page.set_sort_override(None);
+        }
+    }
+
     async fn show_sessions(&mut self) {
+        if !self.parent_scope.can_list_sessions() {
+            self.message_tx
+                .send_or_log(Message::ShowAlert(
+                    "Not permitted".to_string(),
+                    format!(
+                        "You are not allowed to list sessions in scope '{}'",
+                        self.parent_scope.name
+                    ),
+                ))
+                .await;
+            return;
+        }
         if let Some(target) = self.table_page.selected_item() {
             self.message_tx
-                .send(Message::ShowSessions {
+                .send_or_log(Message::ShowSessions {
                     scope: target.scope_id.clone(),
                     target: (*target).clone(),
+                    select_session_id: None,
                 })
-                .await
-                .unwrap();
+                .await;
         }
     }
 
-    pub async fn handle_event(&mut self, event: &Event) {
+    pub async fn handle_event(&mut self, event: &Event)
+    where
+        C: ApiClient + Clone + Send + 'static,
+    {
         // 0. Handle TargetDetailDialog FIRST if it's open
         if let Some(detail_dialog) = &mut self.detail_dialog {
             if event.is_esc() {
@@ -279,30 +1061,115 @@ impl<C, S: RememberUserInput> TargetsPage<C, S> {
             return;
         }
 
-        // 1. Handle ConnectionEstablishedDialog if it's open
-        if let Some(dialog) = &mut self.connect_result_dialog {
+        if let Some(bulk_connect_dialog) = &mut self.bulk_connect_dialog {
             if event.is_esc() {
-                self.close_connect_result_dialog();
-                return; // Consume Esc, don't forward
+                self.bulk_connect_dialog = None;
+                return;
             }
-            // Forward all other events to the dialog
-            dialog.handle_event(event).await;
-            return; // Consume the event, don't let TargetsPage handle it further
+            bulk_connect_dialog.handle_event(event).await;
+            return;
         }
 
-        // 2. Handle ConnectDialog if it's open
-        if let Some(connect_dialog) = &mut self.connect_dialog {
+        if let Some(type_dialog) = &mut self.type_dialog {
             if event.is_esc() {
-                self.close_connect_dialog();
-                return; // Consume Esc, don't forward
+                self.type_dialog = None;
+                return;
             }
-            match connect_dialog.handle_event(event) {
-                Some(ConnectDialogButtons::Cancel) => {
-                    self.close_connect_dialog();
-                    return; // Consume event
-                }
-                Some(ConnectDialogButtons::Ok) => {
-                    self.connect_to_target().await;
+            if let Some(type_name) = type_dialog.handle_event(event).await {
+                self.type_dialog = None;
+                self.filter_by_type(type_name);
+            }
+            return;
+        }
+
+        if let Some(host_dialog) = &mut self.host_dialog {
+            if event.is_esc() {
+                self.host_dialog = None;
+                self.pending_connect = None;
+                return;
+            }
+            if let Some(host_id) = host_dialog.handle_event(event).await {
+                let message = Message::Connect {
+                    target_id: host_dialog.target_id.clone(),
+                    host_id: Some(host_id),
+                    port: host_dialog.port,
+                    keep_alive: host_dialog.keep_alive,
+                    };
+                self.host_dialog = None;
+                self.message_tx.send_or_log(message).await;
+            }
+            return;
+        }
+
+        if let Some(label_dialog) = &mut self.label_dialog {
+            if event.is_esc() {
+                self.label_dialog = None;
+                return;
+            }
+            match label_dialog.handle_event(event) {
+                Some(LabelDialogButtons::Cancel) => self.label_dialog = None,
+                Some(LabelDialogButtons::Save) => self.save_label().await,
+                None => {}
+            }
+            return;
+        }
+
+        // 1. Handle ConnectionEstablishedDialog if it's open
+        if let Some(dialog) = &mut self.connect_result_dialog {
+            // Esc resets the credentials filter first and closes the dialog otherwise
+            if !dialog.handle_event(event).await {
+                if dialog.closes_on(event) {
+                    self.close_connect_result_dialog().await;
+                } else if matches!(event, Event::Key(key) if key.code == KeyCode::Char('l')) {
+                    self.open_label_dialog();
+                }
+            }
+            return; // Consume the event, don't let TargetsPage handle it further
+        }
+
+        if let Some(note_dialog) = &mut self.note_dialog {
+            if event.is_esc() {
+                self.note_dialog = None;
+                return;
+            }
+            match note_dialog.handle_event(event) {
+                Some(NoteDialogButtons::Cancel) => self.note_dialog = None,
+                Some(NoteDialogButtons::Save) => self.save_note().await,
+                None => {}
+            }
+            return;
+        }
+
+        if let Some(production_confirm) = &mut self.production_confirm {
+            if event.is_esc() {
+                self.production_confirm = None;
+                return;
+            }
+            match production_confirm.dialog.handle_event(event) {
+                Some(ProductionDialogButtons::Cancel) => self.production_confirm = None,
+                Some(ProductionDialogButtons::Connect) => self.confirm_production_connect().await,
+                None => {}
+            }
+            return;
+        }
+
+        // 2. Handle ConnectDialog if it's open
+        if let Some(connect_dialog) = &mut self.connect_dialog {
+            if event.is_esc() {
+                self.close_connect_dialog();
+                return; // Consume Esc, don't forward
+            }
+            match connect_dialog.handle_event(event) {
+                Some(ConnectDialogButtons::Cancel) => {
+                    self.close_connect_dialog();
+                    return; // Consume event
+                }
+                Some(ConnectDialogButtons::Ok) => {
+                    self.connect_to_target(false).await;
+                    return; // Consume event
+                }
+                Some(ConnectDialogButtons::KeepAlive) => {
+                    self.connect_to_target(true).await;
                     return; // Consume event
                 }
                 None => {
@@ -329,6 +1196,16 @@ impl<C, S: RememberUserInput> TargetsPage<C, S> {
                         }
                     }
                 }
+                KeyCode::Char('v') => {
+                    // Testing opens a real session, production targets are confirmed like a connect
+                    if let Some(target) = self.table_page.selected_item().filter(|t| t.can_connect()) {
+                        if self.is_production(&target) {
+                            self.open_production_confirm(target, ProductionAction::TestConnection);
+                        } else {
+                            self.send_test_connection(&target).await;
+                        }
+                    }
+                }
                 KeyCode::Char('C') => {
                     // Show sessions for the selected target if possible
                     if self.table_page.selected_item().is_some() {
@@ -338,29 +1215,97 @@ impl<C, S: RememberUserInput> TargetsPage<C, S> {
                 KeyCode::Char('d') => {
                     // Show target detail overlay if a target is selected
                     if let Some(target) = self.table_page.selected_item() {
+                        let note = self.remember_user_input.get_note(&target.id).unwrap_or(None);
                         self.detail_dialog = Some(TargetDetailDialog::new(
                             &target,
+                            note,
                             self.message_tx.clone(),
                         ));
                     }
                 }
+                KeyCode::Char('r') => {
+                    self.reload_targets().await;
+                }
+                KeyCode::Char('s') => {
+                    self.toggle_sort_by_last_connected();
+                }
+                KeyCode::Char('t') => {
+                    self.type_dialog = Some(TargetTypeDialog::new(self.table_page.items()));
+                }
+                KeyCode::Char('b') => {
+                    self.bulk_connect().await;
+                }
+                KeyCode::Char('w') => {
+                    self.open_in_web_ui().await;
+                }
+                KeyCode::Char('n') => {
+                    self.open_note_dialog();
+                }
+                KeyCode::Char('[') => {
+                    self.show_sibling(false).await;
+                }
+                KeyCode::Char(']') => {
+                    self.show_sibling(true).await;
+                }
+                KeyCode::Char('q') => {
+                    self.message_tx.send_or_log(Message::Quit).await;
+                }
                 KeyCode::Esc => {
                     // Go back only if no dialogs are open
-                    self.message_tx.send_or_expect(GoBack).await;
+                    self.message_tx.send_or_log(GoBack).await;
                 }
                 _ => {}
             }
         }
     }
 
-    pub fn handle_message(&mut self, message: TargetsPageMessage) {
+    pub async fn handle_message(&mut self, message: TargetsPageMessage) {
         match message {
             TargetsPageMessage::ConnectedToTarget(response) => {
-                self.connection_establised(response);
+                self.connection_establised(response).await;
             }
             TargetsPageMessage::TargetsLoaded(targets) => {
+                self.load_last_connected();
+                self.load_local_ports();
+                self.table_page.loading = false;
+                self.table_page.notice =
+                    targets.is_empty().then(|| no_targets_notice(&self.parent_scope));
+                self.table_page.set_items_keeping_selection(targets, |t| t.id.clone());
+                self.loaded_at = Some(tokio::time::Instant::now());
+                if let Some(target_id) = self.select_after_load.take() {
+                    self.table_page.select_where(|t| t.id == target_id);
+                }
+            }
+            TargetsPageMessage::SiblingsLoaded { siblings, forward } => {
+                self.siblings = Some(siblings);
+                self.switch_to_sibling(forward).await;
+            }
+            TargetsPageMessage::LoadTargetsFailed => {
                 self.table_page.loading = false;
-                self.table_page.set_items(targets);
+                self.table_page.notice = Some(LOAD_TARGETS_FAILED.to_string());
+            }
+            TargetsPageMessage::HostsLoaded {
+                target_id,
+                port,
+                keep_alive,
+                hosts,
+            } => {
+                self.host_dialog = Some(HostDialog::new(target_id, port, keep_alive, hosts));
+            }
+            TargetsPageMessage::BulkConnected(results) => {
+                for (target_id, result) in results {
+                    if result.is_ok() {
+                        self.store_last_connected(&target_id);
+                    }
+                    if let Some(request) = self
+                        .bulk_connect
+                        .iter_mut()
+                        .find(|r| r.target_id == target_id && r.outcome.is_none())
+                    {
+                        request.outcome = Some(result);
+                    }
+                }
+                self.finish_bulk_connect();
             }
         }
     }
@@ -383,8 +1328,10 @@ impl FilterItems<boundary::Target> for TablePage<boundary::Target> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::boundary::fixtures::credential_entry;
     use crate::bountui::remember_user_input::tests::MockRememberUserInput;
     use std::collections::HashMap;
+    use std::sync::atomic::AtomicUsize;
     use std::sync::Arc;
 
     fn create_parent_scope() -> Scope {
@@ -394,6 +1341,7 @@ mod test {
             description: "A test scope".to_string(),
             type_name: "test".to_string(),
             authorized_collection_actions: HashMap::new(),
+            scope_id: "o_1".to_string(),
         }
     }
 
@@ -413,6 +1361,10 @@ mod test {
     }
 
     fn create_boundary_client() -> boundary::MockClient {
+        create_boundary_client_with_target_failures(0)
+    }
+
+    fn create_boundary_client_with_target_failures(failures: usize) -> boundary::MockClient {
         let mut scopes = HashMap::new();
         scopes.insert(None, vec![create_parent_scope()]);
 
@@ -422,20 +1374,677 @@ mod test {
         boundary::MockClient::builder()
             .scopes(scopes)
             .targets(targets)
+            .get_targets_failures(Arc::new(AtomicUsize::new(failures)))
             .build()
     }
 
 
+    fn sibling_scope(id: &str) -> Scope {
+        Scope {
+            id: id.to_string(),
+            authorized_collection_actions: HashMap::from([(
+                "targets".to_string(),
+                vec!["list".to_string()],
+            )]),
+            ..create_parent_scope()
+        }
+    }
+
+    fn sibling_targets_request(msg_rx: &mut tokio::sync::mpsc::Receiver<Message>) -> Option<String> {
+        let mut requested = None;
+        while let Ok(message) = msg_rx.try_recv() {
+            if let Message::ShowSiblingTargets { parent, .. } = message {
+                requested = Some(parent.id);
+            }
+        }
+        requested
+    }
+
+    #[tokio::test]
+    async fn test_switch_to_sibling_scopes() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let scopes = HashMap::from([(
+            Some("o_1".to_string()),
+            vec![sibling_scope("scope-a"), sibling_scope("scope-id"), sibling_scope("scope-c")],
+        )]);
+        let client = boundary::MockClient::builder().scopes(scopes).build();
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(client), MockRememberUserInput::default()).await;
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(create_targets())).await;
+        while msg_rx.try_recv().is_ok() {}
+
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char(']')))).await;
+        while let Ok(message) = msg_rx.try_recv() {
+            match message {
+                Message::RunFuture(future) => future.await,
+                Message::Targets(message) => sut.handle_message(message).await,
+                Message::ShowSiblingTargets { parent, .. } => {
+                    assert_eq!(parent.id, "scope-c");
+                    break;
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(sut.siblings.as_ref().map(Vec::len), Some(3));
+
+        // The siblings are loaded only once
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('[')))).await;
+        assert_eq!(sibling_targets_request(&mut msg_rx), Some("scope-a".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_no_sibling_after_the_last_scope() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let client = create_boundary_client();
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(client), MockRememberUserInput::default())
+            .await
+            .with_siblings(Some(vec![sibling_scope("scope-a"), sibling_scope("scope-id")]));
+
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char(']')))).await;
+        assert_eq!(sibling_targets_request(&mut msg_rx), None);
+    }
+
     #[tokio::test]
     async fn test_close_connect_dialog() {
         let (msg_tx, _msg_rx) = tokio::sync::mpsc::channel(10);
         let client = create_boundary_client();
         let remember_user_input = MockRememberUserInput::default();
         let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(client), remember_user_input).await;
-        sut.handle_message(TargetsPageMessage::TargetsLoaded(create_targets()));
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(create_targets())).await;
         sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('c')))).await; // Open connect dialog
         assert!(sut.connect_dialog.is_some(), "Connect dialog should be open");
         sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Esc))).await; // Press Esc to close
         assert!(sut.connect_dialog.is_none(), "Connect dialog should be closed after pressing Esc");
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_connect_to_ssh_target_suggests_command() {
+        let (msg_tx, _msg_rx) = tokio::sync::mpsc::channel(10);
+        let client = create_boundary_client();
+        let remember_user_input = MockRememberUserInput::default();
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(client), remember_user_input).await;
+        let mut targets = create_targets();
+        targets[0].type_name = "ssh".to_string();
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(targets)).await;
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('c')))).await;
+        for c in "2222".chars() {
+            sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char(c)))).await;
+        }
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Tab))).await;
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Right))).await;
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Enter))).await;
+        assert!(sut.connect_dialog.is_none(), "Connect dialog should be closed after confirming");
+        sut.handle_message(TargetsPageMessage::ConnectedToTarget(ConnectResponse {
+            credentials: vec![],
+            session_id: "session-1".to_string(),
+            expiration: chrono::Utc::now(),
+        })).await;
+        let dialog = sut.connect_result_dialog.as_ref().expect("Result dialog should be open");
+        assert_eq!(dialog.command(), Some("ssh -p 2222 localhost"));
+    }
+
+    #[tokio::test]
+    async fn test_label_connection_after_connect() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(create_boundary_client()), MockRememberUserInput::default()).await;
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(create_targets())).await;
+        sut.handle_message(TargetsPageMessage::ConnectedToTarget(ConnectResponse {
+            credentials: vec![],
+            session_id: "session-1".to_string(),
+            expiration: chrono::Utc::now(),
+        })).await;
+        while msg_rx.try_recv().is_ok() {}
+
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('l')))).await;
+        assert!(sut.label_dialog.is_some(), "Label dialog should be open");
+        for c in "db".chars() {
+            sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char(c)))).await;
+        }
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Tab))).await;
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Right))).await;
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Enter))).await;
+
+        assert!(sut.label_dialog.is_none(), "Label dialog should be closed after saving");
+        assert!(sut.connect_result_dialog.is_some(), "The result dialog stays open");
+        match msg_rx.try_recv() {
+            Ok(Message::SetConnectionLabel { session_id, label }) => {
+                assert_eq!(session_id, "session-1");
+                assert_eq!(label, "db");
+            }
+            _ => panic!("Expected SetConnectionLabel message"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_edit_note() {
+        let (msg_tx, _msg_rx) = tokio::sync::mpsc::channel(10);
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(create_boundary_client()), MockRememberUserInput::default()).await;
+        let targets = create_targets();
+        let target_id = targets[0].id.clone();
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(targets)).await;
+
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('n')))).await;
+        assert!(sut.note_dialog.is_some(), "Note dialog should be open");
+        for c in "prod".chars() {
+            sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char(c)))).await;
+        }
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Tab))).await;
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Right))).await;
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Enter))).await;
+        assert!(sut.note_dialog.is_none(), "Note dialog should be closed after saving");
+        assert_eq!(sut.remember_user_input.get_note(&target_id).unwrap(), Some("prod".to_string()));
+
+        // The dialog is prefilled with the stored note
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('n')))).await;
+        let dialog = sut.note_dialog.as_ref().unwrap();
+        assert_eq!(dialog.get_value(NoteDialogFields::Note), Some("prod"));
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Esc))).await;
+        assert!(sut.note_dialog.is_none());
+    }
+
+    async fn process_messages<C, S>(
+        sut: &mut TargetsPage<C, S>,
+        msg_rx: &mut tokio::sync::mpsc::Receiver<Message>,
+    ) where
+        S: RememberUserInput,
+    {
+        while let Ok(message) = msg_rx.try_recv() {
+            match message {
+                Message::RunFuture(future) => future.await,
+                Message::Targets(message) => sut.handle_message(message).await,
+                _ => {}
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_failed_load() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let client = create_boundary_client_with_target_failures(1);
+        let remember_user_input = MockRememberUserInput::default();
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(client), remember_user_input).await;
+        process_messages(&mut sut, &mut msg_rx).await;
+        assert_eq!(
+            sut.table_page.notice.as_deref(),
+            Some(LOAD_TARGETS_FAILED),
+            "Loading the targets should have failed"
+        );
+        assert!(sut.table_page.selected_item().is_none());
+
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('r')))).await;
+        process_messages(&mut sut, &mut msg_rx).await;
+        assert_eq!(sut.table_page.notice, None, "Retrying should succeed");
+        assert_eq!(sut.table_page.selected_item().map(|t| t.id.clone()), Some("target-1".to_string()));
+    }
+
+    fn create_parent_scope_with_session_list() -> Scope {
+        let mut scope = create_parent_scope();
+        scope
+            .authorized_collection_actions
+            .insert("sessions".to_string(), vec!["list".to_string()]);
+        scope
+    }
+
+    #[tokio::test]
+    async fn test_show_sessions_without_permission_shows_alert() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let client = create_boundary_client();
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(client), MockRememberUserInput::default()).await;
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(create_targets())).await;
+        let show_sessions = sut.table_page.actions.iter().find(|a| a.name == "Show Sessions").unwrap();
+        assert!(!(show_sessions.enabled)(sut.table_page.selected_item().as_deref()), "Show Sessions should be disabled");
+
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('C')))).await;
+        let mut messages = Vec::new();
+        while let Ok(message) = msg_rx.try_recv() {
+            messages.push(message);
+        }
+        assert!(messages.iter().any(|m| matches!(m, Message::ShowAlert(title, _) if title == "Not permitted")));
+        assert!(!messages.iter().any(|m| matches!(m, Message::ShowSessions { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_show_sessions_with_permission_navigates() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let client = create_boundary_client();
+        let mut sut = TargetsPage::new(create_parent_scope_with_session_list(), msg_tx, Arc::new(client), MockRememberUserInput::default()).await;
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(create_targets())).await;
+        let show_sessions = sut.table_page.actions.iter().find(|a| a.name == "Show Sessions").unwrap();
+        assert!((show_sessions.enabled)(sut.table_page.selected_item().as_deref()), "Show Sessions should be enabled");
+
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('C')))).await;
+        let mut messages = Vec::new();
+        while let Ok(message) = msg_rx.try_recv() {
+            messages.push(message);
+        }
+        assert!(messages.iter().any(|m| matches!(m, Message::ShowSessions { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_reload_forgets_the_known_targets() {
+        let (msg_tx, _msg_rx) = tokio::sync::mpsc::channel(10);
+        let client = create_boundary_client();
+        let known = KnownItems::default();
+        known.targets.set(create_targets());
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(client), MockRememberUserInput::default())
+            .await
+            .with_known_items(&known);
+
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('r')))).await;
+        assert!(known.targets.get().is_none(), "Targets added meanwhile should be found");
+    }
+
+    #[tokio::test]
+    async fn test_sort_by_last_connected() {
+        let (msg_tx, _msg_rx) = tokio::sync::mpsc::channel(10);
+        let client = create_boundary_client();
+        let mut remember_user_input = MockRememberUserInput::default();
+        remember_user_input
+            .store_last_connected("target-2".to_string(), chrono::Utc::now())
+            .unwrap();
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(client), remember_user_input).await;
+        let mut targets = create_targets();
+        let mut second_target = targets[0].clone();
+        second_target.id = "target-2".to_string();
+        second_target.name = "target 2".to_string();
+        targets.push(second_target);
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(targets)).await;
+        assert_eq!(sut.table_page.selected_item().unwrap().id, "target-1");
+
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('s')))).await;
+        assert_eq!(sut.table_page.selected_item().unwrap().id, "target-2", "The last connected target should be first");
+
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('s')))).await;
+        assert_eq!(sut.table_page.selected_item().unwrap().id, "target-1", "Toggling again should restore the name order");
+    }
+
+    #[tokio::test]
+    async fn test_selected_target_after_load() {
+        let (msg_tx, _msg_rx) = tokio::sync::mpsc::channel(10);
+        let client = create_boundary_client();
+        let remember_user_input = MockRememberUserInput::default();
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(client), remember_user_input)
+            .await
+            .with_selected_target(Some("target-2".to_string()));
+        let mut targets = create_targets();
+        let mut second_target = targets[0].clone();
+        second_target.id = "target-2".to_string();
+        second_target.name = "target 2".to_string();
+        targets.push(second_target);
+
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(targets.clone())).await;
+        assert_eq!(sut.table_page.selected_item().unwrap().id, "target-2");
+
+        // Only the first load selects it, reloads keep what the user selected
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Up))).await;
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(targets)).await;
+        assert_eq!(sut.table_page.selected_item().unwrap().id, "target-1");
+    }
+
+    #[tokio::test]
+    async fn test_filter_by_target_type() {
+        let (msg_tx, _msg_rx) = tokio::sync::mpsc::channel(10);
+        let client = create_boundary_client();
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(client), MockRememberUserInput::default()).await;
+        let mut targets = create_targets();
+        targets[0].type_name = "tcp".to_string();
+        let mut ssh_target = targets[0].clone();
+        ssh_target.id = "target-2".to_string();
+        ssh_target.name = "target 2".to_string();
+        ssh_target.type_name = "ssh".to_string();
+        targets.push(ssh_target);
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(targets)).await;
+
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('t')))).await;
+        assert!(sut.type_dialog.is_some(), "Type dialog should be open");
+        // Types are listed alphabetically: ssh, tcp
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Enter))).await;
+        assert!(sut.type_dialog.is_none(), "Type dialog should be closed after selecting a type");
+        assert_eq!(sut.table_page.visible_items.len(), 1);
+        assert_eq!(sut.table_page.selected_item().unwrap().id, "target-2");
+    }
+
+    #[tokio::test]
+    async fn test_picked_host_is_connected_to() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let client = create_boundary_client();
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(client), MockRememberUserInput::default()).await;
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(create_targets())).await;
+        let host = |id: &str, name: &str| Host {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: "".to_string(),
+            attributes: None,
+            ip_addresses: vec![],
+        };
+
+        sut.handle_message(TargetsPageMessage::HostsLoaded {
+            target_id: "target-1".to_string(),
+            port: 8080,
+            keep_alive: true,
+            hosts: vec![host("hst_2", "web 2"), host("hst_1", "web 1")],
+        }).await;
+        assert!(sut.host_dialog.is_some(), "Host dialog should be open");
+        // Hosts are listed by name: web 1, web 2
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Down))).await;
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Enter))).await;
+        assert!(sut.host_dialog.is_none(), "Host dialog should be closed after picking a host");
+
+        let mut connect = None;
+        while let Ok(message) = msg_rx.try_recv() {
+            if let Message::Connect { target_id, host_id, port, keep_alive } = message {
+                connect = Some((target_id, host_id, port, keep_alive));
+            }
+        }
+        assert_eq!(
+            connect,
+            Some(("target-1".to_string(), Some("hst_2".to_string()), 8080, true))
+        );
+    }
+
+    fn port_column_value(sut: &TargetsPage<Arc<boundary::MockClient>, MockRememberUserInput>, target: &Target) -> String {
+        let column = sut.table_page.columns.iter().find(|c| c.header == "Port").unwrap();
+        (column.get_value)(target)
+    }
+
+    #[tokio::test]
+    async fn test_port_column_shows_remembered_port() {
+        let (msg_tx, _msg_rx) = tokio::sync::mpsc::channel(10);
+        let client = create_boundary_client();
+        let mut remember_user_input = MockRememberUserInput::default();
+        remember_user_input.store_local_port("target-1".to_string(), 5432).unwrap();
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(client), remember_user_input).await;
+        let mut targets = create_targets();
+        let mut second_target = targets[0].clone();
+        second_target.id = "target-2".to_string();
+        targets.push(second_target.clone());
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(targets.clone())).await;
+        assert_eq!(port_column_value(&sut, &targets[0]), "5432");
+        assert_eq!(port_column_value(&sut, &second_target), "");
+    }
+
+    #[tokio::test]
+    async fn test_port_column_is_refreshed_after_connect() {
+        let (msg_tx, _msg_rx) = tokio::sync::mpsc::channel(10);
+        let client = create_boundary_client();
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(client), MockRememberUserInput::default()).await;
+        let targets = create_targets();
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(targets.clone())).await;
+        assert_eq!(port_column_value(&sut, &targets[0]), "");
+
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('c')))).await;
+        for c in "8080".chars() {
+            sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char(c)))).await;
+        }
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Tab))).await;
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Right))).await;
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Enter))).await;
+        assert!(sut.last_connected.borrow().is_empty(), "Not connected yet");
+        sut.handle_message(TargetsPageMessage::ConnectedToTarget(ConnectResponse {
+            credentials: vec![],
+            session_id: "session-1".to_string(),
+            expiration: chrono::Utc::now(),
+        })).await;
+        assert_eq!(port_column_value(&sut, &targets[0]), "8080");
+        assert!(sut.last_connected.borrow().contains_key(&targets[0].id));
+    }
+
+    #[tokio::test]
+    async fn test_connect_copies_local_address_if_enabled() {
+        for enabled in [false, true] {
+            let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+            let client = create_boundary_client();
+            let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(client), MockRememberUserInput::default())
+                .await
+                .with_copy_address_on_connect(enabled);
+            sut.handle_message(TargetsPageMessage::TargetsLoaded(create_targets())).await;
+            sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('c')))).await;
+            for c in "8080".chars() {
+                sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char(c)))).await;
+            }
+            sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Tab))).await;
+            sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Right))).await;
+            sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Enter))).await;
+            while msg_rx.try_recv().is_ok() {}
+
+            sut.handle_message(TargetsPageMessage::ConnectedToTarget(ConnectResponse {
+                credentials: vec![],
+                session_id: "session-1".to_string(),
+                expiration: chrono::Utc::now(),
+            })).await;
+            let mut copied = vec![];
+            while let Ok(message) = msg_rx.try_recv() {
+                if let Message::SetClipboard { text, .. } = message {
+                    copied.push(text);
+                }
+            }
+            let expected = if enabled { vec!["127.0.0.1:8080".to_string()] } else { vec![] };
+            assert_eq!(copied, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_single_credential_is_copied_instead_of_showing_the_dialog() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(create_boundary_client()), MockRememberUserInput::default())
+            .await
+            .with_copy_address_on_connect(true)
+            .with_copy_single_password_on_connect(true);
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(create_targets())).await;
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('c')))).await;
+        for c in "8080".chars() {
+            sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char(c)))).await;
+        }
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Tab))).await;
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Right))).await;
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Enter))).await;
+        while msg_rx.try_recv().is_ok() {}
+
+        sut.handle_message(TargetsPageMessage::ConnectedToTarget(ConnectResponse {
+            credentials: vec![credential_entry("admin", "secret")],
+            session_id: "session-1".to_string(),
+            expiration: chrono::Utc::now(),
+        })).await;
+
+        let mut copied = vec![];
+        while let Ok(message) = msg_rx.try_recv() {
+            if let Message::SetClipboard { text, .. } = message {
+                copied.push(text);
+            }
+        }
+        assert_eq!(copied, vec!["secret".to_string()], "The password takes the clipboard");
+        assert!(sut.connect_result_dialog.is_none(), "The dialog should be skipped");
+    }
+
+    #[tokio::test]
+    async fn test_several_credentials_still_show_the_dialog() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(create_boundary_client()), MockRememberUserInput::default())
+            .await
+            .with_copy_single_password_on_connect(true);
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(create_targets())).await;
+        while msg_rx.try_recv().is_ok() {}
+
+        sut.handle_message(TargetsPageMessage::ConnectedToTarget(ConnectResponse {
+            credentials: vec![credential_entry("admin", "secret"), credential_entry("reader", "other")],
+            session_id: "session-1".to_string(),
+            expiration: chrono::Utc::now(),
+        })).await;
+
+        assert!(sut.connect_result_dialog.is_some());
+        assert!(!std::iter::from_fn(|| msg_rx.try_recv().ok()).any(|m| matches!(m, Message::SetClipboard { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_bulk_connect_reports_each_target() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let client = create_boundary_client();
+        let mut remember_user_input = MockRememberUserInput::default();
+        remember_user_input.store_local_port("target-1".to_string(), 5432).unwrap();
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(client), remember_user_input).await;
+        let mut targets = create_targets();
+        for (id, name) in [("target-2", "target 2"), ("target-3", "target 3")] {
+            let mut target = targets[0].clone();
+            target.id = id.to_string();
+            target.name = name.to_string();
+            targets.push(target);
+        }
+        targets[2].authorized_actions.clear();
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(targets)).await;
+        while msg_rx.try_recv().is_ok() {}
+
+        for _ in 0..3 {
+            sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char(' ')))).await;
+            sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Down))).await;
+        }
+        sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('b')))).await;
+
+        let connects = std::iter::from_fn(|| msg_rx.try_recv().ok())
+            .find_map(|message| match message {
+                Message::BulkConnect(connects) => Some(connects),
+                _ => None,
+            })
+            .expect("The targets should be connected at once");
+        assert_eq!(connects.len(), 2, "Only the permitted targets should be connected");
+        assert_eq!(connects[0], ("target-1".to_string(), 5432), "The remembered port should be used");
+        assert_ne!(connects[1].1, 5432);
+        assert!(sut.bulk_connect_dialog.is_none(), "The summary should wait for all results");
+
+        sut.handle_message(TargetsPageMessage::BulkConnected(vec![
+            ("target-1".to_string(), Ok("session-1".to_string())),
+            ("target-2".to_string(), Err("Port is not available".to_string())),
+        ])).await;
+
+        let rows = sut.bulk_connect_dialog.as_ref().expect("Summary should be shown").rows();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].outcome, Ok("session-1".to_string()));
+        assert_eq!(rows[1].outcome, Err("Port is not available".to_string()));
+        assert_eq!(rows[1].port, Some(connects[1].1));
+        assert!(rows[2].outcome.is_err(), "The target without permission should fail");
+        assert_eq!(rows[2].port, None);
+        let last_connected = sut.last_connected.borrow();
+        assert!(last_connected.contains_key("target-1"));
+        assert!(!last_connected.contains_key("target-2"), "Failed connects are not remembered");
+    }
+
+    #[tokio::test]
+    async fn test_empty_scope_explains_missing_targets() {
+        let (msg_tx, _msg_rx) = tokio::sync::mpsc::channel(10);
+        let mut scope = create_parent_scope();
+        scope
+            .authorized_collection_actions
+            .insert("targets".to_string(), vec!["list".to_string()]);
+        let mut sut = TargetsPage::new(scope, msg_tx, Arc::new(create_boundary_client()), MockRememberUserInput::default()).await;
+
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(vec![])).await;
+        assert_eq!(
+            sut.table_page.notice.as_deref(),
+            Some("There are no targets in scope 'Test Scope' — press r to reload")
+        );
+
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(create_targets())).await;
+        assert_eq!(sut.table_page.notice, None);
+    }
+
+    #[tokio::test]
+    async fn test_empty_scope_without_list_permission_explains_why() {
+        let (msg_tx, _msg_rx) = tokio::sync::mpsc::channel(10);
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(create_boundary_client()), MockRememberUserInput::default()).await;
+
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(vec![])).await;
+        let notice = sut.table_page.notice.clone().unwrap();
+        assert!(notice.starts_with("You are not allowed to list the targets of scope 'Test Scope'"));
+    }
+
+    async fn press(sut: &mut TargetsPage<Arc<boundary::MockClient>, MockRememberUserInput>, keys: &[KeyCode]) {
+        for key in keys {
+            sut.handle_event(&Event::Key(crossterm::event::KeyEvent::from(*key))).await;
+        }
+    }
+
+    async fn type_text(sut: &mut TargetsPage<Arc<boundary::MockClient>, MockRememberUserInput>, text: &str) {
+        for c in text.chars() {
+            press(sut, &[KeyCode::Char(c)]).await;
+        }
+    }
+
+    fn sent_connect(msg_rx: &mut tokio::sync::mpsc::Receiver<Message>) -> Option<(String, u16)> {
+        let mut connect = None;
+        while let Ok(message) = msg_rx.try_recv() {
+            if let Message::Connect { target_id, port, .. } = message {
+                connect = Some((target_id, port));
+            }
+        }
+        connect
+    }
+
+    async fn connect_on_port_2222(production_targets: Vec<String>) -> (
+        TargetsPage<Arc<boundary::MockClient>, MockRememberUserInput>,
+        tokio::sync::mpsc::Receiver<Message>,
+    ) {
+        let (msg_tx, msg_rx) = tokio::sync::mpsc::channel(10);
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(create_boundary_client()), MockRememberUserInput::default())
+            .await
+            .with_production_targets(production_targets);
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(create_targets())).await;
+        press(&mut sut, &[KeyCode::Char('c')]).await;
+        type_text(&mut sut, "2222").await;
+        press(&mut sut, &[KeyCode::Tab, KeyCode::Right, KeyCode::Enter]).await;
+        (sut, msg_rx)
+    }
+
+    #[tokio::test]
+    async fn test_production_target_connects_once_its_name_is_typed() {
+        let (mut sut, mut msg_rx) = connect_on_port_2222(vec!["target 1".to_string()]).await;
+        assert!(sut.production_confirm.is_some(), "The target name should be asked for");
+        assert_eq!(sent_connect(&mut msg_rx), None);
+
+        type_text(&mut sut, "target 1").await;
+        press(&mut sut, &[KeyCode::Tab, KeyCode::Right, KeyCode::Enter]).await;
+        assert!(sut.production_confirm.is_none());
+        assert_eq!(sent_connect(&mut msg_rx), Some(("target-1".to_string(), 2222)));
+    }
+
+    #[tokio::test]
+    async fn test_production_target_is_not_connected_with_a_wrong_name() {
+        // Matching the scope name
+        let (mut sut, mut msg_rx) = connect_on_port_2222(vec!["Test *".to_string()]).await;
+        type_text(&mut sut, "target").await;
+        press(&mut sut, &[KeyCode::Tab, KeyCode::Right, KeyCode::Enter]).await;
+        assert!(sut.production_confirm.is_some(), "The name should be asked for again");
+        assert_eq!(sent_connect(&mut msg_rx), None);
+
+        press(&mut sut, &[KeyCode::Esc]).await;
+        assert!(sut.production_confirm.is_none());
+        assert_eq!(sent_connect(&mut msg_rx), None);
+    }
+
+    #[tokio::test]
+    async fn test_production_target_is_tested_once_its_name_is_typed() {
+        let (msg_tx, mut msg_rx) = tokio::sync::mpsc::channel(10);
+        let mut sut = TargetsPage::new(create_parent_scope(), msg_tx, Arc::new(create_boundary_client()), MockRememberUserInput::default())
+            .await
+            .with_production_targets(vec!["target *".to_string()]);
+        sut.handle_message(TargetsPageMessage::TargetsLoaded(create_targets())).await;
+        let tested = |msg_rx: &mut tokio::sync::mpsc::Receiver<Message>| {
+            std::iter::from_fn(|| msg_rx.try_recv().ok())
+                .any(|m| matches!(m, Message::TestConnection { target_id } if target_id == "target-1"))
+        };
+
+        press(&mut sut, &[KeyCode::Char('v')]).await;
+        assert!(sut.production_confirm.is_some(), "The target name should be asked for");
+        assert!(!tested(&mut msg_rx), "Not tested before the name is typed");
+
+        type_text(&mut sut, "target 1").await;
+        press(&mut sut, &[KeyCode::Tab, KeyCode::Right, KeyCode::Enter]).await;
+        assert!(sut.production_confirm.is_none());
+        assert!(tested(&mut msg_rx));
+        assert_eq!(sent_connect(&mut msg_rx), None);
+    }
+
+    #[tokio::test]
+    async fn test_other_targets_connect_without_confirmation() {
+        let (sut, mut msg_rx) = connect_on_port_2222(vec!["prod-*".to_string()]).await;
+        assert!(sut.production_confirm.is_none());
+        assert_eq!(sent_connect(&mut msg_rx), Some(("target-1".to_string(), 2222)));
+    }
+}
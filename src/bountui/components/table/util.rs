@@ -1,6 +1,56 @@
+use crate::bountui::Message;
+use chrono::TimeDelta;
+use tokio::sync::mpsc;
+
 pub fn format_title_with_parent(title: &str, parent: Option<&str>) -> String {
     match parent {
         None => title.to_string(),
         Some(parent) => format!("{}({})", title, parent),
     }
 }
+
+/// Resize callback for `TablePage::enable_column_resizing` that has the app store the widths
+/// under `page`.
+pub fn columns_resized_sender(
+    page: &'static str,
+    message_tx: mpsc::Sender<Message>,
+) -> Box<dyn Fn(Vec<u16>)> {
+    Box::new(move |widths| {
+        let _ = message_tx.try_send(Message::ColumnsResized {
+            page: page.to_string(),
+            widths,
+        });
+    })
+}
+
+/// Address a port connected by bountui listens on.
+pub fn local_address(port: u16) -> String {
+    format!("127.0.0.1:{port}")
+}
+
+/// Formats the time left until something expires, e.g. `1h 05m` or `4m 09s`.
+pub fn format_remaining(remaining: TimeDelta) -> String {
+    let seconds = remaining.num_seconds();
+    if seconds <= 0 {
+        return "expired".to_string();
+    }
+    let (hours, minutes, seconds) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}h {minutes:02}m")
+    } else {
+        format!("{minutes}m {seconds:02}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_remaining() {
+        assert_eq!(format_remaining(TimeDelta::seconds(8 * 3600 + 5 * 60 + 7)), "8h 05m");
+        assert_eq!(format_remaining(TimeDelta::seconds(4 * 60 + 9)), "4m 09s");
+        assert_eq!(format_remaining(TimeDelta::seconds(0)), "expired");
+        assert_eq!(format_remaining(TimeDelta::seconds(-30)), "expired");
+    }
+}
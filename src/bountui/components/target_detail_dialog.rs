@@ -2,6 +2,7 @@ use crate::boundary;
 use crate::bountui::components::table::{Action, FilterItems, SortItems, TableColumn};
 use crate::bountui::components::TablePage;
 use crate::bountui::Message;
+use crate::util::MpscSenderExt;
 use crossterm::event::{Event, KeyCode, KeyModifiers};
 use ratatui::layout::{Constraint, Flex};
 use ratatui::prelude::{Alignment, Stylize};
@@ -31,8 +32,12 @@ pub struct TargetDetailDialog {
 }
 
 impl TargetDetailDialog {
-    pub fn new(target: &boundary::Target, message_tx: mpsc::Sender<Message>) -> Self {
-        let rows = vec![
+    pub fn new(
+        target: &boundary::Target,
+        note: Option<String>,
+        message_tx: mpsc::Sender<Message>,
+    ) -> Self {
+        let mut rows = vec![
             TargetDetailRow::new("Name", &target.name),
             TargetDetailRow::new("Description", &target.description),
             TargetDetailRow::new("Type", &target.type_name),
@@ -54,6 +59,9 @@ impl TargetDetailDialog {
                 },
             ),
         ];
+        if let Some(note) = note {
+            rows.push(TargetDetailRow::new("Note", note));
+        }
 
         let columns = vec![
             TableColumn::new(
@@ -86,7 +94,6 @@ impl TargetDetailDialog {
             columns,
             rows,
             actions,
-            message_tx.clone(),
             false,
         );
 
@@ -135,22 +142,12 @@ impl TargetDetailDialog {
         if let Some(row) = self.table.selected_item() {
             let value = row.value.clone();
             let label = row.label.clone();
-            let _ = self
+            self
                 .message_tx
-                .send(Message::SetClipboard {
+                .send_or_log(Message::SetClipboard {
                     text: value,
-                    on_success: Some(Box::new(Message::Toaster(
-                        crate::bountui::components::toaster::Message::ShowToast {
-                            text: format!("{label} copied"),
-                            duration: std::time::Duration::from_secs(3),
-                        },
-                    ))),
-                    on_error: Some(Box::new(Message::Toaster(
-                        crate::bountui::components::toaster::Message::ShowToast {
-                            text: "Failed to copy".to_string(),
-                            duration: std::time::Duration::from_secs(3),
-                        },
-                    ))),
+                    on_success: Some(Box::new(Message::toast(format!("{label} copied")))),
+                    on_error: Some(Box::new(Message::toast("Failed to copy"))),
                 })
                 .await;
         }
@@ -0,0 +1,118 @@
+use crate::boundary;
+use crate::bountui::components::table::{Action, FilterItems, SortItems, TableColumn};
+use crate::bountui::components::TablePage;
+use crossterm::event::{Event, KeyCode};
+use ratatui::layout::{Constraint, Flex};
+use ratatui::prelude::{Alignment, Stylize};
+use ratatui::widgets::{Block, BorderType, Borders, Clear};
+use ratatui::Frame;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+#[derive(Clone)]
+struct TargetTypeRow {
+    type_name: String,
+    count: usize,
+}
+
+/// Lists the distinct target types with their number of targets to pick one as filter.
+pub struct TargetTypeDialog {
+    table: TablePage<TargetTypeRow>,
+}
+
+impl TargetTypeDialog {
+    pub fn new<'a>(
+        targets: impl IntoIterator<Item = &'a boundary::Target>,
+    ) -> Self {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for target in targets {
+            *counts.entry(target.type_name.clone()).or_default() += 1;
+        }
+        let rows = counts
+            .into_iter()
+            .map(|(type_name, count)| TargetTypeRow { type_name, count })
+            .collect();
+
+        let columns = vec![
+            TableColumn::new(
+                "Type".to_string(),
+                Constraint::Ratio(2, 3),
+                Box::new(|r: &TargetTypeRow| r.type_name.clone()),
+            ),
+            TableColumn::new(
+                "Targets".to_string(),
+                Constraint::Ratio(1, 3),
+                Box::new(|r: &TargetTypeRow| r.count.to_string()),
+            ),
+        ];
+
+        let actions = vec![
+            Action::new(
+                "Close".to_string(),
+                "ESC".to_string(),
+                Box::new(|_: Option<&TargetTypeRow>| true),
+            ),
+            Action::new(
+                "Filter".to_string(),
+                "⏎".to_string(),
+                Box::new(|item: Option<&TargetTypeRow>| item.is_some()),
+            ),
+        ];
+
+        let table = TablePage::new(
+            "Target Types".to_string(),
+            columns,
+            rows,
+            actions,
+            false,
+        );
+
+        Self { table }
+    }
+
+    pub fn view(&self, frame: &mut Frame) {
+        let area = frame.area();
+        let vertical =
+            ratatui::layout::Layout::vertical([Constraint::Percentage(40)]).flex(Flex::Center);
+        let horizontal =
+            ratatui::layout::Layout::horizontal([Constraint::Percentage(40)]).flex(Flex::Center);
+        let [area] = vertical.areas(area);
+        let [area] = horizontal.areas(area);
+
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .light_blue()
+            .on_black();
+
+        let inner_area = block.inner(area);
+        frame.render_widget(block, area);
+        self.table.view(frame, inner_area);
+    }
+
+    /// Returns the selected type once the user confirmed it with Enter.
+    pub async fn handle_event(&mut self, event: &Event) -> Option<String> {
+        if let Event::Key(key_event) = event {
+            if key_event.code == KeyCode::Enter {
+                return self.table.selected_item().map(|r| r.type_name.clone());
+            }
+        }
+        self.table.handle_event(event).await;
+        None
+    }
+}
+
+impl SortItems<TargetTypeRow> for TablePage<TargetTypeRow> {
+    fn sort(_: &mut Vec<Rc<TargetTypeRow>>) {
+        // Already ordered by type name
+    }
+}
+
+impl FilterItems<TargetTypeRow> for TablePage<TargetTypeRow> {
+    fn matches(item: &TargetTypeRow, search: &str) -> bool {
+        Self::match_str(&item.type_name, search)
+    }
+}
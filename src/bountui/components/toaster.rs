@@ -1,7 +1,11 @@
 use crate::bountui::widgets;
+use crate::util::MpscSenderExt;
 use ratatui::layout::{Constraint, Rect};
 use ratatui::Frame;
 
+/// How long the toasts of the app are shown.
+pub const DEFAULT_DURATION: std::time::Duration = std::time::Duration::from_secs(3);
+
 #[derive(Debug, Clone)]
 pub struct Toast {
     pub id: String,
@@ -99,8 +103,8 @@ impl Toaster {
         let message_tx = self.message_tx.clone();
         tokio::spawn(async move {
             tokio::time::sleep(duration).await;
-            let _ = message_tx
-                .send(crate::bountui::Message::Toaster(Message::HideToast { id: toast_id }))
+            message_tx
+                .send_or_log(crate::bountui::Message::Toaster(Message::HideToast { id: toast_id }))
                 .await;
         });
     }
@@ -1,4 +1,5 @@
 use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use std::mem;
 
 pub fn center(area: Rect, horizontal: Constraint, vertical: Constraint) -> Rect {
     let [area] = Layout::horizontal([horizontal])
@@ -6,4 +7,76 @@ pub fn center(area: Rect, horizontal: Constraint, vertical: Constraint) -> Rect
         .areas(area);
     let [area] = Layout::vertical([vertical]).flex(Flex::Center).areas(area);
     area
-}
\ No newline at end of file
+}
+const BREADCRUMB_SEPARATOR: &str = " › ";
+
+/// Joins the scope names to a trail of at most `width` characters. Scopes after the first are
+/// left out from the middle first, the current scope is cut only if nothing else fits.
+pub fn breadcrumb_trail(names: &[String], width: usize) -> String {
+    let Some((first, rest)) = names.split_first() else {
+        return String::new();
+    };
+    let mut trail = names.join(BREADCRUMB_SEPARATOR);
+    for skipped in 1..rest.len() {
+        if trail.chars().count() <= width {
+            return trail;
+        }
+        trail = [first.as_str(), "…"]
+            .into_iter()
+            .chain(rest[skipped..].iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(BREADCRUMB_SEPARATOR);
+    }
+    let chars: Vec<char> = trail.chars().collect();
+    if chars.len() <= width {
+        return trail;
+    }
+    let head = width.saturating_sub(1) / 2;
+    let tail = width.saturating_sub(1) - head;
+    chars[..head]
+        .iter()
+        .chain(std::iter::once(&'…'))
+        .chain(chars[chars.len() - tail..].iter())
+        .collect()
+}
+
+/// Wraps the text at word boundaries into lines of at most `width` characters, keeping its line
+/// breaks. Words longer than a line are split, and if more than `max_lines` lines are needed the
+/// last one shown ends with "…".
+pub fn wrap_text(text: &str, width: usize, max_lines: usize) -> Vec<String> {
+    if width == 0 {
+        return Vec::new();
+    }
+    let mut lines = Vec::new();
+    for paragraph in text.trim().lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            let line_len = line.chars().count();
+            if line_len > 0 && line_len + 1 + word.len() <= width {
+                line.push(' ');
+                line.extend(word);
+                continue;
+            }
+            if line_len > 0 {
+                lines.push(mem::take(&mut line));
+            }
+            while word.len() > width {
+                lines.push(word.drain(..width).collect());
+            }
+            line = word.into_iter().collect();
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+    }
+    if lines.len() > max_lines {
+        lines.truncate(max_lines);
+        if let Some(last) = lines.last_mut() {
+            let mut chars: Vec<char> = last.chars().collect();
+            chars.truncate(width - 1);
+            *last = chars.into_iter().chain(std::iter::once('…')).collect();
+        }
+    }
+    lines
+}
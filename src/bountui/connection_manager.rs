@@ -14,22 +14,71 @@ use tokio_util::sync::CancellationToken;
 pub enum ConnectionError {
     #[error("Boundary error: {0:?}")]
     BoundaryError(#[from] boundary::Error),
-    #[error("Failed to stop the connection: The session id '{0}' is unknown")]
-    StopFailedUnknownSessionId(String),
+    #[error("The session id '{0}' is unknown")]
+    UnknownSessionId(String),
 }
 
 struct ConnectionEntry {
     cancellation_token: CancellationToken,
     join_handle: JoinHandle<()>,
     credentials: Option<Vec<boundary::CredentialEntry>>,
+    port: u16,
+    // Chosen by the user to tell connections apart, kept when a keep alive connection reconnects
+    label: Option<String>,
+    // Connected to again by `reconnect`
+    target_id: String,
+    host_id: Option<String>,
+    keep_alive: bool,
+}
+
+/// Target, host and port to reconnect to when the session of a keep alive connection expires.
+struct KeepAlive<C> {
+    boundary_client: C,
+    target_id: String,
+    host_id: Option<String>,
+    port: u16,
+}
+
+impl<C: ApiClient> KeepAlive<C> {
+    async fn reconnect(&self) -> Result<(boundary::ConnectResponse, C::ConnectionHandle), boundary::Error> {
+        match self.boundary_client.connect(&self.target_id, self.host_id.as_deref(), self.port).await {
+            Err(boundary::Error::ApiError(401, _)) => {
+                info!("Auth token expired while reconnecting, authenticating again");
+                // The client runs its commands with the new token from now on
+                self.boundary_client.authenticate().await?;
+                self.boundary_client.connect(&self.target_id, self.host_id.as_deref(), self.port).await
+            }
+            result => result,
+        }
+    }
 }
 
 #[cfg_attr(test, mockall::automock)]
-pub trait ConnectionManager {
-    fn connect(&self, target_id: &str, port: u16) -> impl Future<Output=Result<boundary::ConnectResponse, boundary::Error>>;
-    fn shutdown(&self) -> impl Future<Output=Result<(), Vec<ConnectionError>>>;
-    fn stop(&self, id: &str) -> impl Future<Output=Result<(), ConnectionError>>;
+pub trait ConnectionManager: Send + Sync + 'static {
+    /// Connects to the target, to the host with `host_id` if the target has several. With
+    /// `keep_alive` the connection is re-established on the same port when the Boundary session
+    /// expires instead of being torn down.
+    // The named lifetime is needed by mockall for the reference inside the `Option`
+    #[allow(clippy::needless_lifetimes)]
+    fn connect<'a>(&self, target_id: &str, host_id: Option<&'a str>, port: u16, keep_alive: bool) -> impl Future<Output=Result<boundary::ConnectResponse, boundary::Error>> + Send;
+    fn shutdown(&self) -> impl Future<Output=Result<(), Vec<ConnectionError>>> + Send;
+    fn stop(&self, id: &str) -> impl Future<Output=Result<(), ConnectionError>> + Send;
     fn get_credentials(&self) -> HashMap<String, Vec<boundary::CredentialEntry>>;
+    /// Returns the credentials of the connect response of the session, cached while it is
+    /// connected, `None` for unknown sessions and sessions connected without credentials.
+    fn get_session_credentials(&self, id: &str) -> Option<Vec<boundary::CredentialEntry>>;
+    /// Returns the local proxy port of every connection by session id.
+    fn get_local_ports(&self) -> HashMap<String, u16>;
+    /// Sets or, with `None`, removes the label of the connection with the given session id.
+    fn set_label(&self, id: &str, label: Option<String>) -> Result<(), ConnectionError>;
+    /// Returns the label of every labeled connection by session id.
+    fn get_labels(&self) -> HashMap<String, String>;
+    /// Returns the session ids of the connections whose Boundary session ended or is gone, e.g.
+    /// while the computer was asleep. The local proxy keeps listening on the port after that.
+    fn find_dead_connections(&self) -> impl Future<Output=Vec<String>> + Send;
+    /// Replaces the connection with the session id by a new one to the same target and port,
+    /// keeping its label.
+    fn reconnect(&self, id: &str) -> impl Future<Output=Result<boundary::ConnectResponse, ConnectionError>> + Send;
 }
 
 pub struct DefaultConnectionManager<C> {
@@ -58,32 +107,63 @@ impl<C> DefaultConnectionManager<C> {
         }
     }
 
-    fn spawn_connection_task<H>(connections: Arc<Mutex<HashMap<String, ConnectionEntry>>>, mut connection_handle: H, cancellation_token: CancellationToken, expiration_time: DateTime<Utc>, session_id: String) -> JoinHandle<()>
+    fn spawn_connection_task(connections: Arc<Mutex<HashMap<String, ConnectionEntry>>>, mut connection_handle: C::ConnectionHandle, cancellation_token: CancellationToken, mut expiration_time: DateTime<Utc>, mut session_id: String, keep_alive: Option<KeepAlive<C>>) -> JoinHandle<()>
     where
-        H: BoundaryConnectionHandle + 'static,
+        C: ApiClient + Send + Sync + 'static,
+        C::ConnectionHandle: 'static,
     {
         tokio::spawn(async move {
-            let stop_result = select! {
-                    _ = cancellation_token.cancelled() =>  {
-                        info!("Session was cancelled via cancellation token");
-                        connection_handle.stop().await
-                    },
-                    _ = connection_handle.wait() =>  {
-                        info!("Connection handle was stopped via connection handle");
-                        Ok(())
-                    },
-                    _ = Self::wait_until_session_is_expired(expiration_time)  => {
-                        info!("Boundary session expired");
-                        connection_handle.stop().await
-                    },
+            loop {
+                let (stop_result, expired) = select! {
+                        _ = cancellation_token.cancelled() =>  {
+                            info!("Session was cancelled via cancellation token");
+                            (connection_handle.stop().await, false)
+                        },
+                        _ = connection_handle.wait() =>  {
+                            info!("Connection handle was stopped via connection handle");
+                            (Ok(()), false)
+                        },
+                        _ = Self::wait_until_session_is_expired(expiration_time)  => {
+                            info!("Boundary session expired");
+                            (connection_handle.stop().await, true)
+                        },
+                    };
+                if let Err(e) = stop_result {
+                    error!("Connection handle was stopped with and error {:?}", e)
+                }
+                let Some(keep_alive) = keep_alive.as_ref().filter(|_| expired) else {
+                    break;
                 };
-            if let Err(e) = stop_result {
-                error!("Connection handle was stopped with and error {:?}", e)
+                match keep_alive.reconnect().await {
+                    Ok((response, new_connection_handle)) => {
+                        info!("Reconnected expired session {} as {}", session_id, response.session_id);
+                        let mut connections = connections.lock().unwrap();
+                        if let Some(mut entry) = connections.remove(&session_id) {
+                            entry.credentials = Self::credentials_of(&response);
+                            connections.insert(response.session_id.clone(), entry);
+                        }
+                        connection_handle = new_connection_handle;
+                        expiration_time = response.expiration;
+                        session_id = response.session_id;
+                    }
+                    Err(e) => {
+                        error!("Failed to reconnect expired session {}: {:?}", session_id, e);
+                        break;
+                    }
+                }
             }
             connections.lock().unwrap().remove(&session_id);
         })
     }
 
+    fn credentials_of(response: &boundary::ConnectResponse) -> Option<Vec<boundary::CredentialEntry>> {
+        if response.credentials.is_empty() {
+            None
+        } else {
+            Some(response.credentials.clone())
+        }
+    }
+
     async fn stop_connection_entry(&self, id: &str, connection_entry: ConnectionEntry) -> Result<(), ConnectionError>
     where
         C: ApiClient,
@@ -97,26 +177,40 @@ impl<C> DefaultConnectionManager<C> {
 
 impl<C> ConnectionManager for DefaultConnectionManager<C>
 where
-    C: boundary::ApiClient,
+    C: boundary::ApiClient + Clone + Send + Sync + 'static,
     C::ConnectionHandle: 'static,
 {
     async fn connect(
         &self,
         target_id: &str,
+        host_id: Option<&str>,
         port: u16,
+        keep_alive: bool,
     ) -> Result<boundary::ConnectResponse, boundary::Error>
 
     {
         let (response, connection_handle) =
-            self.boundary_client.connect(&target_id, port).await?;
+            self.boundary_client.connect(&target_id, host_id, port).await?;
         let cancellation_token = CancellationToken::new();
-        let join_handle = Self::spawn_connection_task(self.connections.clone(), connection_handle, cancellation_token.clone(), response.expiration, response.session_id.clone());
-        let credentials = if response.credentials.is_empty() {
-            None
-        } else {
-            Some(response.credentials.clone())
-        };
-        self.connections.lock().unwrap().insert(response.session_id.clone(), ConnectionEntry { cancellation_token, join_handle, credentials });
+        let keep_alive_enabled = keep_alive;
+        let keep_alive = keep_alive.then(|| KeepAlive {
+            boundary_client: self.boundary_client.clone(),
+            target_id: target_id.to_string(),
+            host_id: host_id.map(str::to_string),
+            port,
+        });
+        let join_handle = Self::spawn_connection_task(self.connections.clone(), connection_handle, cancellation_token.clone(), response.expiration, response.session_id.clone(), keep_alive);
+        let credentials = Self::credentials_of(&response);
+        self.connections.lock().unwrap().insert(response.session_id.clone(), ConnectionEntry {
+            cancellation_token,
+            join_handle,
+            credentials,
+            port,
+            label: None,
+            target_id: target_id.to_string(),
+            host_id: host_id.map(str::to_string),
+            keep_alive: keep_alive_enabled,
+        });
         Ok(response)
     }
 
@@ -145,7 +239,7 @@ where
     {
         let connection_entry = self.connections.lock().unwrap()
             .remove(id)
-            .ok_or(ConnectionError::StopFailedUnknownSessionId(id.to_string()))?;
+            .ok_or(ConnectionError::UnknownSessionId(id.to_string()))?;
         self.stop_connection_entry(id, connection_entry).await
     }
 
@@ -157,11 +251,80 @@ where
             })
             .collect()
     }
+
+    fn get_session_credentials(&self, id: &str) -> Option<Vec<boundary::CredentialEntry>> {
+        self.connections.lock().unwrap()
+            .get(id)
+            .and_then(|entry| entry.credentials.clone())
+    }
+
+    fn get_local_ports(&self) -> HashMap<String, u16> {
+        self.connections.lock().unwrap()
+            .iter()
+            .map(|(id, entry)| (id.clone(), entry.port))
+            .collect()
+    }
+
+    fn set_label(&self, id: &str, label: Option<String>) -> Result<(), ConnectionError> {
+        let mut connections = self.connections.lock().unwrap();
+        let entry = connections
+            .get_mut(id)
+            .ok_or(ConnectionError::UnknownSessionId(id.to_string()))?;
+        entry.label = label;
+        Ok(())
+    }
+
+    fn get_labels(&self) -> HashMap<String, String> {
+        self.connections.lock().unwrap()
+            .iter()
+            .filter_map(|(id, entry)| entry.label.as_ref().map(|label| (id.clone(), label.clone())))
+            .collect()
+    }
+
+    async fn find_dead_connections(&self) -> Vec<String> {
+        let ids: Vec<String> = self.get_local_ports().into_keys().collect();
+        let checks = ids.into_iter().map(|id| async move {
+            let dead = match self.boundary_client.get_session(&id).await {
+                Ok(session) => session.is_ended(),
+                Err(e) if e.is_not_found() => true,
+                Err(e) => {
+                    // Unknown is not dead, reconnecting would end a session that may still work
+                    error!("Failed to read session {} to check it: {}", id, e);
+                    false
+                }
+            };
+            (id, dead)
+        });
+        join_all(checks)
+            .await
+            .into_iter()
+            .filter(|(_, dead)| *dead)
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    async fn reconnect(&self, id: &str) -> Result<boundary::ConnectResponse, ConnectionError> {
+        let entry = self.connections.lock().unwrap()
+            .remove(id)
+            .ok_or(ConnectionError::UnknownSessionId(id.to_string()))?;
+        // The session is usually gone already, the local proxy is stopped either way to free the port
+        if let Err(e) = self.boundary_client.cancel_session(id).await {
+            info!("Could not cancel the dead session {} before reconnecting: {:?}", id, e);
+        }
+        entry.cancellation_token.cancel();
+        let _ = entry.join_handle.await;
+        let response = self.connect(&entry.target_id, entry.host_id.as_deref(), entry.port, entry.keep_alive).await?;
+        if let Some(new_entry) = self.connections.lock().unwrap().get_mut(&response.session_id) {
+            new_entry.label = entry.label;
+        }
+        Ok(response)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::boundary;
+    use crate::boundary::fixtures::credential_entry;
     use crate::boundary::{Scope, Target};
     use crate::bountui::connection_manager::{ConnectionManager, DefaultConnectionManager};
     use chrono::TimeDelta;
@@ -173,6 +336,10 @@ mod tests {
     const SCOPE_ID: &str = "scope-1";
 
     fn create_boundary_client() -> boundary::MockClient {
+        create_boundary_client_with_credentials(vec![])
+    }
+
+    fn create_boundary_client_with_credentials(credentials: Vec<boundary::CredentialEntry>) -> boundary::MockClient {
         let mut scopes = HashMap::new();
         scopes.insert(None, vec![Scope {
             id: SCOPE_ID.to_string(),
@@ -180,6 +347,7 @@ mod tests {
             description: "scope 1".to_string(),
             type_name: "".to_string(),
             authorized_collection_actions: Default::default(),
+            scope_id: "".to_string(),
         }]);
 
         let mut targets = HashMap::new();
@@ -198,6 +366,7 @@ mod tests {
             .session_lifetime(TimeDelta::hours(8))
             .scopes(scopes)
             .targets(targets)
+            .credentials(credentials)
             .build()
     }
 
@@ -205,7 +374,7 @@ mod tests {
     async fn test_connection_is_closed_after_sessions_is_expired() {
         let boundary_client = create_boundary_client();
         let sut = DefaultConnectionManager::new(boundary_client.clone());
-        let connect_response = sut.connect(TARGET_ID, 8080).await.unwrap();
+        let connect_response = sut.connect(TARGET_ID, None, 8080, false).await.unwrap();
         tokio::time::sleep(TimeDelta::hours(8).add(TimeDelta::minutes(1)).to_std().unwrap()).await;
         let connection_handle = boundary_client.get_connection_handle(&connect_response.session_id).await.unwrap();
         assert!(connection_handle.is_stopped(), "The connection handle should be stopped after the session is expired");
@@ -217,7 +386,7 @@ mod tests {
     async fn test_connection_is_not_closed_before_session_is_expired() {
         let boundary_client = create_boundary_client();
         let sut = DefaultConnectionManager::new(boundary_client.clone());
-        let connect_response = sut.connect(TARGET_ID, 8080).await.unwrap();
+        let connect_response = sut.connect(TARGET_ID, None, 8080, false).await.unwrap();
         tokio::time::sleep(Duration::from_secs(5)).await;
         let connection_handle = boundary_client.get_connection_handle(&connect_response.session_id).await.unwrap();
         assert!(!connection_handle.is_stopped(), "The connection handle should not be stopped before the session is expired");
@@ -228,7 +397,7 @@ mod tests {
         let boundary_client = create_boundary_client();
         let sut = DefaultConnectionManager::new(boundary_client.clone());
         let resp = sut
-            .connect(TARGET_ID, 8080)
+            .connect(TARGET_ID, None, 8080, false)
             .await
             .expect("Should be able to connect to target");
         tokio::time::sleep(Duration::from_secs(5)).await;
@@ -244,9 +413,9 @@ mod tests {
         let boundary_client = create_boundary_client();
         let sut = DefaultConnectionManager::new(boundary_client.clone());
 
-        let connect_response_1 = sut.connect(TARGET_ID, 8080).await.expect("Should be able to connect to target");
-        let connect_response_2 = sut.connect(TARGET_ID, 8081).await.expect("Should be able to connect to target");
-        let connect_response_3 = sut.connect(TARGET_ID, 8082).await.expect("Should be able to connect to target");
+        let connect_response_1 = sut.connect(TARGET_ID, None, 8080, false).await.expect("Should be able to connect to target");
+        let connect_response_2 = sut.connect(TARGET_ID, None, 8081, false).await.expect("Should be able to connect to target");
+        let connect_response_3 = sut.connect(TARGET_ID, None, 8082, false).await.expect("Should be able to connect to target");
 
         tokio::time::sleep(Duration::from_secs(5)).await;
         sut.shutdown().await.expect("Shutdown should succeed");
@@ -259,4 +428,82 @@ mod tests {
         assert!(connection_handle_2.is_stopped(), "The connection handle should stop");
         assert!(connection_handle_3.is_stopped(), "The connection handle should stop");
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_keep_alive_connection_is_reconnected_after_session_is_expired() {
+        let boundary_client = create_boundary_client();
+        let sut = DefaultConnectionManager::new(boundary_client.clone());
+        let connect_response = sut.connect(TARGET_ID, None, 8080, true).await.unwrap();
+        tokio::time::sleep(TimeDelta::hours(8).add(TimeDelta::minutes(1)).to_std().unwrap()).await;
+
+        let connection_handle = boundary_client.get_connection_handle(&connect_response.session_id).await.unwrap();
+        assert!(connection_handle.is_stopped(), "The expired connection handle should be stopped");
+        let session_ids: Vec<String> = sut.connections.lock().unwrap().keys().cloned().collect();
+        assert_eq!(session_ids.len(), 1, "The connection should have been re-established");
+        assert_ne!(session_ids[0], connect_response.session_id, "The connection should use a new session");
+        let new_connection_handle = boundary_client.get_connection_handle(&session_ids[0]).await.unwrap();
+        assert!(!new_connection_handle.is_stopped(), "The new connection handle should be running");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_label_connection() {
+        let boundary_client = create_boundary_client();
+        let sut = DefaultConnectionManager::new(boundary_client.clone());
+        let connect_response = sut.connect(TARGET_ID, None, 8080, false).await.unwrap();
+        assert!(sut.get_labels().is_empty());
+
+        sut.set_label(&connect_response.session_id, Some("migration".to_string())).unwrap();
+        assert_eq!(sut.get_labels(), HashMap::from([(connect_response.session_id.clone(), "migration".to_string())]));
+
+        sut.set_label(&connect_response.session_id, None).unwrap();
+        assert!(sut.get_labels().is_empty(), "The label should be removed");
+        assert!(sut.set_label("unknown", Some("migration".to_string())).is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_label_is_kept_after_reconnect() {
+        let boundary_client = create_boundary_client();
+        let sut = DefaultConnectionManager::new(boundary_client.clone());
+        let connect_response = sut.connect(TARGET_ID, None, 8080, true).await.unwrap();
+        sut.set_label(&connect_response.session_id, Some("migration".to_string())).unwrap();
+        tokio::time::sleep(TimeDelta::hours(8).add(TimeDelta::minutes(1)).to_std().unwrap()).await;
+
+        let labels: Vec<String> = sut.get_labels().into_values().collect();
+        assert_eq!(labels, vec!["migration".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_dead_connection_is_detected_and_reconnected() {
+        let boundary_client = create_boundary_client();
+        let sut = DefaultConnectionManager::new(boundary_client.clone());
+        let dead_port = 8081;
+        sut.connect(TARGET_ID, None, 8080, false).await.unwrap();
+        let dead = sut.connect(TARGET_ID, None, dead_port, false).await.unwrap();
+        sut.set_label(&dead.session_id, Some("db".to_string())).unwrap();
+        boundary_client.set_session_status(&dead.session_id, "terminated").await;
+
+        assert_eq!(sut.find_dead_connections().await, vec![dead.session_id.clone()]);
+
+        let reconnected = sut.reconnect(&dead.session_id).await.expect("Should reconnect");
+        assert_ne!(reconnected.session_id, dead.session_id, "The connection should use a new session");
+        let old_handle = boundary_client.get_connection_handle(&dead.session_id).await.unwrap();
+        assert!(old_handle.is_stopped(), "The dead connection should be stopped");
+        assert_eq!(sut.get_local_ports().get(&reconnected.session_id), Some(&dead_port));
+        assert_eq!(sut.get_labels().get(&reconnected.session_id), Some(&"db".to_string()));
+        assert_eq!(sut.get_local_ports().len(), 2);
+        assert!(sut.reconnect(&dead.session_id).await.is_err(), "The old session should be gone");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_credentials_are_cached_by_session_id() {
+        let credentials = vec![credential_entry("admin", "secret")];
+        let boundary_client = create_boundary_client_with_credentials(credentials.clone());
+        let sut = DefaultConnectionManager::new(boundary_client);
+        let connect_response = sut.connect(TARGET_ID, None, 8080, false).await.unwrap();
+
+        assert_eq!(sut.get_session_credentials(&connect_response.session_id), Some(credentials));
+        assert_eq!(sut.get_session_credentials("unknown"), None);
+        sut.stop(&connect_response.session_id).await.unwrap();
+        assert_eq!(sut.get_session_credentials(&connect_response.session_id), None, "Forgotten once stopped");
+    }
 }
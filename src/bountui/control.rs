@@ -0,0 +1,256 @@
+use crate::bountui::Message;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+
+/// A request read from the control socket, one JSON object per line, e.g.
+/// `{"method": "connect", "target_id": "ttcp_1234567890", "port": 5432}`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum ControlRequest {
+    /// Lists the connections made by bountui.
+    ListConnections,
+    /// Connects to the target, on the remembered local port of the target or a free one if no
+    /// port is given.
+    Connect {
+        target_id: String,
+        port: Option<u16>,
+    },
+    /// Stops the connection of the session.
+    Stop { session_id: String },
+}
+
+/// The answer to a request, written back as one JSON line, e.g. `{"ok":true,"result":[]}`.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ControlResponse {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ControlResponse {
+    pub fn success<T: Serialize>(result: T) -> Self {
+        match serde_json::to_value(result) {
+            Ok(result) => ControlResponse {
+                ok: true,
+                result: Some(result),
+                error: None,
+            },
+            Err(e) => ControlResponse::failure(format!("Failed to encode the result: {e}")),
+        }
+    }
+
+    pub fn failure<E: Into<String>>(error: E) -> Self {
+        ControlResponse {
+            ok: false,
+            result: None,
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// A connection made by bountui as listed by `list_connections`.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ControlConnection {
+    pub session_id: String,
+    pub port: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// Parses a request line and passes it to the app as `Message::Control`, returning its answer.
+pub async fn dispatch(line: &str, message_tx: &mpsc::Sender<Message>) -> ControlResponse {
+    let request = match serde_json::from_str::<ControlRequest>(line) {
+        Ok(request) => request,
+        Err(e) => return ControlResponse::failure(format!("Invalid request: {e}")),
+    };
+    let (respond_to, response) = oneshot::channel();
+    if message_tx
+        .send(Message::Control { request, respond_to })
+        .await
+        .is_err()
+    {
+        return ControlResponse::failure("bountui is shutting down");
+    }
+    response
+        .await
+        .unwrap_or_else(|_| ControlResponse::failure("bountui is shutting down"))
+}
+
+/// Listens on the Unix socket at `path`, answering the requests of each client line by line. A
+/// socket file left behind by a bountui that is no longer running is replaced.
+#[cfg(unix)]
+pub fn listen(
+    path: &std::path::Path,
+    message_tx: mpsc::Sender<Message>,
+) -> std::io::Result<tokio::task::JoinHandle<()>> {
+    use log::{error, info};
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    if path.exists() {
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AddrInUse,
+                format!("{} is used by another process", path.display()),
+            ));
+        }
+        std::fs::remove_file(path)?;
+    }
+    let listener = tokio::net::UnixListener::bind(path)?;
+    // Whoever can connect can open sessions with the token of the user
+    if let Err(e) = std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600)) {
+        let _ = std::fs::remove_file(path);
+        return Err(e);
+    }
+    info!("Listening for control requests on {}", path.display());
+    Ok(tokio::spawn(async move {
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    error!("Failed to accept a control connection: {e}");
+                    continue;
+                }
+            };
+            let message_tx = message_tx.clone();
+            tokio::spawn(async move {
+                let (reader, mut writer) = stream.into_split();
+                let mut lines = BufReader::new(reader).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let response = dispatch(&line, &message_tx).await;
+                    let mut answer = serde_json::to_string(&response)
+                        .unwrap_or_else(|e| format!(r#"{{"ok":false,"error":"{e}"}}"#));
+                    answer.push('\n');
+                    if writer.write_all(answer.as_bytes()).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<ControlRequest, serde_json::Error> {
+        serde_json::from_str(line)
+    }
+
+    // Answers the control messages the way the app would, with the request echoed back
+    fn echo_app() -> mpsc::Sender<Message> {
+        let (tx, mut rx) = mpsc::channel(1);
+        tokio::spawn(async move {
+            while let Some(message) = rx.recv().await {
+                if let Message::Control { request, respond_to } = message {
+                    let _ = respond_to.send(ControlResponse::success(format!("{request:?}")));
+                }
+            }
+        });
+        tx
+    }
+
+    #[test]
+    fn requests_are_parsed_by_method() {
+        assert_eq!(
+            parse(r#"{"method": "list_connections"}"#).unwrap(),
+            ControlRequest::ListConnections
+        );
+        assert_eq!(
+            parse(r#"{"method": "connect", "target_id": "ttcp_1", "port": 5432}"#).unwrap(),
+            ControlRequest::Connect {
+                target_id: "ttcp_1".to_string(),
+                port: Some(5432)
+            }
+        );
+        assert_eq!(
+            parse(r#"{"method": "connect", "target_id": "ttcp_1"}"#).unwrap(),
+            ControlRequest::Connect {
+                target_id: "ttcp_1".to_string(),
+                port: None
+            }
+        );
+        assert_eq!(
+            parse(r#"{"method": "stop", "session_id": "s_1"}"#).unwrap(),
+            ControlRequest::Stop {
+                session_id: "s_1".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_requests_are_rejected() {
+        assert!(parse(r#"{"method": "reboot"}"#).is_err());
+        assert!(parse(r#"{"method": "stop"}"#).is_err());
+        assert!(parse(r#"{"method": "connect", "target_id": "ttcp_1", "port": 70000}"#).is_err());
+        assert!(parse("list_connections").is_err());
+    }
+
+    #[test]
+    fn responses_leave_out_what_is_not_set() {
+        let success = serde_json::to_string(&ControlResponse::success(vec![1, 2])).unwrap();
+        assert_eq!(success, r#"{"ok":true,"result":[1,2]}"#);
+        let failure = serde_json::to_string(&ControlResponse::failure("unknown session")).unwrap();
+        assert_eq!(failure, r#"{"ok":false,"error":"unknown session"}"#);
+    }
+
+    #[tokio::test]
+    async fn requests_are_dispatched_to_the_app() {
+        let response = dispatch(r#"{"method": "stop", "session_id": "s_1"}"#, &echo_app()).await;
+        assert_eq!(
+            response,
+            ControlResponse::success(r#"Stop { session_id: "s_1" }"#)
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_requests_are_answered_without_the_app() {
+        let (tx, _rx) = mpsc::channel(1);
+        let response = dispatch("{", &tx).await;
+        assert!(!response.ok);
+        assert!(response.error.unwrap().starts_with("Invalid request"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn socket_answers_each_request_line() {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bountui.sock");
+        let _listener = listen(&path, echo_app()).unwrap();
+
+        let stream = tokio::net::UnixStream::connect(&path).await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        writer
+            .write_all(b"{\"method\": \"list_connections\"}\n\n{\"method\": \"nope\"}\n")
+            .await
+            .unwrap();
+        let mut lines = BufReader::new(reader).lines();
+        assert_eq!(
+            lines.next_line().await.unwrap().unwrap(),
+            r#"{"ok":true,"result":"ListConnections"}"#
+        );
+        assert!(lines.next_line().await.unwrap().unwrap().starts_with(r#"{"ok":false"#));
+        assert!(listen(&path, echo_app()).is_err(), "The socket is in use");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn socket_is_only_accessible_by_the_user() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bountui.sock");
+        let _listener = listen(&path, echo_app()).unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
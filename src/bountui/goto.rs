@@ -0,0 +1,283 @@
+use crate::boundary::{ApiClient, Error, Target};
+use crate::bountui::components::rank;
+use crate::bountui::Message;
+
+// Id prefixes of the items `goto` can jump to
+const SCOPE_PREFIXES: [&str; 2] = ["o_", "p_"];
+const TARGET_PREFIXES: [&str; 2] = ["ttcp_", "tssh_"];
+const SESSION_PREFIXES: [&str; 1] = ["s_"];
+
+/// An id pasted after `goto` in the navigation input, told apart by its prefix.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GotoId {
+    Scope(String),
+    Target(String),
+    Session(String),
+}
+
+impl GotoId {
+    /// Routes the id by its prefix, `None` if no kind of item uses the prefix.
+    pub fn parse(id: &str) -> Option<GotoId> {
+        let has_prefix = |prefixes: &[&str]| prefixes.iter().any(|p| id.starts_with(p));
+        if has_prefix(&SCOPE_PREFIXES) {
+            Some(GotoId::Scope(id.to_string()))
+        } else if has_prefix(&TARGET_PREFIXES) {
+            Some(GotoId::Target(id.to_string()))
+        } else if has_prefix(&SESSION_PREFIXES) {
+            Some(GotoId::Session(id.to_string()))
+        } else {
+            None
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        match self {
+            GotoId::Scope(id) | GotoId::Target(id) | GotoId::Session(id) => id,
+        }
+    }
+}
+
+/// Explains which ids `goto` understands.
+pub fn unknown_id_message(id: &str) -> String {
+    let prefixes: Vec<&str> = SCOPE_PREFIXES
+        .iter()
+        .chain(TARGET_PREFIXES.iter())
+        .chain(SESSION_PREFIXES.iter())
+        .copied()
+        .collect();
+    format!(
+        "'{}' is not the id of a scope, target or session, their ids start with {}",
+        id,
+        prefixes.join(", ")
+    )
+}
+
+/// Looks up the item and returns the message showing it selected on its page, or an alert if it
+/// cannot be found.
+pub async fn resolve<C: ApiClient + Sync>(client: &C, goto_id: GotoId) -> Message {
+    let result = match &goto_id {
+        GotoId::Scope(id) => resolve_scope(client, id).await,
+        GotoId::Target(id) => resolve_target(client, id).await,
+        GotoId::Session(id) => resolve_session(client, id).await,
+    };
+    match result {
+        Ok(Some(message)) => message,
+        Ok(None) => Message::ShowAlert(
+            "Not found".to_string(),
+            format!("Nothing with the id {} was found", goto_id.id()),
+        ),
+        Err(e) => Message::show_error(format!("Failed to look up {}", goto_id.id()), e),
+    }
+}
+
+/// The targets named closest to the name typed after `open`, several if they match equally well.
+/// Targets named exactly like it, ignoring case, beat all others.
+pub fn best_name_matches<'a>(targets: &'a [Target], name: &str) -> Vec<&'a Target> {
+    let exact: Vec<&Target> = targets
+        .iter()
+        .filter(|t| t.name.to_lowercase() == name.to_lowercase())
+        .collect();
+    if !exact.is_empty() {
+        return exact;
+    }
+    let ranked: Vec<(&Target, i32)> = targets
+        .iter()
+        .filter_map(|t| rank(&t.name, name).map(|rank| (t, rank)))
+        .collect();
+    let Some(best) = ranked.iter().map(|(_, rank)| *rank).max() else {
+        return vec![];
+    };
+    ranked
+        .into_iter()
+        .filter(|(_, rank)| *rank == best)
+        .map(|(target, _)| target)
+        .collect()
+}
+
+// Reading an id nothing has is not an error for `goto`, it is reported as not found
+fn found<T>(result: Result<T, Error>) -> Result<Option<T>, Error> {
+    match result {
+        Ok(item) => Ok(Some(item)),
+        Err(e) if e.is_not_found() => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+async fn resolve_scope<C: ApiClient>(
+    client: &C,
+    id: &str,
+) -> Result<Option<Message>, Error> {
+    let Some(scope) = found(client.get_scope(id).await)? else {
+        return Ok(None);
+    };
+    // Scopes directly below the global scope are listed without a parent
+    let parent = if scope.scope_id.is_empty() || scope.scope_id == "global" {
+        None
+    } else {
+        found(client.get_scope(&scope.scope_id).await)?
+    };
+    Ok(Some(Message::ShowScopes {
+        parent,
+        select_scope_id: Some(id.to_string()),
+    }))
+}
+
+async fn resolve_target<C: ApiClient>(
+    client: &C,
+    id: &str,
+) -> Result<Option<Message>, Error> {
+    Ok(found(client.get_target(id).await)?.map(|target| Message::ShowTargetsForTarget {
+        scope_id: target.scope_id,
+        target_id: target.id,
+    }))
+}
+
+async fn resolve_session<C: ApiClient>(
+    client: &C,
+    id: &str,
+) -> Result<Option<Message>, Error> {
+    let Some(session) = found(client.get_session(id).await)? else {
+        return Ok(None);
+    };
+    Ok(found(client.get_target(&session.target_id).await)?.map(|target| Message::ShowSessions {
+        scope: target.scope_id.clone(),
+        target,
+        select_session_id: Some(session.id),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::fixtures::{scope, session, target};
+    use crate::boundary::{MockClient, Target};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    fn client() -> MockClient {
+        MockClient::builder()
+            .scopes(HashMap::from([
+                (None, vec![scope("o_1", "o_1", "global")]),
+                (Some("o_1".to_string()), vec![scope("p_1", "p_1", "o_1")]),
+            ]))
+            .targets(HashMap::from([
+                (None, vec![target("ttcp_1", "ttcp_1", "p_1")]),
+                (Some("p_1".to_string()), vec![target("ttcp_1", "ttcp_1", "p_1")]),
+            ]))
+            .sessions(Arc::new(Mutex::new(HashMap::from([(
+                "p_1".to_string(),
+                vec![session("s_1", "ttcp_1", "")],
+            )]))))
+            .build()
+    }
+
+    #[test]
+    fn ids_are_routed_by_their_prefix() {
+        assert_eq!(GotoId::parse("o_123"), Some(GotoId::Scope("o_123".to_string())));
+        assert_eq!(GotoId::parse("p_123"), Some(GotoId::Scope("p_123".to_string())));
+        assert_eq!(GotoId::parse("ttcp_123"), Some(GotoId::Target("ttcp_123".to_string())));
+        assert_eq!(GotoId::parse("tssh_123"), Some(GotoId::Target("tssh_123".to_string())));
+        assert_eq!(GotoId::parse("s_123"), Some(GotoId::Session("s_123".to_string())));
+        assert_eq!(GotoId::parse("u_123"), None);
+        assert_eq!(GotoId::parse("global"), None);
+    }
+
+    #[tokio::test]
+    async fn scopes_are_shown_selected_below_their_parent() {
+        match resolve(&client(), GotoId::Scope("p_1".to_string())).await {
+            Message::ShowScopes {
+                parent,
+                select_scope_id,
+            } => {
+                assert_eq!(parent.map(|p| p.id), Some("o_1".to_string()));
+                assert_eq!(select_scope_id.as_deref(), Some("p_1"));
+            }
+            _ => panic!("Expected the scopes of o_1"),
+        }
+        match resolve(&client(), GotoId::Scope("o_1".to_string())).await {
+            Message::ShowScopes { parent, .. } => assert!(parent.is_none()),
+            _ => panic!("Expected the global scopes"),
+        }
+    }
+
+    #[tokio::test]
+    async fn targets_are_shown_in_their_scope() {
+        match resolve(&client(), GotoId::Target("ttcp_1".to_string())).await {
+            Message::ShowTargetsForTarget {
+                scope_id,
+                target_id,
+            } => {
+                assert_eq!(scope_id, "p_1");
+                assert_eq!(target_id, "ttcp_1");
+            }
+            _ => panic!("Expected the targets of p_1"),
+        }
+    }
+
+    #[tokio::test]
+    async fn sessions_are_shown_with_their_target() {
+        match resolve(&client(), GotoId::Session("s_1".to_string())).await {
+            Message::ShowSessions {
+                scope,
+                target,
+                select_session_id,
+            } => {
+                assert_eq!(scope, "p_1");
+                assert_eq!(target.id, "ttcp_1");
+                assert_eq!(select_session_id.as_deref(), Some("s_1"));
+            }
+            _ => panic!("Expected the sessions of ttcp_1"),
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_ids_are_alerted() {
+        for goto_id in [
+            GotoId::Scope("p_missing".to_string()),
+            GotoId::Target("tssh_missing".to_string()),
+            GotoId::Session("s_missing".to_string()),
+        ] {
+            match resolve(&client(), goto_id).await {
+                Message::ShowAlert(title, message) => {
+                    assert_eq!(title, "Not found");
+                    assert!(message.contains("_missing"));
+                }
+                _ => panic!("Expected an alert"),
+            }
+        }
+    }
+
+    fn ids(targets: Vec<&Target>) -> Vec<&str> {
+        targets.into_iter().map(|t| t.id.as_str()).collect()
+    }
+
+    #[test]
+    fn exact_name_beats_longer_names() {
+        let targets = vec![
+            target("ttcp_1", "db-prod-replica", "p_1"),
+            target("ttcp_2", "DB-Prod", "p_2"),
+            target("ttcp_3", "db-staging", "p_1"),
+        ];
+        assert_eq!(ids(best_name_matches(&targets, "db-prod")), vec!["ttcp_2"]);
+    }
+
+    #[test]
+    fn closest_names_match_by_abbreviation() {
+        let targets = vec![target("ttcp_1", "db-prod", "p_1"), target("ttcp_2", "web", "p_1")];
+        assert_eq!(ids(best_name_matches(&targets, "dbp")), vec!["ttcp_1"]);
+        assert!(best_name_matches(&targets, "cache").is_empty());
+    }
+
+    #[test]
+    fn equally_close_names_all_match() {
+        let targets = vec![
+            target("ttcp_1", "db-prod", "p_1"),
+            target("ttcp_2", "db-prod", "p_2"),
+            target("ttcp_3", "web", "p_1"),
+        ];
+        assert_eq!(ids(best_name_matches(&targets, "db-prod")), vec!["ttcp_1", "ttcp_2"]);
+        let targets = vec![target("ttcp_1", "db-prod-a", "p_1"), target("ttcp_2", "db-prod-b", "p_2")];
+        assert_eq!(ids(best_name_matches(&targets, "db-prod")), vec!["ttcp_1", "ttcp_2"]);
+    }
+}
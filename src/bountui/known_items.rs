@@ -0,0 +1,138 @@
+use crate::boundary::{ApiClient, ApiClientExt, Error, Scope, ScopeListing, Target};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
+
+// Filled once with the listed items
+type Cell<T> = Arc<OnceCell<Arc<Vec<T>>>>;
+
+/// Items of all scopes, listed the first time they are needed and kept until forgotten.
+pub struct Known<T> {
+    // Replaced by an empty cell to forget the items, listings still running fill the old one
+    cell: Arc<Mutex<Cell<T>>>,
+}
+
+impl<T> Clone for Known<T> {
+    fn clone(&self) -> Self {
+        Self {
+            cell: self.cell.clone(),
+        }
+    }
+}
+
+impl<T> Default for Known<T> {
+    fn default() -> Self {
+        Self {
+            cell: Arc::default(),
+        }
+    }
+}
+
+impl<T> Known<T> {
+    /// The items, listed with `list` unless they are known already.
+    pub async fn get_or_list<F, Fut>(&self, list: F) -> Result<Arc<Vec<T>>, Error>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<T>, Error>>,
+    {
+        let cell = self.current();
+        cell.get_or_try_init(|| async { list().await.map(Arc::new) })
+            .await
+            .cloned()
+    }
+
+    /// The items if they are known already.
+    pub fn get(&self) -> Option<Arc<Vec<T>>> {
+        self.current().get().cloned()
+    }
+
+    /// Keeps the items unless others were listed meanwhile.
+    pub fn set(&self, items: Vec<T>) {
+        let _ = self.current().set(Arc::new(items));
+    }
+
+    /// Forgets the items, they are listed again the next time they are needed.
+    pub fn forget(&self) {
+        *self.cell.lock().unwrap() = Arc::default();
+    }
+
+    fn current(&self) -> Cell<T> {
+        self.cell.lock().unwrap().clone()
+    }
+}
+
+/// The scopes and targets of all scopes, shared by everything that needs all of them instead of
+/// the ones of a single scope: the counts of the scopes page, the scope tree, the deep search,
+/// the palette completions and the restored location.
+#[derive(Clone, Default)]
+pub struct KnownItems {
+    pub scopes: Known<Scope>,
+    pub targets: Known<Target>,
+}
+
+impl KnownItems {
+    pub async fn scopes<C: ApiClient>(&self, client: &C) -> Result<Arc<Vec<Scope>>, Error> {
+        self.scopes.get_or_list(|| client.get_scopes(None, true)).await
+    }
+
+    pub async fn targets<C: ApiClient>(&self, client: &C) -> Result<Arc<Vec<Target>>, Error> {
+        self.targets.get_or_list(|| client.get_targets(None)).await
+    }
+
+    /// Lists the scopes level by level reporting the progress, see
+    /// [`ApiClientExt::get_scopes_with_progress`], unless they are known already. Only complete
+    /// listings are kept, the scopes that failed are tried again the next time.
+    pub async fn scopes_with_progress<C: ApiClient + Sync>(
+        &self,
+        client: &C,
+        progress: tokio::sync::mpsc::UnboundedSender<usize>,
+    ) -> Result<ScopeListing, Error> {
+        if let Some(scopes) = self.scopes.get() {
+            let _ = progress.send(scopes.len());
+            return Ok(ScopeListing {
+                scopes: scopes.to_vec(),
+                ..ScopeListing::default()
+            });
+        }
+        let listing = client.get_scopes_with_progress(None, progress).await?;
+        if listing.failed_scopes.is_empty() {
+            self.scopes.set(listing.scopes.clone());
+        }
+        Ok(listing)
+    }
+
+    /// Forgets the scopes and targets, e.g. when a page is reloaded to see changes.
+    pub fn forget(&self) {
+        self.scopes.forget();
+        self.targets.forget();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn items_are_listed_once_until_forgotten() {
+        let known: Known<u32> = Known::default();
+        let listed = known.get_or_list(|| async { Ok(vec![1]) }).await.unwrap();
+        assert_eq!(*listed, vec![1]);
+        let listed = known.get_or_list(|| async { Ok(vec![2]) }).await.unwrap();
+        assert_eq!(*listed, vec![1], "Listed again");
+
+        known.forget();
+        assert!(known.get().is_none());
+        let listed = known.get_or_list(|| async { Ok(vec![2]) }).await.unwrap();
+        assert_eq!(*listed, vec![2]);
+    }
+
+    #[tokio::test]
+    async fn failed_listings_are_not_kept() {
+        let known: Known<u32> = Known::default();
+        let failed = known
+            .get_or_list(|| async { Err(Error::ApiError(500, "failed".to_string())) })
+            .await;
+        assert!(failed.is_err());
+        assert!(known.get().is_none());
+    }
+}
@@ -0,0 +1,104 @@
+use crate::boundary::{Scope, ScopeRules};
+use serde::{Deserialize, Serialize};
+
+/// The scopes leading to the last shown scopes or targets page, restored on startup.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct LastLocation {
+    /// Ids of the scopes below the global scope, from the top down to the shown one.
+    pub scope_ids: Vec<String>,
+    /// Whether the targets of the last scope were shown instead of its child scopes.
+    #[serde(default)]
+    pub targets: bool,
+}
+
+impl LastLocation {
+    /// Looks up the scopes of the location in all scopes. `None` if the location is the global
+    /// scope or one of its scopes no longer exists, is restricted or cannot be opened anymore.
+    pub fn resolve(&self, scopes: &[Scope], rules: &ScopeRules) -> Option<Vec<Scope>> {
+        let mut parent_id = "global";
+        let mut resolved = Vec::new();
+        for (index, id) in self.scope_ids.iter().enumerate() {
+            let scope = scopes
+                .iter()
+                .find(|s| &s.id == id && s.scope_id == parent_id)?;
+            let shows_targets = self.targets && index == self.scope_ids.len() - 1;
+            let can_open = if shows_targets {
+                scope.can_list_targets()
+            } else {
+                scope.can_list_child_scopes()
+            };
+            if !can_open || rules.is_restricted(scope) {
+                return None;
+            }
+            parent_id = &scope.id;
+            resolved.push(scope.clone());
+        }
+        (!resolved.is_empty()).then_some(resolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::fixtures::{listing, scope};
+
+    fn all_scopes() -> Vec<Scope> {
+        vec![
+            listing(scope("o_1", "o_1", "global"), "scopes"),
+            listing(scope("p_1", "p_1", "o_1"), "scopes"),
+            listing(scope("p_2", "p_2", "p_1"), "targets"),
+        ]
+    }
+
+    fn location(scope_ids: &[&str], targets: bool) -> LastLocation {
+        LastLocation {
+            scope_ids: scope_ids.iter().map(|id| id.to_string()).collect(),
+            targets,
+        }
+    }
+
+    fn ids(scopes: Option<Vec<Scope>>) -> Option<Vec<String>> {
+        scopes.map(|scopes| scopes.into_iter().map(|s| s.id).collect())
+    }
+
+    #[test]
+    fn location_is_resolved_from_the_top_down() {
+        let resolved = location(&["o_1", "p_1", "p_2"], true).resolve(&all_scopes(), &ScopeRules::default());
+        assert_eq!(ids(resolved), Some(vec!["o_1".to_string(), "p_1".to_string(), "p_2".to_string()]));
+
+        let resolved = location(&["o_1", "p_1"], false).resolve(&all_scopes(), &ScopeRules::default());
+        assert_eq!(ids(resolved), Some(vec!["o_1".to_string(), "p_1".to_string()]));
+    }
+
+    #[test]
+    fn deleted_intermediate_scope_is_not_resolved() {
+        let scopes: Vec<Scope> = all_scopes().into_iter().filter(|s| s.id != "p_1").collect();
+        assert_eq!(location(&["o_1", "p_1", "p_2"], true).resolve(&scopes, &ScopeRules::default()), None);
+    }
+
+    #[test]
+    fn scope_moved_below_another_parent_is_not_resolved() {
+        let mut scopes = all_scopes();
+        scopes.push(listing(scope("o_2", "o_2", "global"), "scopes"));
+        assert_eq!(location(&["o_2", "p_1"], false).resolve(&scopes, &ScopeRules::default()), None);
+    }
+
+    #[test]
+    fn scopes_that_cannot_be_opened_are_not_resolved() {
+        assert_eq!(
+            location(&["o_1", "p_1", "p_2"], false).resolve(&all_scopes(), &ScopeRules::default()),
+            None,
+            "The child scopes of p_2 cannot be listed"
+        );
+        let rules = ScopeRules {
+            restricted: vec!["p_1".to_string()],
+            ..ScopeRules::default()
+        };
+        assert_eq!(location(&["o_1", "p_1", "p_2"], true).resolve(&all_scopes(), &rules), None);
+    }
+
+    #[test]
+    fn global_location_is_not_resolved() {
+        assert_eq!(location(&[], false).resolve(&all_scopes(), &ScopeRules::default()), None);
+    }
+}
@@ -1,9 +1,41 @@
 use crate::boundary;
-use crate::bountui::Message;
-use std::marker::PhantomData;
+use crate::bountui::components::{Button, InputDialog, InputField};
+use crate::bountui::{widgets, Message};
+use crate::event_ext::EventExt;
+use crate::util::MpscSenderExt;
+use crossterm::event::Event;
+use ratatui::layout::Rect;
+use ratatui::Frame;
+
+#[derive(Debug)]
+pub enum LoginPageMessage {
+    /// `boundary authenticate` wanted to prompt for credentials, they are asked for in a dialog
+    /// instead. The reason is set if the credentials typed before were not accepted.
+    CredentialsRequired(Option<String>),
+}
+
+impl From<LoginPageMessage> for Message {
+    fn from(value: LoginPageMessage) -> Self {
+        Message::Login(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CredentialsDialogFields {
+    LoginName,
+    Password,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CredentialsDialogButtons {
+    Cancel,
+    Login,
+}
 
 pub struct LoginPage<C: boundary::ApiClient + Clone + Send + Sync + 'static> {
-    _client: PhantomData<C>,
+    boundary_client: C,
+    message_tx: tokio::sync::mpsc::Sender<Message>,
+    credentials_dialog: Option<InputDialog<CredentialsDialogFields, CredentialsDialogButtons>>,
 }
 
 impl<C> LoginPage<C>
@@ -11,15 +43,42 @@ where
     C: boundary::ApiClient + Clone + Send + Sync + 'static,
 {
     pub fn new(boundary_client: C, message_tx: tokio::sync::mpsc::Sender<Message>) -> Self {
+        let login_page = Self {
+            boundary_client,
+            message_tx,
+            credentials_dialog: None,
+        };
+        login_page.authenticate(None);
+        login_page
+    }
+
+    /// Runs `boundary authenticate` in the background, with the login name and password typed
+    /// into the credentials dialog if set.
+    fn authenticate(&self, credentials: Option<(String, String)>) {
+        let boundary_client = self.boundary_client.clone();
+        let message_tx = self.message_tx.clone();
         tokio::spawn(async move {
-            match boundary_client.authenticate().await {
+            let result = match &credentials {
+                Some((login_name, password)) => {
+                    boundary_client.authenticate_with_password(login_name, password).await
+                }
+                None => boundary_client.authenticate().await,
+            };
+            match result {
                 Ok(auth_response) => {
-                    let _ = message_tx.send(Message::Authenticated(auth_response)).await;
+                    message_tx.send_or_log(Message::Authenticated(auth_response)).await;
+                }
+                Err(e) if credentials.is_some() || e.needs_credentials() => {
+                    log::info!("Authentication needs credentials: {e}");
+                    let reason = credentials.is_some().then(|| format!("Login failed: {e}"));
+                    message_tx
+                        .send_or_log(LoginPageMessage::CredentialsRequired(reason).into())
+                        .await;
                 }
                 Err(e) => {
                     log::error!("Authentication failed: {e}");
-                    let _ = message_tx
-                        .send(Message::ShowAlert(
+                    message_tx
+                        .send_or_log(Message::ShowAlert(
                             "Authentication failed".to_string(),
                             format!("Authentication failed. Please try again.\nReason: {e}"),
                         ))
@@ -27,9 +86,155 @@ where
                 }
             }
         });
+    }
+
+    pub fn handle_message(&mut self, message: LoginPageMessage) {
+        match message {
+            LoginPageMessage::CredentialsRequired(reason) => {
+                let hint = reason
+                    .unwrap_or_else(|| "Boundary asks for your login name and password".to_string());
+                self.credentials_dialog = Some(
+                    InputDialog::new(
+                        "Login",
+                        vec![
+                            InputField::new(CredentialsDialogFields::LoginName, "Login Name", ""),
+                            InputField::new(CredentialsDialogFields::Password, "Password", "")
+                                .masked(),
+                        ],
+                        vec![
+                            Button::new(CredentialsDialogButtons::Cancel, "Cancel"),
+                            Button::new(CredentialsDialogButtons::Login, "Login"),
+                        ],
+                    )
+                    .with_hint(hint),
+                );
+            }
+        }
+    }
 
-        Self {
-            _client: PhantomData,
+    pub async fn handle_event(&mut self, event: &Event) {
+        let Some(dialog) = &mut self.credentials_dialog else {
+            return;
+        };
+        let button = if event.is_esc() {
+            Some(CredentialsDialogButtons::Cancel)
+        } else {
+            dialog.handle_event(event)
+        };
+        match button {
+            Some(CredentialsDialogButtons::Cancel) => {
+                self.credentials_dialog = None;
+                self
+                    .message_tx
+                    .send_or_log(Message::ShowAlert(
+                        "Login canceled".to_string(),
+                        "Restart bountui to log in again".to_string(),
+                    ))
+                    .await;
+            }
+            Some(CredentialsDialogButtons::Login) => {
+                let value = |field| dialog.get_value(field).unwrap_or_default().to_string();
+                let credentials = (
+                    value(CredentialsDialogFields::LoginName).trim().to_string(),
+                    value(CredentialsDialogFields::Password),
+                );
+                self.credentials_dialog = None;
+                self.authenticate(Some(credentials));
+            }
+            None => {}
+        }
+    }
+
+    pub fn view(&self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(widgets::LoginScreen, area);
+        if let Some(dialog) = &self.credentials_dialog {
+            dialog.view(frame);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::MockClient;
+    use crossterm::event::{KeyCode, KeyEvent};
+    use std::collections::HashMap;
+
+    fn client_with_password() -> MockClient {
+        MockClient::builder()
+            .user_id("u_1".to_string())
+            .scopes(HashMap::new())
+            .password("s3cret".to_string())
+            .build()
+    }
+
+    async fn next_message(message_rx: &mut tokio::sync::mpsc::Receiver<Message>) -> Message {
+        tokio::time::timeout(std::time::Duration::from_secs(1), message_rx.recv())
+            .await
+            .expect("A message should be sent")
+            .unwrap()
+    }
+
+    async fn type_credentials(page: &mut LoginPage<MockClient>, login_name: &str, password: &str) {
+        let press = |code| Event::Key(KeyEvent::from(code));
+        for c in login_name.chars() {
+            page.handle_event(&press(KeyCode::Char(c))).await;
+        }
+        page.handle_event(&press(KeyCode::Tab)).await;
+        for c in password.chars() {
+            page.handle_event(&press(KeyCode::Char(c))).await;
+        }
+        // To the buttons, then to the login button
+        page.handle_event(&press(KeyCode::Tab)).await;
+        page.handle_event(&press(KeyCode::Right)).await;
+        page.handle_event(&press(KeyCode::Enter)).await;
+    }
+
+    async fn page_asking_for_credentials() -> (LoginPage<MockClient>, tokio::sync::mpsc::Receiver<Message>) {
+        let (message_tx, mut message_rx) = tokio::sync::mpsc::channel(10);
+        let mut page = LoginPage::new(client_with_password(), message_tx);
+        match next_message(&mut message_rx).await {
+            Message::Login(message) => page.handle_message(message),
+            _ => panic!("Expected the credentials to be asked for"),
+        }
+        assert!(page.credentials_dialog.is_some());
+        (page, message_rx)
+    }
+
+    #[tokio::test]
+    async fn credentials_are_asked_for_if_authenticate_prompts() {
+        let (mut page, mut message_rx) = page_asking_for_credentials().await;
+
+        type_credentials(&mut page, "jane", "s3cret").await;
+        assert!(page.credentials_dialog.is_none());
+        match next_message(&mut message_rx).await {
+            Message::Authenticated(response) => assert_eq!(response.attributes.user_id, "u_1"),
+            _ => panic!("Expected to be authenticated"),
+        }
+    }
+
+    #[tokio::test]
+    async fn wrong_credentials_are_asked_for_again() {
+        let (mut page, mut message_rx) = page_asking_for_credentials().await;
+
+        type_credentials(&mut page, "jane", "wrong").await;
+        match next_message(&mut message_rx).await {
+            Message::Login(LoginPageMessage::CredentialsRequired(Some(reason))) => {
+                assert!(reason.starts_with("Login failed"))
+            }
+            _ => panic!("Expected the credentials to be asked for again"),
+        }
+    }
+
+    #[tokio::test]
+    async fn canceling_the_credentials_dialog_is_alerted() {
+        let (mut page, mut message_rx) = page_asking_for_credentials().await;
+
+        page.handle_event(&Event::Key(KeyEvent::from(KeyCode::Esc))).await;
+        assert!(page.credentials_dialog.is_none());
+        assert!(matches!(
+            next_message(&mut message_rx).await,
+            Message::ShowAlert(title, _) if title == "Login canceled"
+        ));
+    }
+}
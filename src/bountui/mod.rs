@@ -1,59 +1,199 @@
 use crate::boundary;
-use crate::boundary::{AuthenticateResponse, Scope, Target};
-use crate::bountui::components::table::scope::{ScopesPage, ScopesPageMessage};
+use crate::boundary::{AuthenticateResponse, Scope, ScopeRules, Target};
+use crate::bountui::components::table::scope::{restricted_message, ScopesPage, ScopesPageMessage};
+use crate::bountui::components::table::scope_tree::{ScopeTreeMessage, ScopeTreePage};
 use crate::bountui::components::table::sessions::{
     LoadTargetSessionsSessions, LoadUserSessions, SessionsPage, SessionsPageMessage,
 };
-use crate::bountui::components::table::target::{TargetsPage, TargetsPageMessage};
-use crate::bountui::components::NavigationInput;
-use crate::bountui::connection_manager::ConnectionManager;
+use crate::bountui::components::table::target::{
+    is_production_target, TargetsPage, TargetsPageMessage,
+};
+use crate::bountui::components::util::breadcrumb_trail;
+use crate::bountui::components::{
+    ArgumentCandidates, ArgumentCompleter, ConfirmDialog, NavigationInput, CONNECT, GOTO, OPEN,
+};
+use crate::bountui::alerts::Alerts;
+use crate::bountui::connection_manager::{ConnectionError, ConnectionManager};
+use crate::bountui::goto::GotoId;
+use crate::bountui::known_items::KnownItems;
+use crate::bountui::control::{ControlConnection, ControlRequest, ControlResponse};
+use crate::bountui::last_location::LastLocation;
+use crate::bountui::recent_pages::{RecentPage, RecentPages};
 use crate::bountui::loading_page::LoadingPage;
-use crate::bountui::login_page::LoginPage;
+use crate::bountui::login_page::{LoginPage, LoginPageMessage};
+use crate::config::Config;
+use crate::cross_term::{init_terminal, restore_terminal};
 use crate::event_ext::EventExt;
 use crate::util::clipboard::ClipboardAccess;
+use crate::util::MpscSenderExt;
 use crossterm::event::{Event, KeyCode};
 use futures::future::BoxFuture;
 use futures::stream::FuturesUnordered;
-use futures::StreamExt;
-use log::error;
-use ratatui::layout::Constraint;
+use futures::{FutureExt, StreamExt};
+use log::{error, info};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::Stylize;
+use ratatui::widgets::{Clear, Paragraph};
 use ratatui::Frame;
 pub use remember_user_input::*;
 use std::fmt::Display;
 use std::mem;
+use std::sync::Arc;
 use tokio::select;
 
+mod alerts;
 pub mod auth_cache;
 pub mod components;
+mod connect_command;
 pub mod connection_manager;
+pub mod control;
+pub mod goto;
+pub mod known_items;
+mod last_location;
 mod loading_page;
 mod login_page;
+pub mod recent_pages;
 mod remember_user_input;
+mod session_export;
+mod web_ui;
 mod widgets;
 
 pub use auth_cache::AuthCache;
 
+// Sessions canceled at the same time when stopping several at once
+const BULK_STOP_CONCURRENCY: usize = 4;
+
+// Pages the `recent` command lists at most
+const RECENT_PAGES: usize = 10;
+
+// How often the idle timeout is checked, bountui exits at most this much later than configured
+const IDLE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
+// First breadcrumb, the global scope has no scope of its own to take the name from
+const GLOBAL_SCOPE_NAME: &str = "global";
+const GLOBAL_SCOPE_ID: &str = "global";
+
+// A scope leading to the shown page, its id is remembered to restore the page on startup
+#[derive(Clone)]
+struct Breadcrumb {
+    scope_id: String,
+    name: String,
+}
+
+impl Breadcrumb {
+    fn global() -> Self {
+        Breadcrumb {
+            scope_id: GLOBAL_SCOPE_ID.to_string(),
+            name: GLOBAL_SCOPE_NAME.to_string(),
+        }
+    }
+
+    fn of(scope: &Scope) -> Self {
+        Breadcrumb {
+            scope_id: scope.id.clone(),
+            name: scope.name.clone(),
+        }
+    }
+}
+
 pub enum Message {
     ShowScopes {
         parent: Option<Scope>,
+        /// Scope to select once the scopes of `parent` are loaded.
+        select_scope_id: Option<String>,
     },
     ShowTargets {
         parent: Scope,
+        /// Target to select once the targets of `parent` are loaded.
+        select_target_id: Option<String>,
+    },
+    /// Replaces the targets page with the targets of a sibling scope.
+    ShowSiblingTargets {
+        parent: Scope,
+        siblings: Vec<Scope>,
+    },
+    /// Looks up the scope of a target and shows its targets with the target selected.
+    ShowTargetsForTarget {
+        scope_id: String,
+        target_id: String,
     },
     ShowSessions {
         scope: String,
         target: Target,
+        /// Session to select once the sessions of `target` are loaded.
+        select_session_id: Option<String>,
+    },
+    /// Looks up the item with the id typed after `goto` and shows it selected on its page.
+    Goto(GotoId),
+    /// Looks up the target named closest to the name typed after `open` in all scopes and shows
+    /// it selected, or lets the user pick one if several are named alike.
+    OpenTarget(String),
+    /// Lists the targets named alike in the palette to pick the one to show.
+    ShowTargetChoices(Vec<Target>),
+    /// Opens the pages of the scopes from the top down, the last one with its targets if
+    /// `targets` is set. Ignored unless the root scopes are shown.
+    RestoreLocation {
+        scopes: Vec<Scope>,
+        targets: bool,
+    },
+    /// Presses the shortcut of an action of the shown page picked in the command palette.
+    PageAction(crossterm::event::KeyEvent),
+    /// Reloads the shown page, picked as `:refresh` in the command palette.
+    RefreshPage,
+    /// Opens the pages of the scopes from the top down on top of the shown page, the last one
+    /// with its targets if `targets` is set.
+    ShowScopePath {
+        scopes: Vec<Scope>,
+        targets: bool,
     },
     Connect {
         target_id: String,
+        /// The host to connect to, for targets with several hosts Boundary cannot pick from.
+        host_id: Option<String>,
         port: u16,
+        keep_alive: bool,
+    },
+    /// Connects to the targets on the ports, by target id, in the background. The results are
+    /// reported at once as `BulkConnected` instead of opening the connection dialog or alerts.
+    BulkConnect(Vec<(String, u16)>),
+    /// Connects from the `connect` command, on the remembered local port of the target or a free
+    /// one if no port was typed.
+    ConnectById {
+        target_id: String,
+        port: Option<u16>,
+    },
+    /// Connects to the target and disconnects right away, the outcome is shown in an alert.
+    TestConnection {
+        target_id: String,
     },
     StopSession {
         session_id: String,
         notify_stopped_tx: tokio::sync::mpsc::Sender<()>,
     },
+    /// Stops all sessions, a failure does not stop the others. Reported with a single summary.
+    StopSessions {
+        session_ids: Vec<String>,
+        notify_stopped_tx: tokio::sync::mpsc::Sender<()>,
+    },
+    /// The outcome of `StopSessions`, by session id with the reason of each failure.
+    SessionsStopped(Vec<(String, Result<(), String>)>),
+    /// Copies the credentials of the connect response of the session made by bountui.
+    CopySessionCredentials {
+        session_id: String,
+    },
+    /// Labels the connection of the session, an empty label removes it.
+    SetConnectionLabel {
+        session_id: String,
+        label: String,
+    },
     GoBack,
     ShowAlert(String, String),
+    OpenInBrowser(String),
+    /// A table was resized by the user, `page` identifies the table in the config.
+    ColumnsResized {
+        page: String,
+        widths: Vec<u16>,
+    },
     SetClipboard {
         text: String,
         on_success: Option<Box<Message>>,
@@ -61,10 +201,44 @@ pub enum Message {
     },
     Targets(TargetsPageMessage),
     Scopes(ScopesPageMessage),
+    ScopeTree(ScopeTreeMessage),
     SessionsPage(SessionsPageMessage),
+    Login(LoginPageMessage),
     // Navigate root pages
     NavigateToScopeTree,
     NavigateToMySessions,
+    /// Shows the last boundary command run, offering to copy it.
+    ShowLastCommand,
+    /// Stops all connections made by bountui, the outcome is shown in a toast or an alert.
+    StopAllConnections,
+    /// Checks the sessions of the connections made by bountui and offers to reconnect the dead
+    /// ones.
+    CheckConnections,
+    /// The session ids of the dead connections `CheckConnections` found.
+    ConnectionsChecked(Vec<String>),
+    /// Replaces the connections of the sessions by new ones to the same targets and ports.
+    ReconnectConnections(Vec<String>),
+    /// Candidates loaded for completing the argument of a palette command.
+    ArgumentCandidates {
+        command: String,
+        candidates: ArgumentCandidates,
+    },
+    /// Lists the pages shown recently in the palette to jump back to one.
+    ShowRecentPages,
+    /// Shows the page picked from the recent pages again.
+    OpenRecentPage(RecentPage),
+    /// A request read from the control socket, answered through `respond_to`.
+    Control {
+        request: ControlRequest,
+        respond_to: tokio::sync::oneshot::Sender<ControlResponse>,
+    },
+    /// Stops all connections and exits once handled.
+    Quit,
+    /// Shows all scopes as a collapsible tree.
+    ShowScopeTree {
+        replace_history: bool,
+    },
+    CloseScopeTree,
     RunFuture(BoxFuture<'static, ()>),
     Toaster(components::toaster::Message),
     Authenticated(AuthenticateResponse),
@@ -81,17 +255,195 @@ impl Message {
             format!("{}: {}", message.into(), error),
         )
     }
+
+    /// Shows the text in a toast for a few seconds.
+    fn toast<T: Into<String>>(text: T) -> Message {
+        Message::Toaster(components::toaster::Message::ShowToast {
+            text: text.into(),
+            duration: components::toaster::DEFAULT_DURATION,
+        })
+    }
+
+    /// Warns about the scopes skipped while listing all scopes, with the reason of each.
+    fn scopes_failed(failed_scopes: &[(String, String)]) -> Message {
+        Message::ShowAlert("Warning".to_string(), failed_scopes_summary(failed_scopes))
+    }
+}
+
+/// Lists scopes that could not be listed in one line, e.g.
+/// "2 scopes could not be listed: p_abc (403), p_def (timeout)".
+fn failed_scopes_summary(failed_scopes: &[(String, String)]) -> String {
+    let scopes: Vec<String> = failed_scopes
+        .iter()
+        .map(|(scope_id, reason)| format!("{} ({})", scope_id, reason))
+        .collect();
+    let noun = if failed_scopes.len() == 1 { "scope" } else { "scopes" };
+    format!(
+        "{} {} could not be listed: {}",
+        failed_scopes.len(),
+        noun,
+        scopes.join(", ")
+    )
+}
+
+async fn stop_or_cancel_session<M: ConnectionManager, C: boundary::ApiClient>(
+    connection_manager: &M,
+    boundary_client: &C,
+    session_id: &str,
+) -> Result<(), ConnectionError> {
+    match connection_manager.stop(session_id).await {
+        // Sessions created outside of bountui can still be canceled through the api
+        Err(ConnectionError::UnknownSessionId(_)) => boundary_client
+            .cancel_session(session_id)
+            .await
+            .map_err(ConnectionError::from),
+        result => result,
+    }
+}
+
+/// One block per credential with its source, username and password, to paste them at once.
+fn credentials_text(credentials: &[boundary::CredentialEntry]) -> String {
+    credentials
+        .iter()
+        .map(|entry| {
+            format!(
+                "{}\nusername: {}\npassword: {}",
+                entry.credential_source.name, entry.credential.username, entry.credential.password
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Keeps the id and a short reason of each failed scope, for `Message::scopes_failed`.
+fn failure_reasons(failed_scopes: &[(String, boundary::Error)]) -> Vec<(String, String)> {
+    failed_scopes
+        .iter()
+        .map(|(scope_id, e)| (scope_id.clone(), e.short_reason()))
+        .collect()
+}
+
+/// Why the target cannot be connected to without opening its targets page first: it is a
+/// production target, which has to be confirmed there, or its scope is restricted. `None` if it
+/// can be connected to right away.
+async fn connect_refusal<C: boundary::ApiClient>(
+    boundary_client: &C,
+    target_id: &str,
+    production_targets: &[String],
+    scope_rules: &ScopeRules,
+) -> Result<Option<String>, boundary::Error> {
+    if production_targets.is_empty() && scope_rules.restricted.is_empty() {
+        return Ok(None);
+    }
+    let target = boundary_client.get_target(target_id).await?;
+    let scope = boundary_client.get_scope(&target.scope_id).await?;
+    if scope_rules.is_restricted(&scope) {
+        return Ok(Some(restricted_message(&scope)));
+    }
+    if is_production_target(production_targets, &target, &scope) {
+        return Ok(Some(format!(
+            "'{}' is a production target, connect to it from its targets page to confirm it",
+            target.name
+        )));
+    }
+    Ok(None)
+}
+
+/// Where the palette floats, centered horizontally in the upper third of the screen.
+fn nav_input_area(area: Rect, height: u16) -> Rect {
+    let height = height.min(area.height);
+    let width = (area.width * 3 / 5).max(40).min(area.width);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 4,
+        width,
+        height,
+    }
+}
+
+// Paging and the mouse wheel scroll the page while the palette is open
+fn is_scroll_event(event: &Event) -> bool {
+    match event {
+        Event::Key(key_event) => matches!(key_event.code, KeyCode::PageUp | KeyCode::PageDown),
+        Event::Mouse(mouse_event) => matches!(
+            mouse_event.kind,
+            crossterm::event::MouseEventKind::ScrollUp | crossterm::event::MouseEventKind::ScrollDown
+        ),
+        _ => false,
+    }
+}
+
+/// A local port nothing listens on right now, for connects without a port.
+fn free_port() -> std::io::Result<u16> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    Ok(listener.local_addr()?.port())
 }
 
 pub enum Page<B: boundary::ApiClient + Clone + Send + Sync + 'static, R: RememberUserInput> {
     Loading(LoadingPage),
     Login(LoginPage<B>),
-    Scopes(ScopesPage),
+    Scopes(ScopesPage<B>),
+    ScopeTree(ScopeTreePage<B>),
     Targets(TargetsPage<B, R>),
     TargetSessions(SessionsPage<LoadTargetSessionsSessions<B>>),
     UserSessions(SessionsPage<LoadUserSessions<B>>),
 }
 
+impl<B: boundary::ApiClient + Clone + Send + Sync + 'static, R: RememberUserInput> Page<B, R> {
+    /// The actions the page offers for its selected item.
+    fn enabled_actions(&self) -> Vec<components::table::EnabledAction> {
+        match self {
+            Page::Loading(_) | Page::Login(_) => vec![],
+            Page::Scopes(scopes_page) => scopes_page.enabled_actions(),
+            Page::ScopeTree(scope_tree_page) => scope_tree_page.enabled_actions(),
+            Page::Targets(targets_page) => targets_page.enabled_actions(),
+            Page::TargetSessions(sessions_page) => sessions_page.enabled_actions(),
+            Page::UserSessions(sessions_page) => sessions_page.enabled_actions(),
+        }
+    }
+
+    /// Reloads a scopes or targets page whose items were loaded more than `max_age` ago.
+    async fn reload_if_stale(&mut self, max_age: std::time::Duration) {
+        match self {
+            Page::Scopes(scopes_page) => scopes_page.reload_if_stale(max_age).await,
+            Page::Targets(targets_page) => targets_page.reload_if_stale(max_age).await,
+            Page::Loading(_)
+            | Page::Login(_)
+            | Page::ScopeTree(_)
+            | Page::TargetSessions(_)
+            | Page::UserSessions(_) => {}
+        }
+    }
+
+    /// Called when the terminal gains or loses the focus, pages that reload in the background
+    /// stop doing so while unfocused.
+    fn set_focused(&mut self, focused: bool) {
+        match self {
+            Page::TargetSessions(sessions_page) => sessions_page.set_focused(focused),
+            Page::UserSessions(sessions_page) => sessions_page.set_focused(focused),
+            Page::Loading(_)
+            | Page::Login(_)
+            | Page::Scopes(_)
+            | Page::ScopeTree(_)
+            | Page::Targets(_) => {}
+        }
+    }
+
+    /// Called when the page moves into or out of the history, pages that reload in the
+    /// background stop doing so while hidden.
+    fn set_hidden(&mut self, hidden: bool) {
+        match self {
+            Page::TargetSessions(sessions_page) => sessions_page.set_hidden(hidden),
+            Page::UserSessions(sessions_page) => sessions_page.set_hidden(hidden),
+            Page::Loading(_)
+            | Page::Login(_)
+            | Page::Scopes(_)
+            | Page::ScopeTree(_)
+            | Page::Targets(_) => {}
+        }
+    }
+}
+
 pub struct BountuiApp<
     C: boundary::ApiClient + Clone + Send + Sync + 'static,
     R: RememberUserInput + Copy,
@@ -100,8 +452,12 @@ pub struct BountuiApp<
     page: Page<C, R>,
     boundary_client: C,
     history: Vec<Page<C, R>>,
-    connection_manager: M,
-    alert: Option<(String, String)>,
+    // Scopes leading to the shown scopes or targets page
+    breadcrumbs: Vec<Breadcrumb>,
+    // Breadcrumbs of the pages in the history, restored when going back
+    breadcrumb_history: Vec<Vec<Breadcrumb>>,
+    connection_manager: Arc<M>,
+    alerts: Alerts,
     message_tx: tokio::sync::mpsc::Sender<Message>,
     message_rx: tokio::sync::mpsc::Receiver<Message>,
     cross_term_event_rx: tokio::sync::mpsc::Receiver<Event>,
@@ -112,7 +468,29 @@ pub struct BountuiApp<
     clipboard: Box<dyn ClipboardAccess>,
     toaster: components::toaster::Toaster,
     auth_cache: Box<dyn AuthCache>,
+    config: Config,
     frame_count: u64,
+    // When the user last pressed a key or used the mouse, for the idle timeout
+    last_input: tokio::time::Instant,
+    // Toggled with F1, shows the shortcuts of the current page below it
+    show_legend: bool,
+    // Whether the terminal has the focus, applied to every page that is shown
+    focused: bool,
+    // The last boundary command with the dialog offering to copy it
+    last_command: Option<(String, ConfirmDialog)>,
+    // The sessions of dead connections with the dialog offering to reconnect them
+    dead_connections: Option<(Vec<String>, ConfirmDialog)>,
+    // Set by the quit command, the run loop exits once the message was handled
+    quit: bool,
+    // The scopes and targets of all scopes, listed once to complete palette arguments, find
+    // targets by name and for the pages that show all scopes
+    known: KnownItems,
+    recent_pages: RecentPages,
+    // The shown page as it is listed in `recent_pages`, and those of the pages in `history`
+    recent_page: Option<RecentPage>,
+    recent_page_history: Vec<Option<RecentPage>>,
+    // Where other processes send control requests, see `control::listen`
+    control_socket: Option<std::path::PathBuf>,
 }
 
 impl<C, R: RememberUserInput + Copy, M> BountuiApp<C, R, M>
@@ -128,19 +506,32 @@ where
         cross_term_event_rx: tokio::sync::mpsc::Receiver<Event>,
         clipboard: Box<dyn ClipboardAccess>,
         auth_cache: Box<dyn AuthCache>,
+        config: Config,
     ) -> Self {
         let (message_tx, message_rx) = tokio::sync::mpsc::channel(64);
 
         let (page, user_id) =
             Self::resolve_initial_page(&auth_cache, &message_tx, &boundary_client);
+        let mut alerts = Alerts::default();
+        if !config.warnings().is_empty() {
+            alerts.push((
+                "Config".to_string(),
+                format!(
+                    "Some settings were ignored and use their defaults:\n{}",
+                    config.warnings().join("\n")
+                ),
+            ));
+        }
 
         BountuiApp {
             boundary_client,
             user_id,
             page,
             history: vec![],
-            connection_manager,
-            alert: None,
+            breadcrumbs: vec![],
+            breadcrumb_history: vec![],
+            connection_manager: Arc::new(connection_manager),
+            alerts,
             message_tx: message_tx.clone(),
             message_rx,
             cross_term_event_rx,
@@ -150,7 +541,19 @@ where
             clipboard,
             toaster: components::toaster::Toaster::new(message_tx),
             auth_cache,
+            config,
             frame_count: 0,
+            last_input: tokio::time::Instant::now(),
+            show_legend: false,
+            focused: true,
+            last_command: None,
+            dead_connections: None,
+            quit: false,
+            known: KnownItems::default(),
+            recent_pages: RecentPages::new(RECENT_PAGES),
+            recent_page: None,
+            recent_page_history: vec![],
+            control_socket: None,
         }
     }
 
@@ -161,9 +564,7 @@ where
     ) -> (Page<C, R>, String) {
         if let Some(cached) = auth_cache.get_cached_token() {
             let token_id = cached.token_id.clone();
-            unsafe {
-                std::env::set_var("BOUNDARY_TOKEN", &cached.token);
-            }
+            boundary_client.set_token(&cached.token);
             let user_id = cached.user_id.clone();
             let expiration_time = cached.expiration_time;
             let tx = message_tx.clone();
@@ -180,11 +581,11 @@ where
                 match client.validate_token(&token_id).await {
                     Ok(()) => {
                         log::info!("auth_cache: cached token is valid — restoring session");
-                        let _ = tx.send(Message::TokenRestored(auth_response)).await;
+                        tx.send_or_log(Message::TokenRestored(auth_response)).await;
                     }
                     Err(e) => {
                         log::warn!("auth_cache: cached token validation failed: {e} — falling back to login");
-                        let _ = tx.send(Message::TokenInvalid).await;
+                        tx.send_or_log(Message::TokenInvalid).await;
                     }
                 }
             });
@@ -198,25 +599,116 @@ where
     }
 
     pub fn navigate_to(&mut self, page: Page<C, R>, replace_history: bool) {
+        let recent_page = self.recent_page.take();
         if replace_history {
             self.history.clear();
+            self.breadcrumbs.clear();
+            self.breadcrumb_history.clear();
+            self.recent_page_history.clear();
             self.page = page;
         } else {
-            self.history.push(mem::replace(&mut self.page, page));
+            let mut previous = mem::replace(&mut self.page, page);
+            previous.set_hidden(true);
+            self.history.push(previous);
+            self.breadcrumb_history.push(self.breadcrumbs.clone());
+            self.recent_page_history.push(recent_page);
         }
+        self.apply_focus();
     }
 
-    async fn stop_session(&mut self, session_id: &str) {
-        if let Err(e) = self.connection_manager.stop(session_id).await {
+    // Pages shown while the terminal is unfocused start paused and catch up once it is focused
+    fn apply_focus(&mut self) {
+        let focused = self.focused || !self.config.pause_refresh_when_unfocused;
+        self.page.set_focused(focused);
+    }
+
+    async fn try_stop_session(&self, session_id: &str) -> Result<(), ConnectionError> {
+        stop_or_cancel_session(self.connection_manager.as_ref(), &self.boundary_client, session_id).await
+    }
+
+    async fn stop_session(&mut self, session_id: &str) -> bool {
+        if let Err(e) = self.try_stop_session(session_id).await {
             error!("Failed to stop session: {:?}", e);
             self.message_tx
-                .send(Message::show_error("Failed to stop session", e))
-                .await
-                .expect("Failed to send stop session error message");
+                .send_or_log(Message::show_error("Failed to stop session", e))
+                .await;
+            return false;
+        }
+        true
+    }
+
+    /// Stops the sessions in the background, the outcome is sent as one `SessionsStopped` once
+    /// all stops finished.
+    fn stop_sessions(&mut self, session_ids: Vec<String>, notify_stopped_tx: tokio::sync::mpsc::Sender<()>) {
+        let connection_manager = self.connection_manager.clone();
+        let boundary_client = self.boundary_client.clone();
+        let message_tx = self.message_tx.clone();
+        self.tasks.push(
+            async move {
+                let (connection_manager, boundary_client) = (&connection_manager, &boundary_client);
+                let results = futures::stream::iter(session_ids)
+                    .map(|session_id| async move {
+                        let result =
+                            stop_or_cancel_session(connection_manager.as_ref(), boundary_client, &session_id)
+                                .await
+                                .map_err(|e| {
+                                    error!("Failed to stop session {}: {:?}", session_id, e);
+                                    e.to_string()
+                                });
+                        (session_id, result)
+                    })
+                    .buffer_unordered(BULK_STOP_CONCURRENCY)
+                    .collect()
+                    .await;
+                message_tx.send_or_log(Message::SessionsStopped(results)).await;
+                notify_stopped_tx.send_or_log(()).await;
+            }
+            .boxed(),
+        );
+    }
+
+    /// Marks the stopped sessions on the shown page and sums up the outcome.
+    async fn sessions_stopped(&mut self, results: Vec<(String, Result<(), String>)>) {
+        let count = results.len();
+        let mut failures = vec![];
+        for (session_id, result) in results {
+            if let Err(e) = &result {
+                failures.push(format!("{session_id}: {e}"));
+            }
+            let message = SessionsPageMessage::StopFinished {
+                session_id,
+                stopped: result.is_ok(),
+            };
+            match &mut self.page {
+                Page::TargetSessions(sessions_page) => sessions_page.handle_message(message).await,
+                Page::UserSessions(sessions_page) => sessions_page.handle_message(message).await,
+                _ => {}
+            }
+        }
+        let summary = format!("Canceled {} of {} sessions", count - failures.len(), count);
+        if failures.is_empty() {
+            self.toaster
+                .handle_message(components::toaster::Message::ShowToast {
+                    text: summary,
+                    duration: components::toaster::DEFAULT_DURATION,
+                })
+                .await;
+        } else {
+            failures.sort();
+            self.show_alert((
+                "Cancel sessions".to_string(),
+                format!("{summary}, failed:\n{}", failures.join("\n")),
+            ));
         }
     }
 
-    async fn show_scope(&mut self, parent: Option<Scope>) {
+    // Records the shown page for the `recent` command
+    fn visit(&mut self, page: RecentPage) {
+        self.recent_pages.visit(page.clone());
+        self.recent_page = Some(page);
+    }
+
+    async fn show_scope(&mut self, parent: Option<Scope>, select_scope_id: Option<String>) {
         self.navigate_to(
             Page::Scopes(
                 ScopesPage::new(
@@ -224,24 +716,247 @@ where
                     self.message_tx.clone(),
                     self.boundary_client.clone(),
                 )
-                .await,
+                .await
+                .with_column_widths(&self.config.column_widths)
+                .with_scope_rules(&self.config.scopes)
+                .with_known_items(&self.known)
+                .with_selected_scope(select_scope_id),
             ),
             false,
         );
+        self.breadcrumbs
+            .push(parent.as_ref().map_or_else(Breadcrumb::global, Breadcrumb::of));
+        self.remember_location(false);
+        self.visit(RecentPage::Scopes(parent));
+    }
+
+    async fn targets_page(&self, parent: Scope) -> TargetsPage<C, R> {
+        TargetsPage::new(
+            parent,
+            self.message_tx.clone(),
+            self.boundary_client.clone(),
+            self.remember_user_input,
+        )
+        .await
+        .with_open_commands(self.config.open_commands.clone())
+        .with_copy_address_on_connect(self.config.copy_address_on_connect)
+        .with_copy_single_password_on_connect(self.config.copy_single_password_on_connect)
+        .with_show_sessions_after_connect(self.config.show_sessions_after_connect)
+        .with_production_targets(self.config.production_targets.clone())
+        .with_column_widths(&self.config.column_widths)
+        .with_known_items(&self.known)
+    }
+
+    async fn show_targets(&mut self, parent: Scope, select_target_id: Option<String>) {
+        let parent_breadcrumb = Breadcrumb::of(&parent);
+        // The scope tree knows the whole path of the scope
+        let tree_path = match &self.page {
+            Page::ScopeTree(scope_tree_page) => scope_tree_page
+                .selected_ids()
+                .zip(scope_tree_page.selected_path()),
+            _ => None,
+        };
+        let recent_page = RecentPage::Targets(parent.clone());
+        let page = self
+            .targets_page(parent)
+            .await
+            .with_selected_target(select_target_id);
+        self.navigate_to(Page::Targets(page), false);
+        self.visit(recent_page);
+        match tree_path {
+            Some((ids, names)) => {
+                self.breadcrumbs = vec![Breadcrumb::global()];
+                self.breadcrumbs.extend(
+                    ids.into_iter()
+                        .zip(names)
+                        .map(|(scope_id, name)| Breadcrumb { scope_id, name }),
+                );
+            }
+            None => self.breadcrumbs.push(parent_breadcrumb),
+        }
+        self.remember_location(true);
+    }
+
+    // Stores the scopes leading to the shown page, to restore it on the next start
+    fn remember_location(&mut self, targets: bool) {
+        if !self.config.restore_last_location {
+            return;
+        }
+        let location = LastLocation {
+            scope_ids: self
+                .breadcrumbs
+                .iter()
+                .filter(|b| b.scope_id != GLOBAL_SCOPE_ID)
+                .map(|b| b.scope_id.clone())
+                .collect(),
+            targets,
+        };
+        if let Err(e) = self.remember_user_input.store_last_location(location) {
+            log::warn!("Failed to store the last location: {e:#}");
+        }
+    }
+
+    /// Looks up the scopes of the location stored last and opens their pages on top of the root
+    /// scopes. The root scopes stay shown if one of the scopes cannot be opened anymore.
+    fn restore_last_location(&mut self) {
+        if !self.config.restore_last_location {
+            return;
+        }
+        let location = match self.remember_user_input.get_last_location() {
+            Ok(Some(location)) => location,
+            Ok(None) => return,
+            Err(e) => {
+                log::warn!("Failed to read the last location: {e:#}");
+                return;
+            }
+        };
+        let boundary_client = self.boundary_client.clone();
+        let message_tx = self.message_tx.clone();
+        let scope_rules = self.config.scopes.clone();
+        let known = self.known.clone();
+        self.tasks.push(
+            async move {
+                match known.scopes(&boundary_client).await {
+                    Ok(scopes) => {
+                        if let Some(scopes) = location.resolve(&scopes, &scope_rules) {
+                            message_tx
+                                .send_or_log(Message::RestoreLocation {
+                                    scopes,
+                                    targets: location.targets,
+                                })
+                                .await;
+                        }
+                    }
+                    Err(e) => log::warn!("Failed to load the scopes of the last location: {e}"),
+                }
+            }
+            .boxed(),
+        );
+    }
+
+    async fn show_location(&mut self, scopes: Vec<Scope>, targets: bool) {
+        // The user may have moved on while the scopes were loading
+        if !self.history.is_empty() || !matches!(self.page, Page::Scopes(_)) {
+            return;
+        }
+        self.show_scope_path(scopes, targets).await;
+    }
+
+    async fn show_scope_path(&mut self, mut scopes: Vec<Scope>, targets: bool) {
+        let targets_scope = if targets { scopes.pop() } else { None };
+        for scope in scopes {
+            self.show_scope(Some(scope), None).await;
+        }
+        if let Some(scope) = targets_scope {
+            self.show_targets(scope, None).await;
+        }
     }
 
-    async fn show_targets(&mut self, parent: Scope) {
+    async fn show_scope_tree(&mut self, replace_history: bool) {
+        self.navigation_input = None;
         self.navigate_to(
-            Page::Targets(
-                TargetsPage::new(
-                    parent,
+            Page::ScopeTree(
+                ScopeTreePage::new(
                     self.message_tx.clone(),
                     self.boundary_client.clone(),
-                    self.remember_user_input,
+                    self.known.clone(),
                 )
-                .await,
+                .await
+                .with_column_widths(&self.config.column_widths)
+                .with_scope_rules(&self.config.scopes),
             ),
-            false,
+            replace_history,
+        );
+    }
+
+    /// Leaves the scope tree for the scopes it was opened from, or the root scopes.
+    async fn close_scope_tree(&mut self) {
+        if self.history.is_empty() {
+            self.navigate_to_scope_tree().await;
+        } else {
+            self.go_back().await;
+        }
+    }
+
+    /// Switches to the targets of a sibling scope, going back still leads to the scopes.
+    async fn show_sibling_targets(&mut self, parent: Scope, siblings: Vec<Scope>) {
+        if self.config.scopes.is_restricted(&parent) {
+            self.show_alert(("Restricted scope".to_string(), restricted_message(&parent)));
+            return;
+        }
+        self.breadcrumbs.pop();
+        self.breadcrumbs.push(Breadcrumb::of(&parent));
+        self.remember_location(true);
+        self.visit(RecentPage::Targets(parent.clone()));
+        let page = self.targets_page(parent).await.with_siblings(Some(siblings));
+        self.page = Page::Targets(page);
+    }
+
+    fn show_targets_for_target(&mut self, scope_id: String, target_id: String) {
+        self.navigation_input = None;
+        let boundary_client = self.boundary_client.clone();
+        let message_tx = self.message_tx.clone();
+        let scope_rules = self.config.scopes.clone();
+        self.tasks.push(
+            async move {
+                let message = match boundary_client.get_scope(&scope_id).await {
+                    Ok(parent) if scope_rules.is_restricted(&parent) => Message::ShowAlert(
+                        "Restricted scope".to_string(),
+                        restricted_message(&parent),
+                    ),
+                    Ok(parent) => Message::ShowTargets {
+                        parent,
+                        select_target_id: Some(target_id),
+                    },
+                    Err(e) if e.is_not_found() => Message::ShowAlert(
+                        "Error".to_string(),
+                        format!("Scope {scope_id} of target {target_id} not found"),
+                    ),
+                    Err(e) => Message::show_error("Failed to load the scope", e),
+                };
+                message_tx.send_or_log(message).await;
+            }
+            .boxed(),
+        );
+    }
+
+    fn open_target(&mut self, name: String) {
+        self.navigation_input = None;
+        let boundary_client = self.boundary_client.clone();
+        let known = self.known.clone();
+        let message_tx = self.message_tx.clone();
+        self.tasks.push(
+            async move {
+                let message = match known.targets(&boundary_client).await {
+                    Ok(targets) => match goto::best_name_matches(&targets, &name).as_slice() {
+                        [] => Message::ShowAlert(
+                            "Not found".to_string(),
+                            format!("No target is named like '{name}'"),
+                        ),
+                        [target] => Message::ShowTargetsForTarget {
+                            scope_id: target.scope_id.clone(),
+                            target_id: target.id.clone(),
+                        },
+                        several => Message::ShowTargetChoices(several.iter().map(|t| (*t).clone()).collect()),
+                    },
+                    Err(e) => Message::show_error("Failed to list the targets", e),
+                };
+                message_tx.send_or_log(message).await;
+            }
+            .boxed(),
+        );
+    }
+
+    fn goto(&mut self, goto_id: GotoId) {
+        self.navigation_input = None;
+        let boundary_client = self.boundary_client.clone();
+        let message_tx = self.message_tx.clone();
+        self.tasks.push(
+            async move {
+                let message = goto::resolve(&boundary_client, goto_id).await;
+                message_tx.send_or_log(message).await;
+            }
+            .boxed(),
         );
     }
 
@@ -249,15 +964,22 @@ where
         self.navigation_input = None;
         self.navigate_to(
             Page::Scopes(
-                ScopesPage::new(None, self.message_tx.clone(), self.boundary_client.clone()).await,
+                ScopesPage::new(None, self.message_tx.clone(), self.boundary_client.clone())
+                    .await
+                    .with_column_widths(&self.config.column_widths)
+                    .with_scope_rules(&self.config.scopes)
+                    .with_known_items(&self.known),
             ),
             true,
         );
+        self.breadcrumbs.push(Breadcrumb::global());
+        self.visit(RecentPage::Scopes(None));
     }
 
     async fn navigate_to_my_sessions(&mut self) {
         self.navigation_input = None;
         let credentials = self.connection_manager.get_credentials();
+        let local_ports = self.connection_manager.get_local_ports();
         self.navigate_to(
             Page::UserSessions(
                 SessionsPage::new(
@@ -269,38 +991,131 @@ where
                     ),
                     self.message_tx.clone(),
                     credentials,
+                    local_ports,
+                    self.sessions_refresh_interval(),
+                    self.user_id.clone(),
                 )
-                .await,
+                .await
+                .with_column_widths(&self.config.column_widths)
+                .with_connection_labels(self.connection_manager.get_labels())
+                .with_bulk_stop(),
             ),
             true,
         );
     }
 
-    fn go_back(&mut self) {
-        if let Some(page) = self.history.pop() {
+    fn sessions_refresh_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.config.sessions_refresh_interval.max(1))
+    }
+
+    async fn go_back(&mut self) {
+        if let Some(mut page) = self.history.pop() {
+            self.breadcrumbs = self.breadcrumb_history.pop().unwrap_or_default();
+            self.recent_page = self.recent_page_history.pop().flatten();
+            page.set_hidden(false);
+            // Scopes or targets may have been added or removed while the page was hidden
+            if self.config.reload_stale_pages_after > 0 {
+                let max_age = std::time::Duration::from_secs(self.config.reload_stale_pages_after);
+                page.reload_if_stale(max_age).await;
+            }
             self.page = page;
+            self.apply_focus();
         }
     }
 
-    async fn connect(&mut self, target_id: &String, port: u16) {
-        match self.connection_manager.connect(target_id, port).await {
+    async fn connect(
+        &mut self,
+        target_id: &String,
+        host_id: Option<&str>,
+        port: u16,
+        keep_alive: bool,
+    ) {
+        let result = self
+            .connection_manager
+            .connect(target_id, host_id, port, keep_alive)
+            .await;
+        match result {
             Ok(resp) => {
                 self.message_tx
-                    .send(Message::Targets(TargetsPageMessage::ConnectedToTarget(
+                    .send_or_log(Message::Targets(TargetsPageMessage::ConnectedToTarget(
                         resp,
                     )))
-                    .await
-                    .unwrap();
+                    .await;
+            }
+            Err(e) if e.needs_host_id() && host_id.is_none() => {
+                self.load_target_hosts(target_id.clone(), port, keep_alive)
             }
             Err(e) => {
-                let _ = self
+                self
                     .message_tx
-                    .send(Message::show_error("Connection Error", e))
+                    .send_or_log(Message::show_error("Connection Error", e))
                     .await;
             }
         }
     }
 
+    /// Loads the hosts of a target Boundary could not pick a host of, for the user to pick one.
+    fn load_target_hosts(&mut self, target_id: String, port: u16, keep_alive: bool) {
+        let boundary_client = self.boundary_client.clone();
+        let message_tx = self.message_tx.clone();
+        self.tasks.push(
+            async move {
+                let message = match boundary_client.get_target_hosts(&target_id).await {
+                    Ok(hosts) => Message::Targets(TargetsPageMessage::HostsLoaded {
+                        target_id,
+                        port,
+                        keep_alive,
+                        hosts,
+                    }),
+                    Err(e) => Message::show_error(
+                        format!("Failed to load the hosts of {}", target_id),
+                        e,
+                    ),
+                };
+                message_tx.send_or_log(message).await;
+            }
+            .boxed(),
+        );
+    }
+
+    async fn test_connection(&mut self, target_id: &str) {
+        // Port 0 lets the proxy listen on any free port, nothing is meant to use it
+        let alert = match self.connection_manager.connect(target_id, None, 0, false).await {
+            Ok(response) => {
+                let mut message = format!("Connected to {target_id}");
+                match self.try_stop_session(&response.session_id).await {
+                    Ok(()) => message.push_str(" and disconnected again."),
+                    Err(e) => message.push_str(&format!(
+                        ", but stopping session {} failed: {e}",
+                        response.session_id
+                    )),
+                }
+                if !response.credentials.is_empty() {
+                    message.push_str("\nCredentials:");
+                    for entry in &response.credentials {
+                        message.push_str(&format!(
+                            "\n  {} ({})",
+                            entry.credential.username, entry.credential_source.name
+                        ));
+                    }
+                }
+                ("Connection test".to_string(), message)
+            }
+            Err(e) => (
+                "Connection test failed".to_string(),
+                format!("Could not connect to {target_id}: {e}"),
+            ),
+        };
+        self.show_alert(alert);
+    }
+
+    /// Shows the alert after those not dismissed yet, closing the palette so that the alerts
+    /// have the focus.
+    fn show_alert(&mut self, alert: (String, String)) {
+        self.navigation_input = None;
+        self.alerts.push(alert);
+    }
+
     fn handle_layout(&mut self, terminal: &mut ratatui::Terminal<impl ratatui::backend::Backend>) {
         let terminal_size = terminal.size().unwrap();
         let frame_area = ratatui::layout::Rect {
@@ -313,26 +1128,36 @@ where
     }
 
     pub fn view(&mut self, frame: &mut Frame) {
-        if let Some((title, message)) = &self.alert {
-            frame.render_widget(
-                widgets::Alert::new(title.to_string(), message.to_string()),
-                frame.area(),
-            );
+        if let (Some(title), Some((_, message))) = (self.alerts.current_title(), self.alerts.current()) {
+            frame.render_widget(widgets::Alert::new(title, message.to_string()), frame.area());
         }
 
-        let layout_constraints = match self.navigation_input {
-            Some(_) => {
-                vec![Constraint::Length(3), Constraint::Fill(1)]
-            }
-            None => vec![Constraint::Length(0), Constraint::Fill(1)],
+        let content_area = frame.area();
+        let content_area = if self.shows_breadcrumbs() && !self.breadcrumbs.is_empty() {
+            let [breadcrumbs_area, content_area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(content_area);
+            let names: Vec<String> = self.breadcrumbs.iter().map(|b| b.name.clone()).collect();
+            let trail = breadcrumb_trail(
+                &names,
+                breadcrumbs_area.width.saturating_sub(1) as usize,
+            );
+            frame.render_widget(Paragraph::new(format!(" {trail}")).gray(), breadcrumbs_area);
+            content_area
+        } else {
+            content_area
         };
 
-        let [nav_input_area, content_area] =
-            ratatui::layout::Layout::vertical(layout_constraints).areas(frame.area());
-
-        if let Some(nav_input) = &self.navigation_input {
-            nav_input.view(frame, nav_input_area);
-        }
+        let content_area = if self.show_legend {
+            let [content_area, legend_area] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(content_area);
+            let legend = widgets::KeyLegend {
+                actions: self.page.enabled_actions(),
+            };
+            frame.render_widget(legend, legend_area);
+            content_area
+        } else {
+            content_area
+        };
 
         match &self.page {
             Page::Loading(_) => {
@@ -342,12 +1167,13 @@ where
                 };
                 frame.render_widget(loading_screen, content_area);
             }
-            Page::Login(_) => {
-                frame.render_widget(widgets::LoginScreen, content_area);
-            }
+            Page::Login(login_page) => login_page.view(frame, content_area),
             Page::Scopes(scopes_page) => {
                 scopes_page.view(frame, content_area);
             }
+            Page::ScopeTree(scope_tree_page) => {
+                scope_tree_page.view(frame, content_area);
+            }
             Page::Targets(targets_page) => {
                 targets_page.view(frame, content_area);
             }
@@ -359,19 +1185,99 @@ where
             }
         }
 
+        // Floating over the page, which keeps its size
+        if let Some(nav_input) = &self.navigation_input {
+            let area = nav_input_area(frame.area(), nav_input.height());
+            frame.render_widget(Clear, area);
+            nav_input.view(frame, area);
+        }
+
+        if let Some((_, dialog)) = &self.last_command {
+            dialog.view(frame);
+        }
+
+        if let Some((_, dialog)) = &self.dead_connections {
+            dialog.view(frame);
+        }
+
         // Render toasts overlaying the content at the bottom
         self.toaster.view(frame);
     }
 
+    fn shows_breadcrumbs(&self) -> bool {
+        matches!(self.page, Page::Scopes(_) | Page::Targets(_))
+    }
+
     pub async fn handle_event(&mut self, event: &Event) {
-        if self.alert.is_some() && event.is_enter() {
-            self.alert = None
+        // Focus changes are no input, they must not keep an idle bountui running
+        if let Event::FocusGained | Event::FocusLost = event {
+            self.focused = matches!(event, Event::FocusGained);
+            self.apply_focus();
+            return;
+        }
+        self.last_input = tokio::time::Instant::now();
+        if self.alerts.current().is_some() && event.is_enter() {
+            self.alerts.dismiss();
+        }
+
+        if let Some((command, dialog)) = &self.last_command {
+            if let Some(copy) = dialog.handle_event(event) {
+                if copy {
+                    self.message_tx
+                        .send_or_log(Message::SetClipboard {
+                            text: command.clone(),
+                            on_success: Some(Box::new(Message::toast("Copied the command to the clipboard"))),
+                            on_error: None,
+                        })
+                        .await;
+                }
+                self.last_command = None;
+            }
+            return;
+        }
+
+        if let Some((session_ids, dialog)) = &self.dead_connections {
+            if let Some(reconnect) = dialog.handle_event(event) {
+                if reconnect {
+                    self.message_tx
+                        .send_or_log(Message::ReconnectConnections(session_ids.clone()))
+                        .await;
+                }
+                self.dead_connections = None;
+            }
+            return;
+        }
+
+        // Nothing to navigate to before logging in, typed keys are meant for the login dialog
+        if let Page::Login(login_page) = &mut self.page {
+            login_page.handle_event(event).await;
+            return;
         }
 
         match event {
             Event::Key(key_event) => match key_event.code {
                 KeyCode::Char(':') => {
-                    self.navigation_input = Some(NavigationInput::new(self.message_tx.clone()));
+                    let history = self
+                        .remember_user_input
+                        .get_command_history()
+                        .unwrap_or_else(|e| {
+                            log::warn!("Failed to read the command history: {e:#}");
+                            vec![]
+                        });
+                    let target_names = self.target_completer(|t| (t.name.clone(), t.id.clone()));
+                    let target_ids = self.target_completer(|t| (t.id.clone(), t.name.clone()));
+                    self.navigation_input = Some(
+                        NavigationInput::new(self.message_tx.clone())
+                            .with_page_actions(self.page.enabled_actions())
+                            .with_history(history)
+                            .with_argument_completer(GOTO, self.goto_completer())
+                            .with_argument_completer(OPEN, target_names)
+                            .with_argument_completer(CONNECT, target_ids),
+                    );
+                    return;
+                }
+                KeyCode::F(1) => {
+                    self.show_legend = !self.show_legend;
                     return;
                 }
                 KeyCode::Esc => {
@@ -386,16 +1292,32 @@ where
         }
 
         if let Some(nav_input) = &mut self.navigation_input {
+            // Scrolling is meant for the page below the palette
+            if is_scroll_event(event) {
+                self.handle_page_event(event).await;
+                return;
+            }
             nav_input.handle_event(event).await;
+            if let Some(command) = nav_input.take_accepted_command() {
+                self.navigation_input = None;
+                if let Err(e) = self.remember_user_input.store_command(command) {
+                    log::warn!("Failed to store the command: {e:#}");
+                }
+            }
             return;
         }
 
+        self.handle_page_event(event).await;
+    }
+
+    async fn handle_page_event(&mut self, event: &Event) {
         match &mut self.page {
             Page::Loading(_) => {}
             Page::Login(_) => {}
             Page::Scopes(scopes_page) => {
                 scopes_page.handle_event(event).await;
             }
+            Page::ScopeTree(scope_tree_page) => scope_tree_page.handle_event(event).await,
             Page::Targets(targets_page) => targets_page.handle_event(event).await,
             Page::TargetSessions(sessions_page) => {
                 sessions_page.handle_event(event).await;
@@ -406,13 +1328,79 @@ where
         }
     }
 
+    async fn refresh_page(&mut self) {
+        match &mut self.page {
+            Page::Loading(_) | Page::Login(_) => {}
+            Page::Scopes(scopes_page) => scopes_page.reload_scopes().await,
+            Page::ScopeTree(scope_tree_page) => scope_tree_page.reload_scopes().await,
+            Page::Targets(targets_page) => targets_page.reload_targets().await,
+            Page::TargetSessions(sessions_page) => sessions_page.refresh_now(),
+            Page::UserSessions(sessions_page) => sessions_page.refresh_now(),
+        }
+    }
+
     pub async fn handle_message(&mut self, message: Message) {
         match message {
-            Message::ShowScopes { parent } => self.show_scope(parent).await,
-            Message::ShowTargets { parent } => self.show_targets(parent).await,
-            Message::Connect { target_id, port } => self.connect(&target_id, port).await,
-            Message::ShowSessions { scope, target } => {
+            Message::ShowScopes {
+                parent,
+                select_scope_id,
+            } => self.show_scope(parent, select_scope_id).await,
+            Message::ShowTargets {
+                parent,
+                select_target_id,
+            } => self.show_targets(parent, select_target_id).await,
+            Message::ShowSiblingTargets { parent, siblings } => {
+                self.show_sibling_targets(parent, siblings).await
+            }
+            Message::ShowTargetsForTarget {
+                scope_id,
+                target_id,
+            } => self.show_targets_for_target(scope_id, target_id),
+            Message::Connect {
+                target_id,
+                host_id,
+                port,
+                keep_alive,
+            } => {
+                self.connect(&target_id, host_id.as_deref(), port, keep_alive)
+                    .await
+            }
+            Message::BulkConnect(requests) => self.bulk_connect(requests),
+            Message::ConnectById { target_id, port } => {
+                self.connect_by_id(target_id, port).await
+            }
+            Message::TestConnection { target_id } => self.test_connection(&target_id).await,
+            Message::Goto(goto_id) => self.goto(goto_id),
+            Message::OpenTarget(name) => self.open_target(name),
+            Message::ShowTargetChoices(targets) => {
+                self.navigation_input =
+                    Some(NavigationInput::target_choices(self.message_tx.clone(), targets));
+            }
+            Message::RestoreLocation { scopes, targets } => {
+                self.show_location(scopes, targets).await
+            }
+            Message::ShowScopePath { scopes, targets } => {
+                self.show_scope_path(scopes, targets).await
+            }
+            Message::PageAction(key_event) => {
+                self.navigation_input = None;
+                self.handle_page_event(&Event::Key(key_event)).await;
+            }
+            Message::RefreshPage => {
+                self.navigation_input = None;
+                self.refresh_page().await;
+            }
+            Message::ShowSessions {
+                scope,
+                target,
+                select_session_id,
+            } => {
+                let recent_page = RecentPage::Sessions {
+                    scope: scope.clone(),
+                    target: target.clone(),
+                };
                 let credentials = self.connection_manager.get_credentials();
+                let local_ports = self.connection_manager.get_local_ports();
                 self.navigate_to(
                     Page::TargetSessions(
                         SessionsPage::new(
@@ -425,43 +1413,181 @@ where
                             ),
                             self.message_tx.clone(),
                             credentials,
+                            local_ports,
+                            self.sessions_refresh_interval(),
+                            self.user_id.clone(),
                         )
-                        .await,
+                        .await
+                        .with_column_widths(&self.config.column_widths)
+                        .with_connection_labels(self.connection_manager.get_labels())
+                        .with_selected_session(select_session_id),
                     ),
                     false,
                 );
+                self.visit(recent_page);
             }
-            Message::StopSession {
+            Message::CopySessionCredentials { session_id } => {
+                let message = match self.connection_manager.get_session_credentials(&session_id) {
+                    Some(credentials) => Message::SetClipboard {
+                        text: credentials_text(&credentials),
+                        on_success: Some(Box::new(Message::toast(format!(
+                            "Copied {} credentials",
+                            credentials.len()
+                        )))),
+                        on_error: Some(Box::new(Message::toast("Failed to copy the credentials"))),
+                    },
+                    None => Message::toast(format!("No credentials are cached for session {session_id}")),
+                };
+                self.message_tx.send_or_log(message).await;
+            }
+            Message::SetConnectionLabel { session_id, label } => {
+                let label = Some(label.trim().to_string()).filter(|l| !l.is_empty());
+                if let Err(e) = self.connection_manager.set_label(&session_id, label) {
+                    self.show_alert((
+                        "Error".to_string(),
+                        format!("Failed to label the connection: {e}"),
+                    ));
+                }
+            }
+            Message::StopSession {
                 session_id,
                 notify_stopped_tx,
             } => {
-                self.stop_session(&session_id).await;
-                let _ = notify_stopped_tx.send(()).await;
+                let stopped = self.stop_session(&session_id).await;
+                self.message_tx
+                    .send_or_log(SessionsPageMessage::StopFinished { session_id, stopped }.into())
+                    .await;
+                notify_stopped_tx.send_or_log(()).await;
+            }
+            Message::StopSessions {
+                session_ids,
+                notify_stopped_tx,
+            } => {
+                self.stop_sessions(session_ids, notify_stopped_tx);
             }
+            Message::SessionsStopped(results) => self.sessions_stopped(results).await,
             Message::ShowAlert(title, message) => {
-                self.alert = Some((title.clone(), message.clone()));
+                self.show_alert((title.clone(), message.clone()));
             }
-            Message::GoBack => self.go_back(),
-            Message::Targets(targets_message) => {
-                if let Page::Targets(targets_page) = &mut self.page {
-                    targets_page.handle_message(targets_message);
+            Message::GoBack => self.go_back().await,
+            // Written to the config file on exit instead of on every key press
+            Message::ColumnsResized { page, widths } => self.config.set_column_widths(&page, widths),
+            Message::OpenInBrowser(url) => {
+                if let Err(e) = open::that_detached(&url) {
+                    error!("Failed to open {}: {}", url, e);
+                    self.show_alert(("Error".to_string(), format!("Failed to open {url}: {e}")));
                 }
             }
-            Message::SessionsPage(msg) => match &mut self.page {
-                Page::TargetSessions(sessions_page) => {
-                    sessions_page.handle_message(msg);
-                }
-                Page::UserSessions(sessions_page) => {
-                    sessions_page.handle_message(msg);
+            Message::Targets(targets_message) => match &mut self.page {
+                Page::Targets(targets_page) => targets_page.handle_message(targets_message).await,
+                // Connected with the `connect` command while another page is shown
+                _ => {
+                    if let TargetsPageMessage::ConnectedToTarget(response) = targets_message {
+                        self.message_tx
+                            .send_or_log(Message::toast(format!("Connected, session {}", response.session_id)))
+                            .await;
+                    }
                 }
-                _ => {}
             },
+            Message::SessionsPage(msg) => {
+                // Connections made or stopped meanwhile are shown with the reloaded sessions
+                let connections = matches!(msg, SessionsPageMessage::SessionsLoaded(_)).then(|| {
+                    (
+                        self.connection_manager.get_credentials(),
+                        self.connection_manager.get_local_ports(),
+                        self.connection_manager.get_labels(),
+                    )
+                });
+                match &mut self.page {
+                    Page::TargetSessions(sessions_page) => {
+                        if let Some((credentials, local_ports, labels)) = connections {
+                            sessions_page.set_connections(credentials, local_ports, labels);
+                        }
+                        sessions_page.handle_message(msg).await;
+                    }
+                    Page::UserSessions(sessions_page) => {
+                        if let Some((credentials, local_ports, labels)) = connections {
+                            sessions_page.set_connections(credentials, local_ports, labels);
+                        }
+                        sessions_page.handle_message(msg).await;
+                    }
+                    _ => {}
+                }
+            }
             Message::NavigateToScopeTree => {
                 self.navigate_to_scope_tree().await;
             }
             Message::NavigateToMySessions => {
                 self.navigate_to_my_sessions().await;
             }
+            Message::ShowLastCommand => {
+                self.navigation_input = None;
+                match self.boundary_client.last_command() {
+                    Some(command) => {
+                        let dialog = ConfirmDialog::new(
+                            "Last boundary command".to_string(),
+                            format!("{command}\n\nCopy it to the clipboard?"),
+                        );
+                        self.last_command = Some((command, dialog));
+                    }
+                    None => {
+                        self.show_alert((
+                            "Last boundary command".to_string(),
+                            "No boundary command was recorded, start bountui with --debug to \
+                             record them"
+                                .to_string(),
+                        ));
+                    }
+                }
+            }
+            Message::StopAllConnections => {
+                self.navigation_input = None;
+                self.stop_all_connections().await;
+            }
+            Message::CheckConnections => {
+                self.navigation_input = None;
+                self.check_connections();
+            }
+            Message::ConnectionsChecked(dead) => {
+                self.connections_checked(dead).await;
+            }
+            Message::ReconnectConnections(session_ids) => {
+                self.reconnect_connections(session_ids);
+            }
+            Message::ArgumentCandidates { command, candidates } => {
+                if let Some(nav_input) = &mut self.navigation_input {
+                    nav_input.set_argument_candidates(&command, candidates);
+                }
+            }
+            Message::ShowRecentPages => {
+                let pages: Vec<RecentPage> = self
+                    .recent_pages
+                    .jump_list(self.recent_page.as_ref())
+                    .into_iter()
+                    .cloned()
+                    .collect();
+                if pages.is_empty() {
+                    self.message_tx
+                        .send_or_log(Message::toast("No other pages were shown yet"))
+                        .await;
+                } else {
+                    self.navigation_input =
+                        Some(NavigationInput::recent_pages(self.message_tx.clone(), pages));
+                }
+            }
+            Message::OpenRecentPage(page) => {
+                self.navigation_input = None;
+                self.message_tx.send_or_log(page.message()).await;
+            }
+            Message::Control { request, respond_to } => {
+                let response = self.handle_control_request(request).await;
+                let _ = respond_to.send(response);
+            }
+            Message::Quit => {
+                self.navigation_input = None;
+                self.shutdown().await;
+                self.quit = true;
+            }
             Message::RunFuture(future) => {
                 self.tasks.push(future);
             }
@@ -470,6 +1596,20 @@ where
                     scopes_page.handle_message(scopes_message).await;
                 }
             }
+            Message::Login(login_message) => {
+                if let Page::Login(login_page) = &mut self.page {
+                    login_page.handle_message(login_message);
+                }
+            }
+            Message::ScopeTree(scope_tree_message) => {
+                if let Page::ScopeTree(scope_tree_page) = &mut self.page {
+                    scope_tree_page.handle_message(scope_tree_message);
+                }
+            }
+            Message::ShowScopeTree { replace_history } => {
+                self.show_scope_tree(replace_history).await;
+            }
+            Message::CloseScopeTree => self.close_scope_tree().await,
             Message::SetClipboard {
                 text,
                 on_success,
@@ -477,14 +1617,14 @@ where
             } => match self.clipboard.set_text(text) {
                 Ok(_) => {
                     if let Some(success_msg) = on_success {
-                        let _ = self.message_tx.send(*success_msg).await;
+                        self.message_tx.send_or_log(*success_msg).await;
                     }
                 }
                 Err(e) => {
                     if let Some(error_msg) = on_error {
-                        let _ = self.message_tx.send(*error_msg).await;
+                        self.message_tx.send_or_log(*error_msg).await;
                     } else {
-                        self.alert = Some((
+                        self.show_alert((
                             "Clipboard Error".to_string(),
                             format!("Failed to set clipboard text: {e}"),
                         ));
@@ -495,9 +1635,7 @@ where
                 self.toaster.handle_message(toaster_message).await;
             }
             Message::Authenticated(auth_response) => {
-                unsafe {
-                    std::env::set_var("BOUNDARY_TOKEN", &auth_response.attributes.token);
-                }
+                self.boundary_client.set_token(&auth_response.attributes.token);
                 self.user_id = auth_response.attributes.user_id.clone();
 
                 // Cache the token after a successful login.
@@ -513,14 +1651,14 @@ where
                 }
 
                 self.navigate_to_scope_tree().await;
+                self.restore_last_location();
             }
             Message::TokenRestored(auth_response) => {
                 // Token was validated — same setup as a fresh login, but without re-caching.
-                unsafe {
-                    std::env::set_var("BOUNDARY_TOKEN", &auth_response.attributes.token);
-                }
+                self.boundary_client.set_token(&auth_response.attributes.token);
                 self.user_id = auth_response.attributes.user_id.clone();
                 self.navigate_to_scope_tree().await;
+                self.restore_last_location();
             }
             Message::TokenInvalid => {
                 // Cached token is expired or revoked — clear it and start the login flow.
@@ -543,12 +1681,328 @@ where
         }
     }
 
+    async fn stop_all_connections(&mut self) {
+        let count = self.connection_manager.get_local_ports().len();
+        let message = match self.connection_manager.shutdown().await {
+            Ok(()) => Message::toast(match count {
+                0 => "There are no connections to stop".to_string(),
+                _ => format!("Stopped {count} connections"),
+            }),
+            Err(errors) => {
+                error!("Failed to stop connections: {:?}", errors);
+                let failures: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+                Message::ShowAlert(
+                    "Disconnect all".to_string(),
+                    format!(
+                        "Failed to stop {} of {count} connections:\n{}",
+                        failures.len(),
+                        failures.join("\n")
+                    ),
+                )
+            }
+        };
+        self.message_tx.send_or_log(message).await;
+    }
+
+    // Reading the sessions takes a while with many connections, the app stays responsive meanwhile
+    fn check_connections(&mut self) {
+        let connection_manager = self.connection_manager.clone();
+        let message_tx = self.message_tx.clone();
+        self.tasks.push(
+            async move {
+                let dead = connection_manager.find_dead_connections().await;
+                message_tx.send_or_log(Message::ConnectionsChecked(dead)).await;
+            }
+            .boxed(),
+        );
+    }
+
+    async fn connections_checked(&mut self, mut dead: Vec<String>) {
+        let ports = self.connection_manager.get_local_ports();
+        // Connections stopped meanwhile are not offered
+        dead.retain(|id| ports.contains_key(id));
+        if dead.is_empty() {
+            let text = match ports.len() {
+                0 => "There are no connections to check".to_string(),
+                count => format!("All {count} connections are alive"),
+            };
+            self.message_tx.send_or_log(Message::toast(text)).await;
+            return;
+        }
+        dead.sort_by_key(|id| ports.get(id).copied());
+        let dead_ports: Vec<String> = dead
+            .iter()
+            .map(|id| ports.get(id).map_or_else(|| id.clone(), u16::to_string))
+            .collect();
+        let dialog = ConfirmDialog::new(
+            "Dead connections".to_string(),
+            format!(
+                "{} of {} connections no longer respond, local ports: {}\n\nReconnect them on the same ports?",
+                dead.len(),
+                ports.len(),
+                dead_ports.join(", ")
+            ),
+        );
+        self.dead_connections = Some((dead, dialog));
+    }
+
+    fn bulk_connect(&mut self, requests: Vec<(String, u16)>) {
+        let connection_manager = self.connection_manager.clone();
+        let message_tx = self.message_tx.clone();
+        self.tasks.push(
+            async move {
+                let mut results = vec![];
+                for (target_id, port) in requests {
+                    let result = connection_manager
+                        .connect(&target_id, None, port, false)
+                        .await
+                        .map(|resp| resp.session_id)
+                        .map_err(|e| e.to_string());
+                    results.push((target_id, result));
+                }
+                message_tx
+                    .send_or_log(Message::Targets(TargetsPageMessage::BulkConnected(results)))
+                    .await;
+            }
+            .boxed(),
+        );
+    }
+
+    fn reconnect_connections(&mut self, session_ids: Vec<String>) {
+        let connection_manager = self.connection_manager.clone();
+        let message_tx = self.message_tx.clone();
+        self.tasks.push(
+            async move {
+                let mut failures = vec![];
+                for session_id in &session_ids {
+                    if let Err(e) = connection_manager.reconnect(session_id).await {
+                        error!("Failed to reconnect session {}: {:?}", session_id, e);
+                        failures.push(format!("{session_id}: {e}"));
+                    }
+                }
+                let message = if failures.is_empty() {
+                    Message::toast(format!("Reconnected {} connections", session_ids.len()))
+                } else {
+                    Message::ShowAlert(
+                        "Reconnect".to_string(),
+                        format!(
+                            "Failed to reconnect {} of {} connections:\n{}",
+                            failures.len(),
+                            session_ids.len(),
+                            failures.join("\n")
+                        ),
+                    )
+                };
+                message_tx.send_or_log(message).await;
+            }
+            .boxed(),
+        );
+    }
+
+    /// Completes the targets of all scopes, `candidate` picks what is completed and what else it
+    /// is found by, e.g. the id and the name. The targets are listed once the first time they are
+    /// needed.
+    fn target_completer(&self, candidate: fn(&Target) -> (String, String)) -> ArgumentCompleter {
+        let boundary_client = self.boundary_client.clone();
+        let known = self.known.clone();
+        Arc::new(move || {
+            let boundary_client = boundary_client.clone();
+            let known = known.clone();
+            async move {
+                match known.targets(&boundary_client).await {
+                    Ok(targets) => targets.iter().map(candidate).collect(),
+                    Err(e) => {
+                        log::warn!("Failed to list the targets to complete: {e}");
+                        vec![]
+                    }
+                }
+            }
+            .boxed()
+        })
+    }
+
+    /// Completes the ids of all scopes and targets, also found by their names.
+    fn goto_completer(&self) -> ArgumentCompleter {
+        let boundary_client = self.boundary_client.clone();
+        let known = self.known.clone();
+        let targets = self.target_completer(|t| (t.id.clone(), t.name.clone()));
+        Arc::new(move || {
+            let boundary_client = boundary_client.clone();
+            let known = known.clone();
+            let targets = targets();
+            async move {
+                let scopes = known.scopes(&boundary_client).await;
+                let mut candidates: ArgumentCandidates = match scopes {
+                    Ok(scopes) => scopes.iter().map(|s| (s.id.clone(), s.name.clone())).collect(),
+                    Err(e) => {
+                        log::warn!("Failed to list the scopes to complete: {e}");
+                        vec![]
+                    }
+                };
+                candidates.extend(targets.await);
+                candidates
+            }
+            .boxed()
+        })
+    }
+
+    // The typed port, else the remembered local port of the target or a free one
+    fn local_port_for(&self, target_id: &String, port: Option<u16>) -> std::io::Result<u16> {
+        let remembered_port = || {
+            self.remember_user_input
+                .get_local_port(target_id)
+                .unwrap_or_else(|e| {
+                    log::warn!("Failed to read the remembered port: {e:#}");
+                    None
+                })
+        };
+        match port.or_else(remembered_port) {
+            Some(port) => Ok(port),
+            None => free_port(),
+        }
+    }
+
+    async fn connect_by_id(&mut self, target_id: String, port: Option<u16>) {
+        self.navigation_input = None;
+        let port = match self.local_port_for(&target_id, port) {
+            Ok(port) => port,
+            Err(e) => {
+                self.message_tx
+                    .send_or_log(Message::show_error("No free port available", e))
+                    .await;
+                return;
+            }
+        };
+        let boundary_client = self.boundary_client.clone();
+        let production_targets = self.config.production_targets.clone();
+        let scope_rules = self.config.scopes.clone();
+        let message_tx = self.message_tx.clone();
+        self.tasks.push(
+            async move {
+                let refusal =
+                    connect_refusal(&boundary_client, &target_id, &production_targets, &scope_rules).await;
+                let message = match refusal {
+                    Ok(None) => Message::Connect {
+                        target_id,
+                        host_id: None,
+                        port,
+                        keep_alive: false,
+                    },
+                    Ok(Some(reason)) => Message::ShowAlert("Not connected".to_string(), reason),
+                    Err(e) => Message::show_error(format!("Failed to look up target {target_id}"), e),
+                };
+                message_tx.send_or_log(message).await;
+            }
+            .boxed(),
+        );
+    }
+
+    async fn handle_control_request(&mut self, request: ControlRequest) -> ControlResponse {
+        match request {
+            ControlRequest::ListConnections => {
+                let labels = self.connection_manager.get_labels();
+                let mut connections: Vec<ControlConnection> = self
+                    .connection_manager
+                    .get_local_ports()
+                    .into_iter()
+                    .map(|(session_id, port)| ControlConnection {
+                        label: labels.get(&session_id).cloned(),
+                        session_id,
+                        port,
+                    })
+                    .collect();
+                connections.sort_by_key(|c| c.port);
+                ControlResponse::success(connections)
+            }
+            ControlRequest::Connect { target_id, port } => {
+                // Nobody is asked to confirm over the socket, so these are not connected at all
+                let refusal = connect_refusal(
+                    &self.boundary_client,
+                    &target_id,
+                    &self.config.production_targets,
+                    &self.config.scopes,
+                )
+                .await;
+                match refusal {
+                    Ok(None) => {}
+                    Ok(Some(reason)) => return ControlResponse::failure(reason),
+                    Err(e) => {
+                        return ControlResponse::failure(format!("Failed to look up target {target_id}: {e}"))
+                    }
+                }
+                let port = match self.local_port_for(&target_id, port) {
+                    Ok(port) => port,
+                    Err(e) => return ControlResponse::failure(format!("No free port available: {e}")),
+                };
+                match self.connection_manager.connect(&target_id, None, port, false).await {
+                    Ok(response) => ControlResponse::success(ControlConnection {
+                        session_id: response.session_id,
+                        port,
+                        label: None,
+                    }),
+                    Err(e) => ControlResponse::failure(e.to_string()),
+                }
+            }
+            ControlRequest::Stop { session_id } => match self.try_stop_session(&session_id).await {
+                Ok(()) => ControlResponse::success(()),
+                Err(e) => ControlResponse::failure(e.to_string()),
+            },
+        }
+    }
+
+    /// Answers requests of other processes on the Unix socket at `path` while running.
+    pub fn with_control_socket(mut self, path: Option<std::path::PathBuf>) -> Self {
+        self.control_socket = path;
+        self
+    }
+
+    fn listen_for_control_requests(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let path = self.control_socket.as_ref()?;
+        #[cfg(unix)]
+        match control::listen(path, self.message_tx.clone()) {
+            Ok(listener) => Some(listener),
+            Err(e) => {
+                error!("Failed to listen on the control socket {}: {}", path.display(), e);
+                None
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            error!("The control socket {} is only supported on Unix", path.display());
+            None
+        }
+    }
+
+    async fn shutdown(&mut self) {
+        if let Err(e) = self.config.store_column_widths() {
+            error!("Failed to store column widths: {:#}", e);
+        }
+        let _ = self.connection_manager.shutdown().await
+            .map_err(|e| error!("Failed to shutdown connection manager: {:?}", e));
+    }
+
+    /// Stops all connections if there was no input for longer than the configured idle timeout,
+    /// returns whether bountui should exit.
+    async fn exit_if_idle(&mut self) -> bool {
+        let Some(minutes) = self.config.idle_timeout else {
+            return false;
+        };
+        if self.last_input.elapsed() < std::time::Duration::from_secs(minutes.saturating_mul(60)) {
+            return false;
+        }
+        info!("No input for {} minutes, exiting", minutes);
+        self.shutdown().await;
+        true
+    }
+
     pub async fn run(&mut self) {
-        let mut terminal = ratatui::init();
+        let mut terminal = init_terminal();
         terminal.clear().unwrap();
 
         // Perform initial layout
         self.handle_layout(&mut terminal);
+        let mut idle_check = tokio::time::interval(IDLE_CHECK_INTERVAL);
+        let control_listener = self.listen_for_control_requests();
 
         loop {
             terminal
@@ -561,28 +2015,42 @@ where
                     if let Some(message) = message {
                         self.handle_message(message).await;
                     }
+                    if self.quit {
+                        break;
+                    }
                 }
                 event = self.cross_term_event_rx.recv() => {
                     if let Some(event) = event {
                         if event.is_stop() {
-                            let _ = self.connection_manager.shutdown().await
-                                .map_err(|e| error!("Failed to shutdown connection manager: {:?}", e));
-                            break;
+                            self.handle_message(Message::Quit).await;
                         }
-                        if event.is_resize() {
+                        else if event.is_resize() {
                             self.handle_layout(&mut terminal);
                         }
                         else {
                             self.handle_event(&event).await;
                         }
-
+                    }
+                    if self.quit {
+                        break;
                     }
                 },
                 _ = self.tasks.next(), if !self.tasks.is_empty() => {}
+                _ = idle_check.tick(), if self.config.idle_timeout.is_some() => {
+                    if self.exit_if_idle().await {
+                        break;
+                    }
+                }
             }
         }
 
-        ratatui::restore()
+        if let Some(listener) = control_listener {
+            listener.abort();
+            if let Some(path) = &self.control_socket {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+        restore_terminal()
     }
 }
 
@@ -590,6 +2058,8 @@ where
 mod tests {
     use super::*;
     use crate::bountui::auth_cache::tests::mock_auth_cache;
+    use crate::boundary::fixtures::credential_entry;
+    use crate::boundary::fixtures::target;
     use crate::bountui::connection_manager::{DefaultConnectionManager, MockConnectionManager};
     use crate::util::clipboard::{ClipboardAccessError, MockClipboardAccess};
     use mockall::predicate::eq;
@@ -609,17 +2079,27 @@ mod tests {
     async fn make_authenticated_app<M: ConnectionManager>(
         connection_manager: M,
         clipboard: Box<dyn ClipboardAccess>,
+    ) -> BountuiApp<boundary::MockClient, Option<UserInputsPath<&'static str>>, M> {
+        make_authenticated_app_with_client(make_boundary_client(), connection_manager, clipboard)
+            .await
+    }
+
+    async fn make_authenticated_app_with_client<M: ConnectionManager>(
+        boundary_client: boundary::MockClient,
+        connection_manager: M,
+        clipboard: Box<dyn ClipboardAccess>,
     ) -> BountuiApp<boundary::MockClient, Option<UserInputsPath<&'static str>>, M> {
         let (_evt_tx, evt_rx) = tokio::sync::mpsc::channel(1);
         let remember_user_input: Option<UserInputsPath<&'static str>> = None;
 
         let mut app = BountuiApp::new(
-            make_boundary_client(),
+            boundary_client,
             connection_manager,
             remember_user_input,
             evt_rx,
             clipboard,
             noop_auth_cache(),
+            Config::default(),
         );
 
         for _ in 0..10 {
@@ -633,6 +2113,210 @@ mod tests {
         app
     }
 
+    fn make_scope(id: &str) -> Scope {
+        Scope {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: "".to_string(),
+            type_name: "".to_string(),
+            authorized_collection_actions: HashMap::new(),
+            scope_id: "".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn single_esc_on_targets_page_goes_back_to_parent_scopes_page() {
+        let mut app =
+            make_authenticated_app(MockConnectionManager::new(), Box::new(MockClipboardAccess::new()))
+                .await;
+        app.handle_message(Message::ShowScopes {
+            parent: Some(make_scope("o_1")),
+            select_scope_id: None,
+        })
+        .await;
+        app.handle_message(Message::ShowTargets {
+            parent: make_scope("p_1"),
+            select_target_id: None,
+        })
+        .await;
+        assert!(matches!(app.page, Page::Targets(_)));
+        assert_eq!(app.history.len(), 2);
+
+        app.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Esc)))
+            .await;
+        app.process_pending_messages().await;
+
+        assert!(
+            matches!(app.page, Page::Scopes(_)),
+            "Esc should go back to the scopes page"
+        );
+        assert_eq!(app.history.len(), 1, "Only one history entry should be popped");
+    }
+
+    fn first_line<C, R, M>(app: &mut BountuiApp<C, R, M>, width: u16) -> String
+    where
+        C: boundary::ApiClient + Clone + Send + Sync + 'static,
+        R: RememberUserInput + Copy + Send + 'static,
+        M: ConnectionManager,
+    {
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(width, 10)).unwrap();
+        terminal.draw(|frame| app.view(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..width).map(|x| buffer[(x, 0)].symbol()).collect::<String>().trim_end().to_string()
+    }
+
+    #[test]
+    fn palette_floats_centered_in_the_upper_third() {
+        let area = Rect::new(0, 0, 100, 40);
+        assert_eq!(nav_input_area(area, 11), Rect::new(20, 7, 60, 11));
+        // At least 40 columns wide, never larger than the screen
+        assert_eq!(nav_input_area(Rect::new(0, 0, 50, 8), 11), Rect::new(5, 0, 40, 8));
+        assert_eq!(nav_input_area(Rect::new(0, 0, 30, 20), 4), Rect::new(0, 4, 30, 4));
+    }
+
+    #[tokio::test]
+    async fn palette_floats_over_the_page_without_moving_it() {
+        let mut app =
+            make_authenticated_app(MockConnectionManager::new(), Box::new(MockClipboardAccess::new()))
+                .await;
+        app.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char(':'))))
+            .await;
+
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(100, 40)).unwrap();
+        terminal.draw(|frame| app.view(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let line = |x: u16, y: u16| (x..100).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+        assert_eq!(line(0, 0).trim_end(), " global", "The page keeps the first row");
+        let popup = nav_input_area(Rect::new(0, 0, 100, 40), app.navigation_input.as_ref().unwrap().height());
+        assert!(line(popup.x, popup.y + 1).starts_with("│> "));
+    }
+
+    #[tokio::test]
+    async fn alerts_and_scrolling_while_the_palette_is_open() {
+        let mut app =
+            make_authenticated_app(MockConnectionManager::new(), Box::new(MockClipboardAccess::new()))
+                .await;
+        let press = |code| Event::Key(crossterm::event::KeyEvent::from(code));
+        app.handle_event(&press(KeyCode::Char(':'))).await;
+        app.handle_event(&press(KeyCode::Char('t'))).await;
+
+        app.handle_event(&press(KeyCode::PageDown)).await;
+        let nav_input = app.navigation_input.as_ref().expect("Scrolling keeps the palette open");
+        assert_eq!(nav_input.input.value(), "t", "The page gets the scroll keys");
+
+        app.handle_message(Message::ShowAlert("Error".to_string(), "Failed".to_string()))
+            .await;
+        assert!(app.navigation_input.is_none(), "The alert takes the focus");
+        assert!(app.alerts.current().is_some());
+    }
+
+    #[tokio::test]
+    async fn breadcrumbs_follow_the_scopes_and_targets_pages() {
+        let mut app =
+            make_authenticated_app(MockConnectionManager::new(), Box::new(MockClipboardAccess::new()))
+                .await;
+        assert_eq!(first_line(&mut app, 80), " global");
+
+        app.handle_message(Message::ShowScopes {
+            parent: Some(make_scope("acme")),
+            select_scope_id: None,
+        })
+        .await;
+        assert_eq!(first_line(&mut app, 80), " global › acme");
+
+        app.handle_message(Message::ShowTargets {
+            parent: make_scope("team-a"),
+            select_target_id: None,
+        })
+        .await;
+        assert_eq!(first_line(&mut app, 80), " global › acme › team-a");
+
+        app.handle_message(Message::ShowSiblingTargets {
+            parent: make_scope("team-b"),
+            siblings: vec![make_scope("team-a"), make_scope("team-b")],
+        })
+        .await;
+        assert_eq!(first_line(&mut app, 80), " global › acme › team-b");
+
+        app.handle_message(Message::GoBack).await;
+        assert_eq!(first_line(&mut app, 80), " global › acme");
+    }
+
+    #[tokio::test]
+    async fn closing_the_scope_tree_returns_to_the_scopes() {
+        let mut app =
+            make_authenticated_app(MockConnectionManager::new(), Box::new(MockClipboardAccess::new()))
+                .await;
+        app.handle_message(Message::ShowScopes {
+            parent: Some(make_scope("acme")),
+            select_scope_id: None,
+        })
+        .await;
+
+        app.handle_message(Message::ShowScopeTree { replace_history: false }).await;
+        assert!(matches!(app.page, Page::ScopeTree(_)));
+        app.handle_message(Message::CloseScopeTree).await;
+        assert!(matches!(app.page, Page::Scopes(_)));
+        assert_eq!(first_line(&mut app, 80), " global › acme");
+
+        // Opened with `:tree` there is nothing to go back to
+        app.handle_message(Message::ShowScopeTree { replace_history: true }).await;
+        app.handle_message(Message::CloseScopeTree).await;
+        assert!(matches!(app.page, Page::Scopes(_)));
+        assert!(app.history.is_empty());
+        assert_eq!(first_line(&mut app, 80), " global");
+    }
+
+    #[tokio::test]
+    async fn long_breadcrumbs_are_cut_in_the_middle() {
+        let mut app =
+            make_authenticated_app(MockConnectionManager::new(), Box::new(MockClipboardAccess::new()))
+                .await;
+        for name in ["acme", "platform", "databases"] {
+            app.handle_message(Message::ShowScopes {
+                parent: Some(make_scope(name)),
+                select_scope_id: None,
+            })
+            .await;
+        }
+        app.handle_message(Message::ShowTargets {
+            parent: make_scope("team-a"),
+            select_target_id: None,
+        })
+        .await;
+
+        assert_eq!(first_line(&mut app, 36), " global › … › databases › team-a");
+        assert_eq!(first_line(&mut app, 18), " global ›…› team-a");
+    }
+
+    #[tokio::test]
+    async fn sibling_targets_replace_the_targets_page() {
+        let mut app =
+            make_authenticated_app(MockConnectionManager::new(), Box::new(MockClipboardAccess::new()))
+                .await;
+        app.handle_message(Message::ShowScopes {
+            parent: Some(make_scope("o_1")),
+            select_scope_id: None,
+        })
+        .await;
+        app.handle_message(Message::ShowTargets {
+            parent: make_scope("p_1"),
+            select_target_id: None,
+        })
+        .await;
+
+        app.handle_message(Message::ShowSiblingTargets {
+            parent: make_scope("p_2"),
+            siblings: vec![make_scope("p_1"), make_scope("p_2")],
+        })
+        .await;
+
+        assert!(matches!(app.page, Page::Targets(_)));
+        assert_eq!(app.history.len(), 2, "Going back still leads to the scopes");
+    }
+
     #[tokio::test]
     async fn failed_authentication_keeps_login_page_open_and_shows_alert() {
         let connection_manager = MockConnectionManager::new();
@@ -650,18 +2334,19 @@ mod tests {
             evt_rx,
             Box::new(MockClipboardAccess::new()),
             noop_auth_cache(),
+            Config::default(),
         );
 
         for _ in 0..10 {
             app.process_pending_messages().await;
-            if app.alert.is_some() {
+            if app.alerts.current().is_some() {
                 break;
             }
             tokio::time::sleep(std::time::Duration::from_millis(10)).await;
         }
 
         assert!(matches!(app.page, Page::Login(_)));
-        assert!(app.alert.is_some(), "Expected authentication failure alert");
+        assert!(app.alerts.current().is_some(), "Expected authentication failure alert");
     }
 
     #[tokio::test]
@@ -683,7 +2368,7 @@ mod tests {
         .await;
 
         assert!(
-            app.alert.is_none(),
+            app.alerts.current().is_none(),
             "Alert should not be set on clipboard success"
         );
     }
@@ -706,7 +2391,7 @@ mod tests {
         })
         .await;
 
-        match &app.alert {
+        match app.alerts.current() {
             Some((title, _msg)) => {
                 assert_eq!(title, "Clipboard Error");
             }
@@ -715,28 +2400,1523 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn connect_shows_error_when_connect_fails() {
-        let boundary_client = make_boundary_client();
-        let connection_manager = DefaultConnectionManager::new(boundary_client);
+    async fn alerts_raised_together_are_dismissed_one_by_one() {
+        let mut app =
+            make_authenticated_app(MockConnectionManager::new(), Box::new(MockClipboardAccess::new()))
+                .await;
+        app.handle_message(Message::ShowAlert("Error".to_string(), "Refresh failed".to_string()))
+            .await;
+        app.handle_message(Message::ShowAlert("Clipboard Error".to_string(), "boom".to_string()))
+            .await;
+
+        let enter = Event::Key(crossterm::event::KeyEvent::from(KeyCode::Enter));
+        assert_eq!(app.alerts.current_title(), Some("Error (1/2)".to_string()));
+        app.handle_event(&enter).await;
+        assert_eq!(app.alerts.current_title(), Some("Clipboard Error (2/2)".to_string()));
+        app.handle_event(&enter).await;
+        assert!(app.alerts.current().is_none());
+    }
 
+    #[tokio::test]
+    async fn bulk_connect_reports_every_result_despite_failures() {
+        let target = Target {
+            id: "ttcp_1".to_string(),
+            name: "target".to_string(),
+            description: "".to_string(),
+            type_name: "tcp".to_string(),
+            authorized_collection_actions: Default::default(),
+            authorized_actions: vec![],
+            scope_id: "p_1".to_string(),
+            attributes: None,
+        };
+        let boundary_client = boundary::MockClient::builder()
+            .user_id("user-1".to_string())
+            .scopes(HashMap::new())
+            .targets(HashMap::from([(Some("p_1".to_string()), vec![target])]))
+            .build();
+        let connection_manager = DefaultConnectionManager::new(boundary_client);
         let mut app =
             make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
 
-        app.handle_message(Message::Connect {
-            target_id: "TARGET_DOES_NOT_EXIST".to_string(),
-            port: 8080,
+        app.handle_message(Message::BulkConnect(vec![
+            ("TARGET_DOES_NOT_EXIST".to_string(), 8080),
+            ("ttcp_1".to_string(), 8080),
+        ]))
+        .await;
+        while app.tasks.next().await.is_some() {}
+
+        let results = std::iter::from_fn(|| app.message_rx.try_recv().ok())
+            .find_map(|message| match message {
+                Message::Targets(TargetsPageMessage::BulkConnected(results)) => Some(results),
+                _ => None,
+            })
+            .expect("The results should be reported at once");
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, "TARGET_DOES_NOT_EXIST");
+        assert!(results[0].1.is_err());
+        assert_eq!(results[1].0, "ttcp_1");
+        assert!(results[1].1.is_ok(), "A failed connect must not abort the others");
+        assert!(app.alerts.current().is_none(), "Bulk connect errors are shown in the summary");
+    }
+
+    #[tokio::test]
+    async fn stop_unmanaged_session_falls_back_to_cancel_session() {
+        let boundary_client = make_boundary_client();
+        let connection_manager = DefaultConnectionManager::new(boundary_client.clone());
+        let mut app = make_authenticated_app_with_client(
+            boundary_client.clone(),
+            connection_manager,
+            Box::new(MockClipboardAccess::new()),
+        )
+        .await;
+        let (notify_stopped_tx, mut notify_stopped_rx) = tokio::sync::mpsc::channel(1);
+
+        app.handle_message(Message::StopSession {
+            session_id: "s_external".to_string(),
+            notify_stopped_tx,
         })
         .await;
-        for _ in 0..10 {
-            app.process_pending_messages().await;
-            if !matches!(app.page, Page::Login(_)) || app.alert.is_some() {
-                break;
+
+        assert!(notify_stopped_rx.try_recv().is_ok());
+        assert_eq!(boundary_client.canceled_sessions().await, vec!["s_external"]);
+        let mut reported = false;
+        while let Ok(message) = app.message_rx.try_recv() {
+            match message {
+                Message::ShowAlert(_, text) => panic!("Unexpected alert: {text}"),
+                Message::SessionsPage(SessionsPageMessage::StopFinished { stopped, .. }) => {
+                    reported = stopped
+                }
+                _ => {}
             }
-            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
         }
-        assert!(
-            app.alert.is_some(),
-            "Expected error alert on connect failure"
-        );
+        assert!(reported, "Expected the stop to be reported as successful");
+    }
+
+    #[tokio::test]
+    async fn failed_stop_session_shows_alert_and_reports_result() {
+        let boundary_client = boundary::MockClient::builder()
+            .user_id("user-1".to_string())
+            .scopes(HashMap::new())
+            .cancel_session_failures(std::sync::Arc::new(1.into()))
+            .build();
+        let connection_manager = DefaultConnectionManager::new(boundary_client.clone());
+        let mut app = make_authenticated_app_with_client(
+            boundary_client,
+            connection_manager,
+            Box::new(MockClipboardAccess::new()),
+        )
+        .await;
+        let (notify_stopped_tx, mut notify_stopped_rx) = tokio::sync::mpsc::channel(1);
+
+        app.handle_message(Message::StopSession {
+            session_id: "s_unknown".to_string(),
+            notify_stopped_tx,
+        })
+        .await;
+
+        assert!(notify_stopped_rx.try_recv().is_ok(), "Page must reload anyway");
+        let mut alerted = false;
+        let mut reported = false;
+        while let Ok(message) = app.message_rx.try_recv() {
+            match message {
+                Message::ShowAlert(_, text) => alerted |= text.starts_with("Failed to stop session"),
+                Message::SessionsPage(SessionsPageMessage::StopFinished { session_id, stopped }) => {
+                    reported |= session_id == "s_unknown" && !stopped
+                }
+                _ => {}
+            }
+        }
+        assert!(alerted, "Expected an alert for the failed stop");
+        assert!(reported, "Expected the failed stop to be reported to the page");
+    }
+
+    #[tokio::test]
+    async fn stop_sessions_continues_after_failures() {
+        let boundary_client = boundary::MockClient::builder()
+            .user_id("user-1".to_string())
+            .scopes(HashMap::new())
+            .cancel_session_failures(std::sync::Arc::new(2.into()))
+            .build();
+        let connection_manager = DefaultConnectionManager::new(boundary_client.clone());
+        let mut app = make_authenticated_app_with_client(
+            boundary_client.clone(),
+            connection_manager,
+            Box::new(MockClipboardAccess::new()),
+        )
+        .await;
+        let (notify_stopped_tx, mut notify_stopped_rx) = tokio::sync::mpsc::channel(1);
+        let session_ids: Vec<String> = (1..=5).map(|i| format!("s_{i}")).collect();
+
+        app.handle_message(Message::StopSessions {
+            session_ids: session_ids.clone(),
+            notify_stopped_tx,
+        })
+        .await;
+
+        assert!(notify_stopped_rx.try_recv().is_err(), "The sessions are stopped in the background");
+        while app.tasks.next().await.is_some() {}
+        assert!(notify_stopped_rx.try_recv().is_ok(), "Page must reload");
+        assert_eq!(boundary_client.canceled_sessions().await.len(), 3);
+        let mut reported = None;
+        while let Ok(message) = app.message_rx.try_recv() {
+            if let Message::SessionsStopped(results) = message {
+                assert!(reported.is_none(), "The outcome is reported once");
+                reported = Some(results);
+            }
+        }
+        let mut reported = reported.expect("Expected the outcome of the stops");
+        reported.sort();
+        assert_eq!(
+            reported.iter().map(|(id, _)| id.clone()).collect::<Vec<_>>(),
+            session_ids,
+            "Every session is reported"
+        );
+        assert_eq!(reported.iter().filter(|(_, result)| result.is_ok()).count(), 3);
+
+        app.handle_message(Message::SessionsStopped(reported)).await;
+        let (_, summary) = app.alerts.current().cloned().expect("Expected a summary alert");
+        assert!(summary.starts_with("Canceled 3 of 5 sessions, failed:"), "{summary}");
+        assert_eq!(summary.lines().count(), 3, "One line per failure");
+    }
+
+    #[tokio::test]
+    async fn ignored_config_settings_are_shown_at_startup() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, b"open_commands = 42\n").unwrap();
+        let config = Config::load(file.path()).unwrap();
+        let (_evt_tx, evt_rx) = tokio::sync::mpsc::channel(1);
+        let remember_user_input: Option<UserInputsPath<&'static str>> = None;
+
+        let app = BountuiApp::new(
+            make_boundary_client(),
+            MockConnectionManager::new(),
+            remember_user_input,
+            evt_rx,
+            Box::new(MockClipboardAccess::new()),
+            noop_auth_cache(),
+            config,
+        );
+
+        let (title, message) = app.alerts.current().cloned().expect("Expected an alert for the ignored setting");
+        assert_eq!(title, "Config");
+        assert!(message.contains("open_commands: invalid type"));
+    }
+
+    #[tokio::test]
+    async fn sessions_page_in_history_stops_refreshing() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager
+            .expect_get_credentials()
+            .returning(HashMap::new);
+        connection_manager
+            .expect_get_local_ports()
+            .returning(HashMap::new);
+        connection_manager
+            .expect_get_labels()
+            .returning(HashMap::new);
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+        app.handle_message(Message::NavigateToMySessions).await;
+        let is_refresh_paused = |page: &Page<_, _>| match page {
+            Page::UserSessions(sessions_page) => sessions_page.is_refresh_paused(),
+            _ => panic!("Expected the user sessions page"),
+        };
+        assert!(!is_refresh_paused(&app.page));
+
+        app.handle_message(Message::ShowScopes { parent: None, select_scope_id: None }).await;
+        assert!(is_refresh_paused(&app.history[0]));
+
+        app.handle_message(Message::GoBack).await;
+        assert!(!is_refresh_paused(&app.page));
+    }
+
+    #[tokio::test]
+    async fn reloaded_sessions_show_the_current_connections() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager
+            .expect_get_credentials()
+            .returning(HashMap::new);
+        connection_manager
+            .expect_get_local_ports()
+            .times(1)
+            .returning(HashMap::new);
+        connection_manager
+            .expect_get_local_ports()
+            .returning(|| HashMap::from([("s_1".to_string(), 8080)]));
+        connection_manager
+            .expect_get_labels()
+            .returning(HashMap::new);
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+        app.handle_message(Message::NavigateToMySessions).await;
+        let copies_address = |page: &Page<_, _>| match page {
+            Page::UserSessions(sessions_page) => sessions_page
+                .enabled_actions()
+                .iter()
+                .any(|action| action.name == "Copy Local Address"),
+            _ => panic!("Expected the user sessions page"),
+        };
+
+        let session = components::table::sessions::tests::session_with_status("s_1", "active");
+        app.handle_message(Message::SessionsPage(SessionsPageMessage::SessionsLoaded(vec![
+            session,
+        ])))
+        .await;
+        assert!(copies_address(&app.page), "Connected after the page was shown");
+    }
+
+    #[tokio::test]
+    async fn sessions_refresh_pauses_while_the_terminal_is_unfocused() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager
+            .expect_get_credentials()
+            .returning(HashMap::new);
+        connection_manager
+            .expect_get_local_ports()
+            .returning(HashMap::new);
+        connection_manager
+            .expect_get_labels()
+            .returning(HashMap::new);
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+        app.handle_message(Message::NavigateToMySessions).await;
+        let is_refresh_paused = |page: &Page<_, _>| match page {
+            Page::UserSessions(sessions_page) => sessions_page.is_refresh_paused(),
+            _ => panic!("Expected the user sessions page"),
+        };
+
+        app.handle_event(&Event::FocusLost).await;
+        assert!(is_refresh_paused(&app.page));
+        app.handle_event(&Event::FocusGained).await;
+        assert!(!is_refresh_paused(&app.page));
+
+        app.config.pause_refresh_when_unfocused = false;
+        app.handle_event(&Event::FocusLost).await;
+        assert!(!is_refresh_paused(&app.page));
+    }
+
+    #[tokio::test]
+    async fn sessions_pages_shown_while_unfocused_start_paused() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager
+            .expect_get_credentials()
+            .returning(HashMap::new);
+        connection_manager
+            .expect_get_local_ports()
+            .returning(HashMap::new);
+        connection_manager
+            .expect_get_labels()
+            .returning(HashMap::new);
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+        app.handle_message(Message::NavigateToMySessions).await;
+        let is_refresh_paused = |page: &Page<_, _>| match page {
+            Page::UserSessions(sessions_page) => sessions_page.is_refresh_paused(),
+            _ => panic!("Expected the user sessions page"),
+        };
+        app.handle_message(Message::ShowScopes { parent: None, select_scope_id: None }).await;
+        app.handle_event(&Event::FocusLost).await;
+
+        app.handle_message(Message::GoBack).await;
+        assert!(is_refresh_paused(&app.page), "Shown again while unfocused");
+        app.handle_event(&Event::FocusGained).await;
+        assert!(!is_refresh_paused(&app.page));
+
+        app.handle_event(&Event::FocusLost).await;
+        app.handle_message(Message::NavigateToMySessions).await;
+        assert!(is_refresh_paused(&app.page), "Shown while unfocused");
+    }
+
+    #[tokio::test]
+    async fn show_targets_for_target_selects_the_target() {
+        let target = |id: &str| Target {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: "".to_string(),
+            type_name: "tcp".to_string(),
+            authorized_collection_actions: HashMap::new(),
+            authorized_actions: vec![],
+            scope_id: "p_1".to_string(),
+            attributes: None,
+        };
+        let boundary_client = boundary::MockClient::builder()
+            .user_id("user-1".to_string())
+            .scopes(HashMap::from([
+                (None, vec![make_scope("o_1")]),
+                (Some("o_1".to_string()), vec![make_scope("p_1")]),
+            ]))
+            .targets(HashMap::from([(
+                Some("p_1".to_string()),
+                vec![target("ttcp_a"), target("ttcp_b"), target("ttcp_c")],
+            )]))
+            .build();
+        let mut app = make_authenticated_app_with_client(
+            boundary_client,
+            MockConnectionManager::new(),
+            Box::new(MockClipboardAccess::new()),
+        )
+        .await;
+
+        app.handle_message(Message::ShowTargetsForTarget {
+            scope_id: "p_1".to_string(),
+            target_id: "ttcp_b".to_string(),
+        })
+        .await;
+        // Resolving the scope and loading the targets run as tasks of the app
+        for _ in 0..3 {
+            while let Ok(Some(())) =
+                tokio::time::timeout(std::time::Duration::from_millis(10), app.tasks.next()).await
+            {}
+            app.process_pending_messages().await;
+        }
+
+        match &app.page {
+            Page::Targets(targets_page) => {
+                assert_eq!(targets_page.selected_target_id(), Some("ttcp_b".to_string()))
+            }
+            _ => panic!("Expected the targets page"),
+        }
+    }
+
+    #[tokio::test]
+    async fn closing_the_connect_result_shows_the_target_sessions_if_enabled() {
+        let target = Target {
+            id: "ttcp_1".to_string(),
+            name: "target".to_string(),
+            description: "".to_string(),
+            type_name: "tcp".to_string(),
+            authorized_collection_actions: HashMap::new(),
+            authorized_actions: vec!["authorize-session".to_string()],
+            scope_id: "p_1".to_string(),
+            attributes: None,
+        };
+        let scope = Scope {
+            authorized_collection_actions: HashMap::from([(
+                "sessions".to_string(),
+                vec!["list".to_string()],
+            )]),
+            ..make_scope("p_1")
+        };
+        let boundary_client = boundary::MockClient::builder()
+            .user_id("user-1".to_string())
+            .scopes(HashMap::new())
+            .targets(HashMap::from([(Some("p_1".to_string()), vec![target])]))
+            .build();
+        let connection_manager = DefaultConnectionManager::new(boundary_client.clone());
+        let mut app = make_authenticated_app_with_client(
+            boundary_client,
+            connection_manager,
+            Box::new(MockClipboardAccess::new()),
+        )
+        .await;
+        app.config.show_sessions_after_connect = true;
+        app.handle_message(Message::ShowTargets {
+            parent: scope,
+            select_target_id: None,
+        })
+        .await;
+        // Loading the targets runs as a task of the app
+        for _ in 0..2 {
+            while let Ok(Some(())) =
+                tokio::time::timeout(std::time::Duration::from_millis(10), app.tasks.next()).await
+            {}
+            app.process_pending_messages().await;
+        }
+
+        let press = |code: KeyCode| Event::Key(crossterm::event::KeyEvent::from(code));
+        for code in [KeyCode::Char('c'), KeyCode::Char('8'), KeyCode::Tab, KeyCode::Right, KeyCode::Enter] {
+            app.handle_event(&press(code)).await;
+        }
+        app.process_pending_messages().await;
+        assert!(matches!(app.page, Page::Targets(_)), "The connect result is shown first");
+
+        app.handle_event(&press(KeyCode::Esc)).await;
+        app.process_pending_messages().await;
+        assert!(matches!(app.page, Page::TargetSessions(_)));
+
+        app.handle_event(&press(KeyCode::Esc)).await;
+        app.process_pending_messages().await;
+        assert!(matches!(app.page, Page::Targets(_)), "Esc goes back to the targets");
+    }
+
+    #[tokio::test]
+    async fn set_connection_label_trims_and_removes_empty_labels() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager
+            .expect_set_label()
+            .with(eq("s_1"), eq(Some("migration".to_string())))
+            .times(1)
+            .returning(|_, _| Ok(()));
+        connection_manager
+            .expect_set_label()
+            .with(eq("s_1"), eq(None))
+            .times(1)
+            .returning(|_, _| Ok(()));
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+
+        for label in [" migration ", "  "] {
+            app.handle_message(Message::SetConnectionLabel {
+                session_id: "s_1".to_string(),
+                label: label.to_string(),
+            })
+            .await;
+        }
+        assert!(app.alerts.current().is_none());
+    }
+
+    #[tokio::test]
+    async fn columns_resized_updates_config() {
+        let mut app = make_authenticated_app(
+            MockConnectionManager::new(),
+            Box::new(MockClipboardAccess::new()),
+        )
+        .await;
+        app.handle_message(Message::ColumnsResized {
+            page: "targets".to_string(),
+            widths: vec![40, 20, 10, 10, 10, 10],
+        })
+        .await;
+        assert_eq!(
+            app.config.column_widths.get("targets"),
+            Some(&vec![40, 20, 10, 10, 10, 10])
+        );
+    }
+
+    #[tokio::test]
+    async fn column_widths_are_written_to_the_config_file_on_quit() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager
+            .expect_shutdown()
+            .returning(|| Box::pin(async { Ok(()) }));
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        app.config = Config::load(&path).unwrap();
+
+        for widths in [vec![40, 20], vec![45, 20]] {
+            app.handle_message(Message::ColumnsResized {
+                page: "targets".to_string(),
+                widths,
+            })
+            .await;
+        }
+        assert!(!path.exists(), "Not written on every resize");
+
+        app.handle_message(Message::Quit).await;
+        assert_eq!(
+            Config::load(&path).unwrap().column_widths.get("targets"),
+            Some(&vec![45, 20])
+        );
+    }
+
+    #[tokio::test]
+    async fn connect_command_connects_to_the_target_on_the_typed_port() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager
+            .expect_connect()
+            .withf(|target_id, host_id, port, keep_alive| {
+                target_id == "ttcp_abc123" && host_id.is_none() && *port == 5432 && !keep_alive
+            })
+            .times(1)
+            .returning(|_, _, _, _| Box::pin(async { Ok(connect_response("s_1")) }));
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+        let press = |code| Event::Key(crossterm::event::KeyEvent::from(code));
+
+        app.handle_event(&press(KeyCode::Char(':'))).await;
+        for c in "connect  ttcp_abc123  5432".chars() {
+            app.handle_event(&press(KeyCode::Char(c))).await;
+        }
+        app.handle_event(&press(KeyCode::Enter)).await;
+        app.process_pending_messages().await;
+        while app.tasks.next().await.is_some() {}
+        app.process_pending_messages().await;
+
+        assert!(app.alerts.current().is_none());
+    }
+
+    #[tokio::test]
+    async fn connect_shows_error_when_connect_fails() {
+        let boundary_client = make_boundary_client();
+        let connection_manager = DefaultConnectionManager::new(boundary_client);
+
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+
+        app.handle_message(Message::Connect {
+            target_id: "TARGET_DOES_NOT_EXIST".to_string(),
+            host_id: None,
+            port: 8080,
+            keep_alive: false,
+        })
+        .await;
+        for _ in 0..10 {
+            app.process_pending_messages().await;
+            if !matches!(app.page, Page::Login(_)) || app.alerts.current().is_some() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert!(
+            app.alerts.current().is_some(),
+            "Expected error alert on connect failure"
+        );
+    }
+
+    async fn restored_app(
+        scope_ids: &[&str],
+    ) -> BountuiApp<boundary::MockClient, Option<UserInputsPath<&'static str>>, MockConnectionManager>
+    {
+        let scope = |id: &str, parent: &str, lists: &str| Scope {
+            scope_id: parent.to_string(),
+            authorized_collection_actions: HashMap::from([(
+                lists.to_string(),
+                vec!["list".to_string()],
+            )]),
+            ..make_scope(id)
+        };
+        let boundary_client = boundary::MockClient::builder()
+            .user_id("user-1".to_string())
+            .scopes(HashMap::from([
+                (None, vec![scope("o_1", "global", "scopes")]),
+                (Some("o_1".to_string()), vec![scope("p_1", "o_1", "targets")]),
+            ]))
+            .build();
+        let mut app = make_authenticated_app_with_client(
+            boundary_client,
+            MockConnectionManager::new(),
+            Box::new(MockClipboardAccess::new()),
+        )
+        .await;
+        // The file outlives the test, the app needs a path it can copy
+        let file = tempfile::NamedTempFile::new().unwrap().into_temp_path().keep().unwrap();
+        let path: &'static str = Box::leak(file.to_str().unwrap().to_string().into_boxed_str());
+        let mut remember_user_input = Some(UserInputsPath(path));
+        remember_user_input
+            .store_last_location(LastLocation {
+                scope_ids: scope_ids.iter().map(|id| id.to_string()).collect(),
+                targets: true,
+            })
+            .unwrap();
+        app.remember_user_input = remember_user_input;
+        app.config.restore_last_location = true;
+
+        app.restore_last_location();
+        while let Ok(Some(())) =
+            tokio::time::timeout(std::time::Duration::from_millis(10), app.tasks.next()).await
+        {}
+        app.process_pending_messages().await;
+        app
+    }
+
+    #[tokio::test]
+    async fn last_location_is_restored_with_its_history() {
+        let mut app = restored_app(&["o_1", "p_1"]).await;
+
+        assert!(matches!(app.page, Page::Targets(_)));
+        assert_eq!(app.history.len(), 2, "The root scopes and the scopes of o_1");
+        assert_eq!(first_line(&mut app, 80), " global › o_1 › p_1");
+
+        app.go_back().await;
+        assert!(matches!(app.page, Page::Scopes(_)));
+        let stored = app.remember_user_input.get_last_location().unwrap().unwrap();
+        assert_eq!(stored.scope_ids, vec!["o_1".to_string(), "p_1".to_string()]);
+        let _ = std::fs::remove_file(app.remember_user_input.unwrap().0);
+    }
+
+    #[tokio::test]
+    async fn last_location_with_a_deleted_scope_stays_at_the_root() {
+        let mut app = restored_app(&["o_deleted", "p_1"]).await;
+
+        assert!(matches!(app.page, Page::Scopes(_)));
+        assert!(app.history.is_empty());
+        assert_eq!(first_line(&mut app, 80), " global");
+        let _ = std::fs::remove_file(app.remember_user_input.unwrap().0);
+    }
+
+    fn connect_response(session_id: &str) -> boundary::ConnectResponse {
+        boundary::ConnectResponse {
+            credentials: vec![credential_entry("admin", "secret")],
+            session_id: session_id.to_string(),
+            expiration: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connection_connects_stops_and_reports() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager
+            .expect_connect()
+            .withf(|target_id, host_id, port, keep_alive| {
+                target_id == "ttcp_1" && host_id.is_none() && *port == 0 && !keep_alive
+            })
+            .times(1)
+            .returning(|_, _, _, _| Box::pin(async { Ok(connect_response("s_1")) }));
+        connection_manager
+            .expect_stop()
+            .with(eq("s_1"))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+
+        app.handle_message(Message::TestConnection {
+            target_id: "ttcp_1".to_string(),
+        })
+        .await;
+
+        let (title, message) = app.alerts.current().cloned().unwrap();
+        assert_eq!(title, "Connection test");
+        assert!(message.starts_with("Connected to ttcp_1 and disconnected again."));
+        assert!(message.contains("admin (vault)"));
+        assert!(!message.contains("secret"));
+    }
+
+    #[tokio::test]
+    async fn test_connection_reports_failures() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager
+            .expect_connect()
+            .times(1)
+            .returning(|_, _, _, _| {
+                Box::pin(async { Err(boundary::Error::ApiError(403, "forbidden".to_string())) })
+            });
+        connection_manager.expect_stop().never();
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+
+        app.handle_message(Message::TestConnection {
+            target_id: "ttcp_1".to_string(),
+        })
+        .await;
+
+        let (title, message) = app.alerts.current().cloned().unwrap();
+        assert_eq!(title, "Connection test failed");
+        assert!(message.contains("forbidden"));
+    }
+
+    #[tokio::test]
+    async fn connect_lets_the_user_pick_a_host_if_boundary_cannot() {
+        let target = Target {
+            id: "ttcp_1".to_string(),
+            name: "target".to_string(),
+            description: "".to_string(),
+            type_name: "tcp".to_string(),
+            authorized_collection_actions: Default::default(),
+            authorized_actions: vec![],
+            scope_id: "p_1".to_string(),
+            attributes: None,
+        };
+        let host = |id: &str| boundary::Host {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: "".to_string(),
+            attributes: None,
+            ip_addresses: vec![],
+        };
+        let boundary_client = boundary::MockClient::builder()
+            .user_id("user-1".to_string())
+            .scopes(HashMap::new())
+            .targets(HashMap::from([(Some("p_1".to_string()), vec![target])]))
+            .target_hosts(HashMap::from([(
+                "ttcp_1".to_string(),
+                vec![host("hst_1"), host("hst_2")],
+            )]))
+            .build();
+        let connection_manager = DefaultConnectionManager::new(boundary_client.clone());
+        let mut app = make_authenticated_app_with_client(
+            boundary_client.clone(),
+            connection_manager,
+            Box::new(MockClipboardAccess::new()),
+        )
+        .await;
+
+        app.handle_message(Message::Connect {
+            target_id: "ttcp_1".to_string(),
+            host_id: None,
+            port: 8080,
+            keep_alive: false,
+        })
+        .await;
+        while let Ok(Some(())) =
+            tokio::time::timeout(std::time::Duration::from_millis(10), app.tasks.next()).await
+        {}
+
+        let mut hosts_loaded = None;
+        while let Ok(message) = app.message_rx.try_recv() {
+            match message {
+                Message::Targets(TargetsPageMessage::HostsLoaded {
+                    target_id,
+                    port,
+                    hosts,
+                    ..
+                }) => hosts_loaded = Some((target_id, port, hosts)),
+                Message::ShowAlert(title, message) => panic!("Unexpected alert {title}: {message}"),
+                _ => {}
+            }
+        }
+        let (target_id, port, hosts) = hosts_loaded.expect("The hosts should be loaded");
+        assert_eq!(target_id, "ttcp_1");
+        assert_eq!(port, 8080);
+        assert_eq!(hosts, vec![host("hst_1"), host("hst_2")]);
+        assert!(boundary_client.connected_host_ids().await.is_empty());
+
+        app.handle_message(Message::Connect {
+            target_id: "ttcp_1".to_string(),
+            host_id: Some("hst_2".to_string()),
+            port: 8080,
+            keep_alive: false,
+        })
+        .await;
+        assert_eq!(
+            boundary_client.connected_host_ids().await,
+            vec![Some("hst_2".to_string())]
+        );
+        assert!(app.alerts.current().is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idle_timeout_stops_all_connections() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager
+            .expect_shutdown()
+            .times(1)
+            .returning(|| Box::pin(async { Ok(()) }));
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+        app.config.idle_timeout = Some(5);
+
+        tokio::time::advance(std::time::Duration::from_secs(4 * 60)).await;
+        assert!(!app.exit_if_idle().await);
+        // Any input restarts the timeout
+        app.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Down)))
+            .await;
+        tokio::time::advance(std::time::Duration::from_secs(4 * 60)).await;
+        assert!(!app.exit_if_idle().await);
+
+        tokio::time::advance(std::time::Duration::from_secs(61)).await;
+        assert!(app.exit_if_idle().await);
+    }
+
+    // Checks for the idle timeout on the interval of the run loop, returns when bountui exits
+    async fn run_idle_checks<M: ConnectionManager>(
+        app: &mut BountuiApp<boundary::MockClient, Option<UserInputsPath<&'static str>>, M>,
+    ) {
+        let mut idle_check = tokio::time::interval(IDLE_CHECK_INTERVAL);
+        loop {
+            idle_check.tick().await;
+            if app.exit_if_idle().await {
+                return;
+            }
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idle_timeout_exits_within_a_check_interval() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager
+            .expect_shutdown()
+            .times(1)
+            .returning(|| Box::pin(async { Ok(()) }));
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+        app.config.idle_timeout = Some(5);
+        let start = tokio::time::Instant::now();
+
+        run_idle_checks(&mut app).await;
+        let idle = start.elapsed();
+        assert!(idle >= std::time::Duration::from_secs(5 * 60));
+        assert!(idle <= std::time::Duration::from_secs(5 * 60) + IDLE_CHECK_INTERVAL);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn huge_idle_timeouts_do_not_overflow() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager.expect_shutdown().never();
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+        app.config.idle_timeout = Some(u64::MAX);
+
+        tokio::time::advance(std::time::Duration::from_secs(24 * 60 * 60)).await;
+        assert!(!app.exit_if_idle().await);
+    }
+
+    #[tokio::test]
+    async fn disconnect_all_stops_every_connection() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager
+            .expect_get_local_ports()
+            .returning(|| HashMap::from([("s_1".to_string(), 8080), ("s_2".to_string(), 8081)]));
+        connection_manager
+            .expect_shutdown()
+            .times(1)
+            .returning(|| Box::pin(async { Ok(()) }));
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+        app.process_pending_messages().await;
+
+        app.handle_message(Message::StopAllConnections).await;
+        let message = app.message_rx.try_recv().expect("The outcome should be shown");
+        assert!(matches!(
+            message,
+            Message::Toaster(components::toaster::Message::ShowToast { text, .. })
+                if text == "Stopped 2 connections"
+        ));
+    }
+
+    #[tokio::test]
+    async fn session_credentials_are_copied_from_the_connection_manager() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager
+            .expect_get_session_credentials()
+            .returning(|id| (id == "s_1").then(|| vec![credential_entry("admin", "secret")]));
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+        app.process_pending_messages().await;
+
+        app.handle_message(Message::CopySessionCredentials {
+            session_id: "s_1".to_string(),
+        })
+        .await;
+        let message = app.message_rx.try_recv().expect("The credentials should be copied");
+        assert!(matches!(
+            message,
+            Message::SetClipboard { text, .. } if text == "vault\nusername: admin\npassword: secret"
+        ));
+
+        app.handle_message(Message::CopySessionCredentials {
+            session_id: "s_2".to_string(),
+        })
+        .await;
+        let message = app.message_rx.try_recv().expect("The missing credentials should be shown");
+        assert!(matches!(
+            message,
+            Message::Toaster(components::toaster::Message::ShowToast { text, .. })
+                if text == "No credentials are cached for session s_2"
+        ));
+    }
+
+    #[tokio::test]
+    async fn accepted_commands_are_recalled_in_the_next_palette() {
+        let mut app = make_authenticated_app(
+            MockConnectionManager::new(),
+            Box::new(MockClipboardAccess::new()),
+        )
+        .await;
+        let file = tempfile::NamedTempFile::new().unwrap().into_temp_path().keep().unwrap();
+        let path: &'static str = Box::leak(file.to_str().unwrap().to_string().into_boxed_str());
+        app.remember_user_input = Some(UserInputsPath(path));
+        let press = |code| Event::Key(crossterm::event::KeyEvent::from(code));
+
+        app.handle_event(&press(KeyCode::Char(':'))).await;
+        for c in "tree".chars() {
+            app.handle_event(&press(KeyCode::Char(c))).await;
+        }
+        app.handle_event(&press(KeyCode::Enter)).await;
+        app.process_pending_messages().await;
+        assert!(app.navigation_input.is_none());
+
+        app.handle_event(&press(KeyCode::Char(':'))).await;
+        app.handle_event(&press(KeyCode::Up)).await;
+        assert_eq!(app.navigation_input.as_ref().unwrap().input.value(), "tree");
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn palette_closes_after_a_command_and_stays_open_on_unknown_input() {
+        let mut app = make_authenticated_app(
+            MockConnectionManager::new(),
+            Box::new(MockClipboardAccess::new()),
+        )
+        .await;
+        let press = |code| Event::Key(crossterm::event::KeyEvent::from(code));
+
+        app.handle_event(&press(KeyCode::Char(':'))).await;
+        for c in "xyz".chars() {
+            app.handle_event(&press(KeyCode::Char(c))).await;
+        }
+        app.handle_event(&press(KeyCode::Enter)).await;
+        assert!(app.navigation_input.is_some());
+
+        for _ in 0..3 {
+            app.handle_event(&press(KeyCode::Backspace)).await;
+        }
+        for c in "disconnect-all".chars() {
+            app.handle_event(&press(KeyCode::Char(c))).await;
+        }
+        app.handle_event(&press(KeyCode::Enter)).await;
+        // Closed before the message is handled
+        assert!(app.navigation_input.is_none());
+    }
+
+    #[tokio::test]
+    async fn quit_command_stops_the_connections_and_exits() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager
+            .expect_shutdown()
+            .times(1)
+            .returning(|| Box::pin(async { Ok(()) }));
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+        app.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char(':'))))
+            .await;
+        app.handle_message(Message::Quit).await;
+        assert!(app.quit);
+        assert!(app.navigation_input.is_none());
+    }
+
+    #[tokio::test]
+    async fn q_on_a_page_stops_the_connections_and_quits() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager
+            .expect_shutdown()
+            .times(1)
+            .returning(|| Box::pin(async { Ok(()) }));
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+        app.navigate_to_scope_tree().await;
+        app.process_pending_messages().await;
+
+        app.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('q'))))
+            .await;
+        app.process_pending_messages().await;
+        assert!(app.quit);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn without_idle_timeout_bountui_never_exits() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager.expect_shutdown().never();
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+
+        tokio::time::advance(std::time::Duration::from_secs(24 * 60 * 60)).await;
+        assert!(!app.exit_if_idle().await);
+    }
+
+    #[tokio::test]
+    async fn scope_path_reconstructs_the_history_of_its_scopes() {
+        let scope = |id: &str, parent: &str| {
+            Scope::builder()
+                .id(id.to_string())
+                .name(id.to_string())
+                .description(String::new())
+                .type_name("org".to_string())
+                .authorized_collection_actions(HashMap::new())
+                .scope_id(parent.to_string())
+                .build()
+        };
+        let mut app = make_authenticated_app(
+            MockConnectionManager::new(),
+            Box::new(MockClipboardAccess::new()),
+        )
+        .await;
+        app.navigate_to_scope_tree().await;
+
+        app.handle_message(Message::ShowScopePath {
+            scopes: vec![scope("o_acme", "global"), scope("p_payments", "o_acme")],
+            targets: true,
+        })
+        .await;
+
+        assert!(matches!(app.page, Page::Targets(_)));
+        let breadcrumbs: Vec<&str> = app.breadcrumbs.iter().map(|b| b.scope_id.as_str()).collect();
+        assert_eq!(breadcrumbs, vec![GLOBAL_SCOPE_ID, "o_acme", "p_payments"]);
+        app.go_back().await;
+        assert!(matches!(app.page, Page::Scopes(_)));
+        let breadcrumbs: Vec<&str> = app.breadcrumbs.iter().map(|b| b.scope_id.as_str()).collect();
+        assert_eq!(breadcrumbs, vec![GLOBAL_SCOPE_ID, "o_acme"]);
+        app.go_back().await;
+        assert!(matches!(app.page, Page::Scopes(_)));
+        assert_eq!(app.breadcrumbs.len(), 1);
+        assert!(app.history.is_empty());
+    }
+
+    #[tokio::test]
+    async fn command_palette_runs_the_actions_of_the_shown_page() {
+        let mut app = make_authenticated_app(
+            MockConnectionManager::new(),
+            Box::new(MockClipboardAccess::new()),
+        )
+        .await;
+        app.navigate_to_scope_tree().await;
+        app.process_pending_messages().await;
+        assert!(matches!(app.page, Page::Scopes(_)));
+
+        app.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char(':'))))
+            .await;
+        for c in "scope tree".chars() {
+            app.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char(c))))
+                .await;
+        }
+        app.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Enter)))
+            .await;
+
+        let message = app.message_rx.try_recv().expect("The palette should send a message");
+        assert!(matches!(
+            &message,
+            Message::PageAction(key) if key.code == KeyCode::Char('T')
+        ));
+        app.handle_message(message).await;
+        assert!(app.navigation_input.is_none());
+        app.process_pending_messages().await;
+        assert!(matches!(app.page, Page::ScopeTree(_)));
+    }
+
+    fn last_line<C, R, M>(app: &mut BountuiApp<C, R, M>, width: u16) -> String
+    where
+        C: boundary::ApiClient + Clone + Send + Sync + 'static,
+        R: RememberUserInput + Copy + Send + 'static,
+        M: ConnectionManager,
+    {
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(width, 10)).unwrap();
+        terminal.draw(|frame| app.view(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..width).map(|x| buffer[(x, 9)].symbol()).collect::<String>().trim_end().to_string()
+    }
+
+    #[tokio::test]
+    async fn f1_toggles_a_legend_of_the_page_actions() {
+        let mut app = make_authenticated_app(
+            MockConnectionManager::new(),
+            Box::new(MockClipboardAccess::new()),
+        )
+        .await;
+        app.process_pending_messages().await;
+        let f1 = Event::Key(crossterm::event::KeyEvent::from(KeyCode::F(1)));
+        let without_legend = last_line(&mut app, 250);
+
+        app.handle_event(&f1).await;
+        let actions = app.page.enabled_actions();
+        assert!(!actions.is_empty());
+        let expected: String = actions
+            .iter()
+            .map(|action| format!(" {}  {}  ", action.shortcut, action.name))
+            .collect();
+        assert_eq!(last_line(&mut app, 250), expected.trim_end());
+
+        app.handle_event(&f1).await;
+        assert_eq!(last_line(&mut app, 250), without_legend);
+    }
+
+    // Runs the started futures and handles the messages they send until nothing is left
+    async fn run_tasks<C, R, M>(app: &mut BountuiApp<C, R, M>)
+    where
+        C: boundary::ApiClient + Clone + Send + Sync + 'static,
+        R: RememberUserInput + Copy + Send + 'static,
+        M: ConnectionManager,
+    {
+        loop {
+            app.process_pending_messages().await;
+            if app.tasks.next().await.is_none() {
+                break;
+            }
+        }
+    }
+
+    async fn app_on_child_scopes(
+        reload_stale_pages_after: u64,
+    ) -> BountuiApp<boundary::MockClient, Option<UserInputsPath<&'static str>>, MockConnectionManager>
+    {
+        let mut org = make_scope("o_1");
+        org.authorized_collection_actions
+            .insert("scopes".to_string(), vec!["list".to_string()]);
+        let boundary_client = boundary::MockClient::builder()
+            .user_id("user-1".to_string())
+            .scopes(HashMap::from([(None, vec![org.clone()])]))
+            .build();
+        let mut app = make_authenticated_app_with_client(
+            boundary_client,
+            MockConnectionManager::new(),
+            Box::new(MockClipboardAccess::new()),
+        )
+        .await;
+        app.config.reload_stale_pages_after = reload_stale_pages_after;
+        run_tasks(&mut app).await;
+        app.handle_message(Message::ShowScopes {
+            parent: Some(org),
+            select_scope_id: None,
+        })
+        .await;
+        run_tasks(&mut app).await;
+        app
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn going_back_reloads_pages_only_once_they_are_stale() {
+        let mut app = app_on_child_scopes(60).await;
+
+        tokio::time::advance(std::time::Duration::from_secs(30)).await;
+        app.handle_message(Message::GoBack).await;
+        assert!(matches!(app.page, Page::Scopes(_)));
+        assert!(app.message_rx.try_recv().is_err(), "Fresh scopes are not reloaded");
+
+        let mut app = app_on_child_scopes(60).await;
+        tokio::time::advance(std::time::Duration::from_secs(61)).await;
+        app.handle_message(Message::GoBack).await;
+        assert!(matches!(app.message_rx.try_recv(), Ok(Message::RunFuture(_))));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stale_pages_are_not_reloaded_if_disabled() {
+        let mut app = app_on_child_scopes(0).await;
+
+        tokio::time::advance(std::time::Duration::from_secs(24 * 60 * 60)).await;
+        app.handle_message(Message::GoBack).await;
+        assert!(matches!(app.page, Page::Scopes(_)));
+        assert!(app.message_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn last_command_is_shown_and_copied() {
+        let boundary_client = boundary::MockClient::builder()
+            .scopes(HashMap::new())
+            .last_command("boundary scopes list -format json".to_string())
+            .build();
+        let mut app = make_authenticated_app_with_client(
+            boundary_client,
+            MockConnectionManager::new(),
+            Box::new(MockClipboardAccess::new()),
+        )
+        .await;
+        app.process_pending_messages().await;
+
+        app.handle_message(Message::ShowLastCommand).await;
+        assert!(app.last_command.is_some());
+        app.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char('y'))))
+            .await;
+
+        assert!(app.last_command.is_none());
+        assert!(matches!(
+            app.message_rx.try_recv(),
+            Ok(Message::SetClipboard { text, .. }) if text == "boundary scopes list -format json"
+        ));
+    }
+
+    #[tokio::test]
+    async fn last_command_explains_how_to_record_commands() {
+        let mut app = make_authenticated_app(
+            MockConnectionManager::new(),
+            Box::new(MockClipboardAccess::new()),
+        )
+        .await;
+
+        app.handle_message(Message::ShowLastCommand).await;
+
+        assert!(app.last_command.is_none());
+        assert!(matches!(app.alerts.current(), Some((_, message)) if message.contains("--debug")));
+    }
+
+    // Returns the first queued message matching `pred`, handling the messages of the pages
+    // loading meanwhile
+    async fn next_message_matching<C, R, M>(
+        app: &mut BountuiApp<C, R, M>,
+        pred: impl Fn(&Message) -> bool,
+    ) -> Message
+    where
+        C: boundary::ApiClient + Clone + Send + Sync + 'static,
+        C::ConnectionHandle: Send,
+        R: RememberUserInput + Copy,
+        M: ConnectionManager,
+    {
+        loop {
+            let message = app.message_rx.try_recv().expect("The expected message should be sent");
+            if pred(&message) {
+                return message;
+            }
+            app.handle_message(message).await;
+        }
+    }
+
+    // Runs the check of the connections and handles its outcome
+    async fn check_connections<C, R, M>(app: &mut BountuiApp<C, R, M>)
+    where
+        C: boundary::ApiClient + Clone + Send + Sync + 'static,
+        C::ConnectionHandle: Send,
+        R: RememberUserInput + Copy,
+        M: ConnectionManager,
+    {
+        app.handle_message(Message::CheckConnections).await;
+        while app.tasks.next().await.is_some() {}
+        let message =
+            next_message_matching(app, |m| matches!(m, Message::ConnectionsChecked(_))).await;
+        app.handle_message(message).await;
+    }
+
+    #[tokio::test]
+    async fn dead_connections_are_detected_and_reconnect_is_offered() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager
+            .expect_get_local_ports()
+            .returning(|| HashMap::from([("s_1".to_string(), 8080), ("s_2".to_string(), 8081)]));
+        connection_manager
+            .expect_find_dead_connections()
+            .returning(|| Box::pin(async { vec!["s_2".to_string()] }));
+        connection_manager
+            .expect_reconnect()
+            .withf(|id| id == "s_2")
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(connect_response("s_3")) }));
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+        app.process_pending_messages().await;
+
+        check_connections(&mut app).await;
+        let (session_ids, _) = app.dead_connections.as_ref().expect("Reconnecting should be offered");
+        assert_eq!(session_ids, &vec!["s_2".to_string()]);
+
+        app.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Enter)))
+            .await;
+        assert!(app.dead_connections.is_none());
+        let reconnect = std::iter::from_fn(|| app.message_rx.try_recv().ok())
+            .find(|m| matches!(m, Message::ReconnectConnections(_)))
+            .expect("Reconnecting should be requested");
+        assert!(matches!(&reconnect, Message::ReconnectConnections(ids) if ids == &vec!["s_2".to_string()]));
+        app.handle_message(reconnect).await;
+        while app.tasks.next().await.is_some() {}
+        assert!(std::iter::from_fn(|| app.message_rx.try_recv().ok()).any(|m| matches!(
+            m,
+            Message::Toaster(components::toaster::Message::ShowToast { text, .. })
+                if text == "Reconnected 1 connections"
+        )));
+    }
+
+    #[tokio::test]
+    async fn no_reconnect_is_offered_when_all_connections_are_alive() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager
+            .expect_get_local_ports()
+            .returning(|| HashMap::from([("s_1".to_string(), 8080)]));
+        connection_manager
+            .expect_find_dead_connections()
+            .returning(|| Box::pin(async { vec![] }));
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+        app.process_pending_messages().await;
+
+        check_connections(&mut app).await;
+        assert!(app.dead_connections.is_none());
+        assert!(std::iter::from_fn(|| app.message_rx.try_recv().ok()).any(|m| matches!(
+            m,
+            Message::Toaster(components::toaster::Message::ShowToast { text, .. })
+                if text == "All 1 connections are alive"
+        )));
+    }
+
+    #[tokio::test]
+    async fn recent_command_jumps_back_to_a_page_shown_before() {
+        let mut app =
+            make_authenticated_app(MockConnectionManager::new(), Box::new(MockClipboardAccess::new()))
+                .await;
+        app.process_pending_messages().await;
+        let show_targets = |id: &str| Message::ShowTargets {
+            parent: make_scope(id),
+            select_target_id: None,
+        };
+        app.handle_message(show_targets("team-a")).await;
+        app.handle_message(Message::GoBack).await;
+        app.handle_message(show_targets("team-b")).await;
+
+        app.handle_message(Message::ShowRecentPages).await;
+        let nav_input = app.navigation_input.as_ref().expect("The recent pages should be listed");
+        let labels: Vec<&str> = nav_input.matching_commands().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, vec!["Targets — team-a", "Scopes — global"]);
+
+        app.handle_event(&Event::Key(crossterm::event::KeyEvent::from(KeyCode::Enter)))
+            .await;
+        app.process_pending_messages().await;
+        assert!(app.navigation_input.is_none());
+        assert!(matches!(app.page, Page::Targets(_)));
+
+        app.handle_message(Message::GoBack).await;
+        app.handle_message(Message::ShowRecentPages).await;
+        let nav_input = app.navigation_input.as_ref().unwrap();
+        let labels: Vec<&str> = nav_input.matching_commands().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, vec!["Targets — team-a", "Scopes — global"], "Going back shows team-b again");
+    }
+
+    #[tokio::test]
+    async fn control_requests_list_and_stop_connections() {
+        let mut connection_manager = MockConnectionManager::new();
+        connection_manager
+            .expect_get_local_ports()
+            .returning(|| HashMap::from([("s_2".to_string(), 8081), ("s_1".to_string(), 8080)]));
+        connection_manager
+            .expect_get_labels()
+            .returning(|| HashMap::from([("s_1".to_string(), "db".to_string())]));
+        connection_manager
+            .expect_stop()
+            .withf(|id| id == "s_1")
+            .returning(|_| Box::pin(async { Ok(()) }));
+        let mut app =
+            make_authenticated_app(connection_manager, Box::new(MockClipboardAccess::new())).await;
+        app.process_pending_messages().await;
+        let request = |request| {
+            let (respond_to, response) = tokio::sync::oneshot::channel();
+            (Message::Control { request, respond_to }, response)
+        };
+
+        let (message, response) = request(ControlRequest::ListConnections);
+        app.handle_message(message).await;
+        assert_eq!(
+            response.await.unwrap().result,
+            Some(serde_json::json!([
+                {"session_id": "s_1", "port": 8080, "label": "db"},
+                {"session_id": "s_2", "port": 8081}
+            ]))
+        );
+
+        let (message, response) = request(ControlRequest::Stop { session_id: "s_1".to_string() });
+        app.handle_message(message).await;
+        assert!(response.await.unwrap().ok);
+    }
+
+    async fn app_with_named_targets(
+    ) -> BountuiApp<boundary::MockClient, Option<UserInputsPath<&'static str>>, MockConnectionManager> {
+        let boundary_client = boundary::MockClient::builder()
+            .user_id("user-1".to_string())
+            .scopes(HashMap::from([(None, vec![make_scope("p_1"), make_scope("p_2")])]))
+            .targets(HashMap::from([(
+                None,
+                vec![
+                    target("ttcp_1", "db-prod", "p_1"),
+                    target("ttcp_2", "db-prod", "p_2"),
+                    target("ttcp_3", "web", "p_1"),
+                ],
+            )]))
+            .build();
+        make_authenticated_app_with_client(
+            boundary_client,
+            MockConnectionManager::new(),
+            Box::new(MockClipboardAccess::new()),
+        )
+        .await
+    }
+
+    // Runs the lookup task of `open` and returns the message it sends
+    async fn open_target_message<C, R, M>(app: &mut BountuiApp<C, R, M>, name: &str) -> Message
+    where
+        C: boundary::ApiClient + Clone + Send + Sync + 'static,
+        C::ConnectionHandle: Send,
+        R: RememberUserInput + Copy,
+        M: ConnectionManager,
+    {
+        app.process_pending_messages().await;
+        app.handle_message(Message::OpenTarget(name.to_string())).await;
+        while let Ok(Some(())) =
+            tokio::time::timeout(std::time::Duration::from_millis(10), app.tasks.next()).await
+        {}
+        next_message_matching(app, |m| {
+            matches!(
+                m,
+                Message::ShowTargetsForTarget { .. }
+                    | Message::ShowTargetChoices(_)
+                    | Message::ShowAlert(..)
+            )
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn open_shows_the_only_target_named_like_the_name() {
+        let mut app = app_with_named_targets().await;
+
+        let message = open_target_message(&mut app, "web").await;
+        assert!(matches!(
+            message,
+            Message::ShowTargetsForTarget { scope_id, target_id } if scope_id == "p_1" && target_id == "ttcp_3"
+        ));
+
+        let message = open_target_message(&mut app, "cache").await;
+        assert!(matches!(message, Message::ShowAlert(title, _) if title == "Not found"));
+    }
+
+    #[tokio::test]
+    async fn open_lets_the_user_pick_from_targets_named_alike() {
+        let mut app = app_with_named_targets().await;
+
+        let message = open_target_message(&mut app, "db-prod").await;
+        app.handle_message(message).await;
+        let nav_input = app.navigation_input.as_ref().expect("The targets should be listed");
+        let choices: Vec<&str> = nav_input.matching_commands().map(|c| c.hint.as_str()).collect();
+        assert_eq!(choices, vec!["ttcp_1 in p_1", "ttcp_2 in p_2"]);
+
+        let press = |code| Event::Key(crossterm::event::KeyEvent::from(code));
+        app.handle_event(&press(KeyCode::Down)).await;
+        app.handle_event(&press(KeyCode::Enter)).await;
+        let message =
+            next_message_matching(&mut app, |m| matches!(m, Message::ShowTargetsForTarget { .. }))
+                .await;
+        assert!(matches!(
+            message,
+            Message::ShowTargetsForTarget { scope_id, target_id } if scope_id == "p_2" && target_id == "ttcp_2"
+        ));
+    }
+
+    #[tokio::test]
+    async fn goto_completes_scope_and_target_ids_by_name() {
+        let app = app_with_named_targets().await;
+
+        let candidates = (app.goto_completer())().await;
+        let values: Vec<&str> = candidates.iter().map(|(value, _)| value.as_str()).collect();
+        assert_eq!(values, vec!["p_1", "p_2", "ttcp_1", "ttcp_2", "ttcp_3"]);
+        let names = (app.target_completer(|t| (t.name.clone(), t.id.clone())))().await;
+        assert_eq!(names[2], ("web".to_string(), "ttcp_3".to_string()));
+    }
+
+    #[tokio::test]
+    async fn connecting_by_id_refuses_production_targets() {
+        let mut app = app_with_named_targets().await;
+        app.config.production_targets = vec!["*-prod".to_string()];
+        app.process_pending_messages().await;
+        for target_id in ["ttcp_1", "ttcp_3"] {
+            app.handle_message(Message::ConnectById {
+                target_id: target_id.to_string(),
+                port: Some(5432),
+            })
+            .await;
+        }
+        while app.tasks.next().await.is_some() {}
+
+        let mut messages = Vec::new();
+        while let Ok(message) = app.message_rx.try_recv() {
+            messages.push(message);
+        }
+        assert!(messages.iter().any(|m| matches!(
+            m,
+            Message::ShowAlert(title, message) if title == "Not connected" && message.contains("'db-prod'")
+        )));
+        assert!(messages.iter().any(|m| matches!(
+            m,
+            Message::Connect { target_id, port: 5432, .. } if target_id == "ttcp_3"
+        )));
+        assert!(!messages.iter().any(|m| matches!(
+            m,
+            Message::Connect { target_id, .. } if target_id == "ttcp_1"
+        )));
+    }
+
+    #[tokio::test]
+    async fn control_requests_do_not_connect_to_production_targets() {
+        let mut app = app_with_named_targets().await;
+        app.config.production_targets = vec!["*-prod".to_string()];
+        app.process_pending_messages().await;
+        let (respond_to, response) = tokio::sync::oneshot::channel();
+
+        app.handle_message(Message::Control {
+            request: ControlRequest::Connect {
+                target_id: "ttcp_1".to_string(),
+                port: Some(5432),
+            },
+            respond_to,
+        })
+        .await;
+
+        let response = response.await.unwrap();
+        assert!(!response.ok);
+        assert!(response.error.unwrap().contains("production target"));
     }
 }
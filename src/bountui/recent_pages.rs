@@ -0,0 +1,143 @@
+use crate::boundary::{Scope, Target};
+use crate::bountui::Message;
+
+/// A page shown before that the `recent` command jumps back to.
+#[derive(Debug, Clone)]
+pub enum RecentPage {
+    /// The child scopes of the scope, the root scopes for `None`.
+    Scopes(Option<Scope>),
+    Targets(Scope),
+    Sessions { scope: String, target: Target },
+}
+
+impl RecentPage {
+    /// The name shown in the palette, e.g. "Targets — team-a".
+    pub fn label(&self) -> String {
+        match self {
+            RecentPage::Scopes(None) => "Scopes — global".to_string(),
+            RecentPage::Scopes(Some(scope)) => format!("Scopes — {}", scope.name),
+            RecentPage::Targets(scope) => format!("Targets — {}", scope.name),
+            RecentPage::Sessions { target, .. } => format!("Sessions — {}", target.name),
+        }
+    }
+
+    /// Shows the page again.
+    pub fn message(&self) -> Message {
+        match self {
+            RecentPage::Scopes(parent) => Message::ShowScopes {
+                parent: parent.clone(),
+                select_scope_id: None,
+            },
+            RecentPage::Targets(parent) => Message::ShowTargets {
+                parent: parent.clone(),
+                select_target_id: None,
+            },
+            RecentPage::Sessions { scope, target } => Message::ShowSessions {
+                scope: scope.clone(),
+                target: target.clone(),
+                select_session_id: None,
+            },
+        }
+    }
+
+    /// The id of the scope or target the page shows.
+    pub fn id(&self) -> &str {
+        match self {
+            RecentPage::Scopes(None) => "global",
+            RecentPage::Scopes(Some(scope)) | RecentPage::Targets(scope) => &scope.id,
+            RecentPage::Sessions { target, .. } => &target.id,
+        }
+    }
+
+    // Pages showing the same scope or target are the same, even if its name changed since
+    fn is_same_page(&self, other: &RecentPage) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other) && self.id() == other.id()
+    }
+}
+
+/// The last distinct pages shown, the most recent first.
+pub struct RecentPages {
+    pages: Vec<RecentPage>,
+    capacity: usize,
+}
+
+impl RecentPages {
+    pub fn new(capacity: usize) -> Self {
+        RecentPages {
+            pages: vec![],
+            capacity,
+        }
+    }
+
+    /// Moves the page to the front, dropping the oldest page once more than `capacity` were shown.
+    pub fn visit(&mut self, page: RecentPage) {
+        self.pages.retain(|p| !p.is_same_page(&page));
+        self.pages.insert(0, page);
+        self.pages.truncate(self.capacity);
+    }
+
+    /// The pages to jump to from the `current` one, the most recent first.
+    pub fn jump_list(&self, current: Option<&RecentPage>) -> Vec<&RecentPage> {
+        self.pages
+            .iter()
+            .filter(|page| !current.is_some_and(|current| current.is_same_page(page)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::fixtures::{scope, target};
+
+    fn labels(pages: Vec<&RecentPage>) -> Vec<String> {
+        pages.into_iter().map(RecentPage::label).collect()
+    }
+
+    #[test]
+    fn most_recent_page_comes_first() {
+        let mut recent = RecentPages::new(10);
+        recent.visit(RecentPage::Scopes(None));
+        recent.visit(RecentPage::Targets(scope("a", "team-a", "global")));
+        recent.visit(RecentPage::Sessions { scope: "p_1".to_string(), target: target("prod", "db-prod", "p_1") });
+
+        assert_eq!(
+            labels(recent.jump_list(None)),
+            vec!["Sessions — db-prod", "Targets — team-a", "Scopes — global"]
+        );
+    }
+
+    #[test]
+    fn revisited_page_moves_to_the_front_once() {
+        let mut recent = RecentPages::new(10);
+        recent.visit(RecentPage::Targets(scope("a", "team-a", "global")));
+        recent.visit(RecentPage::Targets(scope("b", "team-b", "global")));
+        recent.visit(RecentPage::Targets(scope("a", "team-a", "global")));
+
+        assert_eq!(labels(recent.jump_list(None)), vec!["Targets — team-a", "Targets — team-b"]);
+    }
+
+    #[test]
+    fn oldest_pages_are_dropped_beyond_the_capacity() {
+        let mut recent = RecentPages::new(2);
+        recent.visit(RecentPage::Targets(scope("a", "team-a", "global")));
+        recent.visit(RecentPage::Targets(scope("b", "team-b", "global")));
+        recent.visit(RecentPage::Targets(scope("c", "team-c", "global")));
+
+        assert_eq!(labels(recent.jump_list(None)), vec!["Targets — team-c", "Targets — team-b"]);
+    }
+
+    #[test]
+    fn current_page_is_not_listed() {
+        let mut recent = RecentPages::new(10);
+        recent.visit(RecentPage::Targets(scope("a", "team-a", "global")));
+        recent.visit(RecentPage::Scopes(Some(scope("a", "team-a", "global"))));
+        recent.visit(RecentPage::Targets(scope("b", "team-b", "global")));
+
+        let current = RecentPage::Targets(scope("b", "team-b", "global"));
+        assert_eq!(
+            labels(recent.jump_list(Some(&current))),
+            vec!["Scopes — team-a", "Targets — team-a"]
+        );
+    }
+}
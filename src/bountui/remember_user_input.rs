@@ -1,4 +1,6 @@
+use crate::bountui::last_location::LastLocation;
 use anyhow::Context;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs::{create_dir_all, OpenOptions};
@@ -8,12 +10,46 @@ use std::path::Path;
 #[derive(Serialize, Deserialize, Default)]
 struct UserInputs {
     local_ports: HashMap<String, u16>,
+    #[serde(default)]
+    last_connected: HashMap<String, DateTime<Utc>>,
+    #[serde(default)]
+    notes: HashMap<String, String>,
+    #[serde(default)]
+    last_location: Option<LastLocation>,
+    #[serde(default)]
+    command_history: Vec<String>,
+}
+
+// Number of accepted navigation input commands that are remembered
+const COMMAND_HISTORY_SIZE: usize = 50;
+
+/// Appends `command` to the history, the oldest first, dropping an earlier occurrence of it and
+/// the oldest commands beyond `COMMAND_HISTORY_SIZE`.
+fn push_command(history: &mut Vec<String>, command: String) {
+    history.retain(|c| *c != command);
+    history.push(command);
+    let excess = history.len().saturating_sub(COMMAND_HISTORY_SIZE);
+    history.drain(..excess);
 }
 
 
 pub trait RememberUserInput {
     fn store_local_port(&mut self, target: String, port: u16) -> anyhow::Result<()>;
     fn get_local_port(&self, target_id: &String) -> anyhow::Result<Option<u16>>;
+    /// Returns the remembered ports of all targets with a single read.
+    fn get_all_ports(&self) -> anyhow::Result<HashMap<String, u16>>;
+    fn store_last_connected(&mut self, target: String, time: DateTime<Utc>) -> anyhow::Result<()>;
+    /// Returns the last connected times of all targets with a single read.
+    fn get_all_last_connected(&self) -> anyhow::Result<HashMap<String, DateTime<Utc>>>;
+    /// Stores the note of a target, an empty note removes it.
+    fn store_note(&mut self, target: String, note: String) -> anyhow::Result<()>;
+    fn get_note(&self, target_id: &str) -> anyhow::Result<Option<String>>;
+    fn store_last_location(&mut self, location: LastLocation) -> anyhow::Result<()>;
+    fn get_last_location(&self) -> anyhow::Result<Option<LastLocation>>;
+    /// Remembers a command accepted in the navigation input.
+    fn store_command(&mut self, command: String) -> anyhow::Result<()>;
+    /// Returns the remembered commands of the navigation input, the oldest first.
+    fn get_command_history(&self) -> anyhow::Result<Vec<String>>;
 }
 
 fn read_user_inputs<P: AsRef<Path>>(path: P) -> anyhow::Result<UserInputs> {
@@ -76,6 +112,69 @@ where
             .get(target_id)
             .copied())
     }
+
+    fn get_all_ports(&self) -> anyhow::Result<HashMap<String, u16>> {
+        Ok(read_user_inputs(self.0.as_ref())
+            .context("Failed to read user inputs")?
+            .local_ports)
+    }
+
+    fn store_last_connected(&mut self, target: String, time: DateTime<Utc>) -> anyhow::Result<()> {
+        let mut user_inputs =
+            read_user_inputs(self.0.as_ref()).context("Failed to read user inputs")?;
+        user_inputs.last_connected.insert(target, time);
+        write_user_inputs(self.0.as_ref(), &user_inputs)
+    }
+
+    fn get_all_last_connected(&self) -> anyhow::Result<HashMap<String, DateTime<Utc>>> {
+        Ok(read_user_inputs(self.0.as_ref())
+            .context("Failed to read user inputs")?
+            .last_connected)
+    }
+
+    fn store_note(&mut self, target: String, note: String) -> anyhow::Result<()> {
+        let mut user_inputs =
+            read_user_inputs(self.0.as_ref()).context("Failed to read user inputs")?;
+        if note.trim().is_empty() {
+            user_inputs.notes.remove(&target);
+        } else {
+            user_inputs.notes.insert(target, note);
+        }
+        write_user_inputs(self.0.as_ref(), &user_inputs)
+    }
+
+    fn get_note(&self, target_id: &str) -> anyhow::Result<Option<String>> {
+        Ok(read_user_inputs(self.0.as_ref())
+            .context("Failed to read user inputs")?
+            .notes
+            .remove(target_id))
+    }
+
+    fn store_last_location(&mut self, location: LastLocation) -> anyhow::Result<()> {
+        let mut user_inputs =
+            read_user_inputs(self.0.as_ref()).context("Failed to read user inputs")?;
+        user_inputs.last_location = Some(location);
+        write_user_inputs(self.0.as_ref(), &user_inputs)
+    }
+
+    fn get_last_location(&self) -> anyhow::Result<Option<LastLocation>> {
+        Ok(read_user_inputs(self.0.as_ref())
+            .context("Failed to read user inputs")?
+            .last_location)
+    }
+
+    fn store_command(&mut self, command: String) -> anyhow::Result<()> {
+        let mut user_inputs =
+            read_user_inputs(self.0.as_ref()).context("Failed to read user inputs")?;
+        push_command(&mut user_inputs.command_history, command);
+        write_user_inputs(self.0.as_ref(), &user_inputs)
+    }
+
+    fn get_command_history(&self) -> anyhow::Result<Vec<String>> {
+        Ok(read_user_inputs(self.0.as_ref())
+            .context("Failed to read user inputs")?
+            .command_history)
+    }
 }
 
 impl<P> RememberUserInput for Option<P>
@@ -97,11 +196,85 @@ where
             Ok(None)
         }
     }
+
+    fn get_all_ports(&self) -> anyhow::Result<HashMap<String, u16>> {
+        if let Some(inner_self) = self {
+            inner_self.get_all_ports()
+        } else {
+            Ok(HashMap::new())
+        }
+    }
+
+    fn store_last_connected(&mut self, target: String, time: DateTime<Utc>) -> anyhow::Result<()> {
+        if let Some(inner_self) = self {
+            inner_self.store_last_connected(target, time)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn get_all_last_connected(&self) -> anyhow::Result<HashMap<String, DateTime<Utc>>> {
+        if let Some(inner_self) = self {
+            inner_self.get_all_last_connected()
+        } else {
+            Ok(HashMap::new())
+        }
+    }
+
+    fn store_note(&mut self, target: String, note: String) -> anyhow::Result<()> {
+        if let Some(inner_self) = self {
+            inner_self.store_note(target, note)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn get_note(&self, target_id: &str) -> anyhow::Result<Option<String>> {
+        if let Some(inner_self) = self {
+            inner_self.get_note(target_id)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn store_last_location(&mut self, location: LastLocation) -> anyhow::Result<()> {
+        if let Some(inner_self) = self {
+            inner_self.store_last_location(location)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn get_last_location(&self) -> anyhow::Result<Option<LastLocation>> {
+        if let Some(inner_self) = self {
+            inner_self.get_last_location()
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn store_command(&mut self, command: String) -> anyhow::Result<()> {
+        if let Some(inner_self) = self {
+            inner_self.store_command(command)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn get_command_history(&self) -> anyhow::Result<Vec<String>> {
+        if let Some(inner_self) = self {
+            inner_self.get_command_history()
+        } else {
+            Ok(vec![])
+        }
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
+    use crate::bountui::last_location::LastLocation;
     use crate::bountui::{RememberUserInput, UserInputsPath};
+    use chrono::{DateTime, TimeZone, Utc};
     use std::collections::HashMap;
     use std::io::Write;
     use std::path::Path;
@@ -110,6 +283,10 @@ pub mod tests {
     #[derive(Default)]
     pub struct MockRememberUserInput {
         ports: HashMap<String, u16>,
+        last_connected: HashMap<String, DateTime<Utc>>,
+        notes: HashMap<String, String>,
+        last_location: Option<LastLocation>,
+        command_history: Vec<String>,
     }
 
     impl RememberUserInput for MockRememberUserInput {
@@ -121,6 +298,50 @@ pub mod tests {
         fn get_local_port(&self, _target_id: &String) -> anyhow::Result<Option<u16>> {
             Ok(self.ports.get(_target_id).copied())
         }
+
+        fn get_all_ports(&self) -> anyhow::Result<HashMap<String, u16>> {
+            Ok(self.ports.clone())
+        }
+
+        fn store_last_connected(&mut self, target: String, time: DateTime<Utc>) -> anyhow::Result<()> {
+            self.last_connected.insert(target, time);
+            Ok(())
+        }
+
+        fn get_all_last_connected(&self) -> anyhow::Result<HashMap<String, DateTime<Utc>>> {
+            Ok(self.last_connected.clone())
+        }
+
+        fn store_note(&mut self, target: String, note: String) -> anyhow::Result<()> {
+            if note.trim().is_empty() {
+                self.notes.remove(&target);
+            } else {
+                self.notes.insert(target, note);
+            }
+            Ok(())
+        }
+
+        fn get_note(&self, target_id: &str) -> anyhow::Result<Option<String>> {
+            Ok(self.notes.get(target_id).cloned())
+        }
+
+        fn store_last_location(&mut self, location: LastLocation) -> anyhow::Result<()> {
+            self.last_location = Some(location);
+            Ok(())
+        }
+
+        fn get_last_location(&self) -> anyhow::Result<Option<LastLocation>> {
+            Ok(self.last_location.clone())
+        }
+
+        fn store_command(&mut self, command: String) -> anyhow::Result<()> {
+            super::push_command(&mut self.command_history, command);
+            Ok(())
+        }
+
+        fn get_command_history(&self) -> anyhow::Result<Vec<String>> {
+            Ok(self.command_history.clone())
+        }
     }
 
     const JSON: &str = "{\"local_ports\": {\"target_id\": 8080}}";
@@ -165,4 +386,102 @@ pub mod tests {
         assert_eq!(Some(8080), target_id_1_port);
         assert_eq!(Some(8081), target_id_2_port);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_get_all_ports() {
+        let file = create_user_input_file();
+        let path = UserInputsPath(file.path());
+        let ports = path.get_all_ports().unwrap();
+        assert_eq!(HashMap::from([("target_id".to_string(), 8080)]), ports);
+    }
+
+    #[test]
+    fn test_get_all_ports_file_does_not_exist() {
+        let path = UserInputsPath(Path::new("/does/not/exist"));
+        assert!(path.get_all_ports().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_all_last_connected_from_file_without_last_connected() {
+        let file = create_user_input_file();
+        let path = UserInputsPath(file.path());
+        assert!(path.get_all_last_connected().unwrap().is_empty());
+    }
+
+    #[test]
+    fn store_last_connected_and_get_all_last_connected() {
+        let file = create_user_input_file();
+        let mut path = UserInputsPath(file.path());
+        let time = Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap();
+        path.store_last_connected("target_id".to_string(), time).unwrap();
+        assert_eq!(Some(8080), path.get_local_port(&"target_id".to_string()).unwrap());
+        let content = std::fs::read_to_string(file.path()).unwrap();
+        assert!(content.contains("2025-01-02T03:04:05Z"), "The timestamp should be stored in ISO format");
+        assert_eq!(
+            HashMap::from([("target_id".to_string(), time)]),
+            path.get_all_last_connected().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_note_from_file_without_notes() {
+        let file = create_user_input_file();
+        let path = UserInputsPath(file.path());
+        assert!(path.get_note("target_id").unwrap().is_none());
+    }
+
+    #[test]
+    fn store_note_and_get_note() {
+        let file = create_user_input_file();
+        let mut path = UserInputsPath(file.path());
+        path.store_note("target_id".to_string(), "prod DB, be careful".to_string()).unwrap();
+        assert_eq!(Some("prod DB, be careful".to_string()), path.get_note("target_id").unwrap());
+        assert!(path.get_note("other_target_id").unwrap().is_none());
+        assert_eq!(Some(8080), path.get_local_port(&"target_id".to_string()).unwrap());
+    }
+
+    #[test]
+    fn store_empty_note_removes_note() {
+        let file = create_user_input_file();
+        let mut path = UserInputsPath(file.path());
+        path.store_note("target_id".to_string(), "note".to_string()).unwrap();
+        path.store_note("target_id".to_string(), "  ".to_string()).unwrap();
+        assert!(path.get_note("target_id").unwrap().is_none());
+    }
+
+    #[test]
+    fn store_last_location_and_get_last_location() {
+        let file = create_user_input_file();
+        let mut path = UserInputsPath(file.path());
+        assert!(path.get_last_location().unwrap().is_none());
+        let location = LastLocation {
+            scope_ids: vec!["o_1".to_string(), "p_1".to_string()],
+            targets: true,
+        };
+        path.store_last_location(location.clone()).unwrap();
+        assert_eq!(Some(location), path.get_last_location().unwrap());
+        assert_eq!(Some(8080), path.get_local_port(&"target_id".to_string()).unwrap());
+    }
+
+    #[test]
+    fn store_command_keeps_the_most_recent_occurrence() {
+        let file = create_user_input_file();
+        let mut path = UserInputsPath(file.path());
+        assert!(path.get_command_history().unwrap().is_empty());
+        for command in ["tree", "goto p_1", "tree"] {
+            path.store_command(command.to_string()).unwrap();
+        }
+        assert_eq!(path.get_command_history().unwrap(), vec!["goto p_1", "tree"]);
+        assert_eq!(Some(8080), path.get_local_port(&"target_id".to_string()).unwrap());
+    }
+
+    #[test]
+    fn command_history_drops_the_oldest_commands() {
+        let mut history = vec![];
+        for i in 0..super::COMMAND_HISTORY_SIZE + 2 {
+            super::push_command(&mut history, format!("goto p_{i}"));
+        }
+        assert_eq!(history.len(), super::COMMAND_HISTORY_SIZE);
+        assert_eq!(history[0], "goto p_2");
+    }
+}
@@ -0,0 +1,136 @@
+use crate::boundary::SessionWithTarget;
+use anyhow::Context;
+use chrono::{DateTime, Local, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::{create_dir_all, File};
+use std::path::{Path, PathBuf};
+
+/// A single session as written to a sessions export file.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ExportedSession {
+    pub session_id: String,
+    pub target_id: String,
+    pub target_name: String,
+    #[serde(rename = "type")]
+    pub session_type: String,
+    pub status: String,
+    pub created_time: DateTime<Utc>,
+    /// Only set for sessions managed by this bountui instance.
+    pub local_port: Option<u16>,
+}
+
+impl ExportedSession {
+    pub fn new(session: &SessionWithTarget, local_ports: &HashMap<String, u16>) -> Self {
+        ExportedSession {
+            session_id: session.session.id.clone(),
+            target_id: session.target_id().to_string(),
+            target_name: session.target_name(),
+            session_type: session.session.session_type.clone(),
+            status: session.session.status.clone(),
+            created_time: session.session.created_time,
+            local_port: local_ports.get(&session.session.id).copied(),
+        }
+    }
+}
+
+/// The file an export started now is written to: `~/.bountui/exports/sessions-<timestamp>.json`.
+pub fn default_export_path() -> anyhow::Result<PathBuf> {
+    let mut path = home::home_dir().context("Failed to determine home directory")?;
+    path.push(".bountui");
+    path.push("exports");
+    path.push(format!(
+        "sessions-{}.json",
+        Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    Ok(path)
+}
+
+pub fn write_sessions<P: AsRef<Path>>(path: P, sessions: &[ExportedSession]) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent).context("Failed to create parent directories")?;
+    }
+    let file = File::create(path).context("Failed to create export file")?;
+    serde_json::to_writer_pretty(file, sessions).context("Failed to write json")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::{Session, Target};
+    use chrono::TimeZone;
+
+    fn session_with_target(session_id: &str) -> SessionWithTarget {
+        SessionWithTarget::new(
+            Session {
+                id: session_id.to_string(),
+                target_id: "ttcp_1".to_string(),
+                scope_id: String::new(),
+                session_type: "tcp".to_string(),
+                created_time: Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap(),
+                expiration_time: None,
+                status: "active".to_string(),
+                authorized_actions: vec![],
+                user_id: "u_1".to_string(),
+            },
+            Some(Target {
+                id: "ttcp_1".to_string(),
+                name: "database".to_string(),
+                description: "".to_string(),
+                type_name: "tcp".to_string(),
+                authorized_collection_actions: Default::default(),
+                authorized_actions: vec![],
+                scope_id: "p_1".to_string(),
+                attributes: None,
+            }),
+        )
+    }
+
+    #[test]
+    fn test_exported_session_serialization() {
+        let local_ports = HashMap::from([("s_managed".to_string(), 5432)]);
+        let exported = vec![
+            ExportedSession::new(&session_with_target("s_managed"), &local_ports),
+            ExportedSession::new(&session_with_target("s_other"), &local_ports),
+        ];
+        let json = serde_json::to_value(&exported).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {
+                    "session_id": "s_managed",
+                    "target_id": "ttcp_1",
+                    "target_name": "database",
+                    "type": "tcp",
+                    "status": "active",
+                    "created_time": "2025-01-02T03:04:05Z",
+                    "local_port": 5432
+                },
+                {
+                    "session_id": "s_other",
+                    "target_id": "ttcp_1",
+                    "target_name": "database",
+                    "type": "tcp",
+                    "status": "active",
+                    "created_time": "2025-01-02T03:04:05Z",
+                    "local_port": null
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_write_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("exports").join("sessions.json");
+        let exported = vec![ExportedSession::new(
+            &session_with_target("s_1"),
+            &HashMap::new(),
+        )];
+        write_sessions(&path, &exported).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("\"session_id\": \"s_1\""));
+    }
+}
@@ -0,0 +1,67 @@
+use crate::boundary::{Scope, Target};
+
+/// Controller address the boundary cli talks to, the same one passed via `-addr`.
+pub fn controller_addr() -> Option<String> {
+    std::env::var("BOUNDARY_ADDR")
+        .ok()
+        .filter(|addr| !addr.trim().is_empty())
+}
+
+fn base_url(controller_addr: &str) -> String {
+    let addr = controller_addr.trim().trim_end_matches('/');
+    if addr.starts_with("http://") || addr.starts_with("https://") {
+        addr.to_string()
+    } else {
+        format!("https://{addr}")
+    }
+}
+
+pub fn scope_url(controller_addr: &str, scope: &Scope) -> String {
+    format!("{}/scopes/{}", base_url(controller_addr), scope.id)
+}
+
+pub fn target_url(controller_addr: &str, target: &Target) -> String {
+    format!(
+        "{}/scopes/{}/targets/{}",
+        base_url(controller_addr),
+        target.scope_id,
+        target.id
+    )
+}
+
+/// Shown when the web UI is requested but no controller address is configured.
+pub const MISSING_ADDR: &str = "Set BOUNDARY_ADDR to the controller address to open the web UI";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::fixtures::{scope, target};
+
+    #[test]
+    fn scope_url_from_controller_addr() {
+        assert_eq!(
+            scope_url("https://boundary.example.com:9200/", &scope("o_1234567890", "org", "global")),
+            "https://boundary.example.com:9200/scopes/o_1234567890"
+        );
+    }
+
+    #[test]
+    fn target_url_from_controller_addr() {
+        assert_eq!(
+            target_url("https://boundary.example.com", &target("ttcp_1234567890", "database", "p_1234567890")),
+            "https://boundary.example.com/scopes/p_1234567890/targets/ttcp_1234567890"
+        );
+    }
+
+    #[test]
+    fn controller_addr_without_scheme_uses_https() {
+        assert_eq!(
+            scope_url("boundary.example.com", &scope("o_1234567890", "org", "global")),
+            "https://boundary.example.com/scopes/o_1234567890"
+        );
+        assert_eq!(
+            scope_url("http://127.0.0.1:9200", &scope("o_1234567890", "org", "global")),
+            "http://127.0.0.1:9200/scopes/o_1234567890"
+        );
+    }
+}
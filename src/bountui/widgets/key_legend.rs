@@ -0,0 +1,25 @@
+use crate::bountui::components::table::EnabledAction;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::prelude::{Line, Span, Stylize, Widget};
+
+/// A single line listing the shortcuts of the actions enabled on the current page.
+pub struct KeyLegend {
+    pub actions: Vec<EnabledAction>,
+}
+
+impl Widget for KeyLegend {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let spans: Vec<Span> = self
+            .actions
+            .into_iter()
+            .flat_map(|action| {
+                [
+                    Span::from(format!(" {} ", action.shortcut)).black().on_light_blue(),
+                    Span::from(format!(" {}  ", action.name)).light_blue(),
+                ]
+            })
+            .collect();
+        Line::from(spans).on_black().render(area, buf);
+    }
+}
@@ -1,10 +1,12 @@
 mod connection_result_dialog;
 mod alert;
+mod key_legend;
 mod toast;
 mod loading_screen;
 mod login_screen;
 
 pub use alert::Alert;
+pub use key_legend::KeyLegend;
 pub use loading_screen::LoadingScreen;
 pub use login_screen::LoginScreen;
 pub use toast::Toast;
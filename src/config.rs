@@ -0,0 +1,464 @@
+use crate::boundary::ScopeRules;
+use anyhow::Context;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// User configuration read from `~/.bountui/config.toml`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// Command templates by target type to open a connected port with, e.g.
+    /// `tcp = "psql -h 127.0.0.1 -p {port}"`.
+    pub open_commands: HashMap<String, String>,
+    /// Copy `127.0.0.1:<port>` to the clipboard after connecting to a target.
+    pub copy_address_on_connect: bool,
+    /// Copy the password instead of showing the connect result if there is exactly one credential.
+    pub copy_single_password_on_connect: bool,
+    /// Show the sessions of a target once the dialog shown after connecting to it is closed.
+    pub show_sessions_after_connect: bool,
+    /// Bind the local port before connecting to fail early if it is in use. Disabled, a port in
+    /// use is told from the error of `boundary connect`.
+    pub check_port_before_connect: bool,
+    /// Targets to confirm by typing their name before connecting, by target or scope id or name
+    /// pattern, e.g. `prod-*`.
+    pub production_targets: Vec<String>,
+    /// Auth method to authenticate with instead of the primary one, e.g. `ampw_1234567890`.
+    pub auth_method_id: Option<String>,
+    /// Login name passed when authenticating.
+    pub login_name: Option<String>,
+    /// Reopen the scopes or targets page shown last, with the pages above it to go back to.
+    pub restore_last_location: bool,
+    /// Seconds between two reloads of the sessions pages.
+    pub sessions_refresh_interval: u64,
+    /// Stop reloading the sessions pages while the terminal is not focused.
+    pub pause_refresh_when_unfocused: bool,
+    /// Seconds after which the scopes or targets of a page are reloaded when going back to it,
+    /// never if 0.
+    pub reload_stale_pages_after: u64,
+    /// Minutes without any input after which all connections are stopped and bountui exits,
+    /// e.g. on shared machines. Never if unset.
+    pub idle_timeout: Option<u64>,
+    /// Column widths by page as set by the user at runtime, e.g. `targets = [30, 20, 10]`.
+    pub column_widths: HashMap<String, Vec<u16>>,
+    /// Scopes to hide or restrict, see [`ScopeRules`].
+    pub scopes: ScopeRules,
+    /// The file the configuration was loaded from, changes made at runtime are written back to it.
+    path: Option<PathBuf>,
+    /// Pages whose column widths were changed since they were last written to the file.
+    changed_column_widths: Vec<String>,
+    /// Settings that were ignored while loading because they were invalid.
+    warnings: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            open_commands: HashMap::new(),
+            copy_address_on_connect: false,
+            copy_single_password_on_connect: false,
+            show_sessions_after_connect: false,
+            check_port_before_connect: true,
+            production_targets: vec![],
+            auth_method_id: None,
+            login_name: None,
+            restore_last_location: false,
+            sessions_refresh_interval: 5,
+            pause_refresh_when_unfocused: true,
+            reload_stale_pages_after: 60,
+            idle_timeout: None,
+            column_widths: HashMap::new(),
+            scopes: ScopeRules::default(),
+            path: None,
+            changed_column_widths: vec![],
+            warnings: vec![],
+        }
+    }
+}
+
+impl Config {
+    /// Loads the configuration from `path`, falling back to the defaults if the file does not exist.
+    /// Invalid settings fall back to their defaults as well and are reported by [`Config::warnings`].
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Config> {
+        let path = path.as_ref();
+        let mut config = if path.exists() {
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read config file {}", path.display()))?;
+            Self::parse(&content)
+        } else {
+            Config::default()
+        };
+        config.path = Some(path.to_path_buf());
+        Ok(config)
+    }
+
+    /// Parses every setting on its own so that a single invalid one does not discard the others.
+    fn parse(content: &str) -> Config {
+        let mut config = Config::default();
+        let table: toml::Table = match content.parse() {
+            Ok(table) => table,
+            Err(e) => {
+                config
+                    .warnings
+                    .push(format!("The file is not valid TOML, using the defaults: {}", e.message()));
+                return config;
+            }
+        };
+        for (key, value) in table {
+            let result = match key.as_str() {
+                "open_commands" => value.try_into().map(|v| config.open_commands = v),
+                "copy_address_on_connect" => {
+                    value.try_into().map(|v| config.copy_address_on_connect = v)
+                }
+                "copy_single_password_on_connect" => {
+                    value.try_into().map(|v| config.copy_single_password_on_connect = v)
+                }
+                "show_sessions_after_connect" => {
+                    value.try_into().map(|v| config.show_sessions_after_connect = v)
+                }
+                "check_port_before_connect" => {
+                    value.try_into().map(|v| config.check_port_before_connect = v)
+                }
+                "production_targets" => value.try_into().map(|v| config.production_targets = v),
+                "auth_method_id" => value.try_into().map(|v| config.auth_method_id = Some(v)),
+                "login_name" => value.try_into().map(|v| config.login_name = Some(v)),
+                "restore_last_location" => {
+                    value.try_into().map(|v| config.restore_last_location = v)
+                }
+                "sessions_refresh_interval" => match value.try_into() {
+                    Ok(0) => {
+                        config
+                            .warnings
+                            .push(format!("{key}: must be greater than 0, using the default"));
+                        continue;
+                    }
+                    result => result.map(|v| config.sessions_refresh_interval = v),
+                },
+                "pause_refresh_when_unfocused" => {
+                    value.try_into().map(|v| config.pause_refresh_when_unfocused = v)
+                }
+                "reload_stale_pages_after" => {
+                    value.try_into().map(|v| config.reload_stale_pages_after = v)
+                }
+                "idle_timeout" => match value.try_into() {
+                    Ok(0) => {
+                        config
+                            .warnings
+                            .push(format!("{key}: must be greater than 0, ignored"));
+                        continue;
+                    }
+                    result => result.map(|v| config.idle_timeout = Some(v)),
+                },
+                "column_widths" => value.try_into().map(|v| config.column_widths = v),
+                "scopes" => value.try_into().map(|v| config.scopes = v),
+                _ => {
+                    config.warnings.push(format!("{key}: unknown setting, ignored"));
+                    continue;
+                }
+            };
+            if let Err(e) = result {
+                config
+                    .warnings
+                    .push(format!("{key}: {}, using the default", e.message().trim()));
+            }
+        }
+        config
+    }
+
+    /// Describes the settings that were ignored while loading, empty if the file was valid.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Remembers the column widths of a page, they are written to the config file by
+    /// [`Config::store_column_widths`].
+    pub fn set_column_widths(&mut self, page: &str, widths: Vec<u16>) {
+        self.column_widths.insert(page.to_string(), widths);
+        if !self.changed_column_widths.iter().any(|p| p == page) {
+            self.changed_column_widths.push(page.to_string());
+        }
+    }
+
+    /// Writes the column widths changed since the last call to the config file, keeping the
+    /// rest of the file as it is.
+    pub fn store_column_widths(&mut self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if self.changed_column_widths.is_empty() {
+            return Ok(());
+        }
+        let content = if path.exists() {
+            fs::read_to_string(path)
+                .with_context(|| format!("Failed to read config file {}", path.display()))?
+        } else {
+            String::new()
+        };
+        let mut document: toml_edit::DocumentMut = content
+            .parse()
+            .with_context(|| format!("Failed to parse config file {}", path.display()))?;
+        let column_widths = document
+            .entry("column_widths")
+            .or_insert_with(toml_edit::table)
+            .as_table_like_mut()
+            .context("column_widths is not a table")?;
+        for page in &self.changed_column_widths {
+            let widths = self.column_widths.get(page).into_iter().flatten();
+            column_widths.insert(
+                page,
+                toml_edit::value(widths.map(|w| i64::from(*w)).collect::<toml_edit::Array>()),
+            );
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create parent directories")?;
+        }
+        fs::write(path, document.to_string())
+            .with_context(|| format!("Failed to write config file {}", path.display()))?;
+        self.changed_column_widths.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn load_open_commands() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"open_commands = {{ tcp = "psql -h 127.0.0.1 -p {{port}}" }}"#
+        )
+        .unwrap();
+        let config = Config::load(file.path()).unwrap();
+        assert_eq!(
+            config.open_commands.get("tcp"),
+            Some(&"psql -h 127.0.0.1 -p {port}".to_string())
+        );
+    }
+
+    #[test]
+    fn load_reload_stale_pages_after() {
+        assert_eq!(Config::default().reload_stale_pages_after, 60);
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "reload_stale_pages_after = 0").unwrap();
+        let config = Config::load(file.path()).unwrap();
+        assert_eq!(config.reload_stale_pages_after, 0);
+        assert!(config.warnings().is_empty());
+    }
+
+    #[test]
+    fn load_sessions_refresh_interval() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "sessions_refresh_interval = 30").unwrap();
+        let config = Config::load(file.path()).unwrap();
+        assert_eq!(config.sessions_refresh_interval, 30);
+        assert!(config.open_commands.is_empty());
+    }
+
+    #[test]
+    fn load_pause_refresh_when_unfocused() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "pause_refresh_when_unfocused = false").unwrap();
+        let config = Config::load(file.path()).unwrap();
+        assert!(!config.pause_refresh_when_unfocused);
+        assert!(Config::default().pause_refresh_when_unfocused);
+    }
+
+    #[test]
+    fn load_copy_address_on_connect() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "copy_address_on_connect = true").unwrap();
+        let config = Config::load(file.path()).unwrap();
+        assert!(config.copy_address_on_connect);
+        assert!(!Config::default().copy_address_on_connect);
+    }
+
+    #[test]
+    fn load_copy_single_password_on_connect() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "copy_single_password_on_connect = true").unwrap();
+        let config = Config::load(file.path()).unwrap();
+        assert!(config.copy_single_password_on_connect);
+        assert!(!Config::default().copy_single_password_on_connect);
+    }
+
+    #[test]
+    fn load_idle_timeout() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "idle_timeout = 15").unwrap();
+        let config = Config::load(file.path()).unwrap();
+        assert_eq!(config.idle_timeout, Some(15));
+        assert_eq!(Config::default().idle_timeout, None);
+
+        let config = Config::parse("idle_timeout = 0");
+        assert_eq!(config.idle_timeout, None);
+        assert_eq!(config.warnings(), ["idle_timeout: must be greater than 0, ignored"]);
+    }
+
+    #[test]
+    fn load_restore_last_location() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "restore_last_location = true").unwrap();
+        let config = Config::load(file.path()).unwrap();
+        assert!(config.restore_last_location);
+        assert!(!Config::default().restore_last_location);
+    }
+
+    #[test]
+    fn load_show_sessions_after_connect() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "show_sessions_after_connect = true").unwrap();
+        let config = Config::load(file.path()).unwrap();
+        assert!(config.show_sessions_after_connect);
+        assert!(!Config::default().show_sessions_after_connect);
+    }
+
+    #[test]
+    fn load_check_port_before_connect() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "check_port_before_connect = false").unwrap();
+        let config = Config::load(file.path()).unwrap();
+        assert!(!config.check_port_before_connect);
+        assert!(Config::default().check_port_before_connect);
+    }
+
+    #[test]
+    fn load_production_targets() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, r#"production_targets = ["prod-*", "p_1234567890"]"#).unwrap();
+        let config = Config::load(file.path()).unwrap();
+        assert_eq!(config.production_targets, vec!["prod-*", "p_1234567890"]);
+        assert!(Config::default().production_targets.is_empty());
+    }
+
+    #[test]
+    fn load_auth_method_and_login_name() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "auth_method_id = \"ampw_1234567890\"\nlogin_name = \"jane\"").unwrap();
+        let config = Config::load(file.path()).unwrap();
+        assert_eq!(config.auth_method_id, Some("ampw_1234567890".to_string()));
+        assert_eq!(config.login_name, Some("jane".to_string()));
+        assert_eq!(Config::default().auth_method_id, None);
+    }
+
+    #[test]
+    fn load_missing_file_returns_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let config = Config::load(&path).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                path: Some(path),
+                ..Config::default()
+            }
+        );
+    }
+
+    #[test]
+    fn store_column_widths_keeps_existing_settings() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "# my settings\nsessions_refresh_interval = 30").unwrap();
+        let mut config = Config::load(file.path()).unwrap();
+
+        config.set_column_widths("targets", vec![40, 20, 10]);
+        config.set_column_widths("scopes", vec![50, 50]);
+        config.store_column_widths().unwrap();
+
+        let content = std::fs::read_to_string(file.path()).unwrap();
+        assert!(content.contains("# my settings"));
+        let reloaded = Config::load(file.path()).unwrap();
+        assert_eq!(reloaded.sessions_refresh_interval, 30);
+        assert_eq!(reloaded.column_widths.get("targets"), Some(&vec![40, 20, 10]));
+        assert_eq!(reloaded.column_widths.get("scopes"), Some(&vec![50, 50]));
+    }
+
+    #[test]
+    fn store_column_widths_creates_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bountui").join("config.toml");
+        let mut config = Config::load(&path).unwrap();
+        config.set_column_widths("sessions", vec![10, 20]);
+        assert!(!path.exists(), "Written only when stored");
+        config.store_column_widths().unwrap();
+        assert_eq!(
+            Config::load(&path).unwrap().column_widths.get("sessions"),
+            Some(&vec![10, 20])
+        );
+    }
+
+    #[test]
+    fn store_column_widths_writes_only_changed_pages() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "[column_widths]
+scopes = [50, 50]").unwrap();
+        let mut config = Config::load(file.path()).unwrap();
+        config.store_column_widths().unwrap();
+        assert_eq!(std::fs::read_to_string(file.path()).unwrap(), "[column_widths]\nscopes = [50, 50]\n");
+
+        config.set_column_widths("targets", vec![40, 20]);
+        config.set_column_widths("targets", vec![45, 20]);
+        config.store_column_widths().unwrap();
+        let reloaded = Config::load(file.path()).unwrap();
+        assert_eq!(reloaded.column_widths.get("scopes"), Some(&vec![50, 50]));
+        assert_eq!(reloaded.column_widths.get("targets"), Some(&vec![45, 20]));
+    }
+
+    #[test]
+    fn load_invalid_setting_uses_default() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            "open_commands = 42\nsessions_refresh_interval = 30\ncolumn_widths = {{ targets = [\"wide\"] }}"
+        )
+        .unwrap();
+        let config = Config::load(file.path()).unwrap();
+        assert!(config.open_commands.is_empty());
+        assert!(config.column_widths.is_empty());
+        assert_eq!(config.sessions_refresh_interval, 30);
+        assert_eq!(config.warnings().len(), 2);
+        assert!(config.warnings()[0].starts_with("column_widths: "));
+        assert!(config.warnings()[1].starts_with("open_commands: "));
+    }
+
+    #[test]
+    fn load_scope_rules() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "[scopes]\ndeny = [\"o_secret\"]\nrestricted = [\"prod-*\"]").unwrap();
+        let config = Config::load(file.path()).unwrap();
+        assert_eq!(config.scopes.deny, vec!["o_secret"]);
+        assert_eq!(config.scopes.restricted, vec!["prod-*"]);
+        assert!(config.scopes.allow.is_empty());
+        assert!(config.warnings().is_empty());
+    }
+
+    #[test]
+    fn load_zero_refresh_interval_uses_default() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "sessions_refresh_interval = 0\ncolor = \"blue\"").unwrap();
+        let config = Config::load(file.path()).unwrap();
+        assert_eq!(config.sessions_refresh_interval, 5);
+        assert_eq!(
+            config.warnings(),
+            [
+                "color: unknown setting, ignored",
+                "sessions_refresh_interval: must be greater than 0, using the default"
+            ]
+        );
+    }
+
+    #[test]
+    fn load_malformed_file_uses_defaults() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "open_commands = {{ tcp = ").unwrap();
+        let config = Config::load(file.path()).unwrap();
+        assert_eq!(config.open_commands, Config::default().open_commands);
+        assert_eq!(config.sessions_refresh_interval, 5);
+        assert_eq!(config.warnings().len(), 1);
+        assert!(config.warnings()[0].starts_with("The file is not valid TOML"));
+        assert_eq!(config.path, Some(file.path().to_path_buf()));
+    }
+}
@@ -1,4 +1,31 @@
-use crossterm::event::{Event, KeyEventKind};
+use crossterm::event::{
+    DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture, Event, KeyEventKind,
+    MouseEventKind,
+};
+use crossterm::execute;
+use log::warn;
+use ratatui::DefaultTerminal;
+use std::io::stdout;
+
+/// Like `ratatui::init`, but also reports mouse clicks, the wheel and focus changes as events.
+pub fn init_terminal() -> DefaultTerminal {
+    let terminal = ratatui::init();
+    if let Err(e) = execute!(stdout(), EnableMouseCapture) {
+        warn!("Failed to enable mouse capture: {}", e);
+    }
+    // Not every terminal reports focus changes, bountui then behaves as if always focused
+    if let Err(e) = execute!(stdout(), EnableFocusChange) {
+        warn!("Failed to enable focus change events: {}", e);
+    }
+    terminal
+}
+
+/// Undoes `init_terminal`.
+pub fn restore_terminal() {
+    let _ = execute!(stdout(), DisableFocusChange);
+    let _ = execute!(stdout(), DisableMouseCapture);
+    ratatui::restore();
+}
 
 pub fn receive_cross_term_events() -> tokio::sync::mpsc::Receiver<Event> {
 
@@ -14,6 +41,14 @@ pub fn receive_cross_term_events() -> tokio::sync::mpsc::Receiver<Event> {
                         }
                     }
                 }
+                else if let Event::Mouse(mouse_event) = event {
+                    // Only clicks and the wheel are used, plain moves would just cause redraws
+                    if !matches!(mouse_event.kind, MouseEventKind::Moved | MouseEventKind::Drag(_))
+                        && sender.send(event).await.is_err()
+                    {
+                        break;
+                    }
+                }
                 else {
                     if let Err(_) = sender.send(event).await {
                         break;
@@ -5,6 +5,7 @@ pub trait EventExt {
     fn is_enter(&self) -> bool;
     fn is_esc(&self) -> bool;
     fn is_stop(&self) -> bool;
+    fn is_quit(&self) -> bool;
     fn is_resize(&self) -> bool;
 
 }
@@ -32,6 +33,14 @@ impl EventExt for Event {
         }
     }
 
+    /// `q` on pages, which quits like Ctrl+C when nothing is being typed.
+    fn is_quit(&self) -> bool {
+        match self {
+            Event::Key(key_event) => key_event.code == crossterm::event::KeyCode::Char('q') && key_event.modifiers == crossterm::event::KeyModifiers::NONE,
+            _ => false
+        }
+    }
+
     fn is_resize(&self) -> bool {
         matches!(self, Event::Resize(_, _))
     }
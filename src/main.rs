@@ -1,26 +1,43 @@
+mod args;
 mod boundary;
 mod bountui;
+mod config;
 mod cross_term;
 pub mod event_ext;
 mod util;
 
+use crate::args::Args;
+use crate::boundary::ApiClient;
 use crate::bountui::auth_cache::{AuthCache, KeyringAuthCache, NoopAuthCache};
 use crate::bountui::{BountuiApp, UserInputsPath};
-use crate::cross_term::receive_cross_term_events;
+use crate::config::Config;
+use crate::cross_term::{receive_cross_term_events, restore_terminal};
 use crate::util::clipboard::{ArboardClipboard, BrokenClipboard, ClipboardAccess};
 use anyhow::Context;
 use flexi_logger::LoggerHandle;
-use log::error;
+use log::{error, warn};
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-fn init_logger() -> anyhow::Result<LoggerHandle> {
+fn init_logger(args: &Args) -> anyhow::Result<LoggerHandle> {
     // Initialize logging with flexi_logger
     // - Daily rotated log files
     // - Keep 7 days of logs
-    // - Default level: info; overridable via env var "LOG_LEVEL"
-    let log_spec = std::env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string());
+    // - Default level: info; overridable via "--log-level" or env var "LOG_LEVEL"
+    let log_spec = args
+        .log_level
+        .clone()
+        .or_else(|| std::env::var("LOG_LEVEL").ok())
+        .unwrap_or_else(|| "info".to_string());
+
+    // Configure logger from spec string
+    let logger = flexi_logger::Logger::try_with_str(log_spec)
+        .context("Failed to configure logger from log level")?;
+
+    if let Some(log_file) = &args.log_file {
+        return start_file_logger(logger, log_file);
+    }
 
     // Determine log directory per OS
     let log_dir: PathBuf = if cfg!(target_os = "windows") {
@@ -42,10 +59,6 @@ fn init_logger() -> anyhow::Result<LoggerHandle> {
     fs::create_dir_all(&log_dir)
         .with_context(|| format!("Failed to create log directory at '{}'", log_dir.display()))?;
 
-    // Configure logger from spec string
-    let logger = flexi_logger::Logger::try_with_str(log_spec)
-        .context("Failed to configure logger from LOG_LEVEL")?;
-
     // Start logger writing to the file
     let handle = logger
         .log_to_file(flexi_logger::FileSpec::default().directory(&log_dir))
@@ -60,16 +73,129 @@ fn init_logger() -> anyhow::Result<LoggerHandle> {
     Ok(handle)
 }
 
+fn start_file_logger(logger: flexi_logger::Logger, log_file: &Path) -> anyhow::Result<LoggerHandle> {
+    if let Some(parent) = log_file.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).with_context(|| {
+            format!("Failed to create log directory at '{}'", parent.display())
+        })?;
+    }
+    let file_spec = flexi_logger::FileSpec::try_from(log_file)
+        .with_context(|| format!("Invalid log file '{}'", log_file.display()))?;
+    logger
+        .log_to_file(file_spec)
+        .append()
+        .start()
+        .context("Failed to initialize logger")
+}
+
+/// Calls `restore` before a panic is reported, so the terminal leaves raw mode and the alternate
+/// screen and the panic message stays readable. The panic is logged as well.
+fn install_panic_hook<F: Fn() + Send + Sync + 'static>(restore: F) {
+    let previous_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore();
+        error!("{info}");
+        previous_hook(info);
+    }));
+}
+
+/// Prints the information we need in bug reports and returns the exit code.
+async fn run_doctor(config: &Config) -> i32 {
+    let boundary_client = boundary::CliClient::default()
+        .with_auth_method_id(config.auth_method_id.clone())
+        .with_login_name(config.login_name.clone());
+    let mut healthy = true;
+    println!("bountui: {}", env!("CARGO_PKG_VERSION"));
+    match boundary_client.resolve_bin_path() {
+        Some(path) => println!("boundary binary: {}", path.display()),
+        None => {
+            healthy = false;
+            println!("boundary binary: not found in PATH")
+        }
+    }
+    match boundary_client.boundary_version().await {
+        Ok(version) => println!(
+            "boundary version: {} (revision {}, built {})",
+            version.version, version.revision, version.build_date
+        ),
+        Err(e) => {
+            healthy = false;
+            println!("boundary version: failed: {}", e)
+        }
+    }
+    match boundary_client.authenticate().await {
+        Ok(response) => println!("authentication: ok (user {})", response.attributes.user_id),
+        Err(e) => {
+            healthy = false;
+            println!("authentication: failed: {}", e)
+        }
+    }
+    if healthy {
+        0
+    } else {
+        1
+    }
+}
+
 #[tokio::main]
 async fn main() {
-    if let Err(e) = init_logger() {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!(
+                "Usage: bountui [--version] [--doctor] [--debug] [--log-file <path>] [--log-level <spec>] [--refresh-interval <seconds>] [--auth-method-id <id>] [--login-name <name>] [--control-socket <path>]"
+            );
+            std::process::exit(2);
+        }
+    };
+    if args.version {
+        println!("bountui {}", env!("CARGO_PKG_VERSION"));
+        return;
+    }
+    let mut config = match home::home_dir() {
+        Some(mut path) => {
+            path.push(".bountui");
+            path.push("config.toml");
+            match Config::load(&path) {
+                Ok(config) => config,
+                Err(e) => {
+                    eprintln!("{:#}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => Config::default(),
+    };
+    if let Some(refresh_interval) = args.refresh_interval {
+        config.sessions_refresh_interval = refresh_interval;
+    }
+    if let Some(auth_method_id) = args
+        .auth_method_id
+        .clone()
+        .or_else(|| env::var("BOUNTUI_AUTH_METHOD_ID").ok())
+    {
+        config.auth_method_id = Some(auth_method_id);
+    }
+    if let Some(login_name) = args
+        .login_name
+        .clone()
+        .or_else(|| env::var("BOUNTUI_LOGIN_NAME").ok())
+    {
+        config.login_name = Some(login_name);
+    }
+    if args.doctor {
+        std::process::exit(run_doctor(&config).await);
+    }
+    if let Err(e) = init_logger(&args) {
         eprintln!("{}", e);
         std::process::exit(1);
     }
-    let boundary_client = boundary::CliClient::default();
-    let connection_manager =
-        bountui::connection_manager::DefaultConnectionManager::new(boundary_client.clone());
-
+    install_panic_hook(restore_terminal);
+    // The config was loaded before the logger started, its warnings are logged once it runs
+    for warning in config.warnings() {
+        warn!("Config: {warning}");
+    }
     let user_inputs_path_buf = home::home_dir().map(|mut path| {
         path.push(".bountui");
         path.push("user_inputs.json");
@@ -81,6 +207,19 @@ async fn main() {
         None
     };
 
+    let cli_client = boundary::CliClient::default()
+        .with_auth_method_id(config.auth_method_id.clone())
+        .with_login_name(config.login_name.clone())
+        .with_port_check(config.check_port_before_connect);
+    let cli_client = if args.debug {
+        cli_client.with_command_recording()
+    } else {
+        cli_client
+    };
+    let boundary_client = boundary::ScopeFilterClient::new(cli_client, config.scopes.clone());
+    let connection_manager =
+        bountui::connection_manager::DefaultConnectionManager::new(boundary_client.clone());
+
     let cross_term_event_rx = receive_cross_term_events();
 
     let clipboard: Box<dyn ClipboardAccess> = match ArboardClipboard::new() {
@@ -112,6 +251,49 @@ async fn main() {
         cross_term_event_rx,
         clipboard,
         auth_cache,
-    );
+        config,
+    )
+    .with_control_socket(args.control_socket.clone());
     let _ = app.run().await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[test]
+    fn logger_writes_to_configured_log_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let log_file = dir.path().join("logs").join("bountui.log");
+        let args = Args {
+            log_file: Some(log_file.clone()),
+            log_level: Some("info".to_string()),
+            ..Args::default()
+        };
+        let handle = init_logger(&args).expect("Logger should start");
+        log::info!("written to the configured log file");
+        handle.flush();
+        let content = fs::read_to_string(&log_file).expect("Log file should exist");
+        assert!(content.contains("written to the configured log file"));
+    }
+
+    #[test]
+    fn panic_hook_restores_terminal() {
+        static RESTORED: AtomicBool = AtomicBool::new(false);
+        // The hook is process wide, panics of tests running on other threads are not counted
+        let test_thread = std::thread::current().id();
+        let previous_hook = std::panic::take_hook();
+        install_panic_hook(move || {
+            if std::thread::current().id() == test_thread {
+                RESTORED.store(true, Ordering::SeqCst);
+            }
+        });
+
+        let result = std::panic::catch_unwind(|| panic!("port is not a number"));
+        std::panic::set_hook(previous_hook);
+
+        assert!(result.is_err());
+        assert!(RESTORED.load(Ordering::SeqCst));
+    }
+}
@@ -5,12 +5,28 @@ use tokio::sync::mpsc;
 
 pub trait MpscSenderExt<T> {
 
-    fn send_or_expect(&mut self, message: T) -> impl Future<Output = ()>;
+    /// Sends the message, logging instead of panicking when the receiver is gone, which
+    /// happens when a page or task sends late while the app shuts down.
+    fn send_or_log(&self, message: T) -> impl Future<Output = ()>;
 
 }
 
 impl <T> MpscSenderExt<T> for mpsc::Sender<T> {
-    async fn send_or_expect(&mut self, message: T) {
-        self.send(message).await.expect("Failed to send message");
+    async fn send_or_log(&self, message: T) {
+        if self.send(message).await.is_err() {
+            log::warn!("Dropped a message, its receiver is closed");
+        }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn send_or_log_ignores_closed_receiver() {
+        let (tx, rx) = mpsc::channel(1);
+        drop(rx);
+        tx.send_or_log("late message").await;
+    }
+}